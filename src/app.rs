@@ -8,21 +8,46 @@
 // - Integration of all components
 // ═══════════════════════════════════════════════════════════════════════════════
 
-use std::time::Duration;
+use std::path::Path;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
-use crate::csv_loader::pick_and_load_csv;
-use crate::detectors::quick_detect;
-use crate::serial_reader::SerialReader;
-use crate::state::SharedState;
+use csi_tui::csv_follower::CsvFollower;
+use csi_tui::csv_loader::{reload_last_csv, CsvLoader, LoadSummary};
+use csi_tui::detector_config::DetectorConfig;
+use csi_tui::detectors::quick_detect_with;
+use csi_tui::error::{CsiError, Result};
+use csi_tui::export::export_json;
+use csi_tui::features::FrameFeatures;
+use csi_tui::frame_queue::{frame_queue, FrameReceiver, FrameSender, FRAME_QUEUE_CAPACITY};
+use csi_tui::latency::Stage;
+use csi_tui::report::write_report;
+use crate::key_script::KeyRecorder;
+use crate::network_reader::NetworkReader;
+use crate::serial_reader::{store_frame, SerialReader};
+use csi_tui::state::{ChartMode, PortPicker, SharedState, WindowClock, DEFAULT_REFRESH_FPS, REFRESH_RATE_STEPS};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Application Configuration
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Tick rate for the event loop in milliseconds
-const TICK_RATE_MS: u64 = 50;
+/// Event poll timeout while receiving or playing back (ms)
+/// مهلة انتظار الأحداث أثناء الاستقبال أو التشغيل
+const ACTIVE_TICK_MS: u64 = 20;
+
+/// Event poll timeout when nothing is happening (ms)
+/// مهلة انتظار الأحداث عند عدم وجود نشاط
+const IDLE_TICK_MS: u64 = 250;
+
+/// Stay on the fast tick this long after the last key press (ms)
+/// البقاء على النبضة السريعة لهذه المدة بعد آخر ضغطة مفتاح
+const INPUT_GRACE_MS: u64 = 1_000;
+
+/// Event log rows moved per PageUp / PageDown
+/// عدد صفوف سجل الأحداث لكل PageUp / PageDown
+const EVENT_LOG_SCROLL_ROWS: isize = 3;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Application Structure
@@ -35,59 +60,269 @@ pub struct App {
     
     /// Serial reader instance
     serial_reader: Option<SerialReader>,
+    
+    /// UDP reader when a nexmon source is configured / قارئ UDP عند ضبط مصدر nexmon
+    network_reader: Option<NetworkReader>,
+    
+    /// Readers of the named sources (one per room) / قراء المصادر المسماة (واحد لكل غرفة)
+    source_readers: Vec<SerialReader>,
+    
+    /// Growing CSV file being followed / ملف CSV متنامٍ قيد المتابعة
+    follower: Option<CsvFollower>,
+    
+    /// Time of the last key press / وقت آخر ضغطة مفتاح
+    last_input: Instant,
+    
+    /// Frame sequence the detectors last ran on / تسلسل الإطارات عند آخر تشغيل للكاشفات
+    last_detect_seq: Option<u64>,
+    
+    /// Limits terminal redraws to the configured rate / يحد إعادة الرسم بالمعدل المضبوط
+    redraw: RedrawScheduler,
+    
+    /// Ctrl+Z was pressed / تم الضغط على Ctrl+Z
+    suspend_requested: bool,
+    
+    /// Skip redraws while the terminal is unfocused / تخطي الرسم عند فقدان التركيز
+    pause_unfocused: bool,
+    
+    /// Does the terminal window have focus? / هل نافذة الطرفية في التركيز؟
+    focused: bool,
+    
+    /// Key presses are written here when recording / تُكتب ضغطات المفاتيح هنا عند التسجيل
+    recorder: Option<KeyRecorder>,
+    
+    /// File dialogs can be shown (off during replay) / يمكن عرض نوافذ الملفات (معطل أثناء الإعادة)
+    dialogs: bool,
+    
+    /// Readers queue their frames here instead of locking the state per frame
+    /// القراء يرسلون إطاراتهم هنا بدلاً من قفل الحالة لكل إطار
+    frames_out: FrameSender,
+    
+    /// Drained into the state once per tick / يُفرغ في الحالة مرة كل نبضة
+    frames_in: FrameReceiver,
 }
 
 impl App {
     /// Create a new application instance
     pub fn new(state: SharedState) -> Self {
+        let (frames_out, frames_in) = frame_queue(FRAME_QUEUE_CAPACITY);
         Self {
             state,
             serial_reader: None,
+            network_reader: None,
+            source_readers: Vec::new(),
+            follower: None,
+            last_input: Instant::now(),
+            last_detect_seq: None,
+            redraw: RedrawScheduler::new(DEFAULT_REFRESH_FPS),
+            suspend_requested: false,
+            pause_unfocused: false,
+            focused: true,
+            recorder: None,
+            dialogs: true,
+            frames_out,
+            frames_in,
         }
     }
 
+    /// Record every key press to `recorder` / تسجيل كل ضغطة مفتاح
+    pub fn record_keys(&mut self, recorder: KeyRecorder) {
+        self.recorder = Some(recorder);
+    }
+
+    /// Never open file dialogs; L / T report no file instead
+    /// عدم فتح نوافذ الملفات أبداً؛ L / T تبلغ بعدم وجود ملف
+    pub fn disable_dialogs(&mut self) {
+        self.dialogs = false;
+    }
+
+    /// Advance playback by `elapsed_ms`, read followed rows and run the detectors
+    /// تقديم التشغيل بمقدار `elapsed_ms` وقراءة الصفوف المتابعة وتشغيل الكاشفات
+    pub fn tick(&mut self, elapsed_ms: f64) -> Result<()> {
+        self.drain_frames()?;
+        {
+            let mut state_guard = self.state.lock()?;
+            let due = state_guard.advance_playback(elapsed_ms);
+            if !due.is_empty() {
+                state_guard.push_playback_batch(due);
+                state_guard.status_message = format!("Playing: {:.1}s / {:.1}s",
+                    state_guard.get_current_playback_second(),
+                    state_guard.playback_duration_secs
+                );
+            }
+        }
+        self.poll_follow()?;
+        self.run_detectors()?;
+
+        // Scheduled recalibration for unattended runs / إعادة معايرة مجدولة للتشغيل دون مراقبة
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut state_guard = self.state.lock()?;
+        if state_guard.recalibration_due(now) && state_guard.recalibrate(now) {
+            state_guard.status_message = "📐 Baseline recalibrated".to_string();
+        }
+        state_guard.note_status_message(now);
+        Ok(())
+    }
+
+    /// Store every frame the readers queued since the last tick, under one lock
+    /// حفظ كل الإطارات التي أرسلها القراء منذ آخر نبضة تحت قفل واحد
+    fn drain_frames(&mut self) -> Result<()> {
+        let frames = self.frames_in.drain();
+        let dropped = self.frames_in.take_dropped();
+        let mut state_guard = self.state.lock()?;
+        for frame in frames {
+            let waited = frame.received_at.elapsed();
+            store_frame(&mut state_guard, frame, waited);
+        }
+        state_guard.queue_dropped += dropped;
+        Ok(())
+    }
+
+    /// Pick the event poll timeout for the next tick
+    /// اختيار مهلة انتظار الأحداث للنبضة التالية
+    fn tick_timeout(&self) -> Duration {
+        let busy = self.serial_reader.is_some()
+            || !self.source_readers.is_empty()
+            || self.network_reader.is_some()
+            || self.follower.is_some()
+            || self.state.lock().map(|s| s.receiver_active() || (s.playback_mode && s.playback_playing)).unwrap_or(false);
+        tick_timeout(busy, self.last_input.elapsed())
+    }
+
     /// Handle keyboard and other events
     ///
     /// Returns true if should quit
-    pub fn handle_events(&mut self) -> Result<bool, String> {
+    pub fn handle_events(&mut self) -> Result<bool> {
         // Poll for events with timeout
-        if event::poll(Duration::from_millis(TICK_RATE_MS))
-            .map_err(|e| format!("Event poll error: {}", e))?
-        {
-            if let Event::Key(key) = event::read().map_err(|e| format!("Event read error: {}", e))? {
+        if event::poll(self.tick_timeout()).map_err(CsiError::Terminal)? {
+            match event::read().map_err(CsiError::Terminal)? {
                 // Only handle key press events
-                if key.kind == KeyEventKind::Press {
-                    return self.handle_key(key.code);
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    if let Some(recorder) = &mut self.recorder {
+                        if let Err(e) = recorder.record(&key) {
+                            self.recorder = None;
+                            self.state.lock()?.status_message = e.status_message();
+                        }
+                    }
+                    return self.handle_key_event(key);
                 }
+                Event::Resize(..) => self.redraw.force(),
+                Event::FocusLost => self.set_focused(false),
+                Event::FocusGained => self.set_focused(true),
+                _ => {}
             }
         }
 
         Ok(false)
     }
 
+    /// Handle one key press (live or replayed) and show its effect right away
+    ///
+    /// Returns true if should quit
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        self.last_input = Instant::now();
+        self.redraw.force();
+        // Raw mode turns Ctrl+Z into a key; the loop suspends
+        if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.suspend_requested = true;
+            return Ok(false);
+        }
+        self.handle_key(key.code)
+    }
+
+    /// Was Ctrl+Z pressed since the last check? / هل ضُغط Ctrl+Z منذ آخر فحص؟
+    pub fn take_suspend_request(&mut self) -> bool {
+        std::mem::take(&mut self.suspend_requested)
+    }
+
+    /// Redraw everything on the next loop (after resume) / إعادة رسم كل شيء في الدورة التالية
+    pub fn force_redraw(&mut self) {
+        self.focused = true;
+        self.redraw.force();
+    }
+
+    /// Pause redraws while the terminal is unfocused / إيقاف الرسم مؤقتاً عند فقدان التركيز
+    pub fn pause_when_unfocused(&mut self, enabled: bool) {
+        self.pause_unfocused = enabled;
+    }
+
+    /// Track terminal focus; regaining it redraws immediately
+    /// تتبع تركيز الطرفية؛ استعادته تعيد الرسم فوراً
+    fn set_focused(&mut self, focused: bool) {
+        if focused && !self.focused {
+            self.redraw.force();
+        }
+        self.focused = focused;
+    }
+
+    /// Is a terminal redraw due at the configured refresh rate?
+    /// هل حان وقت إعادة رسم الواجهة حسب المعدل المضبوط؟
+    pub fn draw_due(&mut self) -> bool {
+        if self.pause_unfocused && !self.focused {
+            return false;
+        }
+        if let Ok(state_guard) = self.state.lock() {
+            self.redraw.set_fps(state_guard.ui_refresh_fps);
+        }
+        self.redraw.is_due(Instant::now())
+    }
+
+    /// Record a completed redraw, publish the achieved rate and check the latency budget
+    /// تسجيل إعادة رسم مكتملة ونشر المعدل الفعلي وفحص ميزانية زمن المعالجة
+    pub fn mark_drawn(&mut self, draw_time: Duration) {
+        let now = Instant::now();
+        self.redraw.mark_drawn(now);
+        if let Ok(mut state_guard) = self.state.lock() {
+            state_guard.ui_achieved_fps = self.redraw.achieved_fps();
+            state_guard.latency.record(Stage::Draw, draw_time, now);
+            let interval = state_guard.jitter.mean_interval_ms();
+            state_guard.latency.check_budget(interval, now);
+        }
+    }
+
     /// Handle a single key press
-    fn handle_key(&mut self, key: KeyCode) -> Result<bool, String> {
+    fn handle_key(&mut self, key: KeyCode) -> Result<bool> {
+        // Quit confirmation takes every key while open
+        if self.state.lock()?.confirm_quit {
+            return self.handle_confirm_quit_key(key);
+        }
+        // MAC table popup takes the arrows / Enter / Esc while open
+        if self.state.lock()?.show_mac_table {
+            return self.handle_mac_table_key(key);
+        }
+        if self.state.lock()?.interval_stats.is_some() {
+            return self.handle_interval_stats_key(key);
+        }
+        if self.state.lock()?.show_raw_inspector {
+            return self.handle_raw_inspector_key(key);
+        }
+        if self.state.lock()?.port_picker.is_some() {
+            return self.handle_port_picker_key(key);
+        }
+
         match key {
-            // Q - Quit
+            // Q - Quit (asks first while recording)
             KeyCode::Char('q') | KeyCode::Char('Q') => {
-                return Ok(true);
+                return self.request_quit();
             }
 
             // S - Start Serial
             KeyCode::Char('s') | KeyCode::Char('S') => {
-                // Stop playback mode first
+                // Stop playback / follow mode first
+                self.stop_follow()?;
                 {
-                    let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                    let mut state_guard = self.state.lock()?;
                     state_guard.stop_playback();
                 }
                 self.start_serial()?;
             }
 
-            // X - Stop Serial
+            // X - Stop Serial (and file following)
             KeyCode::Char('x') | KeyCode::Char('X') => {
                 self.stop_serial();
+                self.stop_follow()?;
                 // Also stop playback
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 state_guard.stop_playback();
             }
 
@@ -96,9 +331,303 @@ impl App {
                 self.load_csv()?;
             }
 
+            // T - Follow a growing CSV file (tail -f)
+            KeyCode::Char('t') | KeyCode::Char('T') => {
+                self.pick_and_follow_csv()?;
+            }
+
+            // J - Export the loaded or live frames as JSON
+            KeyCode::Char('j') | KeyCode::Char('J') => {
+                self.export_json()?;
+            }
+
+            // F2 - Raw data inspector
+            KeyCode::F(2) => {
+                self.state.lock()?.show_raw_inspector = true;
+            }
+
+            // F3 - Freeze the charts; receiving and logging continue
+            KeyCode::F(3) => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = if state_guard.toggle_view_frozen() {
+                    "❄️ View frozen (receiving continues)".to_string()
+                } else {
+                    "▶️ View live".to_string()
+                };
+            }
+
+            // F4 - Start a fresh measurement period
+            KeyCode::F(4) => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.reset_session_stats();
+                state_guard.status_message = "⏱️ Session statistics reset".to_string();
+            }
+
+            // F5 - Reload last CSV
+            KeyCode::F(5) => {
+                self.reload_csv()?;
+            }
+
+            // F6 - Toggle reopening a lost serial port
+            KeyCode::F(6) => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.auto_reconnect = !state_guard.auto_reconnect;
+                state_guard.status_message = if state_guard.auto_reconnect {
+                    "🔁 Auto-reconnect on".to_string()
+                } else {
+                    "⏹️ Auto-reconnect off: a lost port stops the reader".to_string()
+                };
+            }
+
+            // F7 - Pick the serial port for the next connection
+            KeyCode::F(7) => {
+                let ports = crate::serial_reader::list_ports();
+                self.state.lock()?.port_picker = Some(PortPicker::new(ports));
+            }
+
+            // F8 - Cycle the baud rate for the next connection
+            KeyCode::F(8) => {
+                let running = self.serial_reader.is_some();
+                let mut state_guard = self.state.lock()?;
+                state_guard.cycle_baud_rate();
+                state_guard.status_message = if !running {
+                    format!("⚡ Baud rate: {}", state_guard.baud_rate)
+                } else {
+                    format!("⚡ Baud rate: {} - press S to reconnect", state_guard.baud_rate)
+                };
+            }
+
+            // W - Start / stop CSV recording / بدء أو إيقاف تسجيل CSV
+            KeyCode::Char('w') | KeyCode::Char('W') => {
+                let live = self.serial_reader.is_some() || self.network_reader.is_some();
+                let mut state_guard = self.state.lock()?;
+                if let Some(path) = state_guard.stop_recording() {
+                    state_guard.record_csv = false;
+                    state_guard.status_message = format!("⏹️ Recording stopped: {}", path.display());
+                } else if live {
+                    // A new timestamped file while the session runs / ملف جديد بطابع زمني أثناء الجلسة
+                    state_guard.record_csv = true;
+                    drop(state_guard);
+                    if let Some(path) = crate::serial_reader::open_recording(&self.state) {
+                        self.state.lock()?.status_message = format!("⏺️ Recording to {}", path.display());
+                    }
+                } else {
+                    state_guard.record_csv = !state_guard.record_csv;
+                    state_guard.status_message = if state_guard.record_csv {
+                        "⏺️ Recording starts with the next session".to_string()
+                    } else {
+                        "⏹️ Recording off".to_string()
+                    };
+                }
+            }
+
+            // C - Capture calibration baseline
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = if state_guard.capture_baseline() {
+                    "📐 Baseline captured".to_string()
+                } else {
+                    "⚠️ No frames to capture a baseline from".to_string()
+                };
+            }
+
+            // D - Toggle baseline delta display
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.show_baseline_delta = !state_guard.show_baseline_delta;
+            }
+
+            // +/- - Zoom live CSI chart window
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') => {
+                let mut state_guard = self.state.lock()?;
+                if !state_guard.playback_mode {
+                    state_guard.zoom_live_chart(key != KeyCode::Char('-'));
+                    state_guard.status_message = format!("🔍 Chart window: {} samples",
+                        state_guard.live_chart_samples
+                    );
+                }
+            }
+
+            // ; / ' - Less / more smoothing on the magnitude chart
+            KeyCode::Char(';') | KeyCode::Char('\'') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.step_smoothing(key == KeyCode::Char('\''));
+                state_guard.status_message = match state_guard.smoothing_window {
+                    1 => "〰️ Chart smoothing off".to_string(),
+                    n => format!("〰️ Chart smoothing: {}-point average", n),
+                };
+            }
+
+            // </> - Shrink / grow the live frame window
+            KeyCode::Char('<') | KeyCode::Char(',') | KeyCode::Char('>') | KeyCode::Char('.') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.step_window(matches!(key, KeyCode::Char('>') | KeyCode::Char('.')));
+                state_guard.status_message = format!("🪟 Frame window: {}s",
+                    state_guard.window_secs
+                );
+            }
+
+            // V - Cycle magnitude / dynamic component / spectrum / stability chart
+            KeyCode::Char('v') | KeyCode::Char('V') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.toggle_chart_mode();
+                state_guard.status_message = match state_guard.chart_mode {
+                    ChartMode::Magnitude => "📈 Chart: magnitude".to_string(),
+                    ChartMode::Dynamic => "📈 Chart: dynamic component (minus rolling median)".to_string(),
+                    ChartMode::Spectrum => "📊 Chart: spectrum - press A to average frames".to_string(),
+                    ChartMode::Stability => "📶 Chart: subcarrier stability (std dev over the window)".to_string(),
+                };
+            }
+
+            // Y - Lock / unlock the CSI chart Y bounds
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = if state_guard.toggle_bounds_lock() {
+                    let (min, max) = state_guard.chart_auto_bounds;
+                    format!("🔒 Chart scale locked at {:.1} .. {:.1}", min, max)
+                } else {
+                    "🔓 Chart scale: auto".to_string()
+                };
+            }
+
+            // A - Cycle spectrum averaging (off / 8 / 16 / 32)
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.cycle_spectrum_average();
+                state_guard.status_message = if state_guard.spectrum_averaging {
+                    format!("📊 Spectrum averaging: {} frames", state_guard.spectrum.window())
+                } else {
+                    "📊 Spectrum averaging: off".to_string()
+                };
+            }
+
+            // ~ - Overlay the last frame on the averaged spectrum
+            KeyCode::Char('~') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.spectrum_overlay = !state_guard.spectrum_overlay;
+                state_guard.status_message = format!(
+                    "📊 Spectrum last-frame overlay: {}",
+                    if state_guard.spectrum_overlay { "on" } else { "off" }
+                );
+            }
+
+            // O - Toggle the compact status layout
+            KeyCode::Char('o') | KeyCode::Char('O') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.compact_status = !state_guard.compact_status;
+                state_guard.status_message = if state_guard.compact_status {
+                    "📐 Compact status layout".to_string()
+                } else {
+                    "📐 Full status layout".to_string()
+                };
+            }
+
+            // K - Cycle the CSI format (auto / forced Real/Imag / forced amplitude)
+            KeyCode::Char('k') | KeyCode::Char('K') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.cycle_forced_format();
+                state_guard.status_message = format!("🧮 CSI format: {}", state_guard.format_mode_label());
+            }
+
+            // h/H - Chart the next/previous subcarrier (or the average)
+            KeyCode::Char('h') | KeyCode::Char('H') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.step_selected_subcarrier(key == KeyCode::Char('h'));
+                state_guard.status_message = format!("📈 Magnitude chart: {}", state_guard.subcarrier_label());
+            }
+
+            // N - Cycle the plotted and analyzed stream
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.cycle_stream();
+                state_guard.status_message = format!("🔀 {}", state_guard.stream_label());
+            }
+
+            // Z - Zero the serial block counters
+            KeyCode::Char('z') | KeyCode::Char('Z') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.reset_parse_stats();
+                state_guard.status_message = "🧮 Block counters reset".to_string();
+            }
+
+            // U - Write a report bundle for filing an issue
+            KeyCode::Char('u') | KeyCode::Char('U') => {
+                let mut state_guard = self.state.lock()?;
+                let options = state_guard.report_options;
+                state_guard.status_message = match write_report(Path::new("."), &state_guard, &options) {
+                    Ok(dir) => format!("🧾 Report saved to {}", dir.display()),
+                    Err(e) => e.status_message(),
+                };
+            }
+
+            // G - Cycle magnitude aggregation (mean / median / trimmed mean)
+            KeyCode::Char('g') | KeyCode::Char('G') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.cycle_aggregation();
+                state_guard.status_message = format!("📊 Aggregation: {}",
+                    state_guard.aggregation.label()
+                );
+            }
+
+            // F - Cycle the UI refresh rate
+            KeyCode::Char('f') | KeyCode::Char('F') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.cycle_refresh_rate();
+                state_guard.status_message = format!("🖥️ UI refresh: {} fps",
+                    state_guard.ui_refresh_fps
+                );
+            }
+
+            // P - Cycle the detector shown in the statistics panel
+            KeyCode::Char('p') | KeyCode::Char('P') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.primary_detector = state_guard.primary_detector.next();
+                state_guard.status_message = format!("📊 Analysis range: {} detector",
+                    state_guard.primary_detector.label()
+                );
+            }
+
+            // ( / ) - Narrow / widen the primary detector's analysis range
+            KeyCode::Char('(') | KeyCode::Char(')') => {
+                let mut state_guard = self.state.lock()?;
+                let primary = state_guard.primary_detector;
+                state_guard.detector_windows.ratios.step(primary, key == KeyCode::Char(')'));
+                state_guard.status_message = format!("📊 {} range: {:.0}% of subcarriers",
+                    primary.label(),
+                    state_guard.detector_windows.ratios.get(primary) * 100.0
+                );
+            }
+
+            // M - Open the per-MAC table to pick a filter target
+            KeyCode::Char('m') | KeyCode::Char('M') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.mac_stats.prune(chrono::Utc::now().timestamp_millis());
+                state_guard.mac_table_selected = 0;
+                state_guard.show_mac_table = true;
+            }
+
+            // E - Toggle event log auto-scroll (tail / review)
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.event_log.toggle_auto_scroll();
+                state_guard.status_message = if state_guard.event_log.auto_scroll() {
+                    "📜 Event log: following newest".to_string()
+                } else {
+                    "📜 Event log: review (PgUp/PgDn to scroll)".to_string()
+                };
+            }
+
+            // PageUp / PageDown - Scroll the event log
+            KeyCode::PageUp => {
+                self.state.lock()?.event_log.scroll_by(-EVENT_LOG_SCROLL_ROWS);
+            }
+            KeyCode::PageDown => {
+                self.state.lock()?.event_log.scroll_by(EVENT_LOG_SCROLL_ROWS);
+            }
+
             // Space - Play/Pause playback
             KeyCode::Char(' ') => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 state_guard.toggle_playback();
                 let status = if state_guard.playback_playing { "▶️ Playing" } else { "⏸️ Paused" };
                 state_guard.status_message = format!("{} - {:.1}s / {:.1}s", 
@@ -110,7 +639,7 @@ impl App {
 
             // Left Arrow - Seek backward 5 seconds
             KeyCode::Left => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     state_guard.seek_by_seconds(-5.0);
                     state_guard.status_message = format!("⏪ Seek: {:.1}s / {:.1}s",
@@ -122,7 +651,7 @@ impl App {
 
             // Right Arrow - Seek forward 5 seconds
             KeyCode::Right => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     state_guard.seek_by_seconds(5.0);
                     state_guard.status_message = format!("⏩ Seek: {:.1}s / {:.1}s",
@@ -134,7 +663,7 @@ impl App {
 
             // Up Arrow - Seek backward 30 seconds
             KeyCode::Up => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     state_guard.seek_by_seconds(-30.0);
                     state_guard.status_message = format!("⏪⏪ Seek: {:.1}s / {:.1}s",
@@ -146,7 +675,7 @@ impl App {
 
             // Down Arrow - Seek forward 30 seconds
             KeyCode::Down => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     state_guard.seek_by_seconds(30.0);
                     state_guard.status_message = format!("⏩⏩ Seek: {:.1}s / {:.1}s",
@@ -158,7 +687,7 @@ impl App {
 
             // Home - Go to start
             KeyCode::Home => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     state_guard.seek_to_second(0.0);
                     state_guard.status_message = "⏮️ Start".to_string();
@@ -167,7 +696,7 @@ impl App {
 
             // End - Go to end
             KeyCode::End => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     let duration = state_guard.playback_duration_secs;
                     state_guard.seek_to_second(duration);
@@ -177,7 +706,7 @@ impl App {
 
             // R - Restart playback
             KeyCode::Char('r') | KeyCode::Char('R') => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     state_guard.seek_to_second(0.0);
                     state_guard.playback_playing = true;
@@ -187,39 +716,190 @@ impl App {
 
             // B - Back to Live Mode
             KeyCode::Char('b') | KeyCode::Char('B') => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+                let mut state_guard = self.state.lock()?;
                 if state_guard.playback_mode {
                     // Exit playback mode
                     state_guard.playback_mode = false;
                     state_guard.playback_playing = false;
                     state_guard.loaded_frames.clear();
                     state_guard.playback_position = 0;
-                    state_guard.status_message = "📡 Live Mode - Press C to connect".to_string();
+                    state_guard.status_message = "📡 Live Mode - Press S to connect".to_string();
                 }
             }
 
-            // Escape - Quit
+            // [ / ] - Halve / double the playback speed
+            KeyCode::Char('[') | KeyCode::Char(']') => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.step_playback_speed(key == KeyCode::Char(']'));
+                state_guard.status_message = format!("⏩ Playback speed: {}x", state_guard.playback_speed);
+            }
+
+            // { / } - Set the A / B marker at the current playback frame
+            KeyCode::Char('{') | KeyCode::Char('}') => {
+                let mut state_guard = self.state.lock()?;
+                let is_a = key == KeyCode::Char('{');
+                if let Some(second) = state_guard.set_marker(is_a) {
+                    state_guard.status_message = format!("📍 Marker {} at {:.1}s",
+                        if is_a { "A" } else { "B" },
+                        second
+                    );
+                }
+            }
+
+            // I - Statistics for the A-B interval
+            KeyCode::Char('i') | KeyCode::Char('I') => {
+                let mut state_guard = self.state.lock()?;
+                if state_guard.playback_mode && !state_guard.compute_interval_stats() {
+                    state_guard.status_message = "⚠️ Set markers A ({) and B (}) first".to_string();
+                }
+            }
+
+            // Escape - Quit (asks first while recording)
             KeyCode::Esc => {
-                return Ok(true);
+                return self.request_quit();
+            }
+
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    /// Quit now, or open the confirmation while a CSV recording is active
+    fn request_quit(&mut self) -> Result<bool> {
+        let mut state_guard = self.state.lock()?;
+        if state_guard.csv_logger.is_none() {
+            return Ok(true);
+        }
+        state_guard.confirm_quit = true;
+        Ok(false)
+    }
+
+    /// Handle a key press while the quit confirmation is open; only Y quits
+    fn handle_confirm_quit_key(&mut self, key: KeyCode) -> Result<bool> {
+        let mut state_guard = self.state.lock()?;
+        state_guard.confirm_quit = false;
+        if matches!(key, KeyCode::Char('y') | KeyCode::Char('Y')) {
+            // Close the recording before the readers go away / إغلاق التسجيل قبل إيقاف القراء
+            state_guard.stop_recording();
+            return Ok(true);
+        }
+        state_guard.status_message = "▶️ Quit cancelled, still recording".to_string();
+        Ok(false)
+    }
+
+    /// Handle a key press while the MAC table popup is open
+    fn handle_mac_table_key(&mut self, key: KeyCode) -> Result<bool> {
+        let mut state_guard = self.state.lock()?;
+        match key {
+            KeyCode::Up => state_guard.move_mac_selection(-1),
+            KeyCode::Down => state_guard.move_mac_selection(1),
+            KeyCode::Enter => {
+                state_guard.select_mac_filter();
+                state_guard.show_mac_table = false;
+                state_guard.status_message = match &state_guard.mac_filter {
+                    Some(mac) => format!("🎯 MAC filter: {}", mac),
+                    None => "🎯 MAC filter cleared".to_string(),
+                };
+            }
+            KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('M') => {
+                state_guard.show_mac_table = false;
             }
+            _ => {}
+        }
+
+        Ok(false)
+    }
 
+    /// Handle a key press while the raw data inspector is open
+    fn handle_raw_inspector_key(&mut self, key: KeyCode) -> Result<bool> {
+        let mut state_guard = self.state.lock()?;
+        match key {
+            KeyCode::Char('x') | KeyCode::Char('X') => {
+                state_guard.raw_hex = !state_guard.raw_hex;
+            }
+            KeyCode::Esc | KeyCode::F(2) => {
+                state_guard.show_raw_inspector = false;
+            }
             _ => {}
         }
 
         Ok(false)
     }
 
-    /// Start the serial reader
-    fn start_serial(&mut self) -> Result<(), String> {
+    /// Handle a key press while the serial port picker is open
+    fn handle_port_picker_key(&mut self, key: KeyCode) -> Result<bool> {
+        let mut state_guard = self.state.lock()?;
+        let Some(picker) = state_guard.port_picker.as_mut() else { return Ok(false); };
+        match key {
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            KeyCode::Enter => {
+                if let Some(port) = state_guard.choose_port() {
+                    state_guard.status_message = format!("🔌 Port {} selected - press S to connect", port);
+                }
+            }
+            KeyCode::Esc | KeyCode::F(7) => {
+                state_guard.port_picker = None;
+            }
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    /// Handle a key press while the A-B statistics popup is open
+    fn handle_interval_stats_key(&mut self, key: KeyCode) -> Result<bool> {
+        let mut state_guard = self.state.lock()?;
+        match key {
+            KeyCode::Char('w') | KeyCode::Char('W') => {
+                if let Some(stats) = &state_guard.interval_stats {
+                    state_guard.status_message = match stats.save_with_timestamp() {
+                        Ok(path) => format!("💾 Interval stats saved to {}", path.display()),
+                        Err(e) => e.status_message(),
+                    };
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('i') | KeyCode::Char('I') => {
+                state_guard.interval_stats = None;
+            }
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    /// Start the serial reader, or the UDP reader when `--udp` was given
+    fn start_serial(&mut self) -> Result<()> {
         // Stop existing reader if any
         self.stop_serial();
+        self.start_sources()?;
+
+        if let Some(addr) = self.state.lock()?.udp_source.clone() {
+            // A busy or bad address is shown, not fatal / العنوان المشغول أو الخاطئ يُعرض ولا يوقف البرنامج
+            let mut reader = NetworkReader::new(self.state.clone(), &addr).with_queue(self.frames_out.clone());
+            match reader.start() {
+                Ok(()) => self.network_reader = Some(reader),
+                Err(e) => self.state.lock()?.status_message = e.status_message(),
+            }
+            return Ok(());
+        }
 
         // Create and start new reader
-        let mut reader = SerialReader::new(self.state.clone());
+        let (binary, chosen_port) = {
+            let state_guard = self.state.lock()?;
+            (state_guard.binary_serial, state_guard.port_chosen.then(|| state_guard.port_name.clone()))
+        };
+        let mut reader = SerialReader::new(self.state.clone())
+            .with_binary(binary)
+            .with_queue(self.frames_out.clone());
+        if let Some(port) = chosen_port {
+            reader = reader.with_port(&port);
+        }
         
         if let Err(e) = reader.start() {
-            let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
-            state_guard.status_message = format!("❌ {}", e);
+            let mut state_guard = self.state.lock()?;
+            state_guard.status_message = e.status_message();
             return Err(e);
         }
 
@@ -227,53 +907,307 @@ impl App {
         Ok(())
     }
 
-    /// Stop the serial reader
+    /// Start one reader per named source / بدء قارئ لكل مصدر مسمى
+    ///
+    /// A source that fails to start shows its error; the others still run.
+    fn start_sources(&mut self) -> Result<()> {
+        let (binary, sources): (bool, Vec<(String, String)>) = {
+            let state_guard = self.state.lock()?;
+            let sources = state_guard.sources.iter().map(|s| (s.name.clone(), s.port.clone())).collect();
+            (state_guard.binary_serial, sources)
+        };
+        for (name, port) in sources {
+            let mut reader = SerialReader::new(self.state.clone())
+                .with_binary(binary)
+                .with_source(&name, &port)
+                .with_queue(self.frames_out.clone());
+            match reader.start() {
+                Ok(()) => self.source_readers.push(reader),
+                Err(e) => self.state.lock()?.status_message = e.status_message(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop the serial (or UDP) reader and the named sources
     fn stop_serial(&mut self) {
+        for mut reader in self.source_readers.drain(..) {
+            reader.stop();
+        }
         if let Some(ref mut reader) = self.serial_reader {
             reader.stop();
         }
         self.serial_reader = None;
+        if let Some(ref mut reader) = self.network_reader {
+            reader.stop();
+        }
+        self.network_reader = None;
     }
 
     /// Load CSV file
-    fn load_csv(&mut self) -> Result<(), String> {
-        // Stop serial reader if running
+    fn load_csv(&mut self) -> Result<()> {
+        // Stop serial reader / file following if running
         self.stop_serial();
+        self.stop_follow()?;
+
+        if !self.dialogs {
+            self.state.lock()?.status_message = CsiError::NoFileSelected.status_message();
+            return Ok(());
+        }
 
         // Show loading message
         {
-            let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+            let mut state_guard = self.state.lock()?;
             state_guard.status_message = "📂 Opening file dialog...".to_string();
         }
 
         // Pick and load CSV file
         match pick_and_load_csv(&self.state) {
-            Ok(count) => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
-                state_guard.status_message = format!("✅ Loaded {} frames from CSV", count);
+            Ok(summary) => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = load_message("✅ Loaded", &summary);
+                drop(state_guard);
+                self.offer_recorded_config()?;
             }
             Err(e) => {
-                let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
-                state_guard.status_message = format!("❌ {}", e);
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = e.status_message();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Offer the detector settings saved with the loaded recording
+    fn offer_recorded_config(&mut self) -> Result<()> {
+        let Some(path) = self.state.lock()?.last_csv_path.clone() else {
+            return Ok(());
+        };
+        let config = match DetectorConfig::load_sidecar(&path) {
+            None => return Ok(()),
+            Some(Ok(config)) => config,
+            Some(Err(e)) => {
+                self.state.lock()?.status_message = e.status_message();
+                return Ok(());
             }
+        };
+        let current = DetectorConfig::from_state(&*self.state.lock()?);
+        if config == current {
+            return Ok(());
         }
 
+        let answer = rfd::MessageDialog::new()
+            .set_title("Recorded Detector Settings")
+            .set_description("This recording was made with different detector settings. Apply them?")
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show();
+        if answer == rfd::MessageDialogResult::Yes {
+            let mut state_guard = self.state.lock()?;
+            config.apply(&mut state_guard);
+            state_guard.status_message = "⚙️ Applied the recording's detector settings".to_string();
+        }
+        Ok(())
+    }
+
+    /// Save the loaded (or else live) frames to a JSON file picked in a dialog
+    fn export_json(&mut self) -> Result<()> {
+        if !self.dialogs {
+            self.state.lock()?.status_message = CsiError::NoFileSelected.status_message();
+            return Ok(());
+        }
+
+        let file = rfd::FileDialog::new()
+            .add_filter("JSON Files", &["json"])
+            .set_title("Export CSI Frames as JSON")
+            .set_file_name(format!("csi_{}.json", chrono::Utc::now().format("%Y%m%d_%H%M%S")))
+            .save_file();
+
+        let mut state_guard = self.state.lock()?;
+        state_guard.status_message = match file {
+            Some(path) => {
+                let frames = if state_guard.loaded_frames.is_empty() {
+                    state_guard.frames.as_slice()
+                } else {
+                    &state_guard.loaded_frames
+                };
+                match export_json(frames, &path) {
+                    Ok(count) => format!("💾 Exported {} frames to {}", count, path.display()),
+                    Err(e) => e.status_message(),
+                }
+            }
+            None => CsiError::NoFileSelected.status_message(),
+        };
+        Ok(())
+    }
+
+    /// Reload the last loaded CSV file
+    fn reload_csv(&mut self) -> Result<()> {
+        // Stop serial reader if running
+        self.stop_serial();
+
+        match reload_last_csv(&self.state) {
+            Ok(summary) => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = load_message("🔄 Reloaded", &summary);
+            }
+            Err(e) => {
+                let mut state_guard = self.state.lock()?;
+                state_guard.status_message = e.status_message();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pick a CSV file and follow it as live data
+    fn pick_and_follow_csv(&mut self) -> Result<()> {
+        if !self.dialogs {
+            self.state.lock()?.status_message = CsiError::NoFileSelected.status_message();
+            return Ok(());
+        }
+
+        let file = rfd::FileDialog::new()
+            .add_filter("CSV Files", &["csv"])
+            .set_title("Select CSI CSV File to Follow")
+            .pick_file();
+
+        match file {
+            Some(path) => self.follow_csv(&path),
+            None => {
+                self.state.lock()?.status_message = CsiError::NoFileSelected.status_message();
+                Ok(())
+            }
+        }
+    }
+
+    /// Follow a growing CSV file, pushing rows through the live pipeline
+    /// متابعة ملف CSV متنامٍ وتمرير الصفوف عبر مسار البث المباشر
+    pub fn follow_csv(&mut self, path: &Path) -> Result<()> {
+        self.stop_serial();
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut state_guard = self.state.lock()?;
+        state_guard.stop_playback();
+        state_guard.loaded_frames.clear();
+
+        match CsvFollower::open(path, now) {
+            Ok((follower, frames)) => {
+                // Only the live window would survive anyway
+                // نافذة البث فقط تبقى على أي حال
+                state_guard.clear_frames();
+                state_guard.window_clock = WindowClock::NewestFrame;
+                let newest = frames.last().map_or(0, |f| f.timestamp);
+                let cutoff = newest - state_guard.window_ms();
+                for frame in frames.into_iter().filter(|f| f.timestamp > cutoff) {
+                    state_guard.push_frame(frame);
+                }
+                state_guard.status_message = follower.status(now);
+                self.follower = Some(follower);
+            }
+            Err(e) => state_guard.status_message = e.status_message(),
+        }
+
+        Ok(())
+    }
+
+    /// Read rows appended to the followed file
+    /// قراءة الصفوف المضافة إلى الملف المتابع
+    ///
+    /// The status line only changes when rows arrive or the file is reopened,
+    /// so other messages are not overwritten every tick.
+    fn poll_follow(&mut self) -> Result<()> {
+        let Some(follower) = self.follower.as_mut() else { return Ok(()) };
+        let now = chrono::Utc::now().timestamp_millis();
+
+        match follower.poll(now) {
+            Ok(update) => {
+                if update.frames.is_empty() && update.reopened.is_none() {
+                    return Ok(());
+                }
+                let mut state_guard = self.state.lock()?;
+                if update.reopened.is_some() {
+                    state_guard.clear_frames();
+                }
+                for frame in update.frames {
+                    state_guard.push_frame(frame);
+                }
+                state_guard.status_message = follower.status(now);
+            }
+            Err(e) => {
+                self.stop_follow()?;
+                self.state.lock()?.status_message = e.status_message();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stop following a file; the live window goes back to the wall clock
+    /// إيقاف متابعة الملف؛ نافذة البث تعود إلى ساعة الحائط
+    fn stop_follow(&mut self) -> Result<()> {
+        if self.follower.take().is_some() {
+            self.state.lock()?.window_clock = WindowClock::Wall;
+        }
         Ok(())
     }
 
     /// Run detection algorithms on current frames
-    pub fn run_detectors(&mut self) -> Result<(), String> {
-        let mut state_guard = self.state.lock().map_err(|e| e.to_string())?;
+    ///
+    /// Skipped when no new frame arrived, so slow idle ticks and fast
+    /// active ticks both add one history point per frame.
+    fn run_detectors(&mut self) -> Result<()> {
+        let mut state_guard = self.state.lock()?;
+        
+        // Named sources keep their own detections / المصادر المسماة تحتفظ بنتائجها
+        let state = &mut *state_guard;
+        state.sources.run_detectors(state.aggregation, &state.detector_windows);
+        
+        if self.last_detect_seq == Some(state_guard.frame_seq) {
+            return Ok(());
+        }
+        let new_frames = self.last_detect_seq.map_or(state_guard.frame_seq, |seq| state_guard.frame_seq.saturating_sub(seq));
+        self.last_detect_seq = Some(state_guard.frame_seq);
         
         // Run detectors on all frames
-        let results = quick_detect(&state_guard.frames);
+        let detect_started = Instant::now();
+        let (results, readiness) = quick_detect_with(
+            &state_guard.detection_frames(),
+            state_guard.aggregation,
+            &state_guard.detector_windows,
+        );
+        state_guard.readiness = readiness;
+        let done = Instant::now();
+        state_guard.latency.record(Stage::Detect, done - detect_started, done);
         
-        // Update detection results
-        state_guard.detections = results;
+        // Update detection results and log transitions
+        let now = chrono::Utc::now().timestamp_millis();
+        let previous = std::mem::replace(&mut state_guard.detections, results);
+        state_guard.log_detection_changes(&previous, now);
         
         // Update history for charts
         state_guard.update_detection_history();
 
+        // Persist the sample if detection logging is enabled
+        state_guard.log_detection_sample(now);
+
+        // Export one feature row per frame seen since the last run
+        // تصدير صف خصائص لكل إطار منذ آخر تشغيل
+        if state_guard.feature_logger.is_some() {
+            let state = &mut *state_guard;
+            let ratio = state.detector_windows.ratios.get(state.primary_detector);
+            let start = state.frames.len().saturating_sub(new_frames as usize);
+            let rows: Vec<FrameFeatures> = state.frames[start..]
+                .iter()
+                .map(|f| FrameFeatures::compute(f, ratio, &state.detections))
+                .collect();
+            if let Some(ref mut logger) = state.feature_logger {
+                if let Err(e) = rows.iter().try_for_each(|row| logger.log_features(row)) {
+                    state.feature_logger = None;
+                    state.status_message = e.status_message();
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -284,15 +1218,384 @@ impl App {
 
         // Flush CSV logger if exists
         if let Ok(mut state_guard) = self.state.lock() {
-            if let Some(ref mut logger) = state_guard.csv_logger {
+            if let Some(logger) = &state_guard.csv_logger {
+                logger.flush();
+            }
+            if let Some(ref mut logger) = state_guard.detection_logger {
+                let _ = logger.flush();
+            }
+            if let Some(ref mut logger) = state_guard.feature_logger {
                 let _ = logger.flush();
             }
         }
     }
 }
 
+/// Poll timeout: fast while busy or just after input, slow when idle
+/// مهلة الانتظار: سريعة أثناء النشاط أو بعد الإدخال، بطيئة عند الخمول
+fn tick_timeout(busy: bool, since_input: Duration) -> Duration {
+    if busy || since_input < Duration::from_millis(INPUT_GRACE_MS) {
+        Duration::from_millis(ACTIVE_TICK_MS)
+    } else {
+        Duration::from_millis(IDLE_TICK_MS)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Redraw Scheduling / جدولة إعادة الرسم
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Caps terminal redraws independently of the input poll rate
+/// يحد إعادة رسم الواجهة بشكل مستقل عن معدل فحص الإدخال
+///
+/// Key presses and resizes force a redraw so input never waits for the next slot.
+struct RedrawScheduler {
+    /// Minimum time between redraws / أقل مدة بين مرات الرسم
+    interval: Duration,
+
+    /// Time of the last redraw / وقت آخر رسم
+    last_draw: Option<Instant>,
+
+    /// Redraw on the next check regardless of the rate / الرسم في الفحص التالي مهما كان المعدل
+    forced: bool,
+
+    /// Redraws in the last second / مرات الرسم في آخر ثانية
+    recent: VecDeque<Instant>,
+}
+
+impl RedrawScheduler {
+    fn new(fps: u32) -> Self {
+        let mut scheduler = Self {
+            interval: Duration::ZERO,
+            last_draw: None,
+            forced: false,
+            recent: VecDeque::new(),
+        };
+        scheduler.set_fps(fps);
+        scheduler
+    }
+
+    fn set_fps(&mut self, fps: u32) {
+        let (min, max) = (REFRESH_RATE_STEPS[0], REFRESH_RATE_STEPS[REFRESH_RATE_STEPS.len() - 1]);
+        self.interval = Duration::from_secs(1) / fps.clamp(min, max);
+    }
+
+    fn force(&mut self) {
+        self.forced = true;
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.forced || self.last_draw.is_none_or(|last| now.duration_since(last) >= self.interval)
+    }
+
+    fn mark_drawn(&mut self, now: Instant) {
+        self.forced = false;
+        self.last_draw = Some(now);
+        self.recent.push_back(now);
+        while self.recent.front().is_some_and(|&t| now.duration_since(t) >= Duration::from_secs(1)) {
+            self.recent.pop_front();
+        }
+    }
+
+    fn achieved_fps(&self) -> f64 {
+        self.recent.len() as f64
+    }
+}
+
 impl Drop for App {
     fn drop(&mut self) {
         self.cleanup();
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 File Dialog / نافذة اختيار الملف
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Status line for a finished CSV load, noting the rows left out
+/// سطر الحالة بعد تحميل CSV مع ذكر الصفوف المتروكة
+fn load_message(done: &str, summary: &LoadSummary) -> String {
+    let mut message = format!("{} {} frames from CSV", done, summary.frames);
+    if let Some(first) = summary.skipped.first() {
+        message.push_str(&format!(
+            " - ⚠️ skipped {} bad rows (line {}: {})",
+            summary.skipped.len(),
+            first.line,
+            first.reason
+        ));
+    }
+    message
+}
+
+/// Open file dialog and load CSV (uses rfd crate)
+/// فتح نافذة اختيار الملف وتحميل CSV (يستخدم مكتبة rfd)
+fn pick_and_load_csv(state: &SharedState) -> Result<LoadSummary> {
+    // Use rfd for file dialog / استخدام rfd لنافذة الملفات
+    let file = rfd::FileDialog::new()
+        .add_filter("CSV Files", &["csv", "csv.gz", "gz"])
+        .add_filter("All Files", &["*"])
+        .set_title("Select CSI CSV File")
+        .pick_file();
+    
+    match file {
+        Some(path) => {
+            let mut loader = CsvLoader::new();
+            loader.load_into_state(&path, state)
+        }
+        None => Err(CsiError::NoFileSelected),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_timeout() {
+        let idle = Duration::from_secs(10);
+        assert_eq!(tick_timeout(true, idle), Duration::from_millis(ACTIVE_TICK_MS));
+        assert_eq!(tick_timeout(false, idle), Duration::from_millis(IDLE_TICK_MS));
+        // Snap back right after a key press / العودة فوراً بعد ضغطة مفتاح
+        assert_eq!(tick_timeout(false, Duration::ZERO), Duration::from_millis(ACTIVE_TICK_MS));
+    }
+
+    #[test]
+    fn test_redraw_rate_independent_of_input() {
+        let start = Instant::now();
+        let mut redraw = RedrawScheduler::new(2);
+        assert!(redraw.is_due(start));
+        redraw.mark_drawn(start);
+
+        // 2 fps: next slot after 500 ms / المعدل 2: الفترة التالية بعد 500 مللي ثانية
+        let at = |ms| start + Duration::from_millis(ms);
+        assert!(!redraw.is_due(at(100)));
+        assert!(redraw.is_due(at(500)));
+
+        // A key press still polls fast and draws immediately
+        // ضغطة مفتاح لا تزال تفحص بسرعة وترسم فوراً
+        assert_eq!(tick_timeout(false, Duration::ZERO), Duration::from_millis(ACTIVE_TICK_MS));
+        redraw.force();
+        assert!(redraw.is_due(at(100)));
+        redraw.mark_drawn(at(100));
+        assert!(!redraw.is_due(at(200)));
+
+        // Achieved rate counts draws in the last second / المعدل الفعلي يعد مرات الرسم في آخر ثانية
+        redraw.mark_drawn(at(600));
+        assert_eq!(redraw.achieved_fps(), 3.0);
+        redraw.mark_drawn(at(1_500));
+        assert_eq!(redraw.achieved_fps(), 2.0);
+
+        // Out-of-range rates are clamped / المعدلات خارج النطاق تحصر
+        redraw.set_fps(1_000);
+        assert_eq!(redraw.interval, Duration::from_secs(1) / 30);
+    }
+
+    #[test]
+    fn test_queued_frames_stored_on_tick() {
+        use csi_tui::frame_queue::ReceivedFrame;
+        use csi_tui::parser::CsiParser;
+        use csi_tui::state::CsiMeta;
+
+        let state = csi_tui::state::create_shared_state();
+        state.lock().unwrap().drop_duplicate_frames = true;
+        let mut app = App::new(state.clone());
+        let block = format!("[{}]", vec!["10,-5"; 16].join(","));
+        let parsed = CsiParser::new().parse(&block).unwrap();
+        for _ in 0..2 {
+            let frame = ReceivedFrame::new(parsed.clone(), CsiMeta::default(), None, None, Duration::ZERO);
+            assert!(app.frames_out.send(frame.counted().deduped()));
+        }
+        assert!(state.lock().unwrap().frames.is_empty());
+
+        // One tick stores both; the repeat is dropped there / نبضة واحدة تحفظهما والتكرار يُحذف
+        app.tick(0.0).unwrap();
+        let s = state.lock().unwrap();
+        assert_eq!(s.frames.len(), 1);
+        assert_eq!(s.parse_stats.parsed, 2);
+        assert_eq!((s.duplicates_dropped, s.queue_dropped), (1, 0));
+    }
+
+    #[test]
+    fn test_follow_status_only_on_new_rows() {
+        use std::io::Write;
+
+        let path = std::path::PathBuf::from("test_app_follow_status.csv");
+        std::fs::write(&path, "timestamp,r0,i0\n1000,3,4\n").unwrap();
+        let state = csi_tui::state::create_shared_state();
+        let mut app = App::new(state.clone());
+        app.follow_csv(&path).unwrap();
+        assert_eq!(state.lock().unwrap().window_clock, WindowClock::NewestFrame);
+
+        // Nothing new: another message stays up / لا جديد: الرسالة الأخرى تبقى
+        state.lock().unwrap().status_message = "📌 kept".to_string();
+        std::thread::sleep(Duration::from_millis(csi_tui::csv_follower::FOLLOW_POLL_MS as u64 + 10));
+        app.poll_follow().unwrap();
+        assert_eq!(state.lock().unwrap().status_message, "📌 kept");
+
+        // A new row refreshes the follow status / صف جديد يحدث حالة المتابعة
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"1100,6,8\n").unwrap();
+        std::thread::sleep(Duration::from_millis(csi_tui::csv_follower::FOLLOW_POLL_MS as u64 + 10));
+        app.poll_follow().unwrap();
+        let guard = state.lock().unwrap();
+        assert!(guard.status_message.starts_with("👁 Following"));
+        assert_eq!(guard.frame_count(), 2);
+        drop(guard);
+
+        app.stop_follow().unwrap();
+        assert_eq!(state.lock().unwrap().window_clock, WindowClock::Wall);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_queue_drops_cleared_by_session_reset() {
+        use csi_tui::frame_queue::ReceivedFrame;
+        use csi_tui::parser::CsiParser;
+        use csi_tui::state::CsiMeta;
+
+        let state = csi_tui::state::create_shared_state();
+        let mut app = App::new(state.clone());
+        let parsed = CsiParser::new().parse(&format!("[{}]", vec!["10,-5"; 16].join(","))).unwrap();
+        let fill = |app: &App, n| {
+            for _ in 0..n {
+                app.frames_out.send(ReceivedFrame::new(parsed.clone(), CsiMeta::default(), None, None, Duration::ZERO));
+            }
+        };
+
+        fill(&app, FRAME_QUEUE_CAPACITY + 3);
+        app.tick(0.0).unwrap();
+        assert_eq!(state.lock().unwrap().queue_dropped, 3);
+
+        // F4 clears the count and later drains do not bring it back
+        // F4 يصفر العدد والتفريغات اللاحقة لا تعيده
+        app.handle_key_event(KeyEvent::from(KeyCode::F(4))).unwrap();
+        app.tick(0.0).unwrap();
+        assert_eq!(state.lock().unwrap().queue_dropped, 0);
+        fill(&app, FRAME_QUEUE_CAPACITY + 1);
+        app.tick(0.0).unwrap();
+        assert_eq!(state.lock().unwrap().queue_dropped, 1);
+    }
+
+    /// Tick times with a 200 fps reader, queued vs locking the state per frame
+    /// أزمنة النبضة مع قارئ بمعدل 200 إطار/ث، بالطابور مقابل القفل لكل إطار
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_tick_latency_at_200_fps() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+        use csi_tui::frame_queue::ReceivedFrame;
+        use csi_tui::parser::CsiParser;
+        use csi_tui::state::CsiMeta;
+        use crate::serial_reader::deliver;
+
+        let block = format!("[{}]", (0..128).map(|i| ((i * 37) % 61 - 30).to_string()).collect::<Vec<_>>().join(","));
+        let parsed = CsiParser::new().parse(&block).unwrap();
+
+        // Median, p99 and worst / الوسيط و p99 والأسوأ
+        let stats = |mut times: Vec<Duration>| {
+            times.sort();
+            (times[times.len() / 2], times[times.len() * 99 / 100], times[times.len() - 1])
+        };
+
+        // Whole ticks, and the frame-storing part of them, over ~3 s of 20 ms ticks
+        // النبضات كاملة وجزء حفظ الإطارات منها خلال ~3 ث من نبضات 20 مللي ثانية
+        let run = |queued: bool| {
+            let state = csi_tui::state::create_shared_state();
+            let mut app = App::new(state.clone());
+            let queue = queued.then(|| app.frames_out.clone());
+
+            // Record with integrity on, as a logged session would / التسجيل مع السلامة كجلسة مسجلة
+            let dir = std::env::temp_dir().join(format!("csi_bench_tick_{}_{}", std::process::id(), queued));
+            std::fs::create_dir_all(&dir).unwrap();
+            state.lock().unwrap().integrity_logging = true;
+            crate::serial_reader::start_recording(&state, Some(dir.join("bench.csv"))).unwrap();
+            let stop = Arc::new(AtomicBool::new(false));
+            let reader = {
+                let (state, stop, parsed) = (state.clone(), Arc::clone(&stop), parsed.clone());
+                std::thread::spawn(move || {
+                    let mut next = Instant::now();
+                    while !stop.load(Ordering::SeqCst) {
+                        let frame = ReceivedFrame::new(parsed.clone(), CsiMeta::default(), None, None, Duration::ZERO);
+                        deliver(frame.counted(), queue.as_ref(), &state);
+                        next += Duration::from_millis(5);
+                        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+                    }
+                })
+            };
+
+            let (mut ticks, mut drains) = (Vec::new(), Vec::new());
+            for _ in 0..150 {
+                let started = Instant::now();
+                app.drain_frames().unwrap();
+                drains.push(started.elapsed());
+                app.tick(ACTIVE_TICK_MS as f64).unwrap();
+                ticks.push(started.elapsed());
+                std::thread::sleep(Duration::from_millis(ACTIVE_TICK_MS).saturating_sub(started.elapsed()));
+            }
+            stop.store(true, Ordering::SeqCst);
+            reader.join().unwrap();
+            assert!(state.lock().unwrap().frames.len() > 400);
+            state.lock().unwrap().stop_recording();
+            let _ = std::fs::remove_dir_all(&dir);
+
+            (stats(ticks), stats(drains))
+        };
+
+        let (locked, _) = run(false);
+        let (queued, drained) = run(true);
+        eprintln!("tick at 200 fps (median, p99, max): per-frame lock {:?}, queue {:?}", locked, queued);
+        eprintln!("  of which storing queued frames: {:?}", drained);
+    }
+
+    #[test]
+    fn test_confirm_quit_while_recording() {
+        let state = csi_tui::state::create_shared_state();
+        let mut app = App::new(state.clone());
+        let press = |app: &mut App, c| app.handle_key_event(KeyEvent::from(KeyCode::Char(c))).unwrap();
+
+        // Not recording: quits straight away / بدون تسجيل: خروج مباشر
+        assert!(press(&mut app, 'q'));
+
+        let path = std::env::temp_dir().join(format!("csi_confirm_quit_{}.csv", std::process::id()));
+        state.lock().unwrap().csv_logger = Some(csi_tui::csv_writer::CsvWriter::spawn(csi_tui::CsvLogger::new(path.clone()).unwrap()));
+
+        // First quit opens the prompt, N cancels / أول خروج يفتح السؤال و N يلغي
+        assert!(!press(&mut app, 'q'));
+        assert!(state.lock().unwrap().confirm_quit);
+        assert!(!press(&mut app, 'n'));
+        assert!(!state.lock().unwrap().confirm_quit);
+        assert!(state.lock().unwrap().csv_logger.is_some());
+
+        // Y quits and closes the recording / Y يخرج ويغلق التسجيل
+        assert!(!app.handle_key_event(KeyEvent::from(KeyCode::Esc)).unwrap());
+        assert!(press(&mut app, 'y'));
+        assert!(state.lock().unwrap().csv_logger.is_none());
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(csi_tui::csv_logger::open_marker_for(&path));
+    }
+
+    #[test]
+    fn test_unfocused_pause() {
+        let mut app = App::new(csi_tui::state::create_shared_state());
+        app.mark_drawn(Duration::ZERO);
+
+        // Not configured: focus loss changes nothing / غير مفعل: فقدان التركيز لا يغير شيئاً
+        app.set_focused(false);
+        app.redraw.force();
+        assert!(app.draw_due());
+
+        // Configured: no redraws until focus returns, then one right away
+        // مفعل: لا رسم حتى يعود التركيز، ثم رسم فوري
+        app.pause_when_unfocused(true);
+        assert!(!app.draw_due());
+        app.mark_drawn(Duration::ZERO);
+        app.set_focused(true);
+        assert!(app.draw_due());
+    }
+}
@@ -0,0 +1,152 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 binary_packet.rs - Binary CSI Packet Decoder
+// ═══════════════════════════════════════════════════════════════════════════════
+// Decodes CSI sent as packed bytes by firmware too fast for ASCII output.
+// Features:
+// - Packet: 2-byte sync word, u16 little-endian payload length, int8 I/Q pairs
+// - Resynchronizes on the next sync word after junk or a bad length
+// - Produces the same ParseResult as the text parser
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use crate::parser::{phases_of, ParseResult};
+use crate::state::CsiFormat;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Sync word that starts every packet / كلمة المزامنة في بداية كل حزمة
+pub const SYNC_WORD: [u8; 2] = [0xC5, 0x1A];
+
+/// Sync word plus length field / كلمة المزامنة مع حقل الطول
+pub const HEADER_LEN: usize = 4;
+
+/// Largest payload accepted: 512 subcarriers of I/Q / أكبر حمولة مقبولة
+pub const MAX_PAYLOAD_LEN: usize = 1024;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Decoding / فك الترميز
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One step of scanning the byte buffer / خطوة واحدة من فحص مخزن البايتات
+#[derive(Debug, Clone)]
+pub enum Packet {
+    /// A complete, valid packet / حزمة كاملة وصالحة
+    Frame(ParseResult),
+
+    /// A sync word with an impossible length; skipped / كلمة مزامنة بطول مستحيل؛ تم تخطيها
+    Corrupt,
+}
+
+/// Take the next packet from the front of `buffer`
+/// أخذ الحزمة التالية من بداية المخزن
+///
+/// Bytes before the sync word are dropped. None means more data is needed;
+/// an incomplete packet stays in the buffer.
+pub fn take_packet(buffer: &mut Vec<u8>) -> Option<Packet> {
+    let Some(start) = buffer.windows(2).position(|w| w == SYNC_WORD) else {
+        // Keep a trailing first sync byte / الإبقاء على بايت مزامنة أول في النهاية
+        let keep = usize::from(buffer.last() == Some(&SYNC_WORD[0]));
+        buffer.drain(..buffer.len() - keep);
+        return None;
+    };
+    buffer.drain(..start);
+    if buffer.len() < HEADER_LEN {
+        return None;
+    }
+
+    let len = u16::from_le_bytes([buffer[2], buffer[3]]) as usize;
+    if len == 0 || !len.is_multiple_of(2) || len > MAX_PAYLOAD_LEN {
+        // Skip this sync word and look for the next one / تخطي كلمة المزامنة والبحث عن التالية
+        buffer.drain(..SYNC_WORD.len());
+        return Some(Packet::Corrupt);
+    }
+    if buffer.len() < HEADER_LEN + len {
+        return None;
+    }
+
+    let payload: Vec<u8> = buffer.drain(..HEADER_LEN + len).skip(HEADER_LEN).collect();
+    Some(Packet::Frame(decode_payload(&payload)))
+}
+
+/// Convert int8 I/Q pairs into a RealImag result
+/// تحويل أزواج I/Q بحجم بايت إلى نتيجة حقيقي/تخيلي
+pub fn decode_payload(payload: &[u8]) -> ParseResult {
+    let pairs: Vec<(i32, i32)> = payload
+        .chunks_exact(2)
+        .map(|iq| (iq[0] as i8 as i32, iq[1] as i8 as i32))
+        .collect();
+    let mags = pairs.iter().map(|&(r, i)| ((r * r + i * i) as f64).sqrt()).collect();
+
+    ParseResult {
+        format: CsiFormat::RealImag,
+        phases: phases_of(&pairs),
+        pairs,
+        mags,
+        rssi: None,
+        mac: None,
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(iq: &[i8]) -> Vec<u8> {
+        let mut bytes = SYNC_WORD.to_vec();
+        bytes.extend_from_slice(&(iq.len() as u16).to_le_bytes());
+        bytes.extend(iq.iter().map(|&v| v as u8));
+        bytes
+    }
+
+    #[test]
+    fn test_decode_packets_across_reads() {
+        let mut stream = b"boot log\r\n".to_vec();
+        stream.extend(packet(&[3, 4, -6, 8]));
+        stream.extend(packet(&[-128, 127]));
+
+        // Feed in small chunks like serial reads / التغذية بأجزاء صغيرة مثل قراءات التسلسل
+        let mut buffer = Vec::new();
+        let mut frames = Vec::new();
+        for chunk in stream.chunks(3) {
+            buffer.extend_from_slice(chunk);
+            while let Some(packet) = take_packet(&mut buffer) {
+                match packet {
+                    Packet::Frame(result) => frames.push(result),
+                    Packet::Corrupt => panic!("no corrupt packets expected"),
+                }
+            }
+        }
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].pairs, vec![(3, 4), (-6, 8)]);
+        assert_eq!(frames[0].mags, vec![5.0, 10.0]);
+        assert_eq!(frames[0].phases.len(), 2);
+        assert_eq!(frames[1].pairs, vec![(-128, 127)]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_length_resyncs() {
+        // Odd length, then a good packet / طول فردي ثم حزمة سليمة
+        let mut buffer = vec![0xC5, 0x1A, 0x03, 0x00, 0xFF];
+        buffer.extend(packet(&[1, 1]));
+
+        assert!(matches!(take_packet(&mut buffer), Some(Packet::Corrupt)));
+        assert!(matches!(take_packet(&mut buffer), Some(Packet::Frame(r)) if r.pairs == vec![(1, 1)]));
+        assert!(take_packet(&mut buffer).is_none());
+
+        // Oversized length is corrupt too / الطول الزائد تالف أيضاً
+        let mut buffer = vec![0xC5, 0x1A, 0xFF, 0xFF];
+        assert!(matches!(take_packet(&mut buffer), Some(Packet::Corrupt)));
+
+        // Junk without a sync word is dropped / البيانات بلا كلمة مزامنة تُحذف
+        let mut buffer = vec![1, 2, 3, 0xC5];
+        assert!(take_packet(&mut buffer).is_none());
+        assert_eq!(buffer, vec![0xC5]);
+    }
+}
@@ -0,0 +1,151 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 calibration.rs - Per-Subcarrier Calibration
+// ═══════════════════════════════════════════════════════════════════════════════
+// Fixed gain / phase corrections that flatten the static channel response of
+// a particular receiver before detection.
+// Features:
+// - Text file, one `index,gain,phase` line per corrected subcarrier
+// - Gain multiplies the magnitude, phase (radians) is added and wrapped
+// - Subcarriers missing from the file are left as received
+// - Frames longer or shorter than the file get the overlapping indices only
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::f64::consts::PI;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{CsiError, Result};
+use crate::state::CsiFrame;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Calibration / المعايرة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Gain and phase correction per subcarrier index
+/// تصحيح الكسب والطور لكل ناقل فرعي
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    /// Magnitude multiplier per subcarrier / مضاعف السعة لكل ناقل فرعي
+    gains: Vec<f64>,
+
+    /// Phase offset in radians per subcarrier / إزاحة الطور بالراديان لكل ناقل فرعي
+    phases: Vec<f64>,
+}
+
+impl Calibration {
+    /// Read a calibration file / قراءة ملف معايرة
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| CsiError::Calibration(format!("{}: {}", path.display(), e)))?;
+        Self::parse(&text)
+    }
+
+    /// Parse `index,gain,phase` lines; blank lines and `#` comments are skipped
+    /// تحليل أسطر `index,gain,phase` مع تجاهل الأسطر الفارغة والتعليقات
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut calibration = Calibration { gains: Vec::new(), phases: Vec::new() };
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad_line = || CsiError::Calibration(format!("line {}: '{}'", n + 1, line));
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [index, gain, phase] = fields[..] else { return Err(bad_line()); };
+            let index: usize = index.parse().map_err(|_| bad_line())?;
+            let gain: f64 = gain.parse().map_err(|_| bad_line())?;
+            let phase: f64 = phase.parse().map_err(|_| bad_line())?;
+            if !gain.is_finite() || !phase.is_finite() {
+                return Err(bad_line());
+            }
+
+            if index >= calibration.gains.len() {
+                calibration.gains.resize(index + 1, 1.0);
+                calibration.phases.resize(index + 1, 0.0);
+            }
+            calibration.gains[index] = gain;
+            calibration.phases[index] = phase;
+        }
+        Ok(calibration)
+    }
+
+    /// Subcarriers the file covers / عدد الناقلات التي يغطيها الملف
+    pub fn len(&self) -> usize {
+        self.gains.len()
+    }
+
+    /// True when the file corrects nothing / صحيح إن لم يصحح الملف شيئاً
+    pub fn is_empty(&self) -> bool {
+        self.gains.is_empty()
+    }
+
+    /// Correct a frame's magnitudes and phases in place; raw pairs are kept
+    /// تصحيح سعات وأطوار الإطار في مكانها مع الإبقاء على الأزواج الخام
+    pub fn apply(&self, frame: &mut CsiFrame) {
+        for (mag, gain) in frame.mags.iter_mut().zip(&self.gains) {
+            *mag *= gain;
+        }
+        for (phase, offset) in frame.phases.iter_mut().zip(&self.phases) {
+            *phase = wrap_phase(*phase + offset);
+        }
+    }
+}
+
+/// Wrap an angle into (-π, π] / حصر الزاوية في المجال (-π, π]
+fn wrap_phase(phase: f64) -> f64 {
+    if phase > -PI && phase <= PI {
+        return phase;
+    }
+    let wrapped = (phase + PI).rem_euclid(2.0 * PI) - PI;
+    if wrapped == -PI { PI } else { wrapped }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    #[test]
+    fn test_known_calibration_corrects_magnitudes() {
+        let calibration = Calibration::parse("# index,gain,phase\n0,2.0,0.5\n\n2, 0.5, -0.25\n").unwrap();
+        assert_eq!(calibration.len(), 3);
+
+        // Four subcarriers: index 3 is beyond the file, index 1 is absent from it
+        // أربعة ناقلات: الرابع خارج الملف والثاني غير موجود فيه
+        let pairs = vec![(3, 4), (6, 8), (0, 10), (5, 0)];
+        let mags = vec![5.0, 10.0, 10.0, 5.0];
+        let mut frame = CsiFrame::new(0, mags, pairs.clone(), CsiFormat::RealImag, None);
+        let phases = frame.phases.clone();
+        calibration.apply(&mut frame);
+
+        assert_eq!(frame.mags, vec![10.0, 10.0, 5.0, 5.0]);
+        assert!((frame.phases[0] - (phases[0] + 0.5)).abs() < 1e-12);
+        assert_eq!(frame.phases[1], phases[1]);
+        assert!((frame.phases[2] - (phases[2] - 0.25)).abs() < 1e-12);
+        assert_eq!(frame.phases[3], phases[3]);
+        assert_eq!(frame.pairs, pairs);
+
+        // Shorter frame: only the overlap is corrected / إطار أقصر: يُصحح التداخل فقط
+        let mut short = CsiFrame::new(0, vec![1.0], vec![], CsiFormat::AmplitudeOnly, None);
+        calibration.apply(&mut short);
+        assert_eq!(short.mags, vec![2.0]);
+    }
+
+    #[test]
+    fn test_phase_wraps_and_bad_lines() {
+        let calibration = Calibration::parse("0,1,3.0").unwrap();
+        let mut frame = CsiFrame::new(0, vec![1.0], vec![(-1, 1)], CsiFormat::RealImag, None);
+        calibration.apply(&mut frame);
+        assert!(frame.phases[0] > -PI && frame.phases[0] <= PI);
+        assert!((frame.phases[0] - (3.0 * PI / 4.0 + 3.0 - 2.0 * PI)).abs() < 1e-12);
+
+        assert!(matches!(Calibration::parse("0,1"), Err(CsiError::Calibration(_))));
+        assert!(matches!(Calibration::parse("x,1,0"), Err(CsiError::Calibration(_))));
+        assert!(matches!(Calibration::parse("0,NaN,0"), Err(CsiError::Calibration(_))));
+        assert!(Calibration::parse("").unwrap().is_empty());
+    }
+}
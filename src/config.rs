@@ -0,0 +1,129 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 config.rs - Saved Settings
+// ═══════════════════════════════════════════════════════════════════════════════
+// Remembers the last serial settings between launches.
+// Features:
+// - TOML file in the per-user config directory (~/.config/csi-tui/config.toml)
+// - Port, baud rate and live window
+// - Missing, unreadable or corrupt files fall back to the defaults
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::serial_reader::DEFAULT_PORT;
+use csi_tui::error::{CsiError, Result};
+use csi_tui::state::{DEFAULT_BAUD_RATE, DEFAULT_WINDOW_SECS, WINDOW_SECS_STEPS};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Config / الإعدادات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Settings kept between launches / الإعدادات المحفوظة بين التشغيلات
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Last serial port / آخر منفذ تسلسلي
+    pub port: String,
+
+    /// Last baud rate / آخر معدل بود
+    pub baud: u32,
+
+    /// Live frame window in seconds / نافذة الإطارات المباشرة بالثواني
+    pub window_secs: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_PORT.to_string(),
+            baud: DEFAULT_BAUD_RATE,
+            window_secs: DEFAULT_WINDOW_SECS,
+        }
+    }
+}
+
+impl Config {
+    /// Default location: `<config dir>/csi-tui/config.toml`
+    /// الموقع الافتراضي للملف
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("csi-tui").join("config.toml"))
+    }
+
+    /// Load from the default location / التحميل من الموقع الافتراضي
+    pub fn load() -> Self {
+        Self::path().map(|path| Self::load_from(&path)).unwrap_or_default()
+    }
+
+    /// Load from `path`; anything unusable falls back to the defaults
+    /// التحميل من `path`؛ أي قيمة غير صالحة تعود للافتراضي
+    pub fn load_from(path: &Path) -> Self {
+        let mut config: Self = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default();
+
+        // Keep values the UI can actually show / إبقاء قيم يمكن للواجهة عرضها
+        let defaults = Self::default();
+        if config.port.trim().is_empty() {
+            config.port = defaults.port;
+        }
+        if config.baud == 0 {
+            config.baud = defaults.baud;
+        }
+        if !WINDOW_SECS_STEPS.contains(&config.window_secs) {
+            config.window_secs = defaults.window_secs;
+        }
+        config
+    }
+
+    /// Save to the default location / الحفظ في الموقع الافتراضي
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().ok_or_else(|| CsiError::Config("no config directory".to_string()))?;
+        self.save_to(&path)
+    }
+
+    /// Save to `path`, creating its directory / الحفظ في `path` مع إنشاء المجلد
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| CsiError::Config(format!("failed to create {}: {}", dir.display(), e)))?;
+        }
+        let text = toml::to_string(self).map_err(|e| CsiError::Config(e.to_string()))?;
+        fs::write(path, text).map_err(|e| CsiError::Config(format!("failed to write {}: {}", path.display(), e)))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip_and_fallbacks() {
+        let dir = std::env::temp_dir().join(format!("csi_config_test_{}", std::process::id()));
+        let path = dir.join("nested").join("config.toml");
+
+        // Missing file: defaults / ملف مفقود: القيم الافتراضية
+        assert_eq!(Config::load_from(&path), Config::default());
+
+        let saved = Config { port: "/dev/ttyACM0".to_string(), baud: 921_600, window_secs: 300 };
+        saved.save_to(&path).unwrap();
+        assert_eq!(Config::load_from(&path), saved);
+
+        // Partial file keeps the other defaults / ملف جزئي يبقي بقية القيم الافتراضية
+        fs::write(&path, "baud = 460800\nwindow_secs = 7\n").unwrap();
+        let partial = Config::load_from(&path);
+        assert_eq!((partial.port.as_str(), partial.baud, partial.window_secs), (DEFAULT_PORT, 460_800, DEFAULT_WINDOW_SECS));
+
+        // Corrupt file: defaults / ملف تالف: القيم الافتراضية
+        fs::write(&path, "port = [not toml").unwrap();
+        assert_eq!(Config::load_from(&path), Config::default());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
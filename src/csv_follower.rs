@@ -0,0 +1,335 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 csv_follower.rs - Follow a Growing CSV File
+// ═══════════════════════════════════════════════════════════════════════════════
+// Tails a CSV log written by another machine, like `tail -f`.
+// Features:
+// - Polls the file for growth and parses only appended rows
+// - Keeps a torn trailing line until it is completed
+// - Reopens on truncation or when a newer matching log appears
+// - Reports growth rate (rows/s) and lag behind the newest row
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::csv_loader::CsvLoader;
+use crate::error::{CsiError, Result};
+use crate::state::CsiFrame;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Minimum time between file checks (ms) / أقل مدة بين فحوصات الملف
+pub const FOLLOW_POLL_MS: i64 = 250;
+
+/// Look for a newer log after this long without growth (ms)
+/// البحث عن سجل أحدث بعد هذه المدة بدون نمو
+pub const ROTATION_IDLE_MS: i64 = 2_000;
+
+/// Window used for the growth rate (ms) / نافذة حساب معدل النمو
+const RATE_WINDOW_MS: i64 = 5_000;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Follower / المتابع
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Result of one poll / نتيجة فحص واحد
+#[derive(Debug, Default)]
+pub struct FollowUpdate {
+    /// Newly appended frames / الإطارات المضافة حديثاً
+    pub frames: Vec<CsiFrame>,
+
+    /// Set when the follower switched to a new or truncated file
+    /// يُضبط عند الانتقال إلى ملف جديد أو مقتطع
+    pub reopened: Option<PathBuf>,
+}
+
+/// Incremental reader for a CSV log that is still being written
+/// قارئ تدريجي لسجل CSV لا يزال قيد الكتابة
+pub struct CsvFollower {
+    /// File being followed / الملف المتابَع
+    path: PathBuf,
+
+    /// Header/row parser / محلل الترويسة والصفوف
+    loader: CsvLoader,
+
+    /// Bytes consumed so far / البايتات المقروءة حتى الآن
+    offset: u64,
+
+    /// Incomplete trailing line / السطر الأخير غير المكتمل
+    partial: String,
+
+    /// Has the header been parsed? / هل تم تحليل الترويسة؟
+    header_read: bool,
+
+    /// Time of the last file check / وقت آخر فحص للملف
+    last_poll_ms: i64,
+
+    /// Time the file last grew / آخر وقت نما فيه الملف
+    last_growth_ms: i64,
+
+    /// (poll time, rows appended) for the growth rate / لحساب معدل النمو
+    growth: VecDeque<(i64, usize)>,
+
+    /// Timestamp of the newest row / الطابع الزمني لأحدث صف
+    newest_timestamp: Option<i64>,
+}
+
+impl CsvFollower {
+    /// Start following `path`, returning the rows already in the file
+    /// بدء متابعة `path` وإرجاع الصفوف الموجودة مسبقاً
+    pub fn open<P: AsRef<Path>>(path: P, now: i64) -> Result<(Self, Vec<CsiFrame>)> {
+        let mut follower = Self {
+            path: path.as_ref().to_path_buf(),
+            loader: CsvLoader::new(),
+            offset: 0,
+            partial: String::new(),
+            header_read: false,
+            last_poll_ms: now,
+            last_growth_ms: now,
+            growth: VecDeque::new(),
+            newest_timestamp: None,
+        };
+        let frames = follower.read_appended()?;
+        if !follower.header_read {
+            return Err(CsiError::CsvLoad("file is empty".to_string()));
+        }
+        Ok((follower, frames))
+    }
+
+    /// Check the file and return rows appended since the last poll
+    /// فحص الملف وإرجاع الصفوف المضافة منذ آخر فحص
+    pub fn poll(&mut self, now: i64) -> Result<FollowUpdate> {
+        let mut update = FollowUpdate::default();
+        if now - self.last_poll_ms < FOLLOW_POLL_MS {
+            return Ok(update);
+        }
+        self.last_poll_ms = now;
+
+        let len = fs::metadata(&self.path).map(|m| m.len()).ok();
+
+        // Truncated (or replaced by a shorter file): start over
+        // مقتطع (أو استبدل بملف أقصر): البدء من جديد
+        if len.is_some_and(|len| len < self.offset) {
+            self.reset();
+            update.reopened = Some(self.path.clone());
+        } else if len.is_none_or(|len| len == self.offset)
+            && now - self.last_growth_ms >= ROTATION_IDLE_MS
+        {
+            // Idle or gone: has the writer moved on to a newer log?
+            // خامل أو محذوف: هل انتقل الكاتب إلى سجل أحدث؟
+            if let Some(newer) = newer_sibling(&self.path) {
+                self.path = newer;
+                self.reset();
+                update.reopened = Some(self.path.clone());
+            } else if len.is_none() {
+                return Err(CsiError::FileMissing(self.path.clone()));
+            }
+        }
+
+        update.frames = self.read_appended()?;
+        if !update.frames.is_empty() {
+            self.last_growth_ms = now;
+        }
+        self.growth.push_back((now, update.frames.len()));
+        while self.growth.front().is_some_and(|&(t, _)| t < now - RATE_WINDOW_MS) {
+            self.growth.pop_front();
+        }
+
+        Ok(update)
+    }
+
+    /// File currently followed / الملف المتابع حالياً
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Rows appended per second over the last few seconds
+    /// الصفوف المضافة في الثانية خلال الثواني الأخيرة
+    pub fn rows_per_sec(&self) -> f64 {
+        let rows: usize = self.growth.iter().map(|&(_, n)| n).sum();
+        rows as f64 * 1000.0 / RATE_WINDOW_MS as f64
+    }
+
+    /// Seconds between `now` and the newest row / الثواني بين الآن وأحدث صف
+    pub fn lag_secs(&self, now: i64) -> Option<f64> {
+        self.newest_timestamp.map(|ts| (now - ts).max(0) as f64 / 1000.0)
+    }
+
+    /// One-line status for the receiver box / سطر حالة لمربع المستقبل
+    pub fn status(&self, now: i64) -> String {
+        let name = self.path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        match self.lag_secs(now) {
+            Some(lag) => format!("👁 Following {}: {:.1} rows/s, lag {:.1}s", name, self.rows_per_sec(), lag),
+            None => format!("👁 Following {}: waiting for rows", name),
+        }
+    }
+
+    /// Forget the read position so the file is read from the start
+    /// نسيان موضع القراءة لقراءة الملف من البداية
+    fn reset(&mut self) {
+        self.loader = CsvLoader::new();
+        self.offset = 0;
+        self.partial.clear();
+        self.header_read = false;
+    }
+
+    /// Read complete lines past `offset` and parse them
+    /// قراءة الأسطر المكتملة بعد `offset` وتحليلها
+    fn read_appended(&mut self) -> Result<Vec<CsiFrame>> {
+        let mut file = File::open(&self.path)
+            .map_err(|e| CsiError::CsvLoad(format!("failed to open file: {}", e)))?;
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|e| CsiError::CsvLoad(format!("failed to seek: {}", e)))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .map_err(|e| CsiError::CsvLoad(format!("failed to read: {}", e)))?;
+        self.offset += bytes.len() as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&bytes));
+
+        // Keep the torn tail for the next poll / الاحتفاظ بالذيل الممزق للفحص التالي
+        let complete = match self.partial.rfind('\n') {
+            Some(end) => {
+                let tail = self.partial.split_off(end + 1);
+                std::mem::replace(&mut self.partial, tail)
+            }
+            None => return Ok(Vec::new()),
+        };
+
+        let mut frames = Vec::new();
+        for line in complete.lines().filter(|l| !l.trim().is_empty()) {
+            if !self.header_read {
+                self.loader.parse_header(line)?;
+                self.header_read = true;
+            } else if let Ok(frame) = self.loader.parse_row(line) {
+                frames.push(frame);
+            }
+        }
+
+        if let Some(last) = frames.last() {
+            self.newest_timestamp = Some(last.timestamp);
+        }
+        Ok(frames)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Rotation / تدوير السجلات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Newest log next to `path` with the same prefix and extension
+/// أحدث سجل بجانب `path` بنفس البادئة والامتداد
+///
+/// The prefix is the file name up to its first digit ("csi_log_" for
+/// "csi_log_20251202_021631.csv"); timestamped names sort chronologically.
+pub fn newer_sibling(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let prefix = &name[..name.find(|c: char| c.is_ascii_digit())?];
+    if prefix.is_empty() {
+        return None;
+    }
+    let ext = path.extension().and_then(|e| e.to_str());
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == ext)
+        .filter_map(|p| {
+            let candidate = p.file_name()?.to_str()?.to_string();
+            (candidate.starts_with(prefix) && candidate.as_str() > name).then_some((candidate, p))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, p)| p)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    fn append(path: &Path, text: &str) {
+        let mut f = OpenOptions::new().append(true).open(path).unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_follow_appended_and_torn_rows() {
+        let path = PathBuf::from("test_follow_append.csv");
+        fs::write(&path, "timestamp,r0,i0\n1000,3,4\n").unwrap();
+
+        let (mut follower, initial) = CsvFollower::open(&path, 0).unwrap();
+        assert_eq!(initial.len(), 1);
+
+        // Torn row is held back until its newline arrives
+        // الصف الممزق ينتظر حتى يصل سطره الجديد
+        append(&path, "1100,6,8\n1200,");
+        let update = follower.poll(FOLLOW_POLL_MS).unwrap();
+        assert_eq!(update.frames.len(), 1);
+        assert_eq!(update.frames[0].timestamp, 1100);
+
+        // Too soon: no file access / مبكر جداً: لا وصول للملف
+        append(&path, "9,12\n");
+        assert!(follower.poll(FOLLOW_POLL_MS + 1).unwrap().frames.is_empty());
+
+        let update = follower.poll(2 * FOLLOW_POLL_MS).unwrap();
+        assert_eq!(update.frames[0].timestamp, 1200);
+        assert_eq!(update.frames[0].mags, vec![15.0]);
+        assert_eq!(follower.lag_secs(1_700), Some(0.5));
+        assert!(follower.rows_per_sec() > 0.0);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_follow_reopens_after_truncation() {
+        let path = PathBuf::from("test_follow_truncate.csv");
+        fs::write(&path, "timestamp,r0,i0\n1000,3,4\n1100,3,4\n").unwrap();
+        let (mut follower, _) = CsvFollower::open(&path, 0).unwrap();
+
+        fs::write(&path, "timestamp,r0,i0\n5000,1,1\n").unwrap();
+        let update = follower.poll(FOLLOW_POLL_MS).unwrap();
+        assert_eq!(update.reopened.as_deref(), Some(path.as_path()));
+        assert_eq!(update.frames.len(), 1);
+        assert_eq!(update.frames[0].timestamp, 5000);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_follow_switches_to_newer_log() {
+        let dir = PathBuf::from("test_follow_rotation");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir(&dir).unwrap();
+        let old = dir.join("csi_log_20250101_000000.csv");
+        let newer = dir.join("csi_log_20250101_010000.csv");
+        fs::write(&old, "timestamp,r0,i0\n1000,3,4\n").unwrap();
+        fs::write(dir.join("other_20990101.csv"), "x").unwrap();
+
+        let (mut follower, _) = CsvFollower::open(&old, 0).unwrap();
+        fs::write(&newer, "timestamp,r0,i0\n9000,6,8\n").unwrap();
+        assert_eq!(newer_sibling(&old), Some(newer.clone()));
+
+        // Still within the idle grace period / لا يزال ضمن مهلة الخمول
+        assert!(follower.poll(FOLLOW_POLL_MS).unwrap().reopened.is_none());
+
+        let update = follower.poll(ROTATION_IDLE_MS).unwrap();
+        assert_eq!(update.reopened, Some(newer.clone()));
+        assert_eq!(follower.path(), newer.as_path());
+        assert_eq!(update.frames[0].timestamp, 9000);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
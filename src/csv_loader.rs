@@ -4,7 +4,11 @@
 // This module handles loading historical CSI data from CSV files.
 // Features:
 // - Auto-detect number of subcarrier columns
+// - Optional `rssi` / `mac` columns after the timestamp
+// - Real/imag pair columns or amplitude-only columns (one per subcarrier)
+// - Gzip-compressed files (.csv.gz) decompressed on the fly
 // - Parse rows into CsiFrame structures
+// - Unparsable rows are skipped and reported back to the caller
 // - Load directly into AppState
 // ═══════════════════════════════════════════════════════════════════════════════
 
@@ -12,8 +16,39 @@ use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
+use flate2::read::GzDecoder;
+
+use crate::error::{CsiError, Result};
+use crate::jitter::summarize_timestamps;
 use crate::state::{CsiFormat, CsiFrame, SharedState};
 
+/// First bytes of every gzip stream / أول بايتات كل تدفق gzip
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Load Results / نتائج التحميل
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A data row that could not be parsed / صف بيانات تعذر تحليله
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRow {
+    /// 1-based line number in the file / رقم السطر في الملف (يبدأ من 1)
+    pub line: usize,
+
+    /// Why the row was skipped / سبب تخطي الصف
+    pub reason: String,
+}
+
+/// Outcome of loading a file into the state / نتيجة تحميل ملف إلى الحالة
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadSummary {
+    /// Frames loaded / الإطارات المحملة
+    pub frames: usize,
+
+    /// Rows left out, in file order / الصفوف المتروكة بترتيب الملف
+    pub skipped: Vec<SkippedRow>,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 CSV Loader Structure / هيكل محمّل CSV
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -23,43 +58,96 @@ use crate::state::{CsiFormat, CsiFrame, SharedState};
 pub struct CsvLoader {
     /// Number of subcarrier columns detected / عدد أعمدة الناقلات الفرعية المكتشفة
     sc_count: usize,
+    
+    /// Index of the `rssi` column, if any / فهرس عمود rssi إن وجد
+    rssi_col: Option<usize>,
+    
+    /// Index of the `mac` column, if any / فهرس عمود mac إن وجد
+    mac_col: Option<usize>,
+    
+    /// Index of the `source` column, if any / فهرس عمود source إن وجد
+    source_col: Option<usize>,
+    
+    /// First r/i column / أول عمود r/i
+    data_start: usize,
+    
+    /// Data layout from the header: RealImag pairs or AmplitudeOnly columns
+    /// تخطيط البيانات من الترويسة: أزواج حقيقي/تخيلي أو أعمدة سعة
+    detected_format: CsiFormat,
+
+    /// Rows skipped by the last `load` / الصفوف المتخطاة في آخر تحميل
+    skipped: Vec<SkippedRow>,
 }
 
 impl CsvLoader {
     /// Create a new CSV loader
     /// إنشاء محمّل CSV جديد
     pub fn new() -> Self {
-        Self { sc_count: 0 }
+        Self {
+            sc_count: 0,
+            rssi_col: None,
+            mac_col: None,
+            source_col: None,
+            data_start: 1,
+            detected_format: CsiFormat::Unknown,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Data layout found in the last header parsed / تخطيط البيانات في آخر ترويسة
+    pub fn detected_format(&self) -> CsiFormat {
+        self.detected_format
+    }
+
+    /// Rows the last `load` could not parse / الصفوف التي تعذر تحليلها في آخر تحميل
+    pub fn skipped_rows(&self) -> &[SkippedRow] {
+        &self.skipped
     }
 
     /// Load CSI data from a CSV file
     /// تحميل بيانات CSI من ملف CSV
     /// 
+    /// Gzip files are recognized by their magic bytes, whatever the extension.
+    /// Rows that fail to parse are left out and listed in `skipped_rows`.
+    /// 
     /// # Arguments
     /// * `file_path` - Path to the CSV file
     /// 
     /// # Returns
-    /// * `Result<Vec<CsiFrame>, String>` - Loaded frames or error message
-    pub fn load<P: AsRef<Path>>(&mut self, file_path: P) -> Result<Vec<CsiFrame>, String> {
+    /// * `Result<Vec<CsiFrame>>` - Loaded frames or error
+    pub fn load<P: AsRef<Path>>(&mut self, file_path: P) -> Result<Vec<CsiFrame>> {
         let file = File::open(file_path.as_ref())
-            .map_err(|e| format!("Failed to open CSV file: {}", e))?;
+            .map_err(|e| CsiError::CsvLoad(format!("failed to open file: {}", e)))?;
+        let mut file = BufReader::new(file);
         
-        let reader = BufReader::new(file);
+        // Peek for the gzip magic / فحص بايتات gzip المميزة
+        let gzipped = file
+            .fill_buf()
+            .map_err(|e| CsiError::CsvLoad(format!("failed to read file: {}", e)))?
+            .starts_with(&GZIP_MAGIC);
+        let reader: Box<dyn BufRead> = if gzipped {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(file)
+        };
         let mut frames = Vec::new();
+        self.skipped.clear();
         let mut lines = reader.lines();
         
         // Parse header to detect subcarrier count
         // تحليل الترويسة لكشف عدد الناقلات الفرعية
         if let Some(header_result) = lines.next() {
-            let header = header_result.map_err(|e| format!("Failed to read header: {}", e))?;
+            let header = header_result
+                .map_err(|e| CsiError::CsvLoad(format!("failed to read header: {}", e)))?;
             self.parse_header(&header)?;
         } else {
-            return Err("CSV file is empty".to_string());
+            return Err(CsiError::CsvLoad("file is empty".to_string()));
         }
         
         // Parse data rows / تحليل صفوف البيانات
         for (line_num, line_result) in lines.enumerate() {
-            let line = line_result.map_err(|e| format!("Failed to read line {}: {}", line_num + 2, e))?;
+            let line = line_result
+                .map_err(|e| CsiError::CsvLoad(format!("failed to read line {}: {}", line_num + 2, e)))?;
             
             if line.trim().is_empty() {
                 continue;
@@ -68,8 +156,8 @@ impl CsvLoader {
             match self.parse_row(&line) {
                 Ok(frame) => frames.push(frame),
                 Err(e) => {
-                    // Log warning but continue / تسجيل تحذير ولكن المتابعة
-                    eprintln!("⚠️ Warning: Skipping line {}: {}", line_num + 2, e);
+                    // Keep going; the caller decides how to report it / المتابعة؛ المستدعي يقرر كيف يبلغ عنه
+                    self.skipped.push(SkippedRow { line: line_num + 2, reason: e.to_string() });
                 }
             }
         }
@@ -79,13 +167,14 @@ impl CsvLoader {
 
     /// Load CSI data directly into AppState for playback
     /// تحميل بيانات CSI مباشرة إلى AppState للتشغيل
-    pub fn load_into_state<P: AsRef<Path>>(&mut self, file_path: P, state: &SharedState) -> Result<usize, String> {
-        let frames = self.load(file_path)?;
-        let count = frames.len();
+    ///
+    /// Returns the frame count and skipped rows; status text is left to the caller.
+    pub fn load_into_state<P: AsRef<Path>>(&mut self, file_path: P, state: &SharedState) -> Result<LoadSummary> {
+        let frames = self.load(file_path.as_ref())?;
+        let summary = LoadSummary { frames: frames.len(), skipped: self.skipped.clone() };
         
         // Lock state and add frames / قفل الحالة وإضافة الإطارات
-        let mut state_guard = state.lock()
-            .map_err(|e| format!("Failed to lock state: {}", e))?;
+        let mut state_guard = state.lock()?;
         
         // Clear existing frames / مسح الإطارات الموجودة
         state_guard.clear_frames();
@@ -93,44 +182,56 @@ impl CsvLoader {
         // Store loaded frames for playback / تخزين الإطارات المحملة للتشغيل
         state_guard.loaded_frames = frames;
         
+        // Timing jitter of the recording / تذبذب توقيت التسجيل
+        state_guard.file_jitter = summarize_timestamps(state_guard.loaded_frames.iter().map(|f| f.timestamp));
+        
         // Calculate duration / حساب المدة
         if let (Some(first), Some(last)) = (state_guard.loaded_frames.first(), state_guard.loaded_frames.last()) {
             state_guard.playback_duration_secs = (last.timestamp - first.timestamp) as f64 / 1000.0;
         }
         
+        // Remember file for quick reload / تذكر الملف لإعادة التحميل السريع
+        state_guard.last_csv_path = Some(file_path.as_ref().to_path_buf());
+        
         // Start playback mode / بدء وضع التشغيل
         state_guard.start_playback();
         
-        state_guard.status_message = format!(
-            "✅ Loaded {} frames ({:.1}s) - Space: Play/Pause, ←→: Seek",
-            count,
-            state_guard.playback_duration_secs
-        );
-        
-        Ok(count)
+        Ok(summary)
     }
 
     /// Parse the CSV header to detect column count
     /// تحليل ترويسة CSV لكشف عدد الأعمدة
-    fn parse_header(&mut self, header: &str) -> Result<(), String> {
+    pub(crate) fn parse_header(&mut self, header: &str) -> Result<()> {
         let columns: Vec<&str> = header.split(',').collect();
         
-        // Header format: timestamp,r0,i0,r1,i1,...
-        // صيغة الترويسة: الطابع_الزمني,r0,i0,r1,i1,...
-        // Each subcarrier has 2 columns (real, imag)
-        // كل ناقل فرعي له عمودين (حقيقي، تخيلي)
+        // Header format: timestamp[,rssi][,mac][,source],r0,i0,r1,i1,...
+        // صيغة الترويسة: الطابع_الزمني[,rssi][,mac][,source],r0,i0,r1,i1,...
+        // Each subcarrier has 2 columns (real, imag), or 1 (a0,a1,...) for amplitude-only
+        // كل ناقل فرعي له عمودين (حقيقي، تخيلي)، أو عمود واحد لبيانات السعة فقط
         
         if columns.is_empty() {
-            return Err("Empty header".to_string());
+            return Err(CsiError::CsvLoad("empty header".to_string()));
         }
         
-        // First column is timestamp, rest are r/i pairs
-        // العمود الأول هو الطابع الزمني، والباقي أزواج r/i
-        let data_columns = columns.len() - 1;
-        self.sc_count = data_columns / 2;
+        // First column is timestamp, then optional metadata, rest are r/i pairs
+        // العمود الأول هو الطابع الزمني، ثم بيانات وصفية اختيارية، والباقي أزواج r/i
+        let meta = columns[1..]
+            .iter()
+            .take_while(|c| matches!(c.trim(), "rssi" | "mac" | "source"))
+            .count();
+        let find = |name: &str| (1..=meta).find(|&i| columns[i].trim() == name);
+        self.rssi_col = find("rssi");
+        self.mac_col = find("mac");
+        self.source_col = find("source");
+        self.data_start = 1 + meta;
+        self.detected_format = detect_column_format(&columns[self.data_start..]);
+        self.sc_count = match self.detected_format {
+            CsiFormat::AmplitudeOnly => columns.len() - self.data_start,
+            _ => (columns.len() - self.data_start) / 2,
+        };
         
         if self.sc_count == 0 {
-            return Err("No subcarrier columns found in header".to_string());
+            return Err(CsiError::CsvLoad("no subcarrier columns found in header".to_string()));
         }
         
         Ok(())
@@ -138,24 +239,39 @@ impl CsvLoader {
 
     /// Parse a single data row into a CsiFrame
     /// تحليل صف بيانات واحد إلى CsiFrame
-    fn parse_row(&self, row: &str) -> Result<CsiFrame, String> {
+    pub(crate) fn parse_row(&self, row: &str) -> Result<CsiFrame> {
         let values: Vec<&str> = row.split(',').collect();
         
         if values.is_empty() {
-            return Err("Empty row".to_string());
+            return Err(CsiError::Parse("empty row".to_string()));
         }
         
         // Parse timestamp / تحليل الطابع الزمني
         let timestamp: i64 = values[0]
             .trim()
             .parse()
-            .map_err(|_| "Invalid timestamp")?;
+            .map_err(|_| CsiError::Parse(format!("invalid timestamp: {}", values[0])))?;
+        
+        // Empty or missing RSSI / MAC / source is None / القيم الفارغة أو المفقودة تصبح None
+        let rssi = self.rssi_col.and_then(|i| values.get(i)).and_then(|v| v.trim().parse().ok());
+        let text_at = |col: Option<usize>| {
+            col.and_then(|i| values.get(i))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let mac = text_at(self.mac_col);
+        let source = text_at(self.source_col);
+        
+        if self.detected_format == CsiFormat::AmplitudeOnly {
+            return self.parse_amplitude_row(timestamp, &values, rssi, mac).map(|f| f.with_source(source));
+        }
         
         // Parse real/imag pairs / تحليل أزواج حقيقي/تخيلي
         let mut pairs = Vec::new();
         let mut mags = Vec::new();
         
-        let mut i = 1;
+        let mut i = self.data_start;
         while i + 1 < values.len() {
             let real_str = values[i].trim();
             let imag_str = values[i + 1].trim();
@@ -179,10 +295,29 @@ impl CsvLoader {
         }
         
         if pairs.is_empty() {
-            return Err("No valid data pairs found".to_string());
+            return Err(CsiError::Parse("no valid data pairs found".to_string()));
         }
         
-        Ok(CsiFrame::new(timestamp, mags, pairs, CsiFormat::RealImag))
+        Ok(CsiFrame::new(timestamp, mags, pairs, CsiFormat::RealImag, rssi).with_mac(mac).with_source(source))
+    }
+
+    /// Parse the data columns of an amplitude-only row: one magnitude each
+    /// تحليل أعمدة صف سعة فقط: سعة واحدة لكل عمود
+    fn parse_amplitude_row(&self, timestamp: i64, values: &[&str], rssi: Option<i32>, mac: Option<String>) -> Result<CsiFrame> {
+        let mags: Vec<f64> = values
+            .iter()
+            .skip(self.data_start)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse().unwrap_or(0.0))
+            .collect();
+        
+        if mags.is_empty() {
+            return Err(CsiError::Parse("no valid amplitude values found".to_string()));
+        }
+        
+        let pairs = mags.iter().map(|&m| (m as i32, 0)).collect();
+        Ok(CsiFrame::new(timestamp, mags, pairs, CsiFormat::AmplitudeOnly, rssi).with_mac(mac))
     }
 }
 
@@ -196,23 +331,38 @@ impl Default for CsvLoader {
 // 🔹 Helper Functions / دوال مساعدة
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Open file dialog and load CSV (uses rfd crate)
-/// فتح نافذة اختيار الملف وتحميل CSV (يستخدم مكتبة rfd)
-pub fn pick_and_load_csv(state: &SharedState) -> Result<usize, String> {
-    // Use rfd for file dialog / استخدام rfd لنافذة الملفات
-    let file = rfd::FileDialog::new()
-        .add_filter("CSV Files", &["csv"])
-        .add_filter("All Files", &["*"])
-        .set_title("Select CSI CSV File")
-        .pick_file();
+/// Data layout from the data column names / تخطيط البيانات من أسماء الأعمدة
+///
+/// `a0,a1,...` (or `amp0`, `mag0`) means one amplitude column per subcarrier,
+/// as does an odd column count; anything else is read as r/i pairs.
+fn detect_column_format(data_columns: &[&str]) -> CsiFormat {
+    let amplitude_name = |c: &&str| {
+        let name = c.trim().to_ascii_lowercase();
+        ["amp", "mag", "a"].iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .map(|rest| rest.trim_start_matches('_'))
+                .is_some_and(|index| !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()))
+        })
+    };
+    if data_columns.first().is_some_and(amplitude_name) || data_columns.len() % 2 == 1 {
+        CsiFormat::AmplitudeOnly
+    } else {
+        CsiFormat::RealImag
+    }
+}
+
+/// Reload the last loaded CSV file without opening the dialog
+/// إعادة تحميل آخر ملف CSV بدون فتح نافذة الاختيار
+pub fn reload_last_csv(state: &SharedState) -> Result<LoadSummary> {
+    let path = state.lock()?.last_csv_path.clone();
+    let path = path.ok_or(CsiError::NoCsvLoaded)?;
     
-    match file {
-        Some(path) => {
-            let mut loader = CsvLoader::new();
-            loader.load_into_state(&path, state)
-        }
-        None => Err("No file selected".to_string()),
+    if !path.exists() {
+        return Err(CsiError::FileMissing(path));
     }
+    
+    let mut loader = CsvLoader::new();
+    loader.load_into_state(&path, state)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -233,6 +383,30 @@ mod tests {
         assert_eq!(loader.sc_count, 3);
     }
 
+    #[test]
+    fn test_amplitude_only_layouts() {
+        // Named amplitude columns / أعمدة سعة مسماة
+        let mut loader = CsvLoader::new();
+        loader.parse_header("timestamp,rssi,a0,a1,a2,a3").unwrap();
+        assert_eq!((loader.detected_format(), loader.sc_count), (CsiFormat::AmplitudeOnly, 4));
+        let frame = loader.parse_row("1000,-50,12.5,3,0,7").unwrap();
+        assert_eq!(frame.mags, vec![12.5, 3.0, 0.0, 7.0]);
+        assert_eq!(frame.pairs, vec![(12, 0), (3, 0), (0, 0), (7, 0)]);
+        assert!(frame.phases.is_empty());
+        assert_eq!((frame.format, frame.rssi, frame.timestamp), (CsiFormat::AmplitudeOnly, Some(-50), 1000));
+
+        // Odd column count with other names / عدد أعمدة فردي بأسماء أخرى
+        let mut loader = CsvLoader::new();
+        loader.parse_header("timestamp,sc0,sc1,sc2").unwrap();
+        assert_eq!((loader.detected_format(), loader.sc_count), (CsiFormat::AmplitudeOnly, 3));
+
+        // Pairs stay pairs / الأزواج تبقى أزواجاً
+        let mut loader = CsvLoader::new();
+        loader.parse_header("timestamp,r0,i0,r1,i1").unwrap();
+        assert_eq!((loader.detected_format(), loader.sc_count), (CsiFormat::RealImag, 2));
+        assert_eq!(loader.parse_row("1000,3,4,6,8").unwrap().mags, vec![5.0, 10.0]);
+    }
+
     #[test]
     fn test_parse_row() {
         let mut loader = CsvLoader::new();
@@ -243,5 +417,117 @@ mod tests {
         
         assert_eq!(frame.timestamp, 1234567890);
         assert_eq!(frame.pairs.len(), 2);
+        assert_eq!(frame.rssi, None);
+    }
+
+    #[test]
+    fn test_parse_rssi_column() {
+        let mut loader = CsvLoader::new();
+        loader.parse_header("timestamp,rssi,r0,i0,r1,i1").unwrap();
+        assert_eq!(loader.sc_count, 2);
+
+        let frame = loader.parse_row("1000,-58,3,4,6,8").unwrap();
+        assert_eq!(frame.rssi, Some(-58));
+        assert_eq!(frame.pairs, vec![(3, 4), (6, 8)]);
+        assert_eq!(frame.phases, vec![4f64.atan2(3.0), 8f64.atan2(6.0)]);
+        assert_eq!(frame.phase_at(1), Some(8f64.atan2(6.0)));
+        assert_eq!(frame.phase_at(2), None);
+
+        // Zero-zero pair reads as phase 0 / الزوج صفر-صفر طوره 0
+        assert_eq!(loader.parse_row("1050,-58,0,0,6,8").unwrap().phase_at(0), Some(0.0));
+
+        // Blank RSSI cell / خلية RSSI فارغة
+        assert_eq!(loader.parse_row("1100,,3,4,6,8").unwrap().rssi, None);
+    }
+
+    #[test]
+    fn test_parse_mac_column() {
+        let mut loader = CsvLoader::new();
+        loader.parse_header("timestamp,rssi,mac,r0,i0,r1,i1").unwrap();
+        assert_eq!(loader.sc_count, 2);
+
+        let frame = loader.parse_row("1000,-58,AA:BB:CC:DD:EE:FF,3,4,6,8").unwrap();
+        assert_eq!(frame.mac.as_deref(), Some("AA:BB:CC:DD:EE:FF"));
+        assert_eq!(frame.pairs, vec![(3, 4), (6, 8)]);
+
+        // Malformed MAC was logged blank; the frame is kept / MAC تالف سُجل فارغاً؛ الإطار يبقى
+        let frame = loader.parse_row("1100,-60,,3,4,6,8").unwrap();
+        assert_eq!(frame.mac, None);
+        assert_eq!(frame.rssi, Some(-60));
+
+        // Source column: blank for the primary reader / عمود المصدر فارغ للقارئ الأساسي
+        loader.parse_header("timestamp,rssi,mac,source,r0,i0").unwrap();
+        assert_eq!(loader.sc_count, 1);
+        assert_eq!(loader.parse_row("1200,-50,,kitchen,3,4").unwrap().source.as_deref(), Some("kitchen"));
+        assert_eq!(loader.parse_row("1300,-50,,,3,4").unwrap().source, None);
+    }
+
+    #[test]
+    fn test_reload_last_csv() {
+        use crate::state::create_shared_state;
+        use std::fs;
+
+        let path = std::path::PathBuf::from("test_reload.csv");
+        fs::write(&path, "timestamp,r0,i0\n1000,3,4\n2000,6,8\n").unwrap();
+
+        let state = create_shared_state();
+
+        // Nothing loaded yet / لا يوجد ملف محمل بعد
+        assert!(matches!(reload_last_csv(&state), Err(CsiError::NoCsvLoaded)));
+
+        let mut loader = CsvLoader::new();
+        loader.load_into_state(&path, &state).unwrap();
+        assert_eq!(state.lock().unwrap().last_csv_path.as_deref(), Some(path.as_path()));
+
+        // Reload uses the stored path / إعادة التحميل تستخدم المسار المخزن
+        state.lock().unwrap().loaded_frames.clear();
+        assert_eq!(reload_last_csv(&state).unwrap().frames, 2);
+        assert_eq!(state.lock().unwrap().loaded_frames.len(), 2);
+
+        // Missing file gives a clear error / ملف مفقود يعطي خطأ واضح
+        fs::remove_file(&path).unwrap();
+        let err = reload_last_csv(&state).unwrap_err();
+        assert!(matches!(err, CsiError::FileMissing(ref p) if p == &path));
+        assert!(err.to_string().contains("no longer exists"));
+    }
+
+    #[test]
+    fn test_bad_rows_reported_not_printed() {
+        use crate::state::create_shared_state;
+
+        let path = std::env::temp_dir().join(format!("csi_skipped_rows_{}.csv", std::process::id()));
+        std::fs::write(&path, "timestamp,r0,i0\n1000,3,4\nnot-a-time,3,4\n2000,,\n3000,6,8\n").unwrap();
+
+        let state = create_shared_state();
+        let mut loader = CsvLoader::new();
+        let summary = loader.load_into_state(&path, &state).unwrap();
+        assert_eq!(summary.frames, 2);
+        assert_eq!(summary.skipped.iter().map(|s| s.line).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(loader.skipped_rows(), summary.skipped.as_slice());
+
+        // A clean file clears the list / ملف سليم يفرغ القائمة
+        std::fs::write(&path, "timestamp,r0,i0\n1000,3,4\n").unwrap();
+        assert!(loader.load_into_state(&path, &state).unwrap().skipped.is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_gzipped_csv() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("csi_gzip_test_{}.csv.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(std::fs::File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(b"timestamp,rssi,r0,i0,r1,i1\n1000,-50,3,4,6,8\n2000,-51,5,12,8,15\n").unwrap();
+        encoder.finish().unwrap();
+
+        let frames = CsvLoader::new().load(&path).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].pairs, vec![(3, 4), (6, 8)]);
+        assert_eq!(frames[1].mags, vec![13.0, 17.0]);
+        assert_eq!(frames[1].rssi, Some(-51));
+
+        let _ = std::fs::remove_file(&path);
     }
 }
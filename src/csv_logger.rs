@@ -4,18 +4,30 @@
 // This module handles logging CSI data to CSV files.
 // Features:
 // - Auto-expanding header when subcarrier count increases
-// - Writes timestamp, real, imag pairs for each frame
+// - Writes timestamp, RSSI, MAC, source and real, imag pairs for each frame
 // - Fills missing values with empty cells
 // - Flushes on exit
+// - Optional integrity mode (rolling SHA-256 in a `.meta` sidecar)
+// - Optional size-based rotation into `_part002`, `_part003`, ... files
+// - `.open` marker while logging; crash recovery and resume on next launch
 // ═══════════════════════════════════════════════════════════════════════════════
 
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
 use std::io::{BufWriter, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::Utc;
 
+use crate::error::{CsiError, Result};
+use crate::integrity::{meta_path_for, sidecar_finalized, ChainLink, IntegrityTracker};
 use crate::state::CsiFrame;
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Extension of the marker that exists while a log is open / امتداد علامة الملف المفتوح
+const OPEN_MARKER_EXTENSION: &str = "open";
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 CSV Logger Structure / هيكل مسجل CSV
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -31,6 +43,27 @@ pub struct CsvLogger {
     
     /// Whether header has been written / هل تمت كتابة الترويسة
     header_written: bool,
+    
+    /// Write the `rssi` column (off when resuming an older log) / كتابة عمود rssi
+    rssi_column: bool,
+    
+    /// Write the `mac` column (off when resuming an older log) / كتابة عمود mac
+    mac_column: bool,
+    
+    /// Write the `source` column (off when resuming an older log) / كتابة عمود source
+    source_column: bool,
+    
+    /// Path of the CSV file / مسار ملف CSV
+    file_path: PathBuf,
+    
+    /// Rolling hash of written bytes (integrity mode) / بصمة متدحرجة للبايتات المكتوبة (وضع السلامة)
+    integrity: Option<IntegrityTracker>,
+    
+    /// Size of the file so far, buffered bytes included / حجم الملف حتى الآن مع البايتات المخزنة
+    bytes_written: u64,
+    
+    /// Start a new part once the file reaches this size / بدء جزء جديد عند بلوغ هذا الحجم
+    rotate_bytes: Option<u64>,
 }
 
 impl CsvLogger {
@@ -41,8 +74,8 @@ impl CsvLogger {
     /// * `file_path` - Path where to save the CSV file
     /// 
     /// # Returns
-    /// * `Result<CsvLogger, String>` - Logger instance or error message
-    pub fn new(file_path: PathBuf) -> Result<Self, String> {
+    /// * `Result<CsvLogger>` - Logger instance or error message
+    pub fn new(file_path: PathBuf) -> Result<Self> {
         // Open file in create/append mode
         // فتح الملف في وضع الإنشاء/الإضافة
         let file = OpenOptions::new()
@@ -50,20 +83,69 @@ impl CsvLogger {
             .write(true)
             .truncate(true) // Start fresh each time / البدء من جديد كل مرة
             .open(&file_path)
-            .map_err(|e| format!("Failed to create CSV file: {}", e))?;
+            .map_err(|e| CsiError::CsvLog(format!("failed to create file: {}", e)))?;
         
         let writer = BufWriter::new(file);
+        create_open_marker(&file_path)?;
         
         Ok(Self {
             writer,
             current_sc_count: 0,
             header_written: false,
+            rssi_column: true,
+            mac_column: true,
+            source_column: true,
+            file_path,
+            integrity: None,
+            bytes_written: 0,
+            rotate_bytes: None,
+        })
+    }
+
+    /// Reopen an existing (recovered) log and keep appending to it
+    /// إعادة فتح سجل موجود (تم استرداده) ومتابعة الإضافة إليه
+    ///
+    /// The integrity sidecar, if any, is rebuilt over the existing content.
+    pub fn resume(file_path: PathBuf) -> Result<Self> {
+        let content = fs::read(&file_path)
+            .map_err(|e| CsiError::CsvLog(format!("failed to read {}: {}", file_path.display(), e)))?;
+        
+        // Columns from the header: timestamp[,rssi][,mac][,source],r0,i0,... / الأعمدة من الترويسة
+        let header = content.split(|&b| b == b'\n').next().unwrap_or_default();
+        let columns = header.split(|&b| b == b',').count();
+        let header_written = !header.is_empty();
+        let has_column = |name: &[u8]| !header_written || header.split(|&b| b == b',').any(|c| c == name);
+        let (rssi_column, mac_column, source_column) = (has_column(b"rssi"), has_column(b"mac"), has_column(b"source"));
+        
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&file_path)
+            .map_err(|e| CsiError::CsvLog(format!("failed to open file: {}", e)))?;
+        create_open_marker(&file_path)?;
+        
+        let integrity = meta_path_for(&file_path)
+            .exists()
+            .then(|| IntegrityTracker::resume(&file_path, &content, count_rows(&content)));
+        
+        Ok(Self {
+            writer: BufWriter::new(file),
+            current_sc_count: columns
+                .saturating_sub(1 + usize::from(rssi_column) + usize::from(mac_column) + usize::from(source_column))
+                / 2,
+            header_written,
+            rssi_column,
+            mac_column,
+            source_column,
+            file_path,
+            integrity,
+            bytes_written: content.len() as u64,
+            rotate_bytes: None,
         })
     }
 
     /// Create a new CSV logger with auto-generated filename
     /// إنشاء مسجل CSV جديد باسم ملف تلقائي
-    pub fn new_with_timestamp() -> Result<Self, String> {
+    pub fn new_with_timestamp() -> Result<Self> {
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
         let filename = format!("csi_log_{}.csv", timestamp);
         let path = PathBuf::from(filename);
@@ -71,9 +153,81 @@ impl CsvLogger {
         Self::new(path)
     }
 
+    /// Path of the CSV file being written / مسار ملف CSV الجاري كتابته
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    /// Size of the file so far, including rows not yet flushed / حجم الملف حتى الآن
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Enable integrity mode: every flush writes a digest checkpoint to `<file>.meta`
+    /// تفعيل وضع السلامة: كل تفريغ يكتب نقطة تحقق في `<file>.meta`
+    ///
+    /// A file that already holds data (a resumed log without a sidecar) is
+    /// hashed first, so the digests cover it from byte 0.
+    pub fn enable_integrity(&mut self) -> Result<()> {
+        if self.integrity.is_some() {
+            return Ok(());
+        }
+        let mut tracker = IntegrityTracker::new(&self.file_path);
+        if self.bytes_written > 0 {
+            self.flush()?;
+            let content = fs::read(&self.file_path)
+                .map_err(|e| CsiError::CsvLog(format!("failed to read {}: {}", self.file_path.display(), e)))?;
+            tracker.update(&content, count_rows(&content));
+        }
+        self.integrity = Some(tracker);
+        Ok(())
+    }
+
+    /// Rotate to a new part once a part reaches `max_bytes` (None = never)
+    /// التدوير إلى جزء جديد عندما يبلغ الجزء `max_bytes` (None = أبداً)
+    pub fn set_rotate_bytes(&mut self, max_bytes: Option<u64>) {
+        self.rotate_bytes = max_bytes;
+    }
+
+    /// Close this part and continue in the next one; returns the new path
+    /// إغلاق هذا الجزء والمتابعة في الجزء التالي؛ يعيد المسار الجديد
+    ///
+    /// With integrity on, the new part's digest is chained to this part's
+    /// final digest and the link is recorded in its sidecar.
+    pub fn rotate(&mut self) -> Result<PathBuf> {
+        self.writer
+            .flush()
+            .map_err(|e| CsiError::CsvLog(format!("failed to flush: {}", e)))?;
+        
+        let mut next = CsvLogger::new(next_part_path(&self.file_path))?;
+        next.rssi_column = self.rssi_column;
+        next.mac_column = self.mac_column;
+        next.source_column = self.source_column;
+        next.rotate_bytes = self.rotate_bytes;
+        
+        if let Some(ref mut integrity) = self.integrity {
+            integrity.finalize()?;
+            let link = ChainLink {
+                file: self.file_path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                digest: integrity.digest(),
+            };
+            next.integrity = Some(IntegrityTracker::chained(&next.file_path, link));
+        }
+        
+        // Dropping the finished part removes its open marker / إسقاط الجزء المنتهي يزيل علامته
+        drop(std::mem::replace(self, next));
+        Ok(self.file_path.clone())
+    }
+
     /// Write a CSI frame to the CSV file
     /// كتابة إطار CSI إلى ملف CSV
-    pub fn log_frame(&mut self, frame: &CsiFrame) -> Result<(), String> {
+    pub fn log_frame(&mut self, frame: &CsiFrame) -> Result<()> {
+        // Start the next part before this row would overflow the current one
+        // بدء الجزء التالي قبل أن يتجاوز هذا الصف الجزء الحالي
+        if self.rotate_bytes.is_some_and(|max| self.header_written && self.bytes_written >= max) {
+            self.rotate()?;
+        }
+        
         let sc_count = frame.pairs.len();
         
         // Check if we need to expand the header
@@ -90,7 +244,7 @@ impl CsvLogger {
 
     /// Update/write the header with new subcarrier count
     /// تحديث/كتابة الترويسة بعدد ناقلات فرعية جديد
-    fn update_header(&mut self, new_sc_count: usize) -> Result<(), String> {
+    fn update_header(&mut self, new_sc_count: usize) -> Result<()> {
         // If header already written, we need to recreate the file
         // إذا كانت الترويسة مكتوبة بالفعل، نحتاج لإعادة إنشاء الملف
         if self.header_written {
@@ -103,6 +257,15 @@ impl CsvLogger {
         
         // Build header row / بناء صف الترويسة
         let mut header = String::from("timestamp");
+        if self.rssi_column {
+            header.push_str(",rssi");
+        }
+        if self.mac_column {
+            header.push_str(",mac");
+        }
+        if self.source_column {
+            header.push_str(",source");
+        }
         
         for i in 0..new_sc_count {
             header.push_str(&format!(",r{},i{}", i, i));
@@ -112,7 +275,12 @@ impl CsvLogger {
         // Write header / كتابة الترويسة
         self.writer
             .write_all(header.as_bytes())
-            .map_err(|e| format!("Failed to write header: {}", e))?;
+            .map_err(|e| CsiError::CsvLog(format!("failed to write header: {}", e)))?;
+        self.bytes_written += header.len() as u64;
+        
+        if let Some(ref mut integrity) = self.integrity {
+            integrity.update(header.as_bytes(), 0);
+        }
         
         self.current_sc_count = new_sc_count;
         self.header_written = true;
@@ -122,10 +290,34 @@ impl CsvLogger {
 
     /// Write a single data row
     /// كتابة صف بيانات واحد
-    fn write_row(&mut self, frame: &CsiFrame) -> Result<(), String> {
+    fn write_row(&mut self, frame: &CsiFrame) -> Result<()> {
         // Start with timestamp / البدء بالطابع الزمني
         let mut row = frame.timestamp.to_string();
         
+        // RSSI, empty when unknown / RSSI، فارغ إذا كان غير معروف
+        if self.rssi_column {
+            row.push(',');
+            if let Some(rssi) = frame.rssi {
+                row.push_str(&rssi.to_string());
+            }
+        }
+        
+        // MAC, empty when unknown / MAC، فارغ إذا كان غير معروف
+        if self.mac_column {
+            row.push(',');
+            if let Some(mac) = &frame.mac {
+                row.push_str(mac);
+            }
+        }
+        
+        // Source name, empty for the primary reader / اسم المصدر، فارغ للقارئ الأساسي
+        if self.source_column {
+            row.push(',');
+            if let Some(source) = &frame.source {
+                row.push_str(source);
+            }
+        }
+        
         // Add real/imag pairs / إضافة أزواج حقيقي/تخيلي
         for (real, imag) in &frame.pairs {
             row.push_str(&format!(",{},{}", real, imag));
@@ -143,17 +335,29 @@ impl CsvLogger {
         // Write row / كتابة الصف
         self.writer
             .write_all(row.as_bytes())
-            .map_err(|e| format!("Failed to write row: {}", e))?;
+            .map_err(|e| CsiError::CsvLog(format!("failed to write row: {}", e)))?;
+        self.bytes_written += row.len() as u64;
+        
+        if let Some(ref mut integrity) = self.integrity {
+            integrity.update(row.as_bytes(), 1);
+        }
         
         Ok(())
     }
 
     /// Flush all buffered data to disk
     /// تفريغ جميع البيانات المخزنة إلى القرص
-    pub fn flush(&mut self) -> Result<(), String> {
+    pub fn flush(&mut self) -> Result<()> {
         self.writer
             .flush()
-            .map_err(|e| format!("Failed to flush CSV: {}", e))
+            .map_err(|e| CsiError::CsvLog(format!("failed to flush: {}", e)))?;
+        
+        // Record digest checkpoint / تسجيل نقطة تحقق البصمة
+        if let Some(ref mut integrity) = self.integrity {
+            integrity.checkpoint()?;
+        }
+        
+        Ok(())
     }
 }
 
@@ -162,7 +366,157 @@ impl Drop for CsvLogger {
     /// ضمان تفريغ البيانات عند إسقاط المسجل
     fn drop(&mut self) {
         let _ = self.flush();
+        
+        // Finalize integrity sidecar / إنهاء الملف الجانبي للسلامة
+        if let Some(ref mut integrity) = self.integrity {
+            let _ = integrity.finalize();
+        }
+        
+        // Clean shutdown: the log is no longer open / إغلاق سليم: السجل لم يعد مفتوحاً
+        let _ = fs::remove_file(open_marker_for(&self.file_path));
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Crash Recovery / الاسترداد بعد الانهيار
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Result of repairing an interrupted log
+/// نتيجة إصلاح سجل منقطع
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecoveryOutcome {
+    /// Nothing to repair / لا شيء للإصلاح
+    AlreadyClean,
+
+    /// Torn line removed and sidecar finalized / تمت إزالة السطر الممزق وإنهاء الملف الجانبي
+    Repaired {
+        /// Bytes cut from a torn last line / البايتات المقطوعة من آخر سطر ممزق
+        torn_bytes: u64,
+        /// Data rows kept / صفوف البيانات المحفوظة
+        rows: u64,
+    },
+}
+
+/// Path of the open marker for a CSV file (`file.csv.open`)
+/// مسار علامة الملف المفتوح لملف CSV
+pub fn open_marker_for(csv_path: &Path) -> PathBuf {
+    let mut name = csv_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(OPEN_MARKER_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Path of the part after `path`: `log.csv` → `log_part002.csv` → `log_part003.csv`
+/// مسار الجزء التالي لـ `path`
+pub fn next_part_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let (base, part) = stem
+        .rsplit_once("_part")
+        .and_then(|(base, n)| Some((base, n.parse::<u32>().ok()?)))
+        .unwrap_or((&stem, 1));
+    let name = match path.extension() {
+        Some(ext) => format!("{}_part{:03}.{}", base, part + 1, ext.to_string_lossy()),
+        None => format!("{}_part{:03}", base, part + 1),
+    };
+    path.with_file_name(name)
+}
+
+/// Create the open marker next to a log, holding this process's PID
+/// إنشاء علامة الملف المفتوح بجانب السجل، تحوي معرف هذه العملية
+fn create_open_marker(csv_path: &Path) -> Result<()> {
+    fs::write(open_marker_for(csv_path), std::process::id().to_string())
+        .map_err(|e| CsiError::CsvLog(format!("failed to create open marker: {}", e)))
+}
+
+/// PID recorded in a log's open marker; None for no marker or an older empty one
+/// معرف العملية في علامة الملف المفتوح؛ None إن لم توجد أو كانت قديمة فارغة
+fn marker_owner(csv_path: &Path) -> Option<u32> {
+    fs::read_to_string(open_marker_for(csv_path)).ok()?.trim().parse().ok()
+}
+
+/// Whether process `pid` is still running / هل ما زالت العملية `pid` تعمل
+#[cfg(all(unix, feature = "tui"))]
+fn process_alive(pid: u32) -> bool {
+    // 0 and -1 would address process groups / 0 و -1 تخاطبان مجموعات عمليات
+    let Ok(pid) = libc::pid_t::try_from(pid) else { return false };
+    if pid <= 0 {
+        return false;
     }
+    // SAFETY: signal 0 only checks that the process exists / الإشارة 0 تتحقق من وجود العملية فقط
+    let signalled = unsafe { libc::kill(pid, 0) == 0 };
+    signalled || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without libc: /proc where there is one, else assume the owner is gone
+/// بدون libc: /proc إن وجد، وإلا يُفترض أن المالك انتهى
+#[cfg(not(all(unix, feature = "tui")))]
+fn process_alive(pid: u32) -> bool {
+    pid != 0 && Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Data rows in a log (lines after the header) / صفوف البيانات في السجل
+fn count_rows(content: &[u8]) -> u64 {
+    (content.iter().filter(|&&b| b == b'\n').count() as u64).saturating_sub(1)
+}
+
+/// Whether a log was left open by a crash / هل ترك الانهيار السجل مفتوحاً
+pub fn is_interrupted(csv_path: &Path) -> bool {
+    open_marker_for(csv_path).exists() || sidecar_finalized(csv_path) == Some(false)
+}
+
+/// CSV logs in `dir` that were not closed cleanly
+/// سجلات CSV في `dir` لم تُغلق بشكل سليم
+///
+/// Logs whose marker names a process that is still running are being
+/// written by another instance and are left alone.
+pub fn find_interrupted_logs(dir: &Path) -> Vec<PathBuf> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "csv"))
+                .filter(|p| is_interrupted(p) && !marker_owner(p).is_some_and(process_alive))
+                .collect()
+        })
+        .unwrap_or_default();
+    logs.sort();
+    logs
+}
+
+/// Finalize an interrupted log: cut a torn last line and finalize the sidecar
+/// إنهاء سجل منقطع: قطع آخر سطر ممزق وإنهاء الملف الجانبي
+pub fn recover_log(csv_path: &Path) -> Result<RecoveryOutcome> {
+    let content = fs::read(csv_path)
+        .map_err(|e| CsiError::CsvLog(format!("failed to read {}: {}", csv_path.display(), e)))?;
+    
+    // Keep everything up to the last complete line / الاحتفاظ بكل شيء حتى آخر سطر كامل
+    let keep = content.iter().rposition(|&b| b == b'\n').map(|i| i + 1).unwrap_or(0);
+    let torn_bytes = (content.len() - keep) as u64;
+    
+    if torn_bytes == 0 && !is_interrupted(csv_path) {
+        return Ok(RecoveryOutcome::AlreadyClean);
+    }
+    
+    if torn_bytes > 0 {
+        let file = OpenOptions::new()
+            .write(true)
+            .open(csv_path)
+            .map_err(|e| CsiError::CsvLog(format!("failed to open file: {}", e)))?;
+        file.set_len(keep as u64)
+            .map_err(|e| CsiError::CsvLog(format!("failed to truncate torn line: {}", e)))?;
+    }
+    
+    let content = &content[..keep];
+    let rows = count_rows(content);
+    
+    // Rebuild the sidecar over the repaired content / إعادة بناء الملف الجانبي
+    if meta_path_for(csv_path).exists() {
+        IntegrityTracker::resume(csv_path, content, rows).finalize()?;
+    }
+    
+    let _ = fs::remove_file(open_marker_for(csv_path));
+    
+    Ok(RecoveryOutcome::Repaired { torn_bytes, rows })
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -172,6 +526,7 @@ impl Drop for CsvLogger {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::integrity::{verify_file, VerifyOutcome};
     use crate::state::CsiFormat;
     use std::fs;
 
@@ -196,14 +551,178 @@ mod tests {
             vec![10.0, 15.0, 20.0],
             vec![(8, 6), (12, 9), (16, 12)],
             CsiFormat::RealImag,
-        );
+            Some(-52),
+        )
+        .with_mac(Some("AA:BB:CC:DD:EE:01".to_string()));
         
         let result = logger.log_frame(&frame);
         assert!(result.is_ok());
+        logger.log_frame(&create_test_frame(1234567990).with_source(Some("kitchen".to_string()))).unwrap();
         
         logger.flush().unwrap();
         
+        // RSSI, MAC and source columns round-trip, blank when unknown / أعمدة RSSI و MAC والمصدر تعود كما هي
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("timestamp,rssi,mac,source,r0,i0,r1,i1,r2,i2\n1234567890,-52,AA:BB:CC:DD:EE:01,,8,6,"));
+        assert_eq!(logger.bytes_written(), content.len() as u64);
+        let frames = crate::csv_loader::CsvLoader::new().load(&path).unwrap();
+        assert_eq!(frames[0].rssi, Some(-52));
+        assert_eq!(frames[0].mac.as_deref(), Some("AA:BB:CC:DD:EE:01"));
+        assert_eq!(frames[0].source, None);
+        assert_eq!(frames[1].rssi, None);
+        assert_eq!(frames[1].mac, None);
+        assert_eq!(frames[1].source.as_deref(), Some("kitchen"));
+        assert_eq!(frames[1].pairs, vec![(3, 4), (6, 8)]);
+        drop(logger);
+        
         // Cleanup / تنظيف
         let _ = fs::remove_file(path);
     }
+
+    fn create_test_frame(ts: i64) -> CsiFrame {
+        CsiFrame::new(ts, vec![5.0, 10.0], vec![(3, 4), (6, 8)], CsiFormat::RealImag, None)
+    }
+
+    /// Simulate a crash: logger never dropped, last row cut in half
+    fn write_crashed_log(path: &Path, integrity: bool) {
+        let mut logger = CsvLogger::new(path.to_path_buf()).unwrap();
+        if integrity {
+            logger.enable_integrity().unwrap();
+        }
+        for ts in 0..4 {
+            logger.log_frame(&create_test_frame(ts)).unwrap();
+        }
+        logger.flush().unwrap();
+        std::mem::forget(logger);
+        
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(b"4,3,4,6").unwrap();
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(meta_path_for(path));
+        let _ = fs::remove_file(open_marker_for(path));
+    }
+
+    #[test]
+    fn test_recover_torn_line() {
+        let path = PathBuf::from("test_recover_torn.csv");
+        write_crashed_log(&path, true);
+        assert!(is_interrupted(&path));
+
+        let outcome = recover_log(&path).unwrap();
+        assert_eq!(outcome, RecoveryOutcome::Repaired { torn_bytes: 7, rows: 4 });
+        assert!(!is_interrupted(&path));
+        assert!(fs::read(&path).unwrap().ends_with(b"\n"));
+
+        let len = fs::metadata(&path).unwrap().len();
+        assert_eq!(
+            crate::integrity::verify_file(&path).unwrap(),
+            crate::integrity::VerifyOutcome::Intact { bytes: len, rows: 4, finalized: true }
+        );
+
+        // Resume appending; the sidecar keeps covering the whole file
+        let mut logger = CsvLogger::resume(path.clone()).unwrap();
+        logger.log_frame(&create_test_frame(10)).unwrap();
+        drop(logger);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 6);
+        assert!(matches!(
+            crate::integrity::verify_file(&path).unwrap(),
+            crate::integrity::VerifyOutcome::Intact { rows: 5, finalized: true, .. }
+        ));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_recover_missing_sidecar() {
+        let path = PathBuf::from("test_recover_nosidecar.csv");
+        write_crashed_log(&path, false);
+        assert!(is_interrupted(&path));
+
+        let outcome = recover_log(&path).unwrap();
+        assert_eq!(outcome, RecoveryOutcome::Repaired { torn_bytes: 7, rows: 4 });
+        assert!(!meta_path_for(&path).exists());
+        assert!(!open_marker_for(&path).exists());
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_resume_keeps_legacy_layout() {
+        let path = PathBuf::from("test_resume_legacy.csv");
+        fs::write(&path, "timestamp,r0,i0,r1,i1\n0,3,4,6,8\n").unwrap();
+
+        let mut logger = CsvLogger::resume(path.clone()).unwrap();
+        let mut frame = create_test_frame(1);
+        frame.rssi = Some(-40);
+        logger.log_frame(&frame).unwrap();
+        drop(logger);
+        assert!(fs::read_to_string(&path).unwrap().ends_with("\n1,3,4,6,8\n"));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_integrity_enabled_on_resumed_log() {
+        let path = PathBuf::from("test_resume_integrity.csv");
+        {
+            let mut logger = CsvLogger::new(path.clone()).unwrap();
+            logger.log_frame(&create_test_frame(0)).unwrap();
+            logger.log_frame(&create_test_frame(1)).unwrap();
+        }
+        assert!(!meta_path_for(&path).exists());
+
+        // No sidecar yet: the digests must still start at byte 0 / البصمات تبدأ من البايت 0
+        let mut logger = CsvLogger::resume(path.clone()).unwrap();
+        logger.enable_integrity().unwrap();
+        logger.log_frame(&create_test_frame(2)).unwrap();
+        logger.flush().unwrap();
+        logger.log_frame(&create_test_frame(3)).unwrap();
+        drop(logger);
+
+        assert!(matches!(verify_file(&path).unwrap(), VerifyOutcome::Intact { rows: 4, .. }));
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_live_owner_not_interrupted() {
+        let dir = std::env::temp_dir().join(format!("csi_open_owner_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (live, dead, legacy) = (dir.join("live.csv"), dir.join("dead.csv"), dir.join("legacy.csv"));
+
+        // Marker of this (running) process / علامة هذه العملية العاملة
+        let logger = CsvLogger::new(live.clone()).unwrap();
+        assert_eq!(marker_owner(&live), Some(std::process::id()));
+
+        // A process that has exited, and an old empty marker
+        // عملية انتهت، وعلامة قديمة فارغة
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        child.wait().unwrap();
+        for path in [&dead, &legacy] {
+            fs::write(path, "timestamp,r0,i0\n").unwrap();
+        }
+        fs::write(open_marker_for(&dead), child.id().to_string()).unwrap();
+        fs::write(open_marker_for(&legacy), b"").unwrap();
+
+        assert_eq!(find_interrupted_logs(&dir), vec![dead, legacy]);
+
+        drop(logger);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_recover_already_clean() {
+        let path = PathBuf::from("test_recover_clean.csv");
+        {
+            let mut logger = CsvLogger::new(path.clone()).unwrap();
+            logger.enable_integrity().unwrap();
+            logger.log_frame(&create_test_frame(0)).unwrap();
+        }
+        assert!(!is_interrupted(&path));
+        assert_eq!(recover_log(&path).unwrap(), RecoveryOutcome::AlreadyClean);
+
+        cleanup(&path);
+    }
 }
@@ -0,0 +1,166 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 csv_writer.rs - Background CSV Recording
+// ═══════════════════════════════════════════════════════════════════════════════
+// Runs the CSV logger on its own thread so recording never costs the UI tick
+// or the readers a disk write.
+// Features:
+// - Frames go down a channel; rows, integrity hashing and flushes run on the writer thread
+// - File path and size shared with the UI (follows rotation)
+// - Dropping the writer drains the channel and closes the log (sidecar finalized)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::csv_logger::CsvLogger;
+use crate::state::CsiFrame;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Writer Commands / أوامر الكاتب
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Work sent to the writer thread / عمل مرسل إلى خيط الكاتب
+enum Command {
+    /// Append one frame / إضافة إطار واحد
+    Frame(Box<CsiFrame>),
+
+    /// Flush to disk (and checkpoint the sidecar) / التفريغ إلى القرص
+    Flush,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 CSV Writer / كاتب CSV
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Handle to a CSV logger running on its own thread
+/// مقبض لمسجل CSV يعمل على خيطه الخاص
+pub struct CsvWriter {
+    /// Command channel; closed on drop / قناة الأوامر؛ تُغلق عند الإسقاط
+    tx: Option<Sender<Command>>,
+
+    /// Writer thread / خيط الكاتب
+    handle: Option<JoinHandle<()>>,
+
+    /// Part being written / الجزء الجاري كتابته
+    path: Arc<Mutex<PathBuf>>,
+
+    /// Size of that part so far / حجم ذلك الجزء حتى الآن
+    bytes: Arc<AtomicU64>,
+}
+
+impl CsvWriter {
+    /// Move a logger onto a new writer thread
+    /// نقل مسجل إلى خيط كاتب جديد
+    pub fn spawn(mut logger: CsvLogger) -> Self {
+        let (tx, rx) = channel::<Command>();
+        let path = Arc::new(Mutex::new(logger.file_path().to_path_buf()));
+        let bytes = Arc::new(AtomicU64::new(logger.bytes_written()));
+
+        let handle = {
+            let (path, bytes) = (Arc::clone(&path), Arc::clone(&bytes));
+            thread::spawn(move || {
+                // Everything queued is written before the logger closes
+                // كل ما في القناة يُكتب قبل إغلاق المسجل
+                while let Ok(first) = rx.recv() {
+                    for command in std::iter::once(first).chain(rx.try_iter()) {
+                        let _ = match command {
+                            Command::Frame(frame) => logger.log_frame(&frame),
+                            Command::Flush => logger.flush(),
+                        };
+                    }
+                    if let Ok(mut current) = path.lock() {
+                        if *current != logger.file_path() {
+                            *current = logger.file_path().to_path_buf();
+                        }
+                    }
+                    bytes.store(logger.bytes_written(), Ordering::Relaxed);
+                }
+            })
+        };
+
+        Self { tx: Some(tx), handle: Some(handle), path, bytes }
+    }
+
+    /// Queue a frame for the log / إضافة إطار إلى طابور السجل
+    pub fn log_frame(&self, frame: CsiFrame) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Command::Frame(Box::new(frame)));
+        }
+    }
+
+    /// Ask the writer to flush / طلب التفريغ من الكاتب
+    pub fn flush(&self) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(Command::Flush);
+        }
+    }
+
+    /// Path of the part being written / مسار الجزء الجاري كتابته
+    pub fn file_path(&self) -> PathBuf {
+        self.path.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    /// Size of that part, as of the last batch written / حجم ذلك الجزء حتى آخر دفعة
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for CsvWriter {
+    /// Close the channel and wait for the log to be closed
+    /// إغلاق القناة وانتظار إغلاق السجل
+    fn drop(&mut self) {
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_logger::{next_part_path, open_marker_for};
+    use crate::integrity::{meta_path_for, verify_file, VerifyOutcome};
+    use crate::state::CsiFormat;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_writer_logs_on_its_own_thread() {
+        let path = PathBuf::from("test_csv_writer.csv");
+        let mut logger = CsvLogger::new(path.clone()).unwrap();
+        logger.enable_integrity().unwrap();
+        logger.set_rotate_bytes(Some(50));
+        let writer = CsvWriter::spawn(logger);
+
+        for ts in 0..4 {
+            writer.log_frame(CsiFrame::new(ts, vec![5.0], vec![(3, 4)], CsiFormat::RealImag, None));
+        }
+        writer.flush();
+        let second = next_part_path(&path);
+        while writer.file_path() != second {
+            thread::yield_now();
+        }
+        drop(writer);
+
+        // Rows land in the rotated parts and the chain verifies once closed
+        // الصفوف تصل إلى الأجزاء المدورة والسلسلة سليمة بعد الإغلاق
+        let rows = |p: &Path| fs::read_to_string(p).unwrap().lines().count() - 1;
+        assert_eq!(rows(&path) + rows(&second), 4);
+        assert!(matches!(verify_file(&second).unwrap(), VerifyOutcome::Intact { finalized: true, .. }));
+        assert!(!open_marker_for(&second).exists());
+
+        for part in [&path, &second] {
+            let _ = fs::remove_file(part);
+            let _ = fs::remove_file(meta_path_for(part));
+        }
+    }
+}
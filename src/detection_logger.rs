@@ -0,0 +1,151 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 detection_logger.rs - Detection History Logger
+// ═══════════════════════════════════════════════════════════════════════════════
+// This module appends detector samples to a CSV file for later charting.
+// Features:
+// - One row per detector run: timestamp, values and detected flags
+// - Separate from the raw CSI CSV
+// - Periodic flush, plus flush on exit
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use chrono::Utc;
+
+use crate::error::{CsiError, Result};
+use crate::state::DetectionResults;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// CSV header row / صف الترويسة
+pub const DETECTION_HEADER: &str =
+    "timestamp,motion_value,presence_value,door_value,motion_detected,human_present,door_open";
+
+/// Rows written between automatic flushes / عدد الصفوف بين عمليات التفريغ التلقائية
+const FLUSH_EVERY_ROWS: usize = 50;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Detection Logger / مسجل الكشف
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Logger for saving detector samples to disk
+/// مسجل لحفظ عينات الكاشفات على القرص
+pub struct DetectionLogger {
+    /// Buffered file writer / كاتب الملف المخزن
+    writer: BufWriter<File>,
+
+    /// Rows written since the last flush / الصفوف المكتوبة منذ آخر تفريغ
+    pending_rows: usize,
+}
+
+impl DetectionLogger {
+    /// Create a new detection logger and write the header
+    /// إنشاء مسجل كشف جديد وكتابة الترويسة
+    pub fn new(file_path: PathBuf) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&file_path)
+            .map_err(|e| CsiError::CsvLog(format!("failed to create file: {}", e)))?;
+
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", DETECTION_HEADER)
+            .map_err(|e| CsiError::CsvLog(format!("failed to write header: {}", e)))?;
+
+        Ok(Self { writer, pending_rows: 0 })
+    }
+
+    /// Create a new detection logger with auto-generated filename
+    /// إنشاء مسجل كشف جديد باسم ملف تلقائي
+    pub fn new_with_timestamp() -> Result<Self> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        Self::new(PathBuf::from(format!("detections_{}.csv", timestamp)))
+    }
+
+    /// Append one detector sample
+    /// إضافة عينة كشف واحدة
+    pub fn log_sample(&mut self, timestamp: i64, detections: &DetectionResults) -> Result<()> {
+        writeln!(
+            self.writer,
+            "{},{:.3},{:.3},{:.3},{},{},{}",
+            timestamp,
+            detections.motion_value,
+            detections.presence_value,
+            detections.door_value,
+            detections.motion_detected as u8,
+            detections.human_present as u8,
+            detections.door_open as u8,
+        )
+        .map_err(|e| CsiError::CsvLog(format!("failed to write row: {}", e)))?;
+
+        self.pending_rows += 1;
+        if self.pending_rows >= FLUSH_EVERY_ROWS {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush all buffered rows to disk
+    /// تفريغ جميع الصفوف المخزنة إلى القرص
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .map_err(|e| CsiError::CsvLog(format!("failed to flush: {}", e)))?;
+        self.pending_rows = 0;
+        Ok(())
+    }
+}
+
+impl Drop for DetectionLogger {
+    /// Ensure rows are flushed when logger is dropped
+    /// ضمان تفريغ الصفوف عند إسقاط المسجل
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_samples_appended_and_flushed() {
+        let path = PathBuf::from("test_detections.csv");
+        let mut logger = DetectionLogger::new(path.clone()).unwrap();
+
+        let mut detections = DetectionResults {
+            motion_value: 55.5,
+            presence_value: 12.0,
+            door_value: 3.25,
+            motion_detected: true,
+            ..Default::default()
+        };
+        logger.log_sample(1000, &detections).unwrap();
+
+        detections.motion_detected = false;
+        detections.door_open = true;
+        logger.log_sample(1100, &detections).unwrap();
+        logger.flush().unwrap();
+
+        // Readable before the logger is dropped / قابل للقراءة قبل إسقاط المسجل
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], DETECTION_HEADER);
+        assert_eq!(lines[1], "1000,55.500,12.000,3.250,1,0,0");
+        assert_eq!(lines[2], "1100,55.500,12.000,3.250,0,0,1");
+        assert_eq!(lines[1].split(',').count(), DETECTION_HEADER.split(',').count());
+
+        drop(logger);
+        let _ = fs::remove_file(path);
+    }
+}
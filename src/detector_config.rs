@@ -0,0 +1,144 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 detector_config.rs - Detector Settings Sidecar
+// ═══════════════════════════════════════════════════════════════════════════════
+// Saves the detector settings next to a CSV recording so replays match.
+// Features:
+// - Primary detector, aggregation, windows, subcarrier ratios and outlier sigma
+// - `<recording>.config.toml` written when a recording starts
+// - Read back when the recording is loaded
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::detectors::{Aggregation, DetectorRatios, DetectorWindows, PrimaryDetector};
+use crate::error::{CsiError, Result};
+use crate::state::AppState;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Detector Config / إعدادات الكاشفات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Detector settings a recording was made with / إعدادات الكاشفات التي سُجل بها التسجيل
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectorConfig {
+    /// Detector shown in Statistics / الكاشف المعروض في الإحصائيات
+    pub primary: PrimaryDetector,
+
+    /// Magnitude aggregation / تجميع السعات
+    pub aggregation: Aggregation,
+
+    /// Window sizes in frames / أحجام النوافذ بالإطارات
+    pub motion_window: usize,
+    pub presence_window: usize,
+    pub door_offset: usize,
+
+    /// Middle-subcarrier ratios / نسب الناقلات الوسطى
+    pub motion_ratio: f64,
+    pub presence_ratio: f64,
+    pub door_ratio: f64,
+
+    /// Outlier clamp in standard deviations; 0 (off) for sidecars written before it
+    /// قص القيم الشاذة بالانحرافات المعيارية؛ 0 (معطل) للملفات الأقدم
+    #[serde(default)]
+    pub outlier_sigma: f64,
+}
+
+impl DetectorConfig {
+    /// Settings currently active in `state` / الإعدادات المفعلة حالياً
+    pub fn from_state(state: &AppState) -> Self {
+        let windows = &state.detector_windows;
+        Self {
+            primary: state.primary_detector,
+            aggregation: state.aggregation,
+            motion_window: windows.motion,
+            presence_window: windows.presence,
+            door_offset: windows.door_offset,
+            motion_ratio: windows.ratios.motion,
+            presence_ratio: windows.ratios.presence,
+            door_ratio: windows.ratios.door,
+            outlier_sigma: windows.outlier_sigma,
+        }
+    }
+
+    /// Make these settings active in `state` / تفعيل هذه الإعدادات في الحالة
+    pub fn apply(&self, state: &mut AppState) {
+        state.primary_detector = self.primary;
+        state.aggregation = self.aggregation;
+        state.detector_windows = DetectorWindows {
+            motion: self.motion_window,
+            presence: self.presence_window,
+            door_offset: self.door_offset,
+            ratios: DetectorRatios {
+                motion: self.motion_ratio,
+                presence: self.presence_ratio,
+                door: self.door_ratio,
+            },
+            outlier_sigma: self.outlier_sigma,
+        };
+    }
+
+    /// Write the sidecar for the recording at `csv_path`
+    /// كتابة الملف الجانبي للتسجيل في `csv_path`
+    pub fn save_sidecar(&self, csv_path: &Path) -> Result<()> {
+        let path = sidecar_path(csv_path);
+        let text = toml::to_string(self).map_err(|e| CsiError::Config(e.to_string()))?;
+        fs::write(&path, text).map_err(|e| CsiError::Config(format!("failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Read the sidecar of the recording at `csv_path`; None when there is none
+    /// قراءة الملف الجانبي للتسجيل؛ None إن لم يوجد
+    pub fn load_sidecar(csv_path: &Path) -> Option<Result<Self>> {
+        let path = sidecar_path(csv_path);
+        let text = fs::read_to_string(&path).ok()?;
+        Some(toml::from_str(&text).map_err(|e| CsiError::Config(format!("{}: {}", path.display(), e))))
+    }
+}
+
+/// `capture.csv` → `capture.config.toml` / مسار الملف الجانبي
+pub fn sidecar_path(csv_path: &Path) -> PathBuf {
+    csv_path.with_extension("config.toml")
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidecar_round_trip() {
+        let csv = std::env::temp_dir().join(format!("csi_sidecar_test_{}.csv", std::process::id()));
+        assert_eq!(sidecar_path(&csv), csv.with_file_name(format!("csi_sidecar_test_{}.config.toml", std::process::id())));
+        assert!(DetectorConfig::load_sidecar(&csv).is_none());
+
+        let mut state = AppState::new();
+        state.primary_detector = PrimaryDetector::Door;
+        state.aggregation = Aggregation::TrimmedMean;
+        state.detector_windows.presence = 20;
+        state.detector_windows.ratios.motion = 0.45;
+        state.detector_windows.outlier_sigma = 3.0;
+        let config = DetectorConfig::from_state(&state);
+        config.save_sidecar(&csv).unwrap();
+
+        // Applying the loaded config restores the settings / تطبيق الإعدادات المحملة يستعيدها
+        let loaded = DetectorConfig::load_sidecar(&csv).unwrap().unwrap();
+        assert_eq!(loaded, config);
+        let mut fresh = AppState::new();
+        loaded.apply(&mut fresh);
+        assert_eq!(DetectorConfig::from_state(&fresh), config);
+
+        // Older sidecars did no outlier rejection / الملفات الأقدم لم تقص القيم الشاذة
+        let old = toml::to_string(&config).unwrap().replace("outlier_sigma = 3.0\n", "");
+        fs::write(sidecar_path(&csv), old).unwrap();
+        assert_eq!(DetectorConfig::load_sidecar(&csv).unwrap().unwrap().outlier_sigma, 0.0);
+
+        fs::write(sidecar_path(&csv), "primary = \"sideways\"").unwrap();
+        assert!(DetectorConfig::load_sidecar(&csv).unwrap().is_err());
+        let _ = fs::remove_file(sidecar_path(&csv));
+    }
+}
@@ -0,0 +1,208 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 detectors/breathing.rs - Breathing Rate Estimation
+// ═══════════════════════════════════════════════════════════════════════════════
+// تقدير معدل التنفس من التذبذب البطيء في متوسط السعة
+// Breathing rate estimation from the slow oscillation in average magnitude
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use crate::state::{CsiFrame, DetectionResults};
+use super::Aggregation;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// مدة نافذة تحليل التنفس (ميلي ثانية)
+/// Breathing analysis window (ms)
+pub const BREATHING_WINDOW_MS: i64 = 30_000;
+
+/// أقل عدد إطارات قبل التقدير
+/// Fewest frames in the window before estimating
+pub const BREATHING_MIN_FRAMES: usize = 150;
+
+/// حدود نطاق التنفس (هرتز): 6 إلى 30 نفساً في الدقيقة
+/// Breathing band limits (Hz): 6 to 30 breaths per minute
+pub const BREATHING_MIN_HZ: f64 = 0.1;
+pub const BREATHING_MAX_HZ: f64 = 0.5;
+
+/// معدل إعادة أخذ العينات قبل الارتباط الذاتي (هرتز)
+/// Resampling rate before the autocorrelation (Hz)
+const RESAMPLE_HZ: f64 = 10.0;
+
+/// أقل ارتباط ذاتي مُطبّع لاعتبار القمة تنفساً
+/// Minimum normalized autocorrelation for a peak to count as breathing
+const MIN_CORRELATION: f64 = 0.3;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Helper Functions / دوال مساعدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// إعادة أخذ العينات على شبكة منتظمة بالاستيفاء الخطي
+/// Resample (timestamp ms, value) points onto a uniform grid by linear interpolation
+fn resample(points: &[(i64, f64)], hz: f64) -> Vec<f64> {
+    let (Some(&(first, _)), Some(&(last, _))) = (points.first(), points.last()) else {
+        return Vec::new();
+    };
+    let step_ms = 1000.0 / hz;
+    let count = ((last - first) as f64 / step_ms) as usize + 1;
+
+    let mut out = Vec::with_capacity(count);
+    let mut j = 0;
+    for k in 0..count {
+        let t = first as f64 + k as f64 * step_ms;
+        while j + 2 < points.len() && (points[j + 1].0 as f64) < t {
+            j += 1;
+        }
+        let (t0, v0) = points[j];
+        let (t1, v1) = points[(j + 1).min(points.len() - 1)];
+        let value = if t1 > t0 {
+            v0 + (v1 - v0) * ((t - t0 as f64) / (t1 - t0) as f64).clamp(0.0, 1.0)
+        } else {
+            v0
+        };
+        out.push(value);
+    }
+    out
+}
+
+/// إزالة المتوسط والاتجاه الخطي
+/// Remove the mean and the linear trend
+fn detrend(series: &mut [f64]) {
+    let n = series.len() as f64;
+    if n < 2.0 { return; }
+    let x_mean = (n - 1.0) / 2.0;
+    let y_mean = series.iter().sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (i, &y) in series.iter().enumerate() {
+        cov += (i as f64 - x_mean) * (y - y_mean);
+        var += (i as f64 - x_mean).powi(2);
+    }
+    let slope = cov / var;
+    for (i, y) in series.iter_mut().enumerate() {
+        *y -= y_mean + slope * (i as f64 - x_mean);
+    }
+}
+
+/// الارتباط الذاتي المُطبّع عند إزاحة معينة
+/// Normalized autocorrelation at one lag
+fn autocorrelation(series: &[f64], lag: usize, energy: f64) -> f64 {
+    series.iter().zip(&series[lag..]).map(|(a, b)| a * b).sum::<f64>() / energy
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Detection Function / دالة الكشف
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// تقدير معدل التنفس (نفس في الدقيقة) من آخر 30 ثانية
+/// Estimate the breathing rate (breaths per minute) over the last 30 seconds
+///
+/// # Algorithm / الخوارزمية
+/// ```text
+/// - تجميع سعات كل إطار وإعادة أخذ العينات بـ 10 هرتز
+/// - إزالة الاتجاه ثم الارتباط الذاتي للإزاحات في نطاق 0.1-0.5 هرتز
+/// - أعلى قمة محلية = الدورة السائدة؛ 0 إذا كانت ضعيفة
+/// ```
+pub fn estimate_breathing_bpm(frames: &[CsiFrame], agg: Aggregation) -> f64 {
+    let Some(last) = frames.last() else { return 0.0; };
+    let start = frames.partition_point(|f| f.timestamp < last.timestamp - BREATHING_WINDOW_MS);
+    let window = &frames[start..];
+    if window.len() < BREATHING_MIN_FRAMES { return 0.0; }
+
+    let points: Vec<(i64, f64)> = window.iter().map(|f| (f.timestamp, agg.apply(f.analysis_mags()))).collect();
+    let mut series = resample(&points, RESAMPLE_HZ);
+
+    // نحتاج دورتين على الأقل من أبطأ تنفس
+    // Need at least two periods of the slowest breathing rate
+    let min_lag = (RESAMPLE_HZ / BREATHING_MAX_HZ).round() as usize;
+    let max_lag = (RESAMPLE_HZ / BREATHING_MIN_HZ).round() as usize;
+    if series.len() < 2 * max_lag { return 0.0; }
+
+    detrend(&mut series);
+    let energy: f64 = series.iter().map(|v| v * v).sum();
+    if energy <= f64::EPSILON { return 0.0; }
+
+    // أعلى قمة محلية داخل النطاق (بإزاحة إضافية على كل جانب)
+    // Highest local peak inside the band (one extra lag each side)
+    let acf: Vec<f64> = (min_lag - 1..=max_lag + 1)
+        .map(|lag| autocorrelation(&series, lag, energy))
+        .collect();
+    let peak = (1..acf.len() - 1)
+        .filter(|&i| acf[i] >= acf[i - 1] && acf[i] > acf[i + 1])
+        .max_by(|&a, &b| acf[a].total_cmp(&acf[b]));
+    let Some(i) = peak.filter(|&i| acf[i] >= MIN_CORRELATION) else { return 0.0; };
+
+    // استيفاء قطع مكافئ لإزاحة كسرية
+    // Parabolic interpolation for a fractional lag
+    let (y0, y1, y2) = (acf[i - 1], acf[i], acf[i + 1]);
+    let denom = y0 - 2.0 * y1 + y2;
+    let offset = if denom.abs() > f64::EPSILON { 0.5 * (y0 - y2) / denom } else { 0.0 };
+    let lag = (min_lag - 1 + i) as f64 + offset;
+
+    60.0 * RESAMPLE_HZ / lag
+}
+
+/// كتابة معدل التنفس في نتائج الكشف
+/// Write the breathing rate into the detection results
+pub fn detect_breathing(frames: &[CsiFrame], agg: Aggregation, results: &mut DetectionResults) {
+    results.breathing_bpm = estimate_breathing_bpm(frames, agg);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    /// إطارات بمعدل `hz` لمدة `secs` ثانية تتبع الدالة `level`
+    fn frames_at(hz: f64, secs: f64, level: impl Fn(f64) -> f64) -> Vec<CsiFrame> {
+        let count = (hz * secs) as usize;
+        (0..count)
+            .map(|i| {
+                let t = i as f64 / hz;
+                let v = level(t);
+                CsiFrame::new((t * 1000.0) as i64, vec![v; 8], vec![], CsiFormat::AmplitudeOnly, None)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_breathing_rate_from_sine() {
+        // 15 نفساً في الدقيقة (0.25 هرتز) مع انجراف بطيء
+        // 15 breaths per minute (0.25 Hz) with a slow drift
+        let frames = frames_at(20.0, 40.0, |t| 50.0 + 2.0 * (2.0 * std::f64::consts::PI * 0.25 * t).sin() + 0.05 * t);
+        let bpm = estimate_breathing_bpm(&frames, Aggregation::Mean);
+        assert!((bpm - 15.0).abs() < 0.5, "bpm = {}", bpm);
+
+        // Irregular frame spacing still works / تباعد غير منتظم للإطارات
+        let jittery: Vec<CsiFrame> = frames_at(20.0, 40.0, |t| 50.0 + (2.0 * std::f64::consts::PI * 0.2 * t).sin())
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % 3 != 0)
+            .map(|(_, f)| f)
+            .collect();
+        let bpm = estimate_breathing_bpm(&jittery, Aggregation::Mean);
+        assert!((bpm - 12.0).abs() < 0.5, "bpm = {}", bpm);
+    }
+
+    #[test]
+    fn test_no_breathing_without_rhythm() {
+        // إطارات قليلة / too few frames
+        let short = frames_at(10.0, 10.0, |t| 50.0 + (t * 1.5).sin());
+        assert_eq!(estimate_breathing_bpm(&short, Aggregation::Mean), 0.0);
+
+        // إشارة ثابتة / flat signal
+        let flat = frames_at(10.0, 30.0, |_| 50.0);
+        assert_eq!(estimate_breathing_bpm(&flat, Aggregation::Mean), 0.0);
+
+        // Same timestamp on every frame / نفس الطابع الزمني لكل الإطارات
+        let frozen: Vec<CsiFrame> = (0..200)
+            .map(|i| CsiFrame::new(0, vec![i as f64 % 7.0; 8], vec![], CsiFormat::AmplitudeOnly, None))
+            .collect();
+        let mut results = DetectionResults::default();
+        detect_breathing(&frozen, Aggregation::Mean, &mut results);
+        assert_eq!(results.breathing_bpm, 0.0);
+    }
+}
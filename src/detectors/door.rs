@@ -6,7 +6,7 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 
 use crate::state::{CsiFrame, DetectionResults};
-use super::{get_subcarriers_with_ratio, average_magnitude};
+use super::{analysis_range_usable, get_subcarriers_with_ratio, Aggregation};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Constants / الثوابت
@@ -32,33 +32,28 @@ pub const DOOR_DISPLAY_MULTIPLIER: f64 = 1.0;
 // 🔹 Helper Functions / دوال مساعدة
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// استخراج الـ Subcarriers لكشف الباب (25% من المنتصف)
-/// Extract subcarriers for door detection (25% from middle)
-fn get_door_subcarriers(mags: &[f64]) -> &[f64] {
-    get_subcarriers_with_ratio(mags, DOOR_SUBCARRIER_RATIO)
+/// استخراج الـ Subcarriers لكشف الباب (النسبة من المنتصف، 25% افتراضياً)
+/// Extract subcarriers for door detection (`ratio` of the middle, 25% by default)
+fn get_door_subcarriers(mags: &[f64], ratio: f64) -> &[f64] {
+    get_subcarriers_with_ratio(mags, ratio)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Detection Function / دالة الكشف
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// كشف فتح/إغلاق الباب من إطارات CSI
-/// Detect door open/close from CSI frames
+/// درجة الباب من صفوف السعات (نطاق التحليل): مقارنة الصف الأول بالأخير
+/// Door score from analysis-range magnitude rows: first row vs last row
 /// 
 /// # Algorithm / الخوارزمية
 /// ```text
 /// - مقارنة الإطار الحالي مع إطار قبل 5 إطارات
 /// - إذا > DOOR_THRESHOLD = باب مفتوح/مغلق
 /// ```
-pub fn detect_door(frames: &[CsiFrame], results: &mut DetectionResults) {
-    if frames.len() <= DOOR_FRAME_OFFSET { return; }
-
-    // استخراج الـ subcarriers للباب (25% من المنتصف)
-    let last = &frames[frames.len() - 1];
-    let last_door_mags = get_door_subcarriers(&last.mags);
-    
-    let older = &frames[frames.len() - 1 - DOOR_FRAME_OFFSET];
-    let older_mags = get_door_subcarriers(&older.mags);
+pub fn door_score(rows: &[&[f64]], agg: Aggregation) -> f64 {
+    let (Some(&older_mags), Some(&last_door_mags)) = (rows.first(), rows.last()) else {
+        return 0.0;
+    };
     
     let sc = last_door_mags.len().min(older_mags.len());
     
@@ -75,9 +70,29 @@ pub fn detect_door(frames: &[CsiFrame], results: &mut DetectionResults) {
     }
     
     // حساب درجة الباب
-    let last_door_avg = average_magnitude(last_door_mags);
-    let older_avg = average_magnitude(older_mags);
-    let door_score = (door_max * 0.5) + (door_total * 0.3) + ((last_door_avg - older_avg).abs() * 0.2);
+    let last_door_avg = agg.apply(last_door_mags);
+    let older_avg = agg.apply(older_mags);
+    (door_max * 0.5) + (door_total * 0.3) + ((last_door_avg - older_avg).abs() * 0.2)
+}
+
+/// الحد الأدنى لعدد الإطارات لإزاحة باب معينة
+/// Frames needed before the door can be scored with this offset
+pub fn door_min_frames(offset: usize) -> usize {
+    offset.max(1) + 1
+}
+
+/// كشف فتح/إغلاق الباب من إطارات CSI
+/// Detect door open/close by comparing the last frame with the one `offset` frames earlier
+pub fn detect_door(frames: &[CsiFrame], agg: Aggregation, offset: usize, ratio: f64, results: &mut DetectionResults) {
+    let offset = offset.max(1);
+    if frames.len() < door_min_frames(offset) { return; }
+    if !analysis_range_usable(frames[frames.len() - 1].mags.len(), ratio) { return; }
+
+    // استخراج الـ subcarriers للباب (25% من المنتصف)
+    let older = &frames[frames.len() - 1 - offset];
+    let last = &frames[frames.len() - 1];
+    let rows = [get_door_subcarriers(older.analysis_mags(), ratio), get_door_subcarriers(last.analysis_mags(), ratio)];
+    let door_score = door_score(&rows, agg);
     
     results.door_value = door_score * DOOR_DISPLAY_MULTIPLIER;
     results.door_open = door_score > DOOR_THRESHOLD;
@@ -94,7 +109,7 @@ mod tests {
 
     fn create_test_frame(mags: Vec<f64>) -> CsiFrame {
         let pairs: Vec<(i32, i32)> = mags.iter().map(|&m| (m as i32, 0)).collect();
-        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly)
+        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly, None)
     }
 
     #[test]
@@ -108,10 +123,23 @@ mod tests {
         frames.push(create_test_frame(vec![100.0, 100.0, 100.0]));
         
         let mut results = DetectionResults::default();
-        detect_door(&frames, &mut results);
+        detect_door(&frames, Aggregation::Mean, DOOR_FRAME_OFFSET, DOOR_SUBCARRIER_RATIO, &mut results);
         assert!(results.door_open);
     }
 
+    #[test]
+    fn test_door_score() {
+        // max 30 * 0.5 + mean 20 * 0.3 + avg diff 20 * 0.2 = 25
+        let rows: [&[f64]; 2] = [&[10.0, 10.0], &[40.0, 20.0]];
+        assert!((door_score(&rows, Aggregation::Mean) - 25.0).abs() < 1e-9);
+
+        // Only first and last rows are compared / تتم مقارنة الصف الأول والأخير فقط
+        let rows: [&[f64]; 3] = [&[10.0, 10.0], &[99.0, 99.0], &[40.0, 20.0]];
+        assert!((door_score(&rows, Aggregation::Mean) - 25.0).abs() < 1e-9);
+
+        assert_eq!(door_score(&[], Aggregation::Mean), 0.0);
+    }
+
     #[test]
     fn test_no_door() {
         // إنشاء 6 إطارات متشابهة
@@ -122,7 +150,7 @@ mod tests {
         }
         
         let mut results = DetectionResults::default();
-        detect_door(&frames, &mut results);
+        detect_door(&frames, Aggregation::Mean, DOOR_FRAME_OFFSET, DOOR_SUBCARRIER_RATIO, &mut results);
         assert!(!results.door_open);
     }
 }
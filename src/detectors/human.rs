@@ -6,7 +6,7 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 
 use crate::state::{CsiFrame, DetectionResults};
-use super::{get_subcarriers_with_ratio, average_magnitude};
+use super::{analysis_range_usable, get_subcarriers_with_ratio, Aggregation};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Constants / الثوابت
@@ -36,42 +36,45 @@ pub const PRESENCE_DISPLAY_MULTIPLIER: f64 = 5.0;
 // 🔹 Helper Functions / دوال مساعدة
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// استخراج الـ Subcarriers لكشف الوجود (35% من المنتصف)
-/// Extract subcarriers for presence detection (35% from middle)
-fn get_presence_subcarriers(mags: &[f64]) -> &[f64] {
-    get_subcarriers_with_ratio(mags, PRESENCE_SUBCARRIER_RATIO)
+/// استخراج الـ Subcarriers لكشف الوجود (النسبة من المنتصف، 35% افتراضياً)
+/// Extract subcarriers for presence detection (`ratio` of the middle, 35% by default)
+fn get_presence_subcarriers(mags: &[f64], ratio: f64) -> &[f64] {
+    get_subcarriers_with_ratio(mags, ratio)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Detection Function / دالة الكشف
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// كشف الوجود البشري من إطارات CSI
-/// Detect human presence from CSI frames
+/// نتيجة حساب درجة الوجود
+/// Presence score with the smallest frame-to-frame activity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PresenceScore {
+    /// المتوسط + 2 × الانحراف المعياري للفروقات الصغيرة
+    /// Mean + 2 × std-dev of micro differences
+    pub score: f64,
+    
+    /// أصغر فرق بين إطارين متتاليين
+    /// Smallest difference between consecutive rows
+    pub min_activity: f64,
+}
+
+/// درجة الوجود من صفوف السعات (نطاق التحليل)، الأقدم أولاً
+/// Presence score from analysis-range magnitude rows, oldest first
 /// 
 /// # Algorithm / الخوارزمية
 /// ```text
-/// - تحليل آخر 12 إطار (PRESENCE_WINDOW_SIZE)
 /// - حساب التباين في التغيرات الصغيرة (مثل التنفس)
 /// - إذا بين HUMAN_PRESENCE_MIN و MAX = وجود بشري
 /// ```
-pub fn detect_presence(frames: &[CsiFrame], results: &mut DetectionResults) {
-    if frames.len() < PRESENCE_WINDOW_SIZE { return; }
-
-    // أخذ آخر 12 إطار للتحليل
-    let window = &frames[frames.len() - PRESENCE_WINDOW_SIZE..];
-    let mut micro_diffs: Vec<f64> = Vec::new();
-    
-    // حساب الفروقات الصغيرة بين كل إطارين متتاليين (35% من المنتصف)
-    for i in 1..window.len() {
-        let curr_mags = get_presence_subcarriers(&window[i].mags);
-        let prev_w_mags = get_presence_subcarriers(&window[i - 1].mags);
-        let curr = average_magnitude(curr_mags);
-        let prev_w = average_magnitude(prev_w_mags);
-        micro_diffs.push((curr - prev_w).abs());
-    }
+pub fn presence_score(rows: &[&[f64]], agg: Aggregation) -> Option<PresenceScore> {
+    // حساب الفروقات الصغيرة بين كل صفين متتاليين
+    let micro_diffs: Vec<f64> = rows
+        .windows(2)
+        .map(|w| (agg.apply(w[1]) - agg.apply(w[0])).abs())
+        .collect();
     
-    if micro_diffs.is_empty() { return; }
+    if micro_diffs.is_empty() { return None; }
     
     // حساب المتوسط والتباين للفروقات الصغيرة
     let micro_mean: f64 = micro_diffs.iter().sum::<f64>() / micro_diffs.len() as f64;
@@ -80,15 +83,39 @@ pub fn detect_presence(frames: &[CsiFrame], results: &mut DetectionResults) {
         .sum::<f64>() / micro_diffs.len() as f64;
     
     // درجة الوجود = المتوسط + الجذر التربيعي للتباين * 2
-    let presence_score = micro_mean + micro_var.sqrt() * 2.0;
-    let min_act = micro_diffs.iter().cloned().fold(f64::INFINITY, f64::min);
+    Some(PresenceScore {
+        score: micro_mean + micro_var.sqrt() * 2.0,
+        min_activity: micro_diffs.iter().cloned().fold(f64::INFINITY, f64::min),
+    })
+}
+
+/// الحد الأدنى لعدد الإطارات لنافذة وجود معينة (فرق واحد على الأقل)
+/// Frames needed before presence can be scored (at least one difference)
+pub fn presence_min_frames(window: usize) -> usize {
+    window.max(2)
+}
+
+/// كشف الوجود البشري من إطارات CSI
+/// Detect human presence from the last `window` CSI frames
+pub fn detect_presence(frames: &[CsiFrame], agg: Aggregation, window: usize, ratio: f64, results: &mut DetectionResults) {
+    let window = presence_min_frames(window);
+    if frames.len() < window { return; }
+    if !analysis_range_usable(frames[frames.len() - 1].mags.len(), ratio) { return; }
+
+    // أخذ آخر إطارات النافذة للتحليل (35% من المنتصف)
+    let rows: Vec<&[f64]> = frames[frames.len() - window..]
+        .iter()
+        .map(|f| get_presence_subcarriers(f.analysis_mags(), ratio))
+        .collect();
     
-    results.presence_value = presence_score * PRESENCE_DISPLAY_MULTIPLIER;
+    let Some(presence) = presence_score(&rows, agg) else { return; };
+    
+    results.presence_value = presence.score * PRESENCE_DISPLAY_MULTIPLIER;
     
     // وجود بشري إذا كانت الدرجة ضمن النطاق أو هناك نشاط مستمر
-    results.human_present = (presence_score > HUMAN_PRESENCE_MIN 
-        && presence_score < HUMAN_PRESENCE_MAX) 
-        || min_act > 0.001;
+    results.human_present = (presence.score > HUMAN_PRESENCE_MIN 
+        && presence.score < HUMAN_PRESENCE_MAX) 
+        || presence.min_activity > 0.001;
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -102,7 +129,7 @@ mod tests {
 
     fn create_test_frame(mags: Vec<f64>) -> CsiFrame {
         let pairs: Vec<(i32, i32)> = mags.iter().map(|&m| (m as i32, 0)).collect();
-        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly)
+        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly, None)
     }
 
     #[test]
@@ -115,11 +142,23 @@ mod tests {
         }
         
         let mut results = DetectionResults::default();
-        detect_presence(&frames, &mut results);
+        detect_presence(&frames, Aggregation::Mean, PRESENCE_WINDOW_SIZE, PRESENCE_SUBCARRIER_RATIO, &mut results);
         // يجب أن يكتشف تغيرات صغيرة مستمرة
         assert!(results.presence_value > 0.0);
     }
 
+    #[test]
+    fn test_presence_score() {
+        // Diffs of row averages: 1, 2 → mean 1.5, std 0.5 → 2.5
+        let rows: [&[f64]; 3] = [&[10.0, 10.0], &[11.0, 11.0], &[13.0, 13.0]];
+        let presence = presence_score(&rows, Aggregation::Mean).unwrap();
+        assert!((presence.score - 2.5).abs() < 1e-9);
+        assert!((presence.min_activity - 1.0).abs() < 1e-9);
+
+        // One row has no differences / صف واحد بلا فروقات
+        assert_eq!(presence_score(&rows[..1], Aggregation::Mean), None);
+    }
+
     #[test]
     fn test_no_presence() {
         // إنشاء 12 إطار متطابقة تماماً
@@ -129,7 +168,7 @@ mod tests {
         }
         
         let mut results = DetectionResults::default();
-        detect_presence(&frames, &mut results);
+        detect_presence(&frames, Aggregation::Mean, PRESENCE_WINDOW_SIZE, PRESENCE_SUBCARRIER_RATIO, &mut results);
         // لا يوجد تغيرات = لا يوجد وجود
         assert!(!results.human_present || results.presence_value < HUMAN_PRESENCE_MIN);
     }
@@ -1,14 +1,21 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 // 📦 detectors/mod.rs - Activity Detection Module
 // ═══════════════════════════════════════════════════════════════════════════════
-// كشف النشاط باستخدام بيانات CSI (الحركة، الوجود البشري، فتح الباب)
-// Activity detection using CSI data (motion, human presence, door)
+// كشف النشاط باستخدام بيانات CSI (الحركة، الوجود البشري، فتح الباب، التنفس)
+// Activity detection using CSI data (motion, human presence, door, breathing)
 // ═══════════════════════════════════════════════════════════════════════════════
 
 mod motion;
 mod human;
 mod door;
+mod breathing;
 
+use std::borrow::Cow;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CsiError;
 use crate::state::{CsiFrame, DetectionResults};
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -27,12 +34,371 @@ pub struct SubcarrierInfo {
     pub analysis_range: (usize, usize),
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Primary Detector / الكاشف الأساسي
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// الكاشف الذي يُعرض نطاق تحليله في لوحة الإحصائيات
+/// Detector whose analysis range is shown in the statistics panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PrimaryDetector {
+    #[default]
+    Motion,
+    Presence,
+    Door,
+}
+
+impl PrimaryDetector {
+    /// نسبة الـ Subcarriers الافتراضية لهذا الكاشف
+    /// Default subcarrier ratio of this detector
+    pub fn ratio(self) -> f64 {
+        match self {
+            PrimaryDetector::Motion => motion::MOTION_SUBCARRIER_RATIO,
+            PrimaryDetector::Presence => human::PRESENCE_SUBCARRIER_RATIO,
+            PrimaryDetector::Door => door::DOOR_SUBCARRIER_RATIO,
+        }
+    }
+
+    /// اسم الكاشف للعرض
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            PrimaryDetector::Motion => "Motion",
+            PrimaryDetector::Presence => "Presence",
+            PrimaryDetector::Door => "Door",
+        }
+    }
+
+    /// جميع الكاشفات بترتيب العرض
+    /// All detectors in display order
+    pub const ALL: [PrimaryDetector; 3] = [PrimaryDetector::Motion, PrimaryDetector::Presence, PrimaryDetector::Door];
+
+    /// الكاشف التالي في الدورة
+    /// Next detector in the cycle
+    pub fn next(self) -> Self {
+        match self {
+            PrimaryDetector::Motion => PrimaryDetector::Presence,
+            PrimaryDetector::Presence => PrimaryDetector::Door,
+            PrimaryDetector::Door => PrimaryDetector::Motion,
+        }
+    }
+}
+
+impl FromStr for PrimaryDetector {
+    type Err = CsiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "motion" => Ok(PrimaryDetector::Motion),
+            "presence" | "human" => Ok(PrimaryDetector::Presence),
+            "door" => Ok(PrimaryDetector::Door),
+            other => Err(CsiError::InvalidInput(format!(
+                "unknown detector '{}' (expected motion, presence or door)", other
+            ))),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Aggregation Mode / وضع التجميع
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// نسبة الحذف من كل طرف في المتوسط المقتطع
+/// Fraction dropped from each end by the trimmed mean
+pub const TRIM_FRACTION: f64 = 0.10;
+
+/// طريقة تلخيص سعات الإطار في قيمة واحدة
+/// How a frame's magnitudes are summarized into one value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    Median,
+    TrimmedMean,
+}
+
+impl Aggregation {
+    /// تطبيق طريقة التجميع
+    /// Summarize magnitudes with this mode
+    pub fn apply(self, mags: &[f64]) -> f64 {
+        match self {
+            Aggregation::Mean => average_magnitude(mags),
+            Aggregation::Median => median_magnitude(mags),
+            Aggregation::TrimmedMean => trimmed_mean_magnitude(mags),
+        }
+    }
+
+    /// اسم الوضع للعرض
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            Aggregation::Mean => "Mean",
+            Aggregation::Median => "Median",
+            Aggregation::TrimmedMean => "Trimmed Mean",
+        }
+    }
+
+    /// الوضع التالي في الدورة
+    /// Next mode in the cycle
+    pub fn next(self) -> Self {
+        match self {
+            Aggregation::Mean => Aggregation::Median,
+            Aggregation::Median => Aggregation::TrimmedMean,
+            Aggregation::TrimmedMean => Aggregation::Mean,
+        }
+    }
+}
+
+impl FromStr for Aggregation {
+    type Err = CsiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mean" => Ok(Aggregation::Mean),
+            "median" => Ok(Aggregation::Median),
+            "trimmed" | "trimmed-mean" => Ok(Aggregation::TrimmedMean),
+            other => Err(CsiError::InvalidInput(format!(
+                "unknown aggregation '{}' (expected mean, median or trimmed)", other
+            ))),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Detector Windows / نوافذ الكاشفات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// الانحرافات المعيارية المسموحة حول وسيط الإطار قبل قص السعة
+/// Standard deviations around the frame median before a magnitude is clamped
+pub const DEFAULT_OUTLIER_SIGMA: f64 = 4.0;
+
+/// معامل تحويل الانحراف المطلق الوسيط إلى انحراف معياري
+/// Scales the median absolute deviation to a standard deviation
+pub const MAD_TO_SIGMA: f64 = 1.4826;
+
+/// أحجام نوافذ الكاشفات (عدد الإطارات) ونطاقات تحليلها
+/// Detector window sizes (in frames) and analysis ranges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectorWindows {
+    /// نافذة الحركة
+    /// Motion window
+    pub motion: usize,
+
+    /// نافذة الوجود البشري
+    /// Presence window
+    pub presence: usize,
+
+    /// إزاحة المقارنة لكشف الباب
+    /// Door comparison offset
+    pub door_offset: usize,
+
+    /// نسب الـ Subcarriers لكل كاشف
+    /// Subcarrier ratio of each detector
+    pub ratios: DetectorRatios,
+
+    /// قص السعات الأبعد من هذا العدد من الانحرافات المعيارية (0 = معطل)
+    /// Clamp magnitudes further than this many standard deviations (0 = off)
+    pub outlier_sigma: f64,
+}
+
+impl DetectorWindows {
+    /// جاهزية كل كاشف لعدد إطارات معين
+    /// Which detectors have enough history for `frame_count` frames
+    pub fn readiness(&self, frame_count: usize) -> DetectorReadiness {
+        DetectorReadiness {
+            motion: frame_count >= motion::motion_min_frames(self.motion),
+            presence: frame_count >= human::presence_min_frames(self.presence),
+            door: frame_count >= door::door_min_frames(self.door_offset),
+        }
+    }
+
+    /// الحد الأدنى للإطارات لكل كاشف (حركة، وجود، باب)
+    /// Minimum frames per detector (motion, presence, door)
+    pub fn min_frames(&self) -> (usize, usize, usize) {
+        (
+            motion::motion_min_frames(self.motion),
+            human::presence_min_frames(self.presence),
+            door::door_min_frames(self.door_offset),
+        )
+    }
+}
+
+impl Default for DetectorWindows {
+    fn default() -> Self {
+        Self {
+            motion: motion::MOTION_WINDOW_SIZE,
+            presence: human::PRESENCE_WINDOW_SIZE,
+            door_offset: door::DOOR_FRAME_OFFSET,
+            ratios: DetectorRatios::default(),
+            outlier_sigma: DEFAULT_OUTLIER_SIGMA,
+        }
+    }
+}
+
+/// خطوة تعديل النسبة وحدها الأدنى
+/// Ratio adjustment step and its lower limit
+pub const RATIO_STEP: f64 = 0.05;
+
+/// نسب الـ Subcarriers من المنتصف، قابلة للتعديل أثناء التشغيل
+/// Middle-subcarrier ratios, adjustable at runtime
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectorRatios {
+    pub motion: f64,
+    pub presence: f64,
+    pub door: f64,
+}
+
+impl DetectorRatios {
+    /// نسبة كاشف معين
+    /// Ratio of one detector
+    pub fn get(&self, detector: PrimaryDetector) -> f64 {
+        match detector {
+            PrimaryDetector::Motion => self.motion,
+            PrimaryDetector::Presence => self.presence,
+            PrimaryDetector::Door => self.door,
+        }
+    }
+
+    /// توسيع أو تضييق نطاق كاشف بخطوة واحدة ضمن [5%، 100%]
+    /// Widen or narrow one detector's range by a step, within [5%, 100%]
+    pub fn step(&mut self, detector: PrimaryDetector, wider: bool) {
+        let ratio = match detector {
+            PrimaryDetector::Motion => &mut self.motion,
+            PrimaryDetector::Presence => &mut self.presence,
+            PrimaryDetector::Door => &mut self.door,
+        };
+        let delta = if wider { RATIO_STEP } else { -RATIO_STEP };
+        *ratio = ((*ratio + delta) / RATIO_STEP).round() * RATIO_STEP;
+        *ratio = ratio.clamp(RATIO_STEP, 1.0);
+    }
+
+    /// نطاق تحليل كاشف لعدد ناقلات معين
+    /// One detector's analysis range for `total_sc` subcarriers
+    pub fn range(&self, total_sc: usize, detector: PrimaryDetector) -> (usize, usize) {
+        get_subcarrier_info_with_ratio(total_sc, self.get(detector)).analysis_range
+    }
+}
+
+impl Default for DetectorRatios {
+    fn default() -> Self {
+        Self {
+            motion: PrimaryDetector::Motion.ratio(),
+            presence: PrimaryDetector::Presence.ratio(),
+            door: PrimaryDetector::Door.ratio(),
+        }
+    }
+}
+
+/// هل لدى كل كاشف تاريخ إطارات كافٍ؟
+/// Whether each detector had enough frame history to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DetectorReadiness {
+    pub motion: bool,
+    pub presence: bool,
+    pub door: bool,
+}
+
+impl DetectorReadiness {
+    /// جميع الكاشفات جاهزة
+    /// True once every detector is ready
+    pub fn all(&self) -> bool {
+        self.motion && self.presence && self.door
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Display Scale / مقياس العرض
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// القيمة المعروضة عند عتبة الكشف في مقياس العتبة
+/// Displayed value at a detector's threshold on the threshold scale
+pub const DISPLAY_THRESHOLD: f64 = 100.0;
+
+/// كيفية تحجيم قيم الكاشفات للعرض (اللوحة والرسم البياني)
+/// How detector values are scaled for display (panel and chart alike)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayScale {
+    /// كل كاشف نسبة إلى عتبته: 100 = عتبة الكشف
+    /// Each detector relative to its own threshold: 100 = detection threshold
+    #[default]
+    Threshold,
+
+    /// قيم الكاشفات كما تُسجل
+    /// Detector values as logged
+    Raw,
+}
+
+impl DisplayScale {
+    /// تحجيم نتائج الكشف للعرض
+    /// Scale detection results for display
+    pub fn apply(self, results: &DetectionResults) -> DisplayValues {
+        match self {
+            DisplayScale::Raw => DisplayValues {
+                motion: results.motion_value,
+                presence: results.presence_value,
+                door: results.door_value,
+            },
+            DisplayScale::Threshold => DisplayValues {
+                motion: results.motion_value / (motion::MOTION_THRESHOLD * motion::MOTION_DISPLAY_MULTIPLIER)
+                    * DISPLAY_THRESHOLD,
+                presence: results.presence_value / (human::HUMAN_PRESENCE_MIN * human::PRESENCE_DISPLAY_MULTIPLIER)
+                    * DISPLAY_THRESHOLD,
+                door: results.door_value / (door::DOOR_THRESHOLD * door::DOOR_DISPLAY_MULTIPLIER)
+                    * DISPLAY_THRESHOLD,
+            },
+        }
+    }
+
+    /// أدنى حد أعلى لمحور الرسم البياني
+    /// Smallest upper bound of the chart's Y axis
+    pub fn chart_floor(self) -> f64 {
+        match self {
+            DisplayScale::Threshold => 2.0 * DISPLAY_THRESHOLD,
+            DisplayScale::Raw => 500.0,
+        }
+    }
+
+    /// اسم المقياس للعرض
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayScale::Threshold => "100 = threshold",
+            DisplayScale::Raw => "raw",
+        }
+    }
+}
+
+impl FromStr for DisplayScale {
+    type Err = CsiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "threshold" => Ok(DisplayScale::Threshold),
+            "raw" => Ok(DisplayScale::Raw),
+            other => Err(CsiError::InvalidInput(format!(
+                "unknown display scale '{}' (expected threshold or raw)", other
+            ))),
+        }
+    }
+}
+
+/// قيم الكاشفات بعد التحجيم، كما تعرضها اللوحة والرسم البياني
+/// Detector values after scaling, as both the panel and the chart show them
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DisplayValues {
+    pub motion: f64,
+    pub presence: f64,
+    pub door: f64,
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Subcarrier Analysis / تحليل الموجات الحاملة الفرعية
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// تحديد معيار الواي فاي ونطاق التحليل بناءً على عدد الموجات الحاملة
-/// Determine WiFi standard and analysis range based on subcarrier count
+/// Determine WiFi standard and the primary detector's analysis range
 /// 
 /// # كيفية اختيار الـ Subcarriers / How subcarriers are selected:
 /// ```text
@@ -49,9 +415,9 @@ pub struct SubcarrierInfo {
 /// start = (64 - 16) / 2 = 24
 /// end = 24 + 16 = 40
 /// ```
-pub fn get_subcarrier_info(total_sc: usize) -> SubcarrierInfo {
-    // نستخدم نسبة الحركة كنسبة افتراضية للعرض في الواجهة
-    get_subcarrier_info_with_ratio(total_sc, motion::MOTION_SUBCARRIER_RATIO)
+pub fn get_subcarrier_info(total_sc: usize, primary: PrimaryDetector) -> SubcarrierInfo {
+    // نستخدم نسبة الكاشف الأساسي المختار للعرض في الواجهة
+    get_subcarrier_info_with_ratio(total_sc, primary.ratio())
 }
 
 /// تحديد معيار الواي فاي ونطاق التحليل مع نسبة محددة
@@ -66,11 +432,15 @@ pub(crate) fn get_subcarrier_info_with_ratio(total_sc: usize, ratio: f64) -> Sub
         _ => "Wi-Fi 6+ (80MHz+)",
     };
     
+    // حصر النسبة في (0, 1] حتى يبقى النطاق صالحاً
+    // Clamp the ratio to (0, 1] so the range is always valid
+    let ratio = if ratio.is_nan() { 1.0 } else { ratio.clamp(f64::MIN_POSITIVE, 1.0) };
+    
     // حساب نطاق التحليل بناءً على النسبة المحددة
     // Calculate analysis range based on specified ratio
     let analysis_count = ((total_sc as f64) * ratio).max(1.0) as usize;
     let start = (total_sc.saturating_sub(analysis_count)) / 2;
-    let end = start + analysis_count;
+    let end = (start + analysis_count).min(total_sc);
     
     SubcarrierInfo { 
         wifi_standard: wifi_standard.to_string(),
@@ -78,6 +448,22 @@ pub(crate) fn get_subcarrier_info_with_ratio(total_sc: usize, ratio: f64) -> Sub
     }
 }
 
+/// أقل عدد ناقلات في الإطار ليكون لنطاق المنتصف معنى
+/// Fewest subcarriers a frame needs before its middle range means anything
+pub const MIN_ANALYSIS_SUBCARRIERS: usize = 3;
+
+/// هل نطاق التحليل فارغ أو أصغر من أن يُحلل؟
+/// Whether an analysis range is empty or comes from a frame too small to analyze
+pub fn range_is_degenerate(total_sc: usize, (start, end): (usize, usize)) -> bool {
+    total_sc < MIN_ANALYSIS_SUBCARRIERS || end <= start
+}
+
+/// هل يمكن التحليل بهذه النسبة على إطار بهذا الحجم؟
+/// Whether a frame of `total_sc` subcarriers can be analyzed at `ratio`
+pub(crate) fn analysis_range_usable(total_sc: usize, ratio: f64) -> bool {
+    !range_is_degenerate(total_sc, get_subcarrier_info_with_ratio(total_sc, ratio).analysis_range)
+}
+
 /// الحصول على الـ Subcarriers بنسبة محددة من المنتصف
 /// Get subcarriers with specified ratio from middle
 pub(crate) fn get_subcarriers_with_ratio(mags: &[f64], ratio: f64) -> &[f64] {
@@ -99,11 +485,49 @@ pub(crate) fn get_subcarriers_with_ratio(mags: &[f64], ratio: f64) -> &[f64] {
 
 /// حساب متوسط السعات لمصفوفة من القيم
 /// Calculate average magnitude from an array of values
-pub(crate) fn average_magnitude(mags: &[f64]) -> f64 {
+pub fn average_magnitude(mags: &[f64]) -> f64 {
     if mags.is_empty() { return 0.0; }
     mags.iter().sum::<f64>() / mags.len() as f64
 }
 
+/// حساب وسيط السعات
+/// Median magnitude (robust to a few railed/dead subcarriers)
+pub fn median_magnitude(mags: &[f64]) -> f64 {
+    if mags.is_empty() { return 0.0; }
+    let sorted = sorted_copy(mags);
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    }
+}
+
+/// حساب المتوسط المقتطع (حذف أعلى وأدنى 10%)
+/// Trimmed mean magnitude (drops the top and bottom TRIM_FRACTION)
+pub fn trimmed_mean_magnitude(mags: &[f64]) -> f64 {
+    if mags.is_empty() { return 0.0; }
+    let sorted = sorted_copy(mags);
+    let trim = (sorted.len() as f64 * TRIM_FRACTION) as usize;
+    average_magnitude(&sorted[trim..sorted.len() - trim])
+}
+
+/// الإطارات بسعات مقصوصة القيم الشاذة؛ تُنسخ فقط إن لم تنظف بهذه العتبة
+/// The frames with outlier magnitudes clamped; copied only when they were
+/// not already cleaned at `sigma` (the app cleans each frame once on arrival)
+fn clean_frames(frames: &[CsiFrame], sigma: f64) -> Cow<'_, [CsiFrame]> {
+    if frames.iter().all(|f| f.is_cleaned_at(sigma)) { return Cow::Borrowed(frames); }
+    Cow::Owned(frames.iter().map(|f| f.clone().with_clean_mags(sigma)).collect())
+}
+
+/// نسخة مرتبة من السعات
+/// Sorted copy of the magnitudes
+fn sorted_copy(mags: &[f64]) -> Vec<f64> {
+    let mut sorted = mags.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    sorted
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Main Detection Function / دالة الكشف الرئيسية
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -117,22 +541,42 @@ pub(crate) fn average_magnitude(mags: &[f64]) -> f64 {
 /// 1. **الحركة / Motion**: تغيرات مفاجئة وكبيرة في السعات
 /// 2. **الوجود البشري / Human Presence**: تغيرات صغيرة ومستمرة
 /// 3. **فتح/إغلاق الباب / Door Open/Close**: تغيرات كبيرة مقارنة بإطارات سابقة
+/// 4. **التنفس / Breathing**: تذبذب دوري بطيء (0.1-0.5 هرتز) خلال آخر 30 ثانية
 pub fn quick_detect(frames: &[CsiFrame]) -> DetectionResults {
+    quick_detect_with(frames, Aggregation::Mean, &DetectorWindows::default()).0
+}
+
+/// الكشف السريع مع طريقة تجميع ونوافذ محددة
+/// Quick activity detection with a chosen aggregation mode and windows
+/// 
+/// تُرجع أيضاً جاهزية كل كاشف؛ الكاشف غير الجاهز يبقى بقيمه الافتراضية
+/// Also returns per-detector readiness; a detector that isn't ready keeps default values
+pub fn quick_detect_with(
+    frames: &[CsiFrame],
+    agg: Aggregation,
+    windows: &DetectorWindows,
+) -> (DetectionResults, DetectorReadiness) {
     let mut results = DetectionResults::default();
-    
-    // نحتاج على الأقل 3 إطارات للتحليل
-    if frames.len() < 3 { return results; }
+    let readiness = windows.readiness(frames.len());
+
+    // زوج تالف واحد لا يحرف الدرجات
+    // One corrupted pair must not skew the scores
+    let cleaned = clean_frames(frames, windows.outlier_sigma);
+    let frames = &cleaned[..];
 
     // كشف الحركة
-    motion::detect_motion(frames, &mut results);
+    motion::detect_motion(frames, agg, windows.motion, windows.ratios.motion, &mut results);
     
     // كشف الوجود البشري
-    human::detect_presence(frames, &mut results);
+    human::detect_presence(frames, agg, windows.presence, windows.ratios.presence, &mut results);
+    
+    // تقدير معدل التنفس
+    breathing::detect_breathing(frames, agg, &mut results);
     
     // كشف الباب
-    door::detect_door(frames, &mut results);
+    door::detect_door(frames, agg, windows.door_offset, windows.ratios.door, &mut results);
 
-    results
+    (results, readiness)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -147,7 +591,7 @@ mod tests {
     /// إنشاء إطار اختبار بسعات محددة
     pub fn create_test_frame(mags: Vec<f64>) -> CsiFrame {
         let pairs: Vec<(i32, i32)> = mags.iter().map(|&m| (m as i32, 0)).collect();
-        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly)
+        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly, None)
     }
 
     #[test]
@@ -172,6 +616,164 @@ mod tests {
         assert!(!results.motion_detected);
     }
 
+    #[test]
+    fn test_primary_detector_range() {
+        // 64 SC: motion 50% → [16-48], presence 35% → [21-43], door 25% → [24-40]
+        let expected = [
+            (PrimaryDetector::Motion, (16, 48)),
+            (PrimaryDetector::Presence, (21, 43)),
+            (PrimaryDetector::Door, (24, 40)),
+        ];
+        for (primary, range) in expected {
+            let info = get_subcarrier_info(64, primary);
+            assert_eq!(info.analysis_range, range, "{}", primary.label());
+            assert_eq!(info.analysis_range.1 - info.analysis_range.0,
+                (64.0 * primary.ratio()) as usize);
+        }
+        
+        // Runtime ratios start at the defaults and stay within [5%, 100%]
+        // النسب أثناء التشغيل تبدأ بالافتراضية وتبقى ضمن [5%، 100%]
+        let mut ratios = DetectorRatios::default();
+        assert_eq!(ratios.range(64, PrimaryDetector::Presence), (21, 43));
+        for _ in 0..30 {
+            ratios.step(PrimaryDetector::Motion, true);
+            ratios.step(PrimaryDetector::Door, false);
+        }
+        assert_eq!((ratios.motion, ratios.door), (1.0, RATIO_STEP));
+        assert_eq!(ratios.range(64, PrimaryDetector::Motion), (0, 64));
+
+        assert_eq!("door".parse::<PrimaryDetector>().unwrap(), PrimaryDetector::Door);
+        assert!("nope".parse::<PrimaryDetector>().is_err());
+    }
+
+    #[test]
+    fn test_corrupted_pair_does_not_trigger_motion() {
+        // Steady frames, the newest with one huge value mid-band / إطارات ثابتة وآخرها بقيمة ضخمة
+        let shape: Vec<f64> = (0..32).map(|i| 20.0 + (i % 4) as f64).collect();
+        let mut frames: Vec<CsiFrame> = (0..20).map(|_| create_test_frame(shape.clone())).collect();
+        let mut corrupted = shape.clone();
+        corrupted[16] = 90_000.0;
+        frames.push(create_test_frame(corrupted));
+
+        let raw = DetectorWindows { outlier_sigma: 0.0, ..DetectorWindows::default() };
+        assert!(quick_detect_with(&frames, Aggregation::Mean, &raw).0.motion_detected);
+
+        let (results, _) = quick_detect_with(&frames, Aggregation::Mean, &DetectorWindows::default());
+        assert!(!results.motion_detected);
+        assert!(!results.door_open);
+        assert_eq!(frames[20].mags[16], 90_000.0);
+    }
+
+    #[test]
+    fn test_robust_aggregation_with_saturated_subcarriers() {
+        // 20 subcarriers at 20.0, two railed at 127 / ناقلان مشبعان
+        let mut mags = vec![20.0; 20];
+        mags[3] = 127.0;
+        mags[11] = 127.0;
+
+        let mean = Aggregation::Mean.apply(&mags);
+        assert!((mean - 30.7).abs() < 1e-9, "mean is biased upwards");
+        assert!((Aggregation::Median.apply(&mags) - 20.0).abs() < 1e-9);
+        assert!((Aggregation::TrimmedMean.apply(&mags) - 20.0).abs() < 1e-9);
+
+        assert_eq!("trimmed".parse::<Aggregation>().unwrap(), Aggregation::TrimmedMean);
+        assert_eq!(Aggregation::Median.apply(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_analysis_range_ratio_clamped() {
+        // Ratio above 1.0 → whole band, never past the end
+        assert_eq!(get_subcarrier_info_with_ratio(64, 1.8).analysis_range, (0, 64));
+        assert_eq!(get_subcarrier_info_with_ratio(64, f64::NAN).analysis_range, (0, 64));
+
+        // Ratio near or below 0 → a single middle subcarrier
+        assert_eq!(get_subcarrier_info_with_ratio(64, 1e-9).analysis_range, (31, 32));
+        assert_eq!(get_subcarrier_info_with_ratio(64, -0.5).analysis_range, (31, 32));
+
+        // No subcarriers → empty range / لا توجد ناقلات ← نطاق فارغ
+        assert_eq!(get_subcarrier_info_with_ratio(0, 0.5).analysis_range, (0, 0));
+        assert_eq!(get_subcarriers_with_ratio(&[1.0, 2.0], 3.0).len(), 2);
+    }
+
+    #[test]
+    fn test_empty_and_single_subcarrier_inputs() {
+        // Empty and single-value slices / شرائح فارغة وأحادية القيمة
+        for primary in [PrimaryDetector::Motion, PrimaryDetector::Presence, PrimaryDetector::Door] {
+            let info = get_subcarrier_info(0, primary);
+            assert_eq!(info.wifi_standard, "Unknown");
+            assert_eq!(info.analysis_range, (0, 0));
+            assert_eq!(get_subcarrier_info(1, primary).analysis_range, (0, 1));
+            assert!(get_subcarriers_with_ratio(&[], primary.ratio()).is_empty());
+            assert_eq!(get_subcarriers_with_ratio(&[5.0], primary.ratio()), &[5.0]);
+        }
+
+        // Detectors on zero- and one-subcarrier frames stay quiet
+        // الكاشفات على إطارات بلا ناقلات أو بناقل واحد تبقى هادئة
+        let empty: Vec<CsiFrame> = (0..20).map(|_| create_test_frame(vec![])).collect();
+        let results = quick_detect(&empty);
+        assert!(!results.motion_detected && !results.human_present && !results.door_open);
+        assert_eq!(results.motion_value, 0.0);
+        let single: Vec<CsiFrame> = (0..20).map(|_| create_test_frame(vec![5.0])).collect();
+        assert!(!quick_detect(&single).motion_detected);
+    }
+
+    #[test]
+    fn test_degenerate_ranges_are_skipped() {
+        // 1 and 2 subcarriers are too small for any detector; 3 is enough
+        // ناقل أو ناقلان أصغر من أي كاشف؛ ثلاثة تكفي
+        for (total_sc, degenerate) in [(1, true), (2, true), (3, false)] {
+            for primary in PrimaryDetector::ALL {
+                let range = get_subcarrier_info(total_sc, primary).analysis_range;
+                assert_eq!(range_is_degenerate(total_sc, range), degenerate, "{} SC, {:?}", total_sc, primary);
+                assert_eq!(analysis_range_usable(total_sc, primary.ratio()), !degenerate);
+            }
+        }
+        assert!(range_is_degenerate(64, (10, 10)));
+
+        // Large swings on 2 subcarriers report nothing / تقلبات كبيرة على ناقلين لا تُبلغ عن شيء
+        let frames: Vec<CsiFrame> = (0..20)
+            .map(|i| create_test_frame(vec![if i % 2 == 0 { 10.0 } else { 200.0 }; 2]))
+            .collect();
+        let results = quick_detect(&frames);
+        assert!(!results.motion_detected && !results.human_present && !results.door_open);
+        assert_eq!((results.motion_value, results.presence_value, results.door_value), (0.0, 0.0, 0.0));
+
+        // The same swings on 3 subcarriers are analyzed / نفس التقلبات على ثلاثة ناقلات تُحلل
+        let frames: Vec<CsiFrame> = (0..20)
+            .map(|i| create_test_frame(vec![if i % 2 == 0 { 10.0 } else { 200.0 }; 3]))
+            .collect();
+        assert!(quick_detect(&frames).motion_value > 0.0);
+    }
+
+    #[test]
+    fn test_readiness_follows_window_sizes() {
+        let frames: Vec<CsiFrame> = (0..8)
+            .map(|i| create_test_frame(vec![10.0 + i as f64; 4]))
+            .collect();
+
+        // Defaults: motion 3, presence 12, door 6
+        let defaults = DetectorWindows::default();
+        assert_eq!(defaults.min_frames(), (3, 12, 6));
+        let (_, ready) = quick_detect_with(&frames, Aggregation::Mean, &defaults);
+        assert_eq!(ready, DetectorReadiness { motion: true, presence: false, door: true });
+
+        // Smaller presence window → ready with the same history
+        let small = DetectorWindows { presence: 8, ..defaults };
+        assert_eq!(small.min_frames().1, 8);
+        let (results, ready) = quick_detect_with(&frames, Aggregation::Mean, &small);
+        assert!(ready.all());
+        assert!(results.presence_value > 0.0);
+
+        // Larger windows → more frames required
+        let large = DetectorWindows { motion: 10, presence: 20, door_offset: 9, ..defaults };
+        assert_eq!(large.min_frames(), (10, 20, 10));
+        assert_eq!(large.readiness(9), DetectorReadiness::default());
+
+        // Windows below each detector's floor are raised to it
+        let tiny = DetectorWindows { motion: 0, presence: 0, door_offset: 0, ..defaults };
+        assert_eq!(tiny.min_frames(), (3, 2, 2));
+    }
+
     #[test]
     fn test_average_magnitude() {
         let mags = vec![10.0, 20.0, 30.0];
@@ -6,7 +6,7 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 
 use crate::state::{CsiFrame, DetectionResults};
-use super::{get_subcarriers_with_ratio, average_magnitude};
+use super::{analysis_range_usable, get_subcarriers_with_ratio, Aggregation};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Constants / الثوابت
@@ -16,6 +16,10 @@ use super::{get_subcarriers_with_ratio, average_magnitude};
 /// Motion detection threshold - values above this indicate motion
 pub const MOTION_THRESHOLD: f64 = 42.0;
 
+/// حجم نافذة كشف الحركة الافتراضي (عدد الإطارات)
+/// Default motion window size (number of frames)
+pub const MOTION_WINDOW_SIZE: usize = 3;
+
 /// نسبة الـ Subcarriers المستخدمة لكشف الحركة (50% من المنتصف)
 /// Percentage of middle subcarriers for motion detection (50%)
 pub const MOTION_SUBCARRIER_RATIO: f64 = 0.50;
@@ -28,37 +32,32 @@ pub const MOTION_DISPLAY_MULTIPLIER: f64 = 5.0;
 // 🔹 Helper Functions / دوال مساعدة
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// استخراج الـ Subcarriers لكشف الحركة (50% من المنتصف)
-/// Extract subcarriers for motion detection (50% from middle)
-fn get_motion_subcarriers(mags: &[f64]) -> &[f64] {
-    get_subcarriers_with_ratio(mags, MOTION_SUBCARRIER_RATIO)
+/// استخراج الـ Subcarriers لكشف الحركة (النسبة من المنتصف، 50% افتراضياً)
+/// Extract subcarriers for motion detection (`ratio` of the middle, 50% by default)
+fn get_motion_subcarriers(mags: &[f64], ratio: f64) -> &[f64] {
+    get_subcarriers_with_ratio(mags, ratio)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Detection Function / دالة الكشف
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// كشف الحركة من إطارات CSI
-/// Detect motion from CSI frames
+/// درجة الحركة من صفوف السعات (نطاق التحليل) لآخر 3 إطارات
+/// Motion score from analysis-range magnitude rows of the last 3 frames
 /// 
 /// # Algorithm / الخوارزمية
 /// ```text
-/// - مقارنة آخر 3 إطارات
+/// - مقارنة آخر 3 صفوف (الأقدم أولاً)
 /// - حساب: max_diff * 0.4 + avg_diff * 0.3 + sudden_changes bonus
 /// - إذا > MOTION_THRESHOLD = حركة مكتشفة
 /// ```
-pub fn detect_motion(frames: &[CsiFrame], results: &mut DetectionResults) {
-    if frames.len() < 3 { return; }
+pub fn motion_score(rows: &[&[f64]], agg: Aggregation) -> f64 {
+    if rows.len() < 3 { return 0.0; }
 
-    // الحصول على آخر 3 إطارات للمقارنة
-    let last = &frames[frames.len() - 1];
-    let prev = &frames[frames.len() - 2];
-    let prev2 = &frames[frames.len() - 3];
-    
-    // استخراج الـ Subcarriers لكشف الحركة (50% من المنتصف)
-    let last_mags = get_motion_subcarriers(&last.mags);
-    let prev_mags = get_motion_subcarriers(&prev.mags);
-    let prev2_mags = get_motion_subcarriers(&prev2.mags);
+    // آخر 3 صفوف للمقارنة
+    let last_mags = rows[rows.len() - 1];
+    let prev_mags = rows[rows.len() - 2];
+    let prev2_mags = rows[rows.len() - 3];
     
     // الحد الأدنى لعدد الموجات الحاملة المشتركة
     let sc_count = last_mags.len().min(prev_mags.len()).min(prev2_mags.len());
@@ -83,8 +82,8 @@ pub fn detect_motion(frames: &[CsiFrame], results: &mut DetectionResults) {
     }
     
     // حساب درجة الحركة النهائية
-    let last_avg = average_magnitude(last_mags);
-    let prev_avg = average_magnitude(prev_mags);
+    let last_avg = agg.apply(last_mags);
+    let prev_avg = agg.apply(prev_mags);
     let avg_diff = (last_avg - prev_avg).abs();
     
     // المعادلة: 40% أقصى فرق + 30% متوسط الفروقات + 30% فرق المتوسطات
@@ -92,7 +91,28 @@ pub fn detect_motion(frames: &[CsiFrame], results: &mut DetectionResults) {
     
     // مكافأة إضافية إذا كان هناك أكثر من 5 تغيرات مفاجئة
     let sc_bonus = if sudden_changes > 5 { 1.5 } else { 1.0 };
-    let final_motion = motion_score * sc_bonus;
+    motion_score * sc_bonus
+}
+
+/// الحد الأدنى لعدد الإطارات لنافذة حركة معينة
+/// Frames needed before motion can be scored with this window
+pub fn motion_min_frames(window: usize) -> usize {
+    window.max(MOTION_WINDOW_SIZE)
+}
+
+/// كشف الحركة من إطارات CSI
+/// Detect motion from the last `window` CSI frames (scored on the newest three)
+pub fn detect_motion(frames: &[CsiFrame], agg: Aggregation, window: usize, ratio: f64, results: &mut DetectionResults) {
+    let window = motion_min_frames(window);
+    if frames.len() < window { return; }
+    if !analysis_range_usable(frames[frames.len() - 1].mags.len(), ratio) { return; }
+
+    // استخراج الـ Subcarriers لكشف الحركة (50% من المنتصف) لآخر 3 إطارات
+    let rows: Vec<&[f64]> = frames[frames.len() - window..]
+        .iter()
+        .map(|f| get_motion_subcarriers(f.analysis_mags(), ratio))
+        .collect();
+    let final_motion = motion_score(&rows, agg);
     
     results.motion_value = final_motion * MOTION_DISPLAY_MULTIPLIER;
     results.motion_detected = final_motion > MOTION_THRESHOLD;
@@ -109,7 +129,7 @@ mod tests {
 
     fn create_test_frame(mags: Vec<f64>) -> CsiFrame {
         let pairs: Vec<(i32, i32)> = mags.iter().map(|&m| (m as i32, 0)).collect();
-        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly)
+        CsiFrame::new(0, mags, pairs, CsiFormat::AmplitudeOnly, None)
     }
 
     #[test]
@@ -120,10 +140,24 @@ mod tests {
             create_test_frame(vec![50.0, 50.0, 50.0]),
         ];
         let mut results = DetectionResults::default();
-        detect_motion(&frames, &mut results);
+        detect_motion(&frames, Aggregation::Mean, MOTION_WINDOW_SIZE, MOTION_SUBCARRIER_RATIO, &mut results);
         assert!(results.motion_detected);
     }
 
+    #[test]
+    fn test_motion_score() {
+        // max 30 * 0.4 + mean 40 * 0.3 + avg diff 30 * 0.3 = 33, no bonus
+        let rows: [&[f64]; 3] = [&[10.0, 10.0], &[20.0, 20.0], &[50.0, 50.0]];
+        assert!((motion_score(&rows, Aggregation::Mean) - 33.0).abs() < 1e-9);
+
+        // More than 5 sudden changes → ×1.5
+        let rows: [&[f64]; 3] = [&[0.0; 6], &[0.0; 6], &[1.0; 6]];
+        assert!((motion_score(&rows, Aggregation::Mean) - 1.5).abs() < 1e-9);
+
+        // Too few rows / صفوف غير كافية
+        assert_eq!(motion_score(&rows[..2], Aggregation::Mean), 0.0);
+    }
+
     #[test]
     fn test_no_motion() {
         let frames = vec![
@@ -132,7 +166,7 @@ mod tests {
             create_test_frame(vec![11.0, 11.0, 11.0]),
         ];
         let mut results = DetectionResults::default();
-        detect_motion(&frames, &mut results);
+        detect_motion(&frames, Aggregation::Mean, MOTION_WINDOW_SIZE, MOTION_SUBCARRIER_RATIO, &mut results);
         assert!(!results.motion_detected);
     }
 }
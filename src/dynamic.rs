@@ -0,0 +1,152 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 dynamic.rs - Dynamic Component Filter
+// ═══════════════════════════════════════════════════════════════════════════════
+// Separates the dynamic response from the static multipath profile.
+// Features:
+// - Rolling median of the per-frame average magnitude over a time window
+// - Residual series (average - median) centered at zero for charting
+// - Incremental: one sorted insert/remove per frame, no full re-sort
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Default rolling median window in seconds / نافذة الوسيط الافتراضية بالثواني
+pub const DEFAULT_MEDIAN_WINDOW_SECS: f64 = 10.0;
+
+/// Maximum residual samples kept for the chart / أقصى عدد عينات متبقية للرسم
+const MAX_RESIDUALS: usize = 10_000;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Dynamic Filter / مرشح المكون الديناميكي
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rolling-median filter producing the dynamic component of the signal
+/// مرشح وسيط متحرك ينتج المكون الديناميكي للإشارة
+#[derive(Debug, Clone)]
+pub struct DynamicFilter {
+    /// Median window length in milliseconds / طول نافذة الوسيط بالميلي ثانية
+    window_ms: i64,
+
+    /// Samples inside the window, oldest first / العينات داخل النافذة، الأقدم أولاً
+    window: VecDeque<(i64, f64)>,
+
+    /// Same samples kept sorted for the median / نفس العينات مرتبة لحساب الوسيط
+    sorted: Vec<f64>,
+
+    /// Residual per pushed frame, oldest first / القيمة المتبقية لكل إطار، الأقدم أولاً
+    residuals: VecDeque<f64>,
+}
+
+impl DynamicFilter {
+    /// Create a filter with the given median window
+    /// إنشاء مرشح بنافذة وسيط محددة
+    pub fn new(window_secs: f64) -> Self {
+        Self {
+            window_ms: ((window_secs * 1000.0) as i64).max(1),
+            window: VecDeque::new(),
+            sorted: Vec::new(),
+            residuals: VecDeque::new(),
+        }
+    }
+
+    /// Add a frame's average magnitude and return its residual
+    /// إضافة متوسط سعة الإطار وإرجاع القيمة المتبقية
+    pub fn push(&mut self, timestamp: i64, value: f64) -> f64 {
+        self.window.push_back((timestamp, value));
+        let at = self.sorted.partition_point(|&v| v < value);
+        self.sorted.insert(at, value);
+
+        // Drop samples that fell out of the window
+        // حذف العينات التي خرجت من النافذة
+        let cutoff = timestamp - self.window_ms;
+        while let Some(&(ts, old)) = self.window.front() {
+            if ts > cutoff {
+                break;
+            }
+            self.window.pop_front();
+            let at = self.sorted.partition_point(|&v| v < old);
+            self.sorted.remove(at);
+        }
+
+        let residual = value - self.median();
+        self.residuals.push_back(residual);
+        if self.residuals.len() > MAX_RESIDUALS {
+            self.residuals.pop_front();
+        }
+        residual
+    }
+
+    /// Median of the current window / وسيط النافذة الحالية
+    pub fn median(&self) -> f64 {
+        let n = self.sorted.len();
+        match n {
+            0 => 0.0,
+            _ if n % 2 == 1 => self.sorted[n / 2],
+            _ => (self.sorted[n / 2 - 1] + self.sorted[n / 2]) / 2.0,
+        }
+    }
+
+    /// Last `count` residuals, oldest first / آخر `count` قيمة متبقية، الأقدم أولاً
+    pub fn last_residuals(&self, count: usize) -> impl Iterator<Item = f64> + '_ {
+        self.residuals.iter().skip(self.residuals.len().saturating_sub(count)).copied()
+    }
+
+    /// Forget all samples (new session or seek) / نسيان جميع العينات
+    pub fn reset(&mut self) {
+        self.window.clear();
+        self.sorted.clear();
+        self.residuals.clear();
+    }
+}
+
+impl Default for DynamicFilter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MEDIAN_WINDOW_SECS)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_level_gives_zero_residual() {
+        let mut filter = DynamicFilter::new(1.0);
+        for i in 0..50 {
+            filter.push(i * 100, 40.0);
+        }
+        assert!(filter.last_residuals(50).all(|r| r.abs() < f64::EPSILON));
+    }
+
+    #[test]
+    fn test_spike_stands_out_from_median() {
+        let mut filter = DynamicFilter::new(1.0);
+        for i in 0..9 {
+            filter.push(i * 100, 40.0);
+        }
+        let residual = filter.push(900, 55.0);
+        assert!((residual - 15.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_window_evicts_old_samples() {
+        let mut filter = DynamicFilter::new(1.0);
+        for i in 0..10 {
+            filter.push(i * 100, 10.0);
+        }
+        // Level shift: after one full window the median follows it
+        for i in 10..30 {
+            filter.push(i * 100, 50.0);
+        }
+        assert_eq!(filter.median(), 50.0);
+        assert_eq!(filter.window.len(), filter.sorted.len());
+    }
+}
@@ -0,0 +1,235 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 error.rs - Application Error Type
+// ═══════════════════════════════════════════════════════════════════════════════
+// Crate-wide error enum shared by the reader, loaders, logger, app and menu.
+// Features:
+// - Distinguishes port missing / port busy / parse / CSV / terminal failures
+// - Maps each variant to a status message and suggested action in one place
+// - Derives process exit codes for CLI subcommands
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::io;
+use std::path::PathBuf;
+use std::sync::PoisonError;
+
+use thiserror::Error;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Error Enum / تعداد الأخطاء
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// All errors produced by csi-tui
+/// جميع الأخطاء الناتجة عن csi-tui
+#[derive(Debug, Error)]
+pub enum CsiError {
+    /// Serial port does not exist / المنفذ التسلسلي غير موجود
+    #[error("serial port {port} not found")]
+    PortNotFound { port: String },
+
+    /// Serial port is open elsewhere or access denied / المنفذ مستخدم أو الوصول مرفوض
+    #[error("serial port {port} is busy or access was denied")]
+    PortBusy { port: String },
+
+    /// Other failure while opening the port / فشل آخر أثناء فتح المنفذ
+    #[error("failed to open {port}: {message}")]
+    SerialOpen { port: String, message: String },
+
+    /// Read/write failure on an open port / فشل قراءة/كتابة على منفذ مفتوح
+    #[error("serial I/O error: {0}")]
+    SerialIo(#[source] io::Error),
+
+    /// UDP socket could not be bound or read / تعذر ربط مقبس UDP أو القراءة منه
+    #[error("UDP {addr}: {source}")]
+    Network { addr: String, #[source] source: io::Error },
+
+    /// TCP bridge could not be reached or dropped / تعذر الوصول إلى جسر TCP أو انقطع
+    #[error("TCP bridge {addr}: {source}")]
+    Tcp { addr: String, #[source] source: io::Error },
+
+    /// Serial reader thread is already running / خيط القارئ يعمل بالفعل
+    #[error("serial reader already running")]
+    ReaderRunning,
+
+    /// Malformed CSI data or CSV row / بيانات CSI أو صف CSV تالف
+    #[error("parse error: {0}")]
+    Parse(String),
+
+    /// CSV file could not be loaded / تعذر تحميل ملف CSV
+    #[error("CSV load error: {0}")]
+    CsvLoad(String),
+
+    /// CSV file could not be written / تعذرت كتابة ملف CSV
+    #[error("CSV log error: {0}")]
+    CsvLog(String),
+
+    /// File that was used before is gone / الملف المستخدم سابقاً لم يعد موجوداً
+    #[error("file no longer exists: {}", .0.display())]
+    FileMissing(PathBuf),
+
+    /// Reload requested before any CSV was loaded / طلب إعادة تحميل قبل تحميل أي ملف
+    #[error("no CSV loaded yet")]
+    NoCsvLoaded,
+
+    /// File dialog was cancelled / تم إلغاء نافذة اختيار الملف
+    #[error("no file selected")]
+    NoFileSelected,
+
+    /// Integrity sidecar could not be read or written / تعذرت قراءة/كتابة ملف السلامة
+    #[error("integrity error: {0}")]
+    Integrity(String),
+
+    /// Shared state mutex was poisoned / قفل الحالة المشتركة تالف
+    #[error("failed to lock state: {0}")]
+    StateLock(String),
+
+    /// Terminal setup, input or drawing failed / فشل إعداد الطرفية أو الإدخال أو الرسم
+    #[error("terminal error: {0}")]
+    Terminal(#[source] io::Error),
+
+    /// Invalid user input or command-line usage / إدخال مستخدم أو استخدام غير صالح
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Key script could not be read, parsed or recorded / تعذرت قراءة أو تحليل أو تسجيل نص المفاتيح
+    #[error("key script error: {0}")]
+    KeyScript(String),
+
+    /// Report bundle could not be written / تعذرت كتابة حزمة التقرير
+    #[error("report error: {0}")]
+    Report(String),
+
+    /// Saved settings could not be written / تعذرت كتابة الإعدادات المحفوظة
+    #[error("config error: {0}")]
+    Config(String),
+
+    /// Frame export could not be written / تعذرت كتابة تصدير الإطارات
+    #[error("export error: {0}")]
+    Export(String),
+
+    /// Calibration file could not be read or parsed / تعذرت قراءة أو تحليل ملف المعايرة
+    #[error("calibration error: {0}")]
+    Calibration(String),
+}
+
+/// Result alias used across the crate / اسم مستعار للنتيجة عبر المشروع
+pub type Result<T> = std::result::Result<T, CsiError>;
+
+impl<T> From<PoisonError<T>> for CsiError {
+    fn from(e: PoisonError<T>) -> Self {
+        CsiError::StateLock(e.to_string())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Classification & Presentation / التصنيف والعرض
+// ═══════════════════════════════════════════════════════════════════════════════
+
+impl CsiError {
+    /// Classify a serialport open error / تصنيف خطأ فتح المنفذ التسلسلي
+    #[cfg(feature = "tui")]
+    pub fn from_serial(port: &str, e: serialport::Error) -> Self {
+        let port = port.to_string();
+        match e.kind() {
+            serialport::ErrorKind::NoDevice
+            | serialport::ErrorKind::Io(io::ErrorKind::NotFound) => CsiError::PortNotFound { port },
+            serialport::ErrorKind::Io(io::ErrorKind::PermissionDenied) => CsiError::PortBusy { port },
+            _ => CsiError::SerialOpen { port, message: e.to_string() },
+        }
+    }
+
+    /// Suggested recovery action for the user / الإجراء المقترح للمستخدم
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            CsiError::PortNotFound { .. } => Some("check the cable or pick another port"),
+            CsiError::PortBusy { .. } => Some("close other serial monitors and press S to retry"),
+            CsiError::SerialOpen { .. } | CsiError::SerialIo(_) => Some("press S to retry"),
+            CsiError::Network { .. } => Some("check the address and that nothing else uses the port"),
+            CsiError::Tcp { .. } => Some("check that the bridge (e.g. socat) is running"),
+            CsiError::ReaderRunning => Some("press X to stop it first"),
+            CsiError::Parse(_) | CsiError::CsvLoad(_) => {
+                Some("expected header timestamp[,rssi][,mac],r0,i0,r1,i1,...")
+            }
+            CsiError::FileMissing(_) | CsiError::NoCsvLoaded => Some("press L to pick a file"),
+            CsiError::KeyScript(_) => Some("expected lines '<offset_ms> <key>'"),
+            CsiError::Calibration(_) => Some("expected lines 'index,gain,phase'"),
+            CsiError::CsvLog(_)
+            | CsiError::Integrity(_)
+            | CsiError::Report(_)
+            | CsiError::Config(_)
+            | CsiError::Export(_) => Some("check disk space and permissions"),
+            CsiError::NoFileSelected
+            | CsiError::StateLock(_)
+            | CsiError::Terminal(_)
+            | CsiError::InvalidInput(_) => None,
+        }
+    }
+
+    /// One-line status message for the TUI / رسالة حالة من سطر واحد للواجهة
+    pub fn status_message(&self) -> String {
+        match self.suggestion() {
+            Some(hint) => format!("❌ {} - {}", self, hint),
+            None => format!("❌ {}", self),
+        }
+    }
+
+    /// Process exit code for CLI subcommands / رمز الخروج لأوامر سطر الأوامر
+    ///
+    /// 2 usage, 3 serial, 4 input data, 5 output files, 1 anything else.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CsiError::InvalidInput(_) => 2,
+            CsiError::PortNotFound { .. }
+            | CsiError::PortBusy { .. }
+            | CsiError::SerialOpen { .. }
+            | CsiError::SerialIo(_)
+            | CsiError::Network { .. }
+            | CsiError::Tcp { .. }
+            | CsiError::ReaderRunning => 3,
+            CsiError::Parse(_)
+            | CsiError::CsvLoad(_)
+            | CsiError::FileMissing(_)
+            | CsiError::NoCsvLoaded
+            | CsiError::NoFileSelected
+            | CsiError::KeyScript(_)
+            | CsiError::Calibration(_) => 4,
+            CsiError::CsvLog(_)
+            | CsiError::Integrity(_)
+            | CsiError::Report(_)
+            | CsiError::Config(_)
+            | CsiError::Export(_) => 5,
+            CsiError::StateLock(_) | CsiError::Terminal(_) => 1,
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "tui")]
+    fn test_serial_classification() {
+        let missing = serialport::Error::new(serialport::ErrorKind::NoDevice, "gone");
+        assert!(matches!(CsiError::from_serial("COM9", missing), CsiError::PortNotFound { .. }));
+
+        let busy = serialport::Error::new(
+            serialport::ErrorKind::Io(io::ErrorKind::PermissionDenied),
+            "Access is denied",
+        );
+        let err = CsiError::from_serial("COM3", busy);
+        assert!(matches!(err, CsiError::PortBusy { .. }));
+        assert!(err.status_message().contains("press S to retry"));
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(CsiError::InvalidInput("x".into()).exit_code(), 2);
+        assert_eq!(CsiError::PortNotFound { port: "COM3".into() }.exit_code(), 3);
+        assert_eq!(CsiError::FileMissing(PathBuf::from("a.csv")).exit_code(), 4);
+        assert_eq!(CsiError::CsvLog("disk full".into()).exit_code(), 5);
+    }
+}
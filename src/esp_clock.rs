@@ -0,0 +1,71 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 esp_clock.rs - ESP Timestamp Anchoring
+// ═══════════════════════════════════════════════════════════════════════════════
+// Turns the ESP's local microsecond timer into frame timestamps.
+// Features:
+// - Anchored to the host time of the first stamped frame
+// - Keeps the ESP's spacing when serial reads arrive in bursts
+// - Re-anchors when the ESP timer restarts or wraps
+// - Host time fallback for blocks without a timestamp
+// ═══════════════════════════════════════════════════════════════════════════════
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 ESP Clock / ساعة ESP
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Maps ESP timer values (µs) onto host milliseconds
+/// تحويل قيم مؤقت ESP (ميكروثانية) إلى ميلي ثانية المضيف
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EspClock {
+    /// ESP time (µs) and host time (ms) of the anchor frame / وقت ESP ووقت المضيف لإطار المرجع
+    anchor: Option<(u64, i64)>,
+
+    /// Last ESP time seen (µs) / آخر وقت ESP
+    last_us: u64,
+}
+
+impl EspClock {
+    /// Timestamp (ms) for a frame received at `host_ms` carrying `esp_us`
+    /// الطابع الزمني لإطار وصل في `host_ms` ويحمل `esp_us`
+    pub fn frame_time(&mut self, esp_us: Option<u64>, host_ms: i64) -> i64 {
+        let Some(us) = esp_us else { return host_ms; };
+        match self.anchor {
+            Some((anchor_us, anchor_ms)) if us >= self.last_us => {
+                self.last_us = us;
+                anchor_ms + ((us - anchor_us) / 1000) as i64
+            }
+            // First frame, or the timer went backwards (reboot, wrap) / أول إطار أو رجوع المؤقت
+            _ => {
+                self.anchor = Some((us, host_ms));
+                self.last_us = us;
+                host_ms
+            }
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_and_restart() {
+        let mut clock = EspClock::default();
+
+        // Burst: same host time, ESP spacing kept / دفعة: نفس وقت المضيف مع الإبقاء على تباعد ESP
+        assert_eq!(clock.frame_time(Some(5_000_000), 1_000), 1_000);
+        assert_eq!(clock.frame_time(Some(5_010_000), 1_000), 1_010);
+        assert_eq!(clock.frame_time(Some(5_020_400), 1_000), 1_020);
+
+        // No timestamp: host time / بلا طابع: وقت المضيف
+        assert_eq!(clock.frame_time(None, 1_500), 1_500);
+
+        // ESP rebooted: re-anchor on the host time / إعادة تشغيل ESP: مرجع جديد
+        assert_eq!(clock.frame_time(Some(100), 9_000), 9_000);
+        assert_eq!(clock.frame_time(Some(2_100), 9_000), 9_002);
+    }
+}
@@ -3,131 +3,412 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 // طرفية ESP خام - تعرض كل شيء من ESP مباشرة مثل PuTTY
 // Raw ESP terminal - displays everything from ESP directly like PuTTY
+// Features:
+// - Bounded scrollback of received lines (boot logs can be reviewed)
+// - PageUp / PageDown / Home / End scroll; typed keys still go to the port
+// - Ctrl+S saves everything received to a text file, then keeps appending
+// - Ctrl+] exits
 // ═══════════════════════════════════════════════════════════════════════════════
 
-use std::io::{self, Read, Write};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use crossterm::{
-    cursor::MoveTo,
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
 };
 
+use csi_tui::error::{CsiError, Result};
+
+use crate::suspend::{enter_tui, leave_tui, new_terminal, Tui};
+
+/// Received lines kept for scrolling back / الأسطر المستلمة المحفوظة للتمرير للخلف
+pub const SCROLLBACK_LINES: usize = 5_000;
+
+/// Received bytes kept for Ctrl+S (oldest dropped first) / البايتات المستلمة المحفوظة للحفظ
+pub const TRANSCRIPT_BYTES: usize = 16 * 1024 * 1024;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Scrollback Buffer / ذاكرة التمرير
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Received lines and how far the view is scrolled up
+/// الأسطر المستلمة ومقدار تمرير العرض للأعلى
+#[derive(Debug, Default)]
+struct Scrollback {
+    /// Complete lines, oldest first / الأسطر المكتملة، الأقدم أولاً
+    lines: VecDeque<String>,
+
+    /// Line still being received / السطر الذي ما زال قيد الاستلام
+    partial: String,
+
+    /// Lines hidden below the view (0 = following the output) / الأسطر المخفية أسفل العرض
+    offset: usize,
+}
+
+impl Scrollback {
+    /// Append received text; a scrolled view stays on the same lines
+    /// إضافة النص المستلم؛ العرض الممرر يبقى على نفس الأسطر
+    fn push_text(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\r' => {}
+                '\n' => {
+                    self.lines.push_back(std::mem::take(&mut self.partial));
+                    if self.lines.len() > SCROLLBACK_LINES {
+                        self.lines.pop_front();
+                    }
+                    if self.offset > 0 {
+                        self.offset = (self.offset + 1).min(self.lines.len());
+                    }
+                }
+                c => self.partial.push(c),
+            }
+        }
+    }
+
+    /// All rows, including the partial one / كل الصفوف بما فيها السطر الجزئي
+    fn row_count(&self) -> usize {
+        self.lines.len() + 1
+    }
+
+    /// Scroll `rows` toward older output / التمرير `rows` صفاً نحو الأقدم
+    fn scroll_up(&mut self, rows: usize) {
+        self.offset = (self.offset + rows).min(self.lines.len());
+    }
+
+    /// Scroll `rows` toward newer output / التمرير `rows` صفاً نحو الأحدث
+    fn scroll_down(&mut self, rows: usize) {
+        self.offset = self.offset.saturating_sub(rows);
+    }
+
+    /// Jump to the oldest line / الانتقال إلى أقدم سطر
+    fn scroll_home(&mut self, height: usize) {
+        self.offset = self.row_count().saturating_sub(height);
+    }
+
+    /// Follow the newest output again / متابعة أحدث المخرجات مجدداً
+    fn scroll_end(&mut self) {
+        self.offset = 0;
+    }
+
+    /// Rows shown in a pane `height` rows tall / الصفوف المعروضة في لوحة بارتفاع `height`
+    fn visible(&self, height: usize) -> Vec<&str> {
+        let rows = self.lines.iter().map(String::as_str).chain(std::iter::once(self.partial.as_str()));
+        let end = self.row_count().saturating_sub(self.offset);
+        rows.take(end).skip(end.saturating_sub(height)).collect()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Session Log / سجل الجلسة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Everything received this session, and the file it is appended to after a save
+/// كل ما استُلم في هذه الجلسة والملف الذي يُضاف إليه بعد الحفظ
+#[derive(Debug, Default)]
+struct SessionLog {
+    /// Raw received bytes, up to TRANSCRIPT_BYTES / البايتات الخام المستلمة
+    received: Vec<u8>,
+
+    /// File receiving live output after Ctrl+S / الملف الذي يستقبل المخرجات بعد Ctrl+S
+    live: Option<(PathBuf, File)>,
+}
+
+impl SessionLog {
+    /// Keep received bytes and append them to the saved file
+    /// حفظ البايتات المستلمة وإضافتها إلى الملف المحفوظ
+    fn record(&mut self, bytes: &[u8]) -> Result<()> {
+        self.received.extend_from_slice(bytes);
+        let excess = self.received.len().saturating_sub(TRANSCRIPT_BYTES);
+        self.received.drain(..excess);
+
+        let Some((path, file)) = &mut self.live else { return Ok(()); };
+        if let Err(e) = file.write_all(bytes) {
+            let error = CsiError::Export(format!("failed to append to {}: {}", path.display(), e));
+            self.live = None;
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Write everything received to `path` and keep appending to it
+    /// كتابة كل ما استُلم إلى `path` ومتابعة الإضافة إليه
+    fn save_to(&mut self, path: &Path) -> Result<usize> {
+        let export_error = |e: io::Error| CsiError::Export(format!("failed to write {}: {}", path.display(), e));
+        let mut file = File::create(path).map_err(export_error)?;
+        file.write_all(&self.received).map_err(export_error)?;
+        self.live = Some((path.to_path_buf(), file));
+        Ok(self.received.len())
+    }
+
+    /// Stop appending; returns the file that was being written
+    /// إيقاف الإضافة؛ يعيد الملف الذي كان يُكتب
+    fn stop_live(&mut self) -> Option<PathBuf> {
+        self.live.take().map(|(path, _)| path)
+    }
+}
+
+/// Ask for a file and save the session log to it, or stop a live save
+/// طلب ملف وحفظ سجل الجلسة فيه، أو إيقاف الحفظ المباشر
+fn toggle_save(log: &mut SessionLog) -> String {
+    if let Some(path) = log.stop_live() {
+        return format!("⏹️ Stopped appending to {}", path.display());
+    }
+    let file = rfd::FileDialog::new()
+        .add_filter("Text Files", &["txt", "log"])
+        .set_title("Save ESP Terminal Log")
+        .set_file_name(format!("esp_log_{}.txt", chrono::Utc::now().format("%Y%m%d_%H%M%S")))
+        .save_file();
+    let Some(path) = file else {
+        return CsiError::NoFileSelected.status_message();
+    };
+    match log.save_to(&path) {
+        Ok(bytes) => format!("💾 Saved {} bytes to {} - appending live (Ctrl+S stops)", bytes, path.display()),
+        Err(e) => e.status_message(),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Terminal Session / جلسة الطرفية
+// ═══════════════════════════════════════════════════════════════════════════════
+
 /// Run ESP terminal - raw serial connection like PuTTY
 /// تشغيل طرفية ESP - اتصال تسلسلي خام مثل PuTTY
-pub fn run_esp_terminal(port_name: &str, baud_rate: u32) -> Result<(), String> {
+pub fn run_esp_terminal(port_name: &str, baud_rate: u32) -> Result<()> {
     // Open serial port
     let mut port = serialport::new(port_name, baud_rate)
         .timeout(Duration::from_millis(10))
         .open()
-        .map_err(|e| format!("Failed to open {}: {}", port_name, e))?;
-    
-    // Clear screen and show connection message
-    let mut stdout = io::stdout();
-    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).map_err(|e| e.to_string())?;
-    
-    println!("═══════════════════════════════════════════════════════════════");
-    println!("  🔌 Connected to {} @ {} baud", port_name, baud_rate);
-    println!("  Press Ctrl+] to exit  ");
-    println!("═══════════════════════════════════════════════════════════════");
-    println!();
-    stdout.flush().map_err(|e| e.to_string())?;
-    
-    // Enable raw mode for character-by-character input
-    enable_raw_mode().map_err(|e| e.to_string())?;
-    
+        .map_err(|e| CsiError::from_serial(port_name, e))?;
+
+    let mut terminal = new_terminal()?;
+    enter_tui(&mut terminal, false)?;
+
     // Clear any pending keyboard events (important!)
     // تنظيف أي أحداث لوحة مفاتيح معلقة
     while event::poll(Duration::from_millis(50)).unwrap_or(false) {
         let _ = event::read();
     }
-    
+
+    let result = run_session(&mut terminal, port.as_mut(), port_name, baud_rate);
+
+    // Cleanup
+    leave_tui(&mut terminal, false)?;
+    result?;
+
+    println!();
+    println!("  🔌 Disconnected from {}", port_name);
+    println!("  Press Enter to continue...");
+    io::stdout().flush().map_err(CsiError::Terminal)?;
+
+    // Wait for Enter
+    let mut input = String::new();
+    let _ = io::stdin().read_line(&mut input);
+
+    Ok(())
+}
+
+/// Echo the port into the scrollback pane until Ctrl+]
+/// عرض المنفذ في لوحة التمرير حتى Ctrl+]
+fn run_session(
+    terminal: &mut Tui,
+    port: &mut dyn serialport::SerialPort,
+    port_name: &str,
+    baud_rate: u32,
+) -> Result<()> {
+    let mut scrollback = Scrollback::default();
+    let mut log = SessionLog::default();
+    let mut status = String::new();
     let mut buf = [0u8; 1024];
-    
+    let mut page = 1;
+    let mut dirty = true;
+
     loop {
-        // Read from serial port and print to screen
+        if dirty {
+            terminal
+                .draw(|frame| {
+                    let rows = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Min(3), Constraint::Length(1)])
+                        .split(frame.area());
+                    let area = rows[0];
+                    page = area.height.saturating_sub(2).max(1) as usize;
+                    let title = if scrollback.offset > 0 {
+                        format!(" 🔌 {} @ {} baud ({} lines below) ", port_name, baud_rate, scrollback.offset)
+                    } else {
+                        format!(" 🔌 {} @ {} baud ", port_name, baud_rate)
+                    };
+                    let hint = Line::from(Span::styled(
+                        " Ctrl+] exit  Ctrl+S save  PgUp/PgDn scroll  Home/End ",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                    let block = Block::default()
+                        .title(title)
+                        .title_bottom(hint)
+                        .borders(Borders::ALL)
+                        .border_style(Style::default().fg(Color::Cyan));
+                    let text: Vec<Line> = scrollback.visible(page).into_iter().map(Line::raw).collect();
+                    frame.render_widget(Paragraph::new(text).block(block), area);
+                    frame.render_widget(Paragraph::new(status.as_str()), rows[1]);
+                })
+                .map_err(CsiError::Terminal)?;
+            dirty = false;
+        }
+
+        // Read from serial port into the scrollback
         match port.read(&mut buf) {
             Ok(n) if n > 0 => {
                 // Convert to UTF-8 string (replace invalid bytes)
                 // تحويل إلى UTF-8 (استبدال البايتات غير الصالحة)
-                let text = String::from_utf8_lossy(&buf[..n]);
-                print!("{}", text);
-                stdout.flush().map_err(|e| e.to_string())?;
+                scrollback.push_text(&String::from_utf8_lossy(&buf[..n]));
+                if let Err(e) = log.record(&buf[..n]) {
+                    status = e.status_message();
+                }
+                dirty = true;
             }
             Ok(_) => {}
             Err(ref e) if e.kind() == io::ErrorKind::TimedOut => {}
-            Err(e) => {
-                disable_raw_mode().ok();
-                return Err(format!("Read error: {}", e));
-            }
+            Err(e) => return Err(CsiError::SerialIo(e)),
         }
-        
+
         // Check for keyboard input
-        if event::poll(Duration::from_millis(1)).unwrap_or(false) {
-            if let Ok(Event::Key(key)) = event::read() {
-                // Only handle key press, not release (fixes double character issue on Windows)
-                // معالجة الضغط فقط، وليس الإفلات (يصلح مشكلة الحرف المزدوج على Windows)
-                if key.kind != KeyEventKind::Press {
-                    continue;
-                }
-                
-                match key.code {
-                    // Ctrl+] to exit (like PuTTY)
-                    KeyCode::Char(']') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        break;
-                    }
-                    // Ctrl+C also exits
-                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        // Send Ctrl+C to ESP
-                        let _ = port.write_all(&[0x03]);
-                    }
-                    // Enter key
-                    KeyCode::Enter => {
-                        let _ = port.write_all(b"\r\n");
-                    }
-                    // Backspace
-                    KeyCode::Backspace => {
-                        let _ = port.write_all(&[0x08]);
-                    }
-                    // Tab
-                    KeyCode::Tab => {
-                        let _ = port.write_all(&[0x09]);
-                    }
-                    // Escape
-                    KeyCode::Esc => {
-                        let _ = port.write_all(&[0x1B]);
-                    }
-                    // Regular character - send to ESP
-                    KeyCode::Char(c) => {
-                        let mut buf = [0u8; 4];
-                        let s = c.encode_utf8(&mut buf);
-                        let _ = port.write_all(s.as_bytes());
-                    }
-                    // Arrow keys
-                    KeyCode::Up => { let _ = port.write_all(b"\x1B[A"); }
-                    KeyCode::Down => { let _ = port.write_all(b"\x1B[B"); }
-                    KeyCode::Right => { let _ = port.write_all(b"\x1B[C"); }
-                    KeyCode::Left => { let _ = port.write_all(b"\x1B[D"); }
-                    _ => {}
-                }
+        if !event::poll(Duration::from_millis(1)).unwrap_or(false) {
+            continue;
+        }
+        let Ok(event) = event::read() else { continue; };
+        let key = match event {
+            Event::Key(key) => key,
+            Event::Resize(..) => {
+                dirty = true;
+                continue;
+            }
+            _ => continue,
+        };
+
+        // Only handle key press, not release (fixes double character issue on Windows)
+        // معالجة الضغط فقط، وليس الإفلات (يصلح مشكلة الحرف المزدوج على Windows)
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            // Ctrl+] to exit (like PuTTY)
+            KeyCode::Char(']') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break;
+            }
+            // Ctrl+S saves the session log / Ctrl+S يحفظ سجل الجلسة
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                status = toggle_save(&mut log);
+                terminal.clear().map_err(CsiError::Terminal)?;
+            }
+            // Scrolling stays local / التمرير يبقى محلياً
+            KeyCode::PageUp => scrollback.scroll_up(page),
+            KeyCode::PageDown => scrollback.scroll_down(page),
+            KeyCode::Home => scrollback.scroll_home(page),
+            KeyCode::End => scrollback.scroll_end(),
+            // Ctrl+C is sent to ESP
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let _ = port.write_all(&[0x03]);
             }
+            // Enter key
+            KeyCode::Enter => {
+                let _ = port.write_all(b"\r\n");
+            }
+            // Backspace
+            KeyCode::Backspace => {
+                let _ = port.write_all(&[0x08]);
+            }
+            // Tab
+            KeyCode::Tab => {
+                let _ = port.write_all(&[0x09]);
+            }
+            // Escape
+            KeyCode::Esc => {
+                let _ = port.write_all(&[0x1B]);
+            }
+            // Regular character - send to ESP
+            KeyCode::Char(c) => {
+                let mut buf = [0u8; 4];
+                let s = c.encode_utf8(&mut buf);
+                let _ = port.write_all(s.as_bytes());
+            }
+            // Arrow keys
+            KeyCode::Up => { let _ = port.write_all(b"\x1B[A"); }
+            KeyCode::Down => { let _ = port.write_all(b"\x1B[B"); }
+            KeyCode::Right => { let _ = port.write_all(b"\x1B[C"); }
+            KeyCode::Left => { let _ = port.write_all(b"\x1B[D"); }
+            _ => {}
         }
+        dirty = true;
     }
-    
-    // Cleanup
-    disable_raw_mode().map_err(|e| e.to_string())?;
-    
-    println!();
-    println!();
-    println!("  🔌 Disconnected from {}", port_name);
-    println!("  Press Enter to continue...");
-    stdout.flush().map_err(|e| e.to_string())?;
-    
-    // Wait for Enter
-    let mut input = String::new();
-    let _ = io::stdin().read_line(&mut input);
-    
+
     Ok(())
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scrollback_scrolling() {
+        let mut scrollback = Scrollback::default();
+        scrollback.push_text("boot\r\nline 1\nline 2\nline ");
+        scrollback.push_text("3\nprompt> ");
+        assert_eq!(scrollback.visible(3), vec!["line 2", "line 3", "prompt> "]);
+
+        // Scrolled up, new output does not move the view / بعد التمرير لا تحرك المخرجات الجديدة العرض
+        scrollback.scroll_up(2);
+        assert_eq!(scrollback.visible(3), vec!["boot", "line 1", "line 2"]);
+        scrollback.push_text("ok\nmore\n");
+        assert_eq!(scrollback.visible(3), vec!["boot", "line 1", "line 2"]);
+
+        scrollback.scroll_up(100);
+        assert_eq!(scrollback.visible(2), vec!["boot"]);
+        scrollback.scroll_home(3);
+        assert_eq!(scrollback.visible(3), vec!["boot", "line 1", "line 2"]);
+        scrollback.scroll_down(1);
+        assert_eq!(scrollback.visible(3), vec!["line 1", "line 2", "line 3"]);
+        scrollback.scroll_end();
+        assert_eq!(scrollback.visible(2), vec!["more", ""]);
+    }
+
+    #[test]
+    fn test_session_log_saves_then_appends() {
+        let path = std::env::temp_dir().join(format!("esp_log_test_{}.txt", std::process::id()));
+        let mut log = SessionLog::default();
+        log.record(b"boot\r\n").unwrap();
+
+        assert_eq!(log.save_to(&path).unwrap(), 6);
+        log.record(b"ready\r\n").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"boot\r\nready\r\n");
+
+        // After stopping, the file is left as it was / بعد الإيقاف يبقى الملف كما هو
+        assert_eq!(log.stop_live(), Some(path.clone()));
+        log.record(b"more\r\n").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"boot\r\nready\r\n");
+        assert_eq!(log.received, b"boot\r\nready\r\nmore\r\n");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_scrollback_is_bounded() {
+        let mut scrollback = Scrollback::default();
+        for i in 0..SCROLLBACK_LINES + 10 {
+            scrollback.push_text(&format!("{}\n", i));
+        }
+        assert_eq!(scrollback.lines.len(), SCROLLBACK_LINES);
+        assert_eq!(scrollback.lines.front().map(String::as_str), Some("10"));
+    }
+}
@@ -0,0 +1,276 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 event_log.rs - Detection Event Log
+// ═══════════════════════════════════════════════════════════════════════════════
+// This module keeps a short log of detector transitions for the UI.
+// Features:
+// - Timestamped entries, capped in memory
+// - Hard rate limit on appends (max N entries per second)
+// - Excess entries collapse into a "(N events suppressed)" summary line
+// - Tail mode (follow the newest entry) or review mode (view stays put)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Default maximum appended entries per second / الحد الافتراضي للإدخالات في الثانية
+pub const DEFAULT_MAX_EVENTS_PER_SEC: usize = 5;
+
+/// Maximum entries kept in memory / أقصى عدد إدخالات في الذاكرة
+pub const MAX_EVENT_ENTRIES: usize = 200;
+
+/// Length of one rate-limit window in milliseconds / طول نافذة تحديد المعدل بالميلي ثانية
+const RATE_WINDOW_MS: i64 = 1_000;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Event Entry / إدخال الحدث
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A single event log line / سطر واحد في سجل الأحداث
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventEntry {
+    /// Unix timestamp in milliseconds / الطابع الزمني بالميلي ثانية
+    pub timestamp: i64,
+
+    /// Text to display / النص المعروض
+    pub message: String,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Event Log / سجل الأحداث
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rate-limited log of detection events
+/// سجل أحداث الكشف مع تحديد المعدل
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    /// Stored entries, oldest first / الإدخالات المخزنة، الأقدم أولاً
+    entries: VecDeque<EventEntry>,
+
+    /// Maximum appends per second / أقصى عدد إضافات في الثانية
+    max_per_sec: usize,
+
+    /// Start of the current rate window / بداية نافذة المعدل الحالية
+    window_start: i64,
+
+    /// Entries appended in the current window / الإدخالات المضافة في النافذة الحالية
+    window_count: usize,
+
+    /// Entries dropped since the last summary / الإدخالات المحذوفة منذ آخر ملخص
+    suppressed: usize,
+
+    /// Follow the newest entry (tail) or keep the view (review) / متابعة الأحدث أو تثبيت العرض
+    auto_scroll: bool,
+
+    /// First visible entry in review mode / أول إدخال ظاهر في وضع المراجعة
+    scroll_top: usize,
+
+    /// Rows available at the last render / الصفوف المتاحة عند آخر رسم
+    viewport: usize,
+}
+
+impl EventLog {
+    /// Create an event log with the given rate limit
+    /// إنشاء سجل أحداث بحد معدل محدد
+    pub fn new(max_per_sec: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            max_per_sec: max_per_sec.max(1),
+            window_start: i64::MIN,
+            window_count: 0,
+            suppressed: 0,
+            auto_scroll: true,
+            scroll_top: 0,
+            viewport: 0,
+        }
+    }
+
+    /// Append an event, respecting the rate limit
+    /// إضافة حدث مع احترام حد المعدل
+    pub fn push(&mut self, timestamp: i64, message: impl Into<String>) {
+        // New window: report what was dropped in the previous one
+        // نافذة جديدة: الإبلاغ عما تم حذفه في السابقة
+        if timestamp.saturating_sub(self.window_start) >= RATE_WINDOW_MS {
+            self.flush_suppressed(timestamp);
+            self.window_start = timestamp;
+            self.window_count = 0;
+        }
+
+        if self.window_count >= self.max_per_sec {
+            self.suppressed += 1;
+            return;
+        }
+
+        self.window_count += 1;
+        self.append(EventEntry { timestamp, message: message.into() });
+    }
+
+    /// Write the suppression summary once the rate window has passed
+    /// كتابة ملخص الحذف بعد انتهاء نافذة المعدل
+    pub fn tick(&mut self, now: i64) {
+        if now.saturating_sub(self.window_start) >= RATE_WINDOW_MS {
+            self.flush_suppressed(now);
+        }
+    }
+
+    /// Write the pending suppression summary, if any
+    /// كتابة ملخص الحذف المعلق إن وجد
+    fn flush_suppressed(&mut self, timestamp: i64) {
+        if self.suppressed > 0 {
+            let message = format!("({} events suppressed)", self.suppressed);
+            self.suppressed = 0;
+            self.append(EventEntry { timestamp, message });
+        }
+    }
+
+    /// All stored entries, oldest first / جميع الإدخالات المخزنة، الأقدم أولاً
+    pub fn entries(&self) -> &VecDeque<EventEntry> {
+        &self.entries
+    }
+
+    /// Store an entry and enforce the memory cap / تخزين إدخال وفرض حد الذاكرة
+    fn append(&mut self, entry: EventEntry) {
+        self.entries.push_back(entry);
+        while self.entries.len() > MAX_EVENT_ENTRIES {
+            self.entries.pop_front();
+            // Keep review mode on the same entries / إبقاء وضع المراجعة على نفس الإدخالات
+            self.scroll_top = self.scroll_top.saturating_sub(1);
+        }
+    }
+
+    /// Is tail mode on? / هل وضع المتابعة مفعل؟
+    pub fn auto_scroll(&self) -> bool {
+        self.auto_scroll
+    }
+
+    /// Switch between tail and review mode, keeping the current view
+    /// التبديل بين وضع المتابعة والمراجعة مع إبقاء العرض الحالي
+    pub fn toggle_auto_scroll(&mut self) {
+        self.scroll_top = self.scroll_top();
+        self.auto_scroll = !self.auto_scroll;
+    }
+
+    /// Record how many rows the panel shows / تسجيل عدد صفوف اللوحة
+    pub fn set_viewport(&mut self, rows: usize) {
+        self.viewport = rows;
+    }
+
+    /// Index of the first visible entry / فهرس أول إدخال ظاهر
+    ///
+    /// A log shorter than the viewport always starts at 0.
+    pub fn scroll_top(&self) -> usize {
+        let max_top = self.entries.len().saturating_sub(self.viewport);
+        if self.auto_scroll {
+            max_top
+        } else {
+            self.scroll_top.min(max_top)
+        }
+    }
+
+    /// Scroll by `rows` (negative = older); scrolling up leaves tail mode
+    /// التمرير بمقدار `rows` (السالب = الأقدم)؛ التمرير للأعلى يوقف المتابعة
+    pub fn scroll_by(&mut self, rows: isize) {
+        let max_top = self.entries.len().saturating_sub(self.viewport);
+        self.scroll_top = self.scroll_top().saturating_add_signed(rows).min(max_top);
+        if rows < 0 {
+            self.auto_scroll = false;
+        }
+    }
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_EVENTS_PER_SEC)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_is_rate_limited() {
+        let mut log = EventLog::new(3);
+
+        // 20 flapping transitions within one second
+        for i in 0..20 {
+            log.push(10_000 + i * 10, format!("event {}", i));
+        }
+        assert_eq!(log.entries().len(), 3);
+
+        // Next window writes the summary before the new entry
+        log.push(11_500, "next");
+        let messages: Vec<&str> = log.entries().iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["event 0", "event 1", "event 2", "(17 events suppressed)", "next"]);
+    }
+
+    #[test]
+    fn test_summary_written_after_quiet_period() {
+        let mut log = EventLog::new(1);
+        log.push(0, "a");
+        log.push(10, "b");
+
+        log.tick(500);
+        assert_eq!(log.entries().len(), 1);
+
+        log.tick(1_000);
+        assert_eq!(log.entries().back().unwrap().message, "(1 events suppressed)");
+    }
+
+    #[test]
+    fn test_scroll_offset_in_both_modes() {
+        let mut log = EventLog::new(100);
+        log.set_viewport(5);
+        for i in 0..3 {
+            log.push(i, format!("event {}", i));
+        }
+        // Shorter than the viewport / أقصر من مساحة العرض
+        assert_eq!(log.scroll_top(), 0);
+
+        // Tail mode follows new entries / وضع المتابعة يلحق بالجديد
+        for i in 3..12 {
+            log.push(i, format!("event {}", i));
+        }
+        assert_eq!(log.scroll_top(), 7);
+
+        // Review mode keeps the view / وضع المراجعة يثبت العرض
+        log.toggle_auto_scroll();
+        log.scroll_by(-4);
+        assert_eq!(log.scroll_top(), 3);
+        for i in 12..20 {
+            log.push(i, format!("event {}", i));
+        }
+        assert_eq!(log.scroll_top(), 3);
+        log.scroll_by(100);
+        assert_eq!(log.scroll_top(), 15);
+
+        // Back to tail mode jumps to the newest / العودة للمتابعة تقفز للأحدث
+        log.toggle_auto_scroll();
+        log.push(20, "event 20");
+        assert!(log.auto_scroll());
+        assert_eq!(log.scroll_top(), 16);
+    }
+
+    #[test]
+    fn test_memory_cap() {
+        let mut log = EventLog::new(1);
+        for i in 0..(MAX_EVENT_ENTRIES as i64 + 50) {
+            log.push(i * RATE_WINDOW_MS, "event");
+        }
+        assert_eq!(log.entries().len(), MAX_EVENT_ENTRIES);
+
+        // Evictions keep review mode on the same entries / الحذف يبقي المراجعة على نفس الإدخالات
+        log.set_viewport(10);
+        log.toggle_auto_scroll();
+        log.scroll_by(-50);
+        let top = log.scroll_top();
+        log.push(1_000_000, "newer");
+        assert_eq!(log.scroll_top(), top - 1);
+    }
+}
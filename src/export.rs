@@ -0,0 +1,106 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 export.rs - JSON Frame Export
+// ═══════════════════════════════════════════════════════════════════════════════
+// Writes captured CSI frames as JSON for notebooks and other tools.
+// Features:
+// - Array of {timestamp, mags, pairs, format} objects
+// - Format as a string ("RealImag", "AmplitudeOnly", "Unknown")
+// - Streams frame by frame; no whole-document string is built
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::{CsiError, Result};
+use crate::state::CsiFrame;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 JSON Frame / إطار JSON
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Borrowed view of a frame as it appears in the export
+/// عرض مستعار للإطار كما يظهر في التصدير
+#[derive(Serialize)]
+struct JsonFrame<'a> {
+    timestamp: i64,
+    mags: &'a [f64],
+    pairs: &'a [(i32, i32)],
+    format: &'static str,
+}
+
+impl<'a> From<&'a CsiFrame> for JsonFrame<'a> {
+    fn from(frame: &'a CsiFrame) -> Self {
+        Self {
+            timestamp: frame.timestamp,
+            mags: &frame.mags,
+            pairs: &frame.pairs,
+            format: frame.format.as_str(),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Export / التصدير
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Stream `frames` to `writer` as a JSON array, one object per line
+/// كتابة الإطارات كمصفوفة JSON، كائن لكل سطر
+///
+/// Returns the number of frames written.
+pub fn write_json<W: Write>(frames: &[CsiFrame], mut writer: W) -> Result<usize> {
+    let io_err = |e: std::io::Error| CsiError::Export(e.to_string());
+
+    writer.write_all(b"[").map_err(io_err)?;
+    for (i, frame) in frames.iter().enumerate() {
+        writer.write_all(if i == 0 { b"\n" } else { b",\n" }).map_err(io_err)?;
+        serde_json::to_writer(&mut writer, &JsonFrame::from(frame)).map_err(|e| CsiError::Export(e.to_string()))?;
+    }
+    writer.write_all(b"\n]\n").map_err(io_err)?;
+    writer.flush().map_err(io_err)?;
+    Ok(frames.len())
+}
+
+/// Export `frames` to a JSON file at `path`
+/// تصدير الإطارات إلى ملف JSON
+pub fn export_json(frames: &[CsiFrame], path: &Path) -> Result<usize> {
+    let file = File::create(path).map_err(|e| CsiError::Export(format!("failed to create {}: {}", path.display(), e)))?;
+    write_json(frames, BufWriter::new(file))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    #[test]
+    fn test_json_export_round_trip() {
+        let frames = vec![
+            CsiFrame::new(1_000, vec![5.0, 10.0], vec![(3, 4), (-6, 8)], CsiFormat::RealImag, Some(-40)),
+            CsiFrame::new(1_050, vec![7.0], vec![(7, 0)], CsiFormat::AmplitudeOnly, None),
+        ];
+
+        let mut out = Vec::new();
+        assert_eq!(write_json(&frames, &mut out).unwrap(), 2);
+
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let array = parsed.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array[0]["timestamp"], 1_000);
+        assert_eq!(array[0]["mags"], serde_json::json!([5.0, 10.0]));
+        assert_eq!(array[0]["pairs"], serde_json::json!([[3, 4], [-6, 8]]));
+        assert_eq!(array[0]["format"], "RealImag");
+        assert_eq!(array[1]["format"], "AmplitudeOnly");
+
+        // Empty capture is still valid JSON / التقاط فارغ يبقى JSON صالحاً
+        let mut empty = Vec::new();
+        write_json(&[], &mut empty).unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&empty).unwrap(), serde_json::json!([]));
+    }
+}
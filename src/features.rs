@@ -0,0 +1,255 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 features.rs - Per-Frame Feature Export
+// ═══════════════════════════════════════════════════════════════════════════════
+// This module turns each CSI frame into a fixed-length feature row for ML.
+// Features:
+// - Same columns for every frame, whatever the subcarrier count
+// - Magnitude statistics over an analysis range (the primary detector's)
+// - Detector scores and flags from the run that saw the frame
+// - Periodic flush, plus flush on exit
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use chrono::Utc;
+
+use crate::detectors::get_subcarriers_with_ratio;
+use crate::error::{CsiError, Result};
+use crate::state::{CsiFrame, DetectionResults};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Feature columns, in row order / أعمدة الخصائص بترتيب الصف
+pub const FEATURE_COLUMNS: [&str; 14] = [
+    "timestamp",
+    "subcarriers",
+    "rssi",
+    "mean",
+    "std",
+    "min",
+    "max",
+    "motion_value",
+    "presence_value",
+    "door_value",
+    "breathing_bpm",
+    "motion_detected",
+    "human_present",
+    "door_open",
+];
+
+/// Rows written between automatic flushes / عدد الصفوف بين عمليات التفريغ التلقائية
+const FLUSH_EVERY_ROWS: usize = 50;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Frame Features / خصائص الإطار
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Fixed-length description of one frame / وصف ثابت الطول لإطار واحد
+#[derive(Debug, Clone, Default)]
+pub struct FrameFeatures {
+    /// Frame timestamp (ms) / الطابع الزمني للإطار
+    pub timestamp: i64,
+
+    /// Subcarriers in the frame / الناقلات الفرعية في الإطار
+    pub subcarriers: usize,
+
+    /// RSSI in dBm, if reported / قوة الإشارة إن وجدت
+    pub rssi: Option<i32>,
+
+    /// Magnitude statistics over the analysis range / إحصائيات السعة في نطاق التحليل
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+
+    /// Detector results at the time of the frame / نتائج الكاشفات وقت الإطار
+    pub detections: DetectionResults,
+}
+
+impl FrameFeatures {
+    /// Compute the features of `frame` over the middle `ratio` of its subcarriers
+    /// حساب خصائص الإطار على النسبة الوسطى `ratio` من ناقلاته
+    pub fn compute(frame: &CsiFrame, ratio: f64, detections: &DetectionResults) -> Self {
+        let range = get_subcarriers_with_ratio(&frame.mags, ratio);
+
+        let (mean, std, min, max) = if range.is_empty() {
+            (0.0, 0.0, 0.0, 0.0)
+        } else {
+            let n = range.len() as f64;
+            let mean = range.iter().sum::<f64>() / n;
+            let variance = range.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / n;
+            let min = range.iter().copied().fold(f64::INFINITY, f64::min);
+            let max = range.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            (mean, variance.sqrt(), min, max)
+        };
+
+        Self {
+            timestamp: frame.timestamp,
+            subcarriers: frame.subcarrier_count(),
+            rssi: frame.rssi,
+            mean,
+            std,
+            min,
+            max,
+            detections: detections.clone(),
+        }
+    }
+
+    /// CSV row matching FEATURE_COLUMNS (missing RSSI is an empty cell)
+    /// صف CSV مطابق لـ FEATURE_COLUMNS (قوة الإشارة المفقودة خلية فارغة)
+    pub fn to_row(&self) -> String {
+        let d = &self.detections;
+        format!(
+            "{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.2},{},{},{}",
+            self.timestamp,
+            self.subcarriers,
+            self.rssi.map(|r| r.to_string()).unwrap_or_default(),
+            self.mean,
+            self.std,
+            self.min,
+            self.max,
+            d.motion_value,
+            d.presence_value,
+            d.door_value,
+            d.breathing_bpm,
+            d.motion_detected as u8,
+            d.human_present as u8,
+            d.door_open as u8,
+        )
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Feature Logger / مسجل الخصائص
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Writer for one feature row per frame
+/// كاتب صف خصائص واحد لكل إطار
+pub struct FeatureLogger {
+    /// Buffered file writer / كاتب الملف المخزن
+    writer: BufWriter<File>,
+
+    /// Rows written since the last flush / الصفوف المكتوبة منذ آخر تفريغ
+    pending_rows: usize,
+}
+
+impl FeatureLogger {
+    /// Create a new feature logger and write the header
+    /// إنشاء مسجل خصائص جديد وكتابة الترويسة
+    pub fn new(file_path: PathBuf) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&file_path)
+            .map_err(|e| CsiError::CsvLog(format!("failed to create file: {}", e)))?;
+
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}", FEATURE_COLUMNS.join(","))
+            .map_err(|e| CsiError::CsvLog(format!("failed to write header: {}", e)))?;
+
+        Ok(Self { writer, pending_rows: 0 })
+    }
+
+    /// Create a new feature logger with auto-generated filename
+    /// إنشاء مسجل خصائص جديد باسم ملف تلقائي
+    pub fn new_with_timestamp() -> Result<Self> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        Self::new(PathBuf::from(format!("features_{}.csv", timestamp)))
+    }
+
+    /// Append one feature row
+    /// إضافة صف خصائص واحد
+    pub fn log_features(&mut self, features: &FrameFeatures) -> Result<()> {
+        writeln!(self.writer, "{}", features.to_row())
+            .map_err(|e| CsiError::CsvLog(format!("failed to write row: {}", e)))?;
+
+        self.pending_rows += 1;
+        if self.pending_rows >= FLUSH_EVERY_ROWS {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush all buffered rows to disk
+    /// تفريغ جميع الصفوف المخزنة إلى القرص
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .map_err(|e| CsiError::CsvLog(format!("failed to flush: {}", e)))?;
+        self.pending_rows = 0;
+        Ok(())
+    }
+}
+
+impl Drop for FeatureLogger {
+    /// Ensure rows are flushed when logger is dropped
+    /// ضمان تفريغ الصفوف عند إسقاط المسجل
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::PrimaryDetector;
+    use crate::state::CsiFormat;
+    use std::fs;
+
+    #[test]
+    fn test_features_of_known_frame() {
+        // 8 SC, motion range (50%) covers indices 2..6 / نطاق الحركة يغطي 2..6
+        let mags = vec![100.0, 100.0, 2.0, 4.0, 4.0, 6.0, 100.0, 100.0];
+        let frame = CsiFrame::new(1_000, mags, vec![], CsiFormat::AmplitudeOnly, Some(-48));
+        let detections = DetectionResults { motion_value: 7.5, human_present: true, ..Default::default() };
+
+        let features = FrameFeatures::compute(&frame, PrimaryDetector::Motion.ratio(), &detections);
+        assert_eq!(features.subcarriers, 8);
+        assert_eq!((features.mean, features.min, features.max), (4.0, 2.0, 6.0));
+        assert!((features.std - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(
+            features.to_row(),
+            "1000,8,-48,4.000,1.414,2.000,6.000,7.500,0.000,0.000,0.00,0,1,0"
+        );
+
+        // Empty frame: zeros, not NaN / إطار فارغ: أصفار وليس NaN
+        let empty = CsiFrame::new(0, vec![], vec![], CsiFormat::Unknown, None);
+        let features = FrameFeatures::compute(&empty, PrimaryDetector::Motion.ratio(), &detections);
+        assert_eq!((features.mean, features.std, features.max), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_fixed_column_layout() {
+        let path = PathBuf::from("test_features.csv");
+        let mut logger = FeatureLogger::new(path.clone()).unwrap();
+        let detections = DetectionResults::default();
+
+        // 4, 64 and 256 subcarriers give the same columns / نفس الأعمدة لكل عدد ناقلات
+        for (i, sc) in [4usize, 64, 256].into_iter().enumerate() {
+            let rssi = if i == 1 { None } else { Some(-60) };
+            let frame = CsiFrame::new(i as i64, vec![1.0; sc], vec![], CsiFormat::AmplitudeOnly, rssi);
+            logger.log_features(&FrameFeatures::compute(&frame, PrimaryDetector::Presence.ratio(), &detections)).unwrap();
+        }
+        logger.flush().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines[0], FEATURE_COLUMNS.join(","));
+        assert_eq!(lines.len(), 4);
+        for line in &lines[1..] {
+            assert_eq!(line.split(',').count(), FEATURE_COLUMNS.len());
+        }
+
+        drop(logger);
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,140 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 frame_buffer.rs - Sliding Frame Window
+// ═══════════════════════════════════════════════════════════════════════════════
+// Holds the most recent CSI frames with cheap eviction from the front.
+// Features:
+// - Amortized O(1) eviction of the oldest frames (no shift per frame)
+// - Always contiguous: derefs to `&[CsiFrame]` for detectors and charts
+// - Eviction by count or by timestamp cutoff
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::ops::Deref;
+
+use crate::state::CsiFrame;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Evicted slots tolerated before compacting / عدد الخانات المحذوفة قبل الضغط
+const MIN_COMPACT_SLOTS: usize = 256;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Frame Buffer / مخزن الإطارات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Oldest-first frame window; evicted frames are dropped in bulk
+/// نافذة إطارات الأقدم أولاً؛ الإطارات المحذوفة تُزال دفعة واحدة
+///
+/// Evicting only moves `head`; the storage is compacted once the dead
+/// prefix is as long as the live part, so each frame is moved at most once.
+#[derive(Debug, Clone, Default)]
+pub struct FrameBuffer {
+    /// Storage; frames before `head` are already evicted / التخزين؛ ما قبل head محذوف
+    storage: Vec<CsiFrame>,
+
+    /// Index of the oldest live frame / فهرس أقدم إطار حي
+    head: usize,
+}
+
+impl FrameBuffer {
+    /// Append the newest frame / إضافة أحدث إطار
+    pub fn push(&mut self, frame: CsiFrame) {
+        self.storage.push(frame);
+    }
+
+    /// Append frames in order / إضافة إطارات بالترتيب
+    pub fn extend<I: IntoIterator<Item = CsiFrame>>(&mut self, frames: I) {
+        self.storage.extend(frames);
+    }
+
+    /// Evict the `count` oldest frames / حذف أقدم `count` إطار
+    pub fn drop_oldest(&mut self, count: usize) {
+        self.head = (self.head + count).min(self.storage.len());
+        self.compact();
+    }
+
+    /// Evict frames from the front while they are at or before `cutoff` (ms)
+    /// حذف الإطارات من البداية طالما أنها عند `cutoff` أو قبله
+    pub fn drop_until(&mut self, cutoff: i64) {
+        let stale = self.iter().take_while(|f| f.timestamp <= cutoff).count();
+        self.drop_oldest(stale);
+    }
+
+    /// Remove every frame / حذف جميع الإطارات
+    pub fn clear(&mut self) {
+        self.storage.clear();
+        self.head = 0;
+    }
+
+    /// Live frames as a slice, oldest first / الإطارات الحية كشريحة، الأقدم أولاً
+    pub fn as_slice(&self) -> &[CsiFrame] {
+        &self.storage[self.head..]
+    }
+
+    /// Slots held including evicted ones / الخانات المحجوزة بما فيها المحذوفة
+    pub fn storage_len(&self) -> usize {
+        self.storage.len()
+    }
+
+    fn compact(&mut self) {
+        if self.head == self.storage.len() {
+            self.clear();
+        } else if self.head >= MIN_COMPACT_SLOTS && self.head >= self.storage.len() - self.head {
+            self.storage.drain(..self.head);
+            self.head = 0;
+        }
+    }
+}
+
+impl Deref for FrameBuffer {
+    type Target = [CsiFrame];
+
+    fn deref(&self) -> &[CsiFrame] {
+        self.as_slice()
+    }
+}
+
+impl FromIterator<CsiFrame> for FrameBuffer {
+    fn from_iter<I: IntoIterator<Item = CsiFrame>>(iter: I) -> Self {
+        Self { storage: iter.into_iter().collect(), head: 0 }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    fn frame(ts: i64) -> CsiFrame {
+        CsiFrame::new(ts, vec![1.0], vec![], CsiFormat::AmplitudeOnly, None)
+    }
+
+    #[test]
+    fn test_sliding_window_stays_bounded() {
+        // 10k frames, keep the last 500 / عشرة آلاف إطار مع الاحتفاظ بآخر 500
+        let mut buffer = FrameBuffer::default();
+        for ts in 0..10_000 {
+            buffer.push(frame(ts));
+            buffer.drop_until(ts - 500);
+            assert_eq!(buffer.len(), (ts + 1).min(500) as usize);
+            assert!(buffer.storage_len() <= 2 * buffer.len() + MIN_COMPACT_SLOTS);
+        }
+        assert_eq!(buffer[0].timestamp, 9_500);
+        assert_eq!(buffer.last().unwrap().timestamp, 9_999);
+    }
+
+    #[test]
+    fn test_drop_oldest_and_clear() {
+        let mut buffer: FrameBuffer = (0..10).map(frame).collect();
+        buffer.drop_oldest(4);
+        assert_eq!(buffer.first().unwrap().timestamp, 4);
+        buffer.drop_oldest(100);
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.storage_len(), 0);
+    }
+}
@@ -0,0 +1,197 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 frame_queue.rs - Reader → UI Frame Queue
+// ═══════════════════════════════════════════════════════════════════════════════
+// Hands parsed frames from the reader threads to the UI loop without taking
+// the state lock once per frame.
+// Features:
+// - Bounded channel; a full queue drops the frame and counts it
+// - Frames carry their receive time, so timestamps do not depend on the drain
+// - The UI drains everything queued once per tick under one lock
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use crate::parser::ParseResult;
+use crate::state::CsiMeta;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Frames held between two UI ticks (several seconds at 200 fps)
+/// الإطارات المحفوظة بين نبضتين للواجهة (عدة ثوان عند 200 إطار/ث)
+pub const FRAME_QUEUE_CAPACITY: usize = 2048;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Received Frame / الإطار المستلم
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// A parsed frame on its way to the state / إطار محلل في طريقه إلى الحالة
+#[derive(Debug, Clone)]
+pub struct ReceivedFrame {
+    /// Parsed CSI values / قيم CSI المحللة
+    pub result: ParseResult,
+
+    /// Radio metadata from the block / بيانات الراديو من الكتلة
+    pub meta: CsiMeta,
+
+    /// ESP timer value, when the block carried one / قيمة مؤقت ESP إن وجدت
+    pub esp_timestamp_us: Option<u64>,
+
+    /// Named source the frame came from / المصدر المسمى الذي جاء منه الإطار
+    pub source: Option<String>,
+
+    /// Time spent parsing the block / الوقت المستغرق في تحليل الكتلة
+    pub parse_time: Duration,
+
+    /// Host receive time (ms) / وقت الاستقبال على المضيف
+    pub host_ms: i64,
+
+    /// Receive instant, for the queue wait / لحظة الاستقبال لقياس انتظار الطابور
+    pub received_at: Instant,
+
+    /// Counted as a parsed serial block when stored / يُعد ككتلة تسلسلية محللة عند الحفظ
+    pub counted: bool,
+
+    /// Dropped when it repeats the previous frame / يُحذف إن كرر الإطار السابق
+    pub dedupe: bool,
+}
+
+impl ReceivedFrame {
+    /// Frame received now / إطار مستلم الآن
+    pub fn new(
+        result: ParseResult,
+        meta: CsiMeta,
+        esp_timestamp_us: Option<u64>,
+        source: Option<&str>,
+        parse_time: Duration,
+    ) -> Self {
+        Self {
+            result,
+            meta,
+            esp_timestamp_us,
+            source: source.map(str::to_string),
+            parse_time,
+            host_ms: Utc::now().timestamp_millis(),
+            received_at: Instant::now(),
+            counted: false,
+            dedupe: false,
+        }
+    }
+
+    /// Count it in the parse stats when stored / عده في إحصائيات التحليل عند الحفظ
+    pub fn counted(mut self) -> Self {
+        self.counted = true;
+        self
+    }
+
+    /// Drop it when it repeats the previous frame / حذفه إن كرر الإطار السابق
+    pub fn deduped(mut self) -> Self {
+        self.dedupe = true;
+        self
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Queue Ends / طرفا الطابور
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Reader side; cloned into every reader thread / جهة القارئ؛ تُنسخ لكل خيط قارئ
+#[derive(Debug, Clone)]
+pub struct FrameSender {
+    tx: SyncSender<ReceivedFrame>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// UI side, drained once per tick / جهة الواجهة، تُفرغ مرة كل نبضة
+#[derive(Debug)]
+pub struct FrameReceiver {
+    rx: Receiver<ReceivedFrame>,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Bounded queue holding `capacity` frames / طابور محدود يسع `capacity` إطاراً
+pub fn frame_queue(capacity: usize) -> (FrameSender, FrameReceiver) {
+    let (tx, rx) = sync_channel(capacity);
+    let dropped = Arc::new(AtomicU64::new(0));
+    (FrameSender { tx, dropped: Arc::clone(&dropped) }, FrameReceiver { rx, dropped })
+}
+
+impl FrameSender {
+    /// Queue a frame without blocking; false when it was dropped
+    /// إضافة إطار دون انتظار؛ false عند حذفه
+    pub fn send(&self, frame: ReceivedFrame) -> bool {
+        match self.tx.try_send(frame) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
+
+impl FrameReceiver {
+    /// Everything queued so far, oldest first / كل ما في الطابور، الأقدم أولاً
+    pub fn drain(&self) -> Vec<ReceivedFrame> {
+        self.rx.try_iter().collect()
+    }
+
+    /// Frames dropped because the queue was full / الإطارات المحذوفة لامتلاء الطابور
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Drops since the last call, resetting the counter / المحذوفات منذ آخر استدعاء مع تصفير العداد
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    fn received(value: i32) -> ReceivedFrame {
+        let result = ParseResult {
+            format: CsiFormat::RealImag,
+            phases: vec![0.0],
+            pairs: vec![(value, 0)],
+            mags: vec![value as f64],
+            rssi: None,
+            mac: None,
+        };
+        ReceivedFrame::new(result, CsiMeta::default(), None, None, Duration::ZERO)
+    }
+
+    #[test]
+    fn test_full_queue_drops_and_counts() {
+        let (tx, rx) = frame_queue(2);
+        assert!(tx.send(received(1)));
+        assert!(tx.clone().send(received(2)));
+        assert!(!tx.send(received(3)));
+        assert_eq!(rx.dropped(), 1);
+
+        let drained = rx.drain();
+        assert_eq!(drained.iter().map(|f| f.result.pairs[0].0).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(rx.drain().is_empty());
+
+        // Room again after the drain / مساحة مجدداً بعد التفريغ
+        assert!(tx.send(received(4)));
+        assert_eq!(rx.drain().len(), 1);
+        assert_eq!(rx.dropped(), 1);
+        assert_eq!(rx.take_dropped(), 1);
+        assert_eq!(rx.dropped(), 0);
+    }
+}
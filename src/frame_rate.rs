@@ -0,0 +1,259 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 frame_rate.rs - Live Frame Rate
+// ═══════════════════════════════════════════════════════════════════════════════
+// Measures how many CSI frames arrive per second, for firmware debugging.
+// Features:
+// - Frames per second over the last second (drops to 0 when frames stop)
+// - Rolling average over the last ten seconds
+// - Subcarrier count of the newest frame
+// - Serial / UDP bytes per second over the last second
+// - Smoothed (EMA) rate from frame timestamps, with its session min/max
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Window for the current rate / نافذة المعدل الحالي
+pub const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Window for the rolling average / نافذة المتوسط المتحرك
+pub const AVERAGE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Weight of each new interval in the smoothed rate / وزن كل فاصل جديد في المعدل المنعم
+pub const SMOOTHING_ALPHA: f64 = 0.1;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Frame Rate Meter / مقياس معدل الإطارات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Arrival times of recent frames / أوقات وصول الإطارات الأخيرة
+#[derive(Debug, Clone, Default)]
+pub struct FrameRateMeter {
+    /// Arrivals within AVERAGE_WINDOW, oldest first / الوصول خلال نافذة المتوسط، الأقدم أولاً
+    arrivals: VecDeque<Instant>,
+
+    /// First arrival since the last reset / أول وصول منذ آخر إعادة تعيين
+    first_arrival: Option<Instant>,
+
+    /// Subcarriers in the newest frame / الناقلات في أحدث إطار
+    last_subcarriers: usize,
+
+    /// Bytes read within RATE_WINDOW, oldest first / البايتات المقروءة خلال نافذة المعدل
+    byte_arrivals: VecDeque<(Instant, usize)>,
+}
+
+impl FrameRateMeter {
+    /// Record a frame of `subcarriers` arriving at `now`
+    /// تسجيل وصول إطار بعدد `subcarriers` ناقلاً في `now`
+    pub fn record(&mut self, now: Instant, subcarriers: usize) {
+        self.first_arrival.get_or_insert(now);
+        self.arrivals.push_back(now);
+        self.last_subcarriers = subcarriers;
+        while self.arrivals.front().is_some_and(|&t| now.duration_since(t) > AVERAGE_WINDOW) {
+            self.arrivals.pop_front();
+        }
+    }
+
+    /// Record `bytes` read from the link at `now` / تسجيل قراءة `bytes` بايت في `now`
+    pub fn record_bytes(&mut self, now: Instant, bytes: usize) {
+        self.byte_arrivals.push_back((now, bytes));
+        while self.byte_arrivals.front().is_some_and(|&(t, _)| now.duration_since(t) > RATE_WINDOW) {
+            self.byte_arrivals.pop_front();
+        }
+    }
+
+    /// Bytes per second over the last second / البايتات في الثانية خلال آخر ثانية
+    pub fn byte_rate(&self, now: Instant) -> f64 {
+        let bytes: usize = self
+            .byte_arrivals
+            .iter()
+            .filter(|&&(t, _)| now.duration_since(t) <= RATE_WINDOW)
+            .map(|&(_, bytes)| bytes)
+            .sum();
+        bytes as f64 / RATE_WINDOW.as_secs_f64()
+    }
+
+    /// True once a frame has arrived since the last reset / صحيح بعد وصول أول إطار
+    pub fn has_frames(&self) -> bool {
+        self.first_arrival.is_some()
+    }
+
+    /// Frames per second over the last second / الإطارات في الثانية خلال آخر ثانية
+    pub fn rate(&self, now: Instant) -> f64 {
+        self.count_within(now, RATE_WINDOW) as f64 / RATE_WINDOW.as_secs_f64()
+    }
+
+    /// Frames per second over the last ten seconds (or since the first frame)
+    /// الإطارات في الثانية خلال آخر عشر ثوان (أو منذ أول إطار)
+    pub fn average(&self, now: Instant) -> f64 {
+        let Some(first) = self.first_arrival else { return 0.0; };
+        // At least one rate window so the first frames don't read as a spike
+        // نافذة معدل واحدة على الأقل حتى لا تظهر الإطارات الأولى كقفزة
+        let span = now.duration_since(first).clamp(RATE_WINDOW, AVERAGE_WINDOW);
+        self.count_within(now, span) as f64 / span.as_secs_f64()
+    }
+
+    /// Subcarriers in the newest frame (0 before any) / الناقلات في أحدث إطار
+    pub fn last_subcarriers(&self) -> usize {
+        self.last_subcarriers
+    }
+
+    /// Forget all arrivals / نسيان كل حالات الوصول
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Arrivals no older than `window` / حالات الوصول خلال `window`
+    fn count_within(&self, now: Instant, window: Duration) -> usize {
+        let stale = self.arrivals.partition_point(|&t| now.duration_since(t) > window);
+        self.arrivals.len() - stale
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Smoothed Rate / المعدل المنعم
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Exponential moving average of the rate from frame timestamps
+/// متوسط متحرك أسي للمعدل من الطوابع الزمنية للإطارات
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmoothedRate {
+    /// Timestamp of the previous frame (ms) / الطابع الزمني للإطار السابق
+    last_timestamp: Option<i64>,
+
+    /// Smoothed frames per second / الإطارات في الثانية بعد التنعيم
+    fps: Option<f64>,
+
+    /// Lowest and highest smoothed rate this session / أدنى وأعلى معدل منعم في الجلسة
+    min_fps: f64,
+    max_fps: f64,
+}
+
+impl SmoothedRate {
+    /// Feed the timestamp (ms) of the next frame / تمرير الطابع الزمني للإطار التالي
+    ///
+    /// Frames with the same or an earlier timestamp add no interval.
+    pub fn push(&mut self, timestamp: i64) {
+        let previous = self.last_timestamp.replace(timestamp);
+        let Some(interval) = previous.map(|p| timestamp - p).filter(|&dt| dt > 0) else {
+            return;
+        };
+        let instant = 1000.0 / interval as f64;
+        let fps = match self.fps {
+            Some(fps) => fps + SMOOTHING_ALPHA * (instant - fps),
+            None => {
+                self.min_fps = instant;
+                self.max_fps = instant;
+                instant
+            }
+        };
+        self.fps = Some(fps);
+        self.min_fps = self.min_fps.min(fps);
+        self.max_fps = self.max_fps.max(fps);
+    }
+
+    /// Smoothed rate, None before two frames / المعدل المنعم، لا شيء قبل إطارين
+    pub fn fps(&self) -> Option<f64> {
+        self.fps
+    }
+
+    /// Lowest and highest smoothed rate, None before two frames / أدنى وأعلى معدل منعم
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.fps.map(|_| (self.min_fps, self.max_fps))
+    }
+
+    /// Start a new session / بدء جلسة جديدة
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_and_average() {
+        let start = Instant::now();
+        let mut meter = FrameRateMeter::default();
+        assert_eq!((meter.rate(start), meter.average(start), meter.last_subcarriers()), (0.0, 0.0, 0));
+
+        // 50 fps for 4 s, then 100 fps for 1 s / 50 إطاراً لمدة 4 ثوان ثم 100 لمدة ثانية
+        for i in 0..200 {
+            meter.record(start + Duration::from_millis(i * 20), 64);
+        }
+        for i in 0..100 {
+            meter.record(start + Duration::from_millis(4_000 + i * 10), 128);
+        }
+        let now = start + Duration::from_millis(4_995);
+        assert_eq!(meter.rate(now), 100.0);
+        assert!((meter.average(now) - 300.0 / 4.995).abs() < 1e-9);
+        assert_eq!(meter.last_subcarriers(), 128);
+
+        // Frames stop: the rate falls to 0 after a second / توقف الإطارات: المعدل يصبح 0 بعد ثانية
+        let later = now + Duration::from_millis(1_100);
+        assert_eq!(meter.rate(later), 0.0);
+        assert!(meter.average(later) > 0.0);
+
+        meter.reset();
+        assert_eq!(meter.average(later), 0.0);
+        assert!(!meter.has_frames());
+    }
+
+    #[test]
+    fn test_byte_rate() {
+        let start = Instant::now();
+        let mut meter = FrameRateMeter::default();
+        assert_eq!(meter.byte_rate(start), 0.0);
+
+        // 100 reads of 256 bytes in one second / 100 قراءة من 256 بايت في ثانية
+        for i in 0..100 {
+            meter.record_bytes(start + Duration::from_millis(i * 10), 256);
+        }
+        assert_eq!(meter.byte_rate(start + Duration::from_millis(995)), 25_600.0);
+
+        // Older reads leave the window / القراءات الأقدم تخرج من النافذة
+        assert_eq!(meter.byte_rate(start + Duration::from_millis(1_495)), 12_800.0);
+        assert_eq!(meter.byte_rate(start + Duration::from_secs(3)), 0.0);
+    }
+
+    #[test]
+    fn test_smoothed_rate_with_min_max() {
+        let mut rate = SmoothedRate::default();
+        rate.push(0);
+        assert_eq!((rate.fps(), rate.range()), (None, None));
+
+        // 10 ms intervals seed 100 fps / فواصل 10 مللي ثانية تبدأ بـ 100
+        rate.push(10);
+        assert_eq!(rate.fps(), Some(100.0));
+
+        // One 20 ms interval pulls 10% toward 50 fps / فاصل 20 يسحب 10% نحو 50
+        rate.push(30);
+        assert!((rate.fps().unwrap() - 95.0).abs() < 1e-9);
+
+        // A repeated timestamp adds nothing / طابع مكرر لا يضيف شيئاً
+        rate.push(30);
+        assert!((rate.fps().unwrap() - 95.0).abs() < 1e-9);
+
+        // Back to 5 ms intervals: rises past the old max / العودة لفواصل 5: يتجاوز الحد الأعلى السابق
+        let mut ts = 30;
+        for _ in 0..50 {
+            ts += 5;
+            rate.push(ts);
+        }
+        let (min, max) = rate.range().unwrap();
+        assert!((min - 95.0).abs() < 1e-9);
+        assert!(max > 190.0 && max == rate.fps().unwrap());
+
+        rate.reset();
+        assert_eq!(rate.range(), None);
+    }
+}
@@ -0,0 +1,657 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 integrity.rs - CSV Integrity Hash Chain
+// ═══════════════════════════════════════════════════════════════════════════════
+// This module keeps a rolling SHA-256 over the bytes written by the CSV logger.
+// Features:
+// - Appends a checkpoint (bytes, rows, digest) to a `.meta` sidecar at every flush
+// - Finalizes the sidecar when the logger is closed
+// - Chains rotated parts: each part's digest starts from the previous part's final digest
+// - Verifies a logged CSV (and the parts before it) and reports the first divergent offset
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::{CsiError, Result};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// First line of every sidecar file / السطر الأول في كل ملف جانبي
+const META_HEADER: &str = "# csi-tui integrity v1";
+
+/// Sidecar file extension / امتداد الملف الجانبي
+const META_EXTENSION: &str = "meta";
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Checkpoint Structure / هيكل نقطة التحقق
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Digest of the file prefix up to `bytes`
+/// بصمة بداية الملف حتى `bytes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// Number of bytes covered / عدد البايتات المغطاة
+    pub bytes: u64,
+
+    /// Number of data rows covered / عدد صفوف البيانات المغطاة
+    pub rows: u64,
+
+    /// Hex SHA-256 of the first `bytes` bytes / بصمة SHA-256 لأول `bytes` بايت
+    pub digest: String,
+}
+
+/// Link from a rotated part back to the part before it
+/// رابط من جزء مُدوَّر إلى الجزء الذي قبله
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainLink {
+    /// File name of the previous part / اسم ملف الجزء السابق
+    pub file: String,
+
+    /// Final digest of the previous part / البصمة النهائية للجزء السابق
+    pub digest: String,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Integrity Tracker / متتبع السلامة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rolling SHA-256 over everything the logger writes
+/// بصمة SHA-256 متدحرجة لكل ما يكتبه المسجل
+pub struct IntegrityTracker {
+    /// Running hasher / المجزئ الجاري
+    hasher: Sha256,
+
+    /// Bytes hashed so far / البايتات المجزأة حتى الآن
+    bytes: u64,
+
+    /// Rows hashed so far / الصفوف المجزأة حتى الآن
+    rows: u64,
+
+    /// Path of the `.meta` sidecar / مسار الملف الجانبي
+    meta_path: PathBuf,
+
+    /// Previous part this one continues / الجزء السابق الذي يتابعه هذا الجزء
+    previous: Option<ChainLink>,
+
+    /// Byte count of the last checkpoint written / عدد بايتات آخر نقطة تحقق مكتوبة
+    last_checkpoint: Option<u64>,
+
+    /// Whether the sidecar header has been written / هل كُتبت ترويسة الملف الجانبي
+    started: bool,
+
+    /// Whether the sidecar has been finalized / هل تم إنهاء الملف الجانبي
+    finalized: bool,
+}
+
+impl IntegrityTracker {
+    /// Create a tracker for the given CSV file
+    /// إنشاء متتبع لملف CSV المحدد
+    pub fn new(csv_path: &Path) -> Self {
+        Self {
+            hasher: Sha256::new(),
+            bytes: 0,
+            rows: 0,
+            meta_path: meta_path_for(csv_path),
+            previous: None,
+            last_checkpoint: None,
+            started: false,
+            finalized: false,
+        }
+    }
+
+    /// Create a tracker for a rotated part that continues `previous`
+    /// إنشاء متتبع لجزء مُدوَّر يتابع `previous`
+    ///
+    /// The hash starts from the previous part's final digest, so dropping or
+    /// reordering parts breaks every digest after the gap.
+    pub fn chained(csv_path: &Path, previous: ChainLink) -> Self {
+        let mut tracker = Self::new(csv_path);
+        tracker.hasher.update(previous.digest.as_bytes());
+        tracker.previous = Some(previous);
+        tracker
+    }
+
+    /// Rebuild the tracker of a log that already holds `content`
+    /// إعادة بناء متتبع سجل يحتوي على `content` مسبقاً
+    ///
+    /// Keeps the chain link recorded in the existing sidecar, if any.
+    pub fn resume(csv_path: &Path, content: &[u8], rows: u64) -> Self {
+        let previous = fs::read_to_string(meta_path_for(csv_path))
+            .ok()
+            .and_then(|meta| parse_meta(&meta).ok())
+            .and_then(|sidecar| sidecar.previous);
+        let mut tracker = match previous {
+            Some(link) => Self::chained(csv_path, link),
+            None => Self::new(csv_path),
+        };
+        tracker.update(content, rows);
+        tracker
+    }
+
+    /// Feed bytes that were just written to the CSV
+    /// تمرير البايتات التي كُتبت للتو إلى CSV
+    pub fn update(&mut self, data: &[u8], rows: u64) {
+        self.hasher.update(data);
+        self.bytes += data.len() as u64;
+        self.rows += rows;
+    }
+
+    /// Append a checkpoint to the sidecar
+    /// إضافة نقطة تحقق إلى الملف الجانبي
+    pub fn checkpoint(&mut self) -> Result<()> {
+        if self.finalized {
+            return Ok(());
+        }
+
+        // Skip duplicate checkpoints / تخطي نقاط التحقق المكررة
+        if self.last_checkpoint == Some(self.bytes) {
+            return Ok(());
+        }
+        let c = self.current();
+        self.append_meta(&format!("checkpoint,{},{},{}\n", c.bytes, c.rows, c.digest))?;
+        self.last_checkpoint = Some(c.bytes);
+
+        Ok(())
+    }
+
+    /// Append the final digest to the sidecar
+    /// إضافة البصمة النهائية إلى الملف الجانبي
+    pub fn finalize(&mut self) -> Result<()> {
+        if self.finalized {
+            return Ok(());
+        }
+
+        self.checkpoint()?;
+        let c = self.current();
+        self.append_meta(&format!("final,{},{},{}\n", c.bytes, c.rows, c.digest))?;
+        self.finalized = true;
+
+        Ok(())
+    }
+
+    /// Hex digest of everything hashed so far / البصمة الحالية لكل ما تم تجزئته
+    pub fn digest(&self) -> String {
+        self.current().digest
+    }
+
+    /// Digest of everything hashed so far
+    /// بصمة كل ما تم تجزئته حتى الآن
+    fn current(&self) -> Checkpoint {
+        Checkpoint {
+            bytes: self.bytes,
+            rows: self.rows,
+            digest: to_hex(&self.hasher.clone().finalize()),
+        }
+    }
+
+    /// Append a line to the sidecar, starting it on first use
+    /// إضافة سطر إلى الملف الجانبي، مع إنشائه عند أول استخدام
+    ///
+    /// Earlier lines are never rewritten, so a crash mid-write can only
+    /// tear the line being added.
+    fn append_meta(&mut self, line: &str) -> Result<()> {
+        let mut text = String::new();
+        if !self.started {
+            text.push_str(&format!("{}\n", META_HEADER));
+            if let Some(link) = &self.previous {
+                text.push_str(&format!("previous,{},{}\n", link.file, link.digest));
+            }
+        }
+        text.push_str(line);
+
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.started)
+            .truncate(!self.started)
+            .open(&self.meta_path)
+            .and_then(|mut file| file.write_all(text.as_bytes()))
+            .map_err(|e| CsiError::Integrity(format!("failed to write sidecar: {}", e)))?;
+        self.started = true;
+
+        Ok(())
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Verification / التحقق
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Result of verifying a CSV against its sidecar
+/// نتيجة التحقق من ملف CSV مقابل ملفه الجانبي
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerifyOutcome {
+    /// All checkpoints match / جميع نقاط التحقق متطابقة
+    Intact {
+        bytes: u64,
+        rows: u64,
+        /// Sidecar has a final line / الملف الجانبي يحتوي على سطر نهائي
+        finalized: bool,
+    },
+
+    /// Content differs from the recorded digests / المحتوى يختلف عن البصمات المسجلة
+    Diverged {
+        /// First byte offset that can no longer be trusted / أول إزاحة لا يمكن الوثوق بها
+        offset: u64,
+        /// Human-readable reason / السبب بصيغة مقروءة
+        reason: String,
+    },
+}
+
+/// Path of the sidecar for a CSV file (`file.csv.meta`)
+/// مسار الملف الجانبي لملف CSV
+pub fn meta_path_for(csv_path: &Path) -> PathBuf {
+    let mut name = csv_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(META_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Whether a CSV's sidecar has a final line (`None` if there is no sidecar)
+/// هل يحتوي الملف الجانبي على سطر نهائي (`None` إذا لم يوجد ملف جانبي)
+pub fn sidecar_finalized(csv_path: &Path) -> Option<bool> {
+    let meta = fs::read_to_string(meta_path_for(csv_path)).ok()?;
+    Some(meta.lines().any(|line| line.starts_with("final,")))
+}
+
+/// Recompute digests for a CSV and compare against its sidecar
+/// إعادة حساب البصمات لملف CSV ومقارنتها بالملف الجانبي
+///
+/// A rotated part is only intact if every part before it is intact and
+/// ends with the digest this part was chained to.
+pub fn verify_file(csv_path: &Path) -> Result<VerifyOutcome> {
+    verify_chain(csv_path, &mut Vec::new())
+}
+
+/// Verify the parts before `csv_path`, then `csv_path` itself
+/// التحقق من الأجزاء السابقة لـ `csv_path` ثم منه هو
+fn verify_chain(csv_path: &Path, seen: &mut Vec<PathBuf>) -> Result<VerifyOutcome> {
+    let sidecar = read_sidecar(csv_path)?;
+
+    if let Some(link) = &sidecar.previous {
+        let previous = csv_path.with_file_name(&link.file);
+        seen.push(csv_path.to_path_buf());
+        let broken = |reason: String| Ok(VerifyOutcome::Diverged { offset: 0, reason });
+
+        if seen.contains(&previous) {
+            return broken(format!("chain loops back to {}", link.file));
+        }
+        if !previous.exists() {
+            return broken(format!("previous part {} is missing", link.file));
+        }
+        match verify_chain(&previous, seen)? {
+            VerifyOutcome::Diverged { offset, reason } => {
+                return broken(format!("previous part {} diverges at byte {} - {}", link.file, offset, reason));
+            }
+            VerifyOutcome::Intact { .. } => {
+                let ends_with = read_sidecar(&previous)?.final_cp.map(|c| c.digest);
+                if ends_with.as_ref() != Some(&link.digest) {
+                    return broken(format!("chain broken: {} does not end with the linked digest", link.file));
+                }
+            }
+        }
+    }
+
+    verify_part(csv_path, &sidecar)
+}
+
+/// Read and parse the sidecar of a CSV / قراءة وتحليل الملف الجانبي لملف CSV
+fn read_sidecar(csv_path: &Path) -> Result<Sidecar> {
+    let meta_path = meta_path_for(csv_path);
+    let meta = fs::read_to_string(&meta_path)
+        .map_err(|e| CsiError::Integrity(format!("failed to read {}: {}", meta_path.display(), e)))?;
+    parse_meta(&meta)
+}
+
+/// Recompute the digests of one part / إعادة حساب بصمات جزء واحد
+fn verify_part(csv_path: &Path, sidecar: &Sidecar) -> Result<VerifyOutcome> {
+    let (checkpoints, final_cp) = (&sidecar.checkpoints, &sidecar.final_cp);
+
+    let file = File::open(csv_path)
+        .map_err(|e| CsiError::CsvLoad(format!("failed to open {}: {}", csv_path.display(), e)))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| CsiError::CsvLoad(e.to_string()))?
+        .len();
+    let mut reader = BufReader::new(file);
+
+    let mut hasher = Sha256::new();
+    if let Some(link) = &sidecar.previous {
+        hasher.update(link.digest.as_bytes());
+    }
+    let mut hashed: u64 = 0;
+    let mut last_good: u64 = 0;
+    let mut buf = [0u8; 8192];
+
+    for cp in checkpoints.iter().chain(final_cp.iter()) {
+        if cp.bytes > file_len {
+            return Ok(VerifyOutcome::Diverged {
+                offset: last_good,
+                reason: format!("file truncated: expected at least {} bytes, found {}", cp.bytes, file_len),
+            });
+        }
+
+        // Hash up to the checkpoint offset / التجزئة حتى إزاحة نقطة التحقق
+        while hashed < cp.bytes {
+            let want = ((cp.bytes - hashed) as usize).min(buf.len());
+            reader
+                .read_exact(&mut buf[..want])
+                .map_err(|e| CsiError::CsvLoad(e.to_string()))?;
+            hasher.update(&buf[..want]);
+            hashed += want as u64;
+        }
+
+        if to_hex(&hasher.clone().finalize()) != cp.digest {
+            return Ok(VerifyOutcome::Diverged {
+                offset: last_good,
+                reason: format!("digest mismatch in bytes {}..{}", last_good, cp.bytes),
+            });
+        }
+        last_good = cp.bytes;
+    }
+
+    // Bytes appended after finalization / بايتات مضافة بعد الإنهاء
+    if let Some(cp) = &final_cp {
+        if file_len > cp.bytes {
+            return Ok(VerifyOutcome::Diverged {
+                offset: cp.bytes,
+                reason: format!("{} unexpected bytes after final digest", file_len - cp.bytes),
+            });
+        }
+    }
+
+    let rows = final_cp.as_ref().or(checkpoints.last()).map(|c| c.rows).unwrap_or(0);
+
+    Ok(VerifyOutcome::Intact {
+        bytes: last_good,
+        rows,
+        finalized: final_cp.is_some(),
+    })
+}
+
+/// Parsed sidecar contents / محتوى الملف الجانبي بعد التحليل
+struct Sidecar {
+    /// Previous part, for rotated logs / الجزء السابق، للسجلات المُدوَّرة
+    previous: Option<ChainLink>,
+
+    /// Checkpoints sorted by offset / نقاط التحقق مرتبة حسب الإزاحة
+    checkpoints: Vec<Checkpoint>,
+
+    /// Final line, if the log was closed / السطر النهائي إذا أُغلق السجل
+    final_cp: Option<Checkpoint>,
+}
+
+/// Parse sidecar contents into checkpoints and optional final line
+/// تحليل محتوى الملف الجانبي إلى نقاط تحقق وسطر نهائي اختياري
+///
+/// A last line without its newline was torn by a crash and is ignored.
+fn parse_meta(meta: &str) -> Result<Sidecar> {
+    let complete = match meta.rfind('\n') {
+        Some(end) => &meta[..=end],
+        None => meta,
+    };
+    let mut lines = complete.lines();
+    if lines.next() != Some(META_HEADER) {
+        return Err(CsiError::Integrity("not a csi-tui integrity sidecar".to_string()));
+    }
+
+    let mut previous = None;
+    let mut checkpoints = Vec::new();
+    let mut final_cp = None;
+
+    for line in lines {
+        let fields: Vec<&str> = line.trim().split(',').collect();
+        if let ["previous", file, digest] = fields[..] {
+            previous = Some(ChainLink { file: file.to_string(), digest: digest.to_string() });
+            continue;
+        }
+        if fields.len() != 4 {
+            continue;
+        }
+
+        let cp = Checkpoint {
+            bytes: fields[1]
+                .parse()
+                .map_err(|_| CsiError::Integrity(format!("invalid byte count: {}", fields[1])))?,
+            rows: fields[2]
+                .parse()
+                .map_err(|_| CsiError::Integrity(format!("invalid row count: {}", fields[2])))?,
+            digest: fields[3].to_string(),
+        };
+
+        match fields[0] {
+            "checkpoint" => checkpoints.push(cp),
+            "final" => final_cp = Some(cp),
+            _ => {}
+        }
+    }
+
+    checkpoints.sort_by_key(|c| c.bytes);
+    Ok(Sidecar { previous, checkpoints, final_cp })
+}
+
+/// Encode bytes as lowercase hex / ترميز البايتات كنص سداسي عشري
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_logger::CsvLogger;
+    use crate::state::{CsiFormat, CsiFrame};
+
+    fn create_test_frame(ts: i64) -> CsiFrame {
+        CsiFrame::new(ts, vec![5.0, 10.0], vec![(3, 4), (6, 8)], CsiFormat::RealImag, None)
+    }
+
+    /// Log two flushed segments and close the logger
+    fn write_log(path: &Path) {
+        let mut logger = CsvLogger::new(path.to_path_buf()).unwrap();
+        logger.enable_integrity().unwrap();
+        for ts in 0..3 {
+            logger.log_frame(&create_test_frame(ts)).unwrap();
+        }
+        logger.flush().unwrap();
+        for ts in 3..6 {
+            logger.log_frame(&create_test_frame(ts)).unwrap();
+        }
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_file(path);
+        let _ = fs::remove_file(meta_path_for(path));
+    }
+
+    #[test]
+    fn test_verify_intact() {
+        let path = PathBuf::from("test_integrity_intact.csv");
+        write_log(&path);
+
+        let outcome = verify_file(&path).unwrap();
+        let len = fs::metadata(&path).unwrap().len();
+        assert_eq!(outcome, VerifyOutcome::Intact { bytes: len, rows: 6, finalized: true });
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_verify_detects_edit_after_checkpoint() {
+        let path = PathBuf::from("test_integrity_edit.csv");
+        write_log(&path);
+
+        let first = read_sidecar(&path).unwrap().checkpoints[0].bytes;
+
+        // Change a digit in the second segment / تغيير رقم في المقطع الثاني
+        let mut data = fs::read(&path).unwrap();
+        let pos = data.len() - 2;
+        data[pos] = if data[pos] == b'9' { b'1' } else { b'9' };
+        fs::write(&path, data).unwrap();
+
+        match verify_file(&path).unwrap() {
+            VerifyOutcome::Diverged { offset, .. } => assert_eq!(offset, first),
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_verify_detects_partial_line() {
+        let path = PathBuf::from("test_integrity_partial.csv");
+        write_log(&path);
+
+        // Cut the last row in half / قطع الصف الأخير إلى نصفين
+        let data = fs::read(&path).unwrap();
+        fs::write(&path, &data[..data.len() - 3]).unwrap();
+        assert!(matches!(verify_file(&path).unwrap(), VerifyOutcome::Diverged { .. }));
+
+        // A trailing partial line after the final digest is also caught
+        // سطر جزئي بعد البصمة النهائية يتم اكتشافه أيضاً
+        let mut appended = data.clone();
+        appended.extend_from_slice(b"99,1");
+        fs::write(&path, appended).unwrap();
+        match verify_file(&path).unwrap() {
+            VerifyOutcome::Diverged { offset, .. } => assert_eq!(offset, data.len() as u64),
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        cleanup(&path);
+    }
+
+    /// Log enough rows for three parts; returns the part paths
+    fn write_rotated_log(path: &Path) -> Vec<PathBuf> {
+        let mut logger = CsvLogger::new(path.to_path_buf()).unwrap();
+        logger.enable_integrity().unwrap();
+        logger.set_rotate_bytes(Some(70));
+        let mut parts = vec![path.to_path_buf()];
+        for ts in 0..9 {
+            logger.log_frame(&create_test_frame(ts)).unwrap();
+            if logger.file_path() != parts.last().unwrap() {
+                parts.push(logger.file_path().to_path_buf());
+            }
+            logger.flush().unwrap();
+        }
+        parts
+    }
+
+    fn cleanup_parts(parts: &[PathBuf]) {
+        for part in parts {
+            cleanup(part);
+            let _ = fs::remove_file(crate::csv_logger::open_marker_for(part));
+        }
+    }
+
+    #[test]
+    fn test_rotation_chains_parts() {
+        let parts = write_rotated_log(Path::new("test_integrity_chain.csv"));
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[1], PathBuf::from("test_integrity_chain_part002.csv"));
+
+        // Every part is intact and linked to the one before it
+        // كل جزء سليم ومرتبط بالجزء الذي قبله
+        for part in &parts {
+            assert!(matches!(verify_file(part).unwrap(), VerifyOutcome::Intact { finalized: true, .. }));
+        }
+        let link = read_sidecar(&parts[2]).unwrap().previous.unwrap();
+        assert_eq!(link.file, "test_integrity_chain_part002.csv");
+        assert_eq!(Some(link.digest), read_sidecar(&parts[1]).unwrap().final_cp.map(|c| c.digest));
+
+        // Rows at the rotation boundary are neither lost nor repeated
+        // الصفوف عند حد التدوير لا تُفقد ولا تتكرر
+        let rows: usize = parts
+            .iter()
+            .map(|p| crate::csv_loader::CsvLoader::new().load(p).unwrap().len())
+            .sum();
+        assert_eq!(rows, 9);
+
+        cleanup_parts(&parts);
+    }
+
+    #[test]
+    fn test_rotation_detects_dropped_part() {
+        let parts = write_rotated_log(Path::new("test_integrity_drop.csv"));
+
+        // Drop the middle part and rename the last one into its place
+        // حذف الجزء الأوسط ووضع الأخير مكانه
+        cleanup(&parts[1]);
+        fs::rename(&parts[2], &parts[1]).unwrap();
+        fs::rename(meta_path_for(&parts[2]), meta_path_for(&parts[1])).unwrap();
+        match verify_file(&parts[1]).unwrap() {
+            VerifyOutcome::Diverged { reason, .. } => assert!(reason.contains("loops back"), "{}", reason),
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        // Without the renamed part the gap is reported as missing
+        // بدون الجزء المعاد تسميته يُبلغ عن الفجوة كجزء مفقود
+        let parts = write_rotated_log(Path::new("test_integrity_gap.csv"));
+        cleanup(&parts[1]);
+        match verify_file(&parts[2]).unwrap() {
+            VerifyOutcome::Diverged { reason, .. } => assert!(reason.contains("is missing"), "{}", reason),
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        cleanup_parts(&parts);
+        cleanup_parts(&[PathBuf::from("test_integrity_drop.csv"), PathBuf::from("test_integrity_drop_part002.csv")]);
+    }
+
+    #[test]
+    fn test_rotation_detects_edit_in_earlier_part() {
+        let parts = write_rotated_log(Path::new("test_integrity_chain_edit.csv"));
+
+        // Rewrite part 1 and its sidecar consistently: only the link can tell
+        // إعادة كتابة الجزء الأول وملفه الجانبي بشكل متسق: الرابط وحده يكشف ذلك
+        let mut data = fs::read(&parts[0]).unwrap();
+        let pos = data.len() - 2;
+        data[pos] = if data[pos] == b'9' { b'1' } else { b'9' };
+        fs::write(&parts[0], &data).unwrap();
+        let mut forged = IntegrityTracker::new(&parts[0]);
+        forged.update(&data, 0);
+        forged.finalize().unwrap();
+        assert!(matches!(verify_file(&parts[0]).unwrap(), VerifyOutcome::Intact { .. }));
+
+        match verify_file(&parts[2]).unwrap() {
+            VerifyOutcome::Diverged { reason, .. } => {
+                assert!(reason.contains("test_integrity_chain_edit.csv"), "{}", reason)
+            }
+            other => panic!("expected divergence, got {:?}", other),
+        }
+
+        cleanup_parts(&parts);
+    }
+
+    #[test]
+    fn test_torn_part_recovered_keeps_chain() {
+        let parts = write_rotated_log(Path::new("test_integrity_chain_torn.csv"));
+
+        // Crash in the last part: a torn row and a torn sidecar line
+        // انهيار في الجزء الأخير: صف ممزق وسطر جانبي ممزق
+        let last = &parts[2];
+        let meta = fs::read_to_string(meta_path_for(last)).unwrap();
+        let unfinished = &meta[..meta.find("final,").unwrap()];
+        fs::write(meta_path_for(last), format!("{}checkpoint,99", unfinished)).unwrap();
+        let mut data = fs::read(last).unwrap();
+        data.extend_from_slice(b"9,3,4");
+        fs::write(last, &data).unwrap();
+        assert!(matches!(verify_file(last).unwrap(), VerifyOutcome::Intact { finalized: false, .. }));
+
+        crate::csv_logger::recover_log(last).unwrap();
+        let len = fs::metadata(last).unwrap().len();
+        assert_eq!(len, data.len() as u64 - 5);
+        assert!(matches!(
+            verify_file(last).unwrap(),
+            VerifyOutcome::Intact { bytes, finalized: true, .. } if bytes == len
+        ));
+
+        cleanup_parts(&parts);
+    }
+}
@@ -0,0 +1,193 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 interval_stats.rs - A/B Interval Statistics
+// ═══════════════════════════════════════════════════════════════════════════════
+// Quantifies a marked stretch of a recording for quick tuning comparisons.
+// Features:
+// - Replays the interval through the same windowed detector pipeline as playback
+// - Mean / max motion, detection events, average magnitude, frame count, duration
+// - Plain-text report that can be saved next to the logs
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+use crate::detectors::{quick_detect_with, Aggregation, DetectorWindows};
+use crate::error::{CsiError, Result};
+use crate::state::{CsiFrame, DetectionResults};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Interval Statistics / إحصائيات الفترة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Statistics for the frames between the A and B markers
+/// إحصائيات الإطارات بين العلامتين A و B
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntervalStats {
+    /// Start / end offsets from the first loaded frame (s) / بداية ونهاية الفترة بالثواني
+    pub start_secs: f64,
+    pub end_secs: f64,
+
+    /// Frames inside the interval / عدد الإطارات داخل الفترة
+    pub frame_count: usize,
+
+    /// Mean and max motion value / متوسط وأقصى قيمة حركة
+    pub mean_motion: f64,
+    pub max_motion: f64,
+
+    /// Rising edges per detector / عدد مرات بدء الكشف لكل كاشف
+    pub motion_events: usize,
+    pub presence_events: usize,
+    pub door_events: usize,
+
+    /// Average aggregated magnitude / متوسط السعة المجمعة
+    pub mean_magnitude: f64,
+}
+
+impl IntervalStats {
+    /// Interval length in seconds / طول الفترة بالثواني
+    pub fn duration_secs(&self) -> f64 {
+        self.end_secs - self.start_secs
+    }
+
+    /// Detection events across all detectors / أحداث الكشف لجميع الكاشفات
+    pub fn total_events(&self) -> usize {
+        self.motion_events + self.presence_events + self.door_events
+    }
+
+    /// Plain-text report / تقرير نصي
+    pub fn report(&self) -> String {
+        format!(
+            "Interval: {:.1}s - {:.1}s ({:.1}s)\n\
+             Frames: {}\n\
+             Motion: mean {:.3}, max {:.3}\n\
+             Events: {} (motion {}, presence {}, door {})\n\
+             Mean magnitude: {:.2}\n",
+            self.start_secs,
+            self.end_secs,
+            self.duration_secs(),
+            self.frame_count,
+            self.mean_motion,
+            self.max_motion,
+            self.total_events(),
+            self.motion_events,
+            self.presence_events,
+            self.door_events,
+            self.mean_magnitude,
+        )
+    }
+
+    /// Save the report with a timestamped name in the working directory
+    /// حفظ التقرير باسم يحمل الطابع الزمني في مجلد العمل
+    pub fn save_with_timestamp(&self) -> Result<PathBuf> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let path = PathBuf::from(format!("interval_stats_{}.txt", timestamp));
+        self.save(&path)?;
+        Ok(path)
+    }
+
+    /// Save the report to `path` / حفظ التقرير في `path`
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.report())
+            .map_err(|e| CsiError::CsvLog(format!("failed to write {}: {}", path.display(), e)))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Interval Analysis / تحليل الفترة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Replay `frames[range]` through the detectors the way playback does
+/// إعادة تشغيل الإطارات عبر الكاشفات كما يفعل وضع التشغيل
+///
+/// Each frame sees the last `window_frames` frames, including those before
+/// the A marker, so values match what playback showed at that point.
+pub fn analyze_interval(
+    frames: &[CsiFrame],
+    range: RangeInclusive<usize>,
+    agg: Aggregation,
+    windows: &DetectorWindows,
+    window_frames: usize,
+) -> Option<IntervalStats> {
+    let (start, end) = (*range.start(), *range.end());
+    if start > end || end >= frames.len() {
+        return None;
+    }
+
+    let first_ts = frames[0].timestamp;
+    let mut stats = IntervalStats {
+        start_secs: (frames[start].timestamp - first_ts) as f64 / 1000.0,
+        end_secs: (frames[end].timestamp - first_ts) as f64 / 1000.0,
+        frame_count: end - start + 1,
+        ..IntervalStats::default()
+    };
+
+    let mut previous: Option<DetectionResults> = None;
+    let mut motion_sum = 0.0;
+    let mut mag_sum = 0.0;
+    for i in range {
+        let window = &frames[(i + 1).saturating_sub(window_frames.max(1))..=i];
+        let (results, _) = quick_detect_with(window, agg, windows);
+
+        motion_sum += results.motion_value;
+        stats.max_motion = stats.max_motion.max(results.motion_value);
+        mag_sum += agg.apply(&frames[i].mags);
+
+        // Count rising edges; a detection already on at A counts once
+        // عد بدايات الكشف؛ الكشف النشط عند A يحسب مرة واحدة
+        let was = previous.clone().unwrap_or_default();
+        stats.motion_events += usize::from(results.motion_detected && !was.motion_detected);
+        stats.presence_events += usize::from(results.human_present && !was.human_present);
+        stats.door_events += usize::from(results.door_open && !was.door_open);
+        previous = Some(results);
+    }
+
+    stats.mean_motion = motion_sum / stats.frame_count as f64;
+    stats.mean_magnitude = mag_sum / stats.frame_count as f64;
+    Some(stats)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    fn frame(ts: i64, level: f64) -> CsiFrame {
+        CsiFrame::new(ts, vec![level; 64], vec![], CsiFormat::AmplitudeOnly, None)
+    }
+
+    #[test]
+    fn test_interval_stats() {
+        // Quiet, two bursts of motion, quiet / هدوء ثم دفعتان من الحركة ثم هدوء
+        let mut frames: Vec<CsiFrame> = (0..20).map(|i| frame(i * 100, 50.0)).collect();
+        for (n, ts) in (20..60).enumerate() {
+            let level = if (5..10).contains(&n) || (25..30).contains(&n) {
+                if n % 2 == 0 { 10.0 } else { 90.0 }
+            } else {
+                50.0
+            };
+            frames.push(frame(ts * 100, level));
+        }
+
+        let windows = DetectorWindows::default();
+        let stats = analyze_interval(&frames, 20..=59, Aggregation::Mean, &windows, 100).unwrap();
+        assert_eq!(stats.frame_count, 40);
+        assert!((stats.duration_secs() - 3.9).abs() < 1e-9);
+        assert_eq!(stats.motion_events, 2);
+        assert!(stats.max_motion > stats.mean_motion);
+        assert!(stats.report().contains("Frames: 40"));
+
+        // A quiet interval has no motion / فترة هادئة بلا حركة
+        let quiet = analyze_interval(&frames, 0..=19, Aggregation::Mean, &windows, 100).unwrap();
+        assert_eq!(quiet.motion_events, 0);
+        assert_eq!(quiet.mean_magnitude, 50.0);
+
+        assert_eq!(analyze_interval(&frames, 10..=60, Aggregation::Mean, &windows, 100), None);
+    }
+}
@@ -0,0 +1,227 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 jitter.rs - Inter-Frame Timing Jitter
+// ═══════════════════════════════════════════════════════════════════════════════
+// Measures how evenly CSI frames arrive, for firmware debugging.
+// Features:
+// - Rolling one-minute window of inter-frame intervals (O(1) per frame)
+// - Mean, standard deviation, p95 and max gap (cached between frames)
+// - Same summary for a whole recorded file
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rolling window for live jitter (ms) / نافذة التذبذب المباشر بالميلي ثانية
+pub const JITTER_WINDOW_MS: i64 = 60_000;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Jitter Summary / ملخص التذبذب
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Statistics of inter-frame intervals (ms)
+/// إحصائيات الفواصل بين الإطارات (بالميلي ثانية)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JitterSummary {
+    /// Mean interval / متوسط الفاصل
+    pub mean_ms: f64,
+
+    /// Standard deviation of the intervals / الانحراف المعياري للفواصل
+    pub std_dev_ms: f64,
+
+    /// 95th percentile interval / المئين 95 للفواصل
+    pub p95_ms: f64,
+
+    /// Largest gap / أكبر فجوة
+    pub max_gap_ms: f64,
+
+    /// Number of intervals measured / عدد الفواصل المقاسة
+    pub intervals: usize,
+}
+
+/// Summarize a set of intervals (ms); None if empty
+/// تلخيص مجموعة فواصل؛ لا شيء إذا كانت فارغة
+pub fn summarize_intervals(intervals: &[f64]) -> Option<JitterSummary> {
+    if intervals.is_empty() {
+        return None;
+    }
+
+    let n = intervals.len() as f64;
+    let mean = intervals.iter().sum::<f64>() / n;
+    let var = intervals.iter().map(|&d| (d - mean).powi(2)).sum::<f64>() / n;
+
+    // Nearest-rank percentile / المئين بطريقة أقرب رتبة
+    let mut sorted = intervals.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((0.95 * n).ceil() as usize).clamp(1, sorted.len());
+
+    Some(JitterSummary {
+        mean_ms: mean,
+        std_dev_ms: var.sqrt(),
+        p95_ms: sorted[rank - 1],
+        max_gap_ms: sorted[sorted.len() - 1],
+        intervals: intervals.len(),
+    })
+}
+
+/// Summary of a whole recording from its timestamps (ms)
+/// ملخص تسجيل كامل من طوابعه الزمنية
+pub fn summarize_timestamps<I: IntoIterator<Item = i64>>(timestamps: I) -> Option<JitterSummary> {
+    let mut previous = None;
+    let intervals: Vec<f64> = timestamps
+        .into_iter()
+        .filter_map(|ts| {
+            let interval = previous.filter(|&p| ts >= p).map(|p| (ts - p) as f64);
+            previous = Some(ts);
+            interval
+        })
+        .collect();
+    summarize_intervals(&intervals)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Live Tracker / المتتبع المباشر
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rolling inter-frame intervals over the last JITTER_WINDOW_MS
+/// الفواصل بين الإطارات خلال آخر JITTER_WINDOW_MS
+#[derive(Debug, Clone, Default)]
+pub struct JitterTracker {
+    /// Timestamp of the previous frame / الطابع الزمني للإطار السابق
+    last_timestamp: Option<i64>,
+
+    /// (frame timestamp, interval ms), oldest first / (الطابع، الفاصل) الأقدم أولاً
+    intervals: VecDeque<(i64, f64)>,
+
+    /// Sum of the intervals in the window / مجموع الفواصل في النافذة
+    sum_ms: f64,
+
+    /// Summary of the window until the next push; renders reuse it
+    /// ملخص النافذة حتى الإضافة التالية؛ تعيد الرسومات استخدامه
+    cached: Cell<Option<Option<JitterSummary>>>,
+}
+
+impl JitterTracker {
+    /// Record a frame timestamp (amortized O(1))
+    /// تسجيل طابع زمني لإطار
+    ///
+    /// A timestamp going backwards restarts the interval chain.
+    pub fn push(&mut self, timestamp: i64) {
+        self.cached.set(None);
+        if let Some(previous) = self.last_timestamp.filter(|&p| timestamp >= p) {
+            let interval = (timestamp - previous) as f64;
+            self.intervals.push_back((timestamp, interval));
+            self.sum_ms += interval;
+        }
+        self.last_timestamp = Some(timestamp);
+
+        let cutoff = timestamp - JITTER_WINDOW_MS;
+        while self.intervals.front().is_some_and(|&(t, _)| t < cutoff) {
+            if let Some((_, interval)) = self.intervals.pop_front() {
+                self.sum_ms -= interval;
+            }
+        }
+    }
+
+    /// Mean interval of the current window without sorting (O(1))
+    /// متوسط الفاصل في النافذة الحالية دون ترتيب
+    pub fn mean_interval_ms(&self) -> Option<f64> {
+        (!self.intervals.is_empty()).then(|| self.sum_ms / self.intervals.len() as f64)
+    }
+
+    /// Summary of the current window; sorted once per new frame, not per call
+    /// ملخص النافذة الحالية؛ يُرتب مرة لكل إطار جديد لا لكل استدعاء
+    pub fn summary(&self) -> Option<JitterSummary> {
+        if let Some(summary) = self.cached.get() {
+            return summary;
+        }
+        let intervals: Vec<f64> = self.intervals.iter().map(|&(_, d)| d).collect();
+        let summary = summarize_intervals(&intervals);
+        self.cached.set(Some(summary));
+        summary
+    }
+
+    /// Forget all intervals / نسيان جميع الفواصل
+    pub fn reset(&mut self) {
+        self.cached.set(None);
+        self.last_timestamp = None;
+        self.intervals.clear();
+        self.sum_ms = 0.0;
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bursty_stream() {
+        // 10-frame bursts 10 ms apart, then a 300 ms silence
+        // دفعات من 10 إطارات بفاصل 10 مللي ثانية ثم صمت 300 مللي ثانية
+        let mut tracker = JitterTracker::default();
+        let mut ts = 0;
+        for _ in 0..5 {
+            for _ in 0..10 {
+                tracker.push(ts);
+                ts += 10;
+            }
+            ts += 290;
+        }
+
+        let summary = tracker.summary().unwrap();
+        assert_eq!(summary.intervals, 49);
+        assert_eq!(summary.max_gap_ms, 300.0);
+        assert_eq!(summary.p95_ms, 300.0);
+        assert!(summary.std_dev_ms > 50.0);
+
+        // Steady stream has no jitter / تدفق منتظم بلا تذبذب
+        let steady = summarize_timestamps((0..100).map(|i| i * 20)).unwrap();
+        assert_eq!(steady.mean_ms, 20.0);
+        assert_eq!(steady.std_dev_ms, 0.0);
+        assert_eq!(steady.p95_ms, 20.0);
+    }
+
+    #[test]
+    fn test_window_and_backwards_time() {
+        let mut tracker = JitterTracker::default();
+        tracker.push(0);
+        tracker.push(5_000);
+        tracker.push(100_000);
+        tracker.push(100_010);
+
+        // The 5 s gap left the window / الفجوة القديمة خرجت من النافذة
+        let summary = tracker.summary().unwrap();
+        assert_eq!(summary.intervals, 2);
+        assert_eq!(summary.max_gap_ms, 95_000.0);
+        assert_eq!(tracker.mean_interval_ms(), Some(summary.mean_ms));
+
+        // Clock jump backwards is not an interval / القفزة للخلف ليست فاصلاً
+        tracker.push(50);
+        assert_eq!(tracker.summary().unwrap().intervals, 2);
+        assert_eq!(summarize_timestamps([10]), None);
+    }
+
+    #[test]
+    fn test_summary_cached_until_push() {
+        let mut tracker = JitterTracker::default();
+        tracker.push(0);
+        tracker.push(10);
+        assert_eq!(tracker.summary().unwrap().max_gap_ms, 10.0);
+        assert!(tracker.cached.get().is_some());
+
+        // A new frame drops the cached summary / الإطار الجديد يسقط الملخص المخزن
+        tracker.push(40);
+        assert!(tracker.cached.get().is_none());
+        assert_eq!(tracker.summary().unwrap().max_gap_ms, 30.0);
+
+        tracker.reset();
+        assert_eq!(tracker.summary(), None);
+    }
+}
@@ -0,0 +1,369 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 key_script.rs - Keystroke Recording & Replay
+// ═══════════════════════════════════════════════════════════════════════════════
+// Turns a UI bug report into a short script that can be replayed anywhere.
+// Features:
+// - Records key presses with their offset from the start of the session
+// - Plain-text script format: one `<offset_ms> <key>` per line, `#` comments
+// - Headless replay on ratatui's TestBackend with a scripted clock (no wall time)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::app::App;
+use crate::ui::{self, ColorDepth};
+use csi_tui::error::{CsiError, Result};
+use csi_tui::state::SharedState;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Scripted clock step between keys (ms) / خطوة الساعة المبرمجة بين المفاتيح
+const REPLAY_TICK_MS: u64 = 50;
+
+/// Headless terminal size (columns, rows) / حجم الطرفية بدون شاشة
+pub const REPLAY_SIZE: (u16, u16) = (160, 60);
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Key Names / أسماء المفاتيح
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Script name for a key press, or None if the key is not scriptable
+/// اسم المفتاح في النص، أو None إذا كان غير قابل للبرمجة
+pub fn format_key(key: &KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        _ => return None,
+    };
+
+    let mut prefix = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        prefix.push_str("Ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        prefix.push_str("Alt+");
+    }
+    Some(prefix + &name)
+}
+
+/// Parse a script key name (`q`, `Space`, `F5`, `Ctrl+z`, ...)
+/// تحليل اسم مفتاح من النص
+pub fn parse_key(name: &str) -> Result<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name;
+    loop {
+        if let Some(r) = rest.strip_prefix("Ctrl+") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("Alt+") {
+            modifiers |= KeyModifiers::ALT;
+            rest = r;
+        } else {
+            break;
+        }
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest {
+            "Space" => KeyCode::Char(' '),
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Tab" => KeyCode::Tab,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            f => match f.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+                Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                _ => return Err(CsiError::KeyScript(format!("unknown key '{}'", name))),
+            },
+        },
+    };
+    Ok(KeyEvent::new(code, modifiers))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Recorder / المسجل
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Appends key presses to a script file as they happen
+/// يضيف ضغطات المفاتيح إلى ملف النص عند حدوثها
+pub struct KeyRecorder {
+    /// Script file / ملف النص
+    writer: BufWriter<File>,
+
+    /// Session start; offsets are measured from here / بداية الجلسة
+    started: Instant,
+}
+
+impl KeyRecorder {
+    /// Create (truncate) the script file / إنشاء ملف النص
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .map_err(|e| CsiError::KeyScript(format!("failed to create {}: {}", path.display(), e)))?;
+        let mut recorder = Self { writer: BufWriter::new(file), started: Instant::now() };
+        recorder.write_line("# csi-tui key script: <offset_ms> <key>")?;
+        Ok(recorder)
+    }
+
+    /// Record one key press; flushed right away so a crash keeps it
+    /// تسجيل ضغطة مفتاح؛ تُفرغ فوراً ليبقى التسجيل بعد الانهيار
+    pub fn record(&mut self, key: &KeyEvent) -> Result<()> {
+        let Some(name) = format_key(key) else {
+            return Ok(());
+        };
+        let line = format!("{} {}", self.started.elapsed().as_millis(), name);
+        self.write_line(&line)
+    }
+
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        writeln!(self.writer, "{}", line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| CsiError::KeyScript(format!("failed to record key: {}", e)))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Script / النص
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One scripted key press / ضغطة مفتاح مبرمجة
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyStep {
+    /// Offset from the start of the replay (ms) / الإزاحة من بداية الإعادة
+    pub at_ms: u64,
+
+    /// Key to press / المفتاح المضغوط
+    pub key: KeyEvent,
+}
+
+/// Ordered key presses loaded from a script / ضغطات مفاتيح مرتبة من نص
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct KeyScript {
+    pub steps: Vec<KeyStep>,
+}
+
+impl KeyScript {
+    /// Load a script file / تحميل ملف نص
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| CsiError::KeyScript(format!("failed to read {}: {}", path.display(), e)))?;
+        Self::parse(&text)
+    }
+
+    /// Parse script text; offsets must not go backwards
+    /// تحليل نص المفاتيح؛ يجب ألا تتراجع الإزاحات
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut steps: Vec<KeyStep> = Vec::new();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let bad = |what: &str| CsiError::KeyScript(format!("line {}: {}", n + 1, what));
+
+            let (offset, name) = line.split_once(char::is_whitespace).ok_or_else(|| bad("missing key"))?;
+            let at_ms: u64 = offset.parse().map_err(|_| bad("invalid offset"))?;
+            if steps.last().is_some_and(|s| at_ms < s.at_ms) {
+                return Err(bad("offset goes backwards"));
+            }
+            let name = name.trim();
+            let key = parse_key(name).map_err(|_| bad(&format!("unknown key '{}'", name)))?;
+            steps.push(KeyStep { at_ms, key });
+        }
+        Ok(Self { steps })
+    }
+
+    /// Number of key presses / عدد ضغطات المفاتيح
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Headless Replay / الإعادة بدون شاشة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Replay `script` into `app`, drawing to an off-screen terminal
+/// إعادة تشغيل النص في التطبيق مع الرسم على طرفية خارج الشاشة
+///
+/// Time only advances as the script says, so the same script and data give
+/// the same screen. File dialogs are disabled; data comes from the state.
+pub fn replay(
+    app: &mut App,
+    state: &SharedState,
+    script: &KeyScript,
+    size: (u16, u16),
+    colors: ColorDepth,
+) -> Result<Terminal<TestBackend>> {
+    let mut terminal = Terminal::new(TestBackend::new(size.0, size.1)).map_err(CsiError::Terminal)?;
+    app.disable_dialogs();
+    draw(&mut terminal, state, colors)?;
+
+    let mut now_ms = 0;
+    for step in &script.steps {
+        while now_ms < step.at_ms {
+            let dt = (step.at_ms - now_ms).min(REPLAY_TICK_MS);
+            app.tick(dt as f64)?;
+            now_ms += dt;
+            draw(&mut terminal, state, colors)?;
+        }
+
+        let quit = app.handle_key_event(step.key)?;
+        // No real terminal to hand back / لا توجد طرفية حقيقية لإعادتها
+        app.take_suspend_request();
+        app.tick(0.0)?;
+        draw(&mut terminal, state, colors)?;
+        if quit || state.lock()?.should_quit {
+            break;
+        }
+    }
+    Ok(terminal)
+}
+
+fn draw(terminal: &mut Terminal<TestBackend>, state: &SharedState, colors: ColorDepth) -> Result<()> {
+    terminal
+        .draw(|frame| ui::render(frame, state, colors))
+        .map(|_| ())
+        .map_err(CsiError::Terminal)
+}
+
+/// Text of the off-screen terminal, one line per row / نص الطرفية خارج الشاشة
+pub fn screen_text(terminal: &Terminal<TestBackend>) -> String {
+    let buffer = terminal.backend().buffer();
+    buffer
+        .content()
+        .chunks(buffer.area.width.max(1) as usize)
+        .map(|row| row.iter().map(|c| c.symbol()).collect::<String>().trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csi_tui::state::{create_shared_state, CsiFormat, CsiFrame};
+
+    /// Replay `script` against a state prepared by `setup`
+    fn run(script: &str, setup: impl FnOnce(&mut csi_tui::state::AppState)) -> (SharedState, String) {
+        let state = create_shared_state();
+        setup(&mut state.lock().unwrap());
+        let mut app = App::new(state.clone());
+        let script = KeyScript::parse(script).unwrap();
+        let terminal = replay(&mut app, &state, &script, REPLAY_SIZE, ColorDepth::Full).unwrap();
+        (state, screen_text(&terminal))
+    }
+
+    fn loaded(state: &mut csi_tui::state::AppState) {
+        state.loaded_frames = (0..60)
+            .map(|i| CsiFrame::new(i * 100, vec![50.0 + (i % 3) as f64; 64], vec![], CsiFormat::AmplitudeOnly, None))
+            .collect();
+        state.playback_duration_secs = 5.9;
+        state.start_playback();
+    }
+
+    #[test]
+    fn test_key_names_round_trip() {
+        for name in ["q", "Space", "F5", "Ctrl+z", "Left", "[", "Esc", "Alt+Enter"] {
+            let key = parse_key(name).unwrap();
+            assert_eq!(format_key(&key).as_deref(), Some(name));
+        }
+        assert!(parse_key("F13").is_err());
+        assert!(parse_key("Hyper").is_err());
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let script = KeyScript::parse("# seek test\n0 Space\n\n250 Right\n250 q\n").unwrap();
+        assert_eq!(script.len(), 3);
+        assert_eq!(script.steps[1], KeyStep { at_ms: 250, key: KeyEvent::from(KeyCode::Right) });
+
+        assert!(KeyScript::parse("100 q\n50 q").is_err());
+        assert!(KeyScript::parse("soon q").is_err());
+        assert!(KeyScript::parse("100").is_err());
+    }
+
+    #[test]
+    fn test_recorder_output_replays() {
+        let path = std::path::PathBuf::from("test_record.keys");
+        let mut recorder = KeyRecorder::create(&path).unwrap();
+        for key in [KeyEvent::from(KeyCode::Char(' ')), KeyEvent::from(KeyCode::F(5))] {
+            recorder.record(&key).unwrap();
+        }
+        // Not scriptable: skipped / غير قابل للبرمجة: يتخطى
+        recorder.record(&KeyEvent::from(KeyCode::CapsLock)).unwrap();
+        drop(recorder);
+
+        let script = KeyScript::load(&path).unwrap();
+        let keys: Vec<KeyCode> = script.steps.iter().map(|s| s.key.code).collect();
+        assert_eq!(keys, vec![KeyCode::Char(' '), KeyCode::F(5)]);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_replay_playback_session() {
+        // Play, pause, seek, mark an interval, open and close its stats, go live
+        // تشغيل، إيقاف، تنقل، تحديد فترة، فتح إحصائياتها وإغلاقها، العودة للبث
+        let script = "0 Space\n1000 Space\n1100 Home\n1200 {\n1300 End\n1400 }\n1500 i\n1600 Esc\n1700 b\n";
+        let (state, screen) = run(script, loaded);
+        let s = state.lock().unwrap();
+        assert!(!s.playback_mode);
+        assert!(s.interval_stats.is_none());
+        assert!(screen.contains("Statistics"));
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        // Loading starts playback; pause after 1.234 s / التحميل يبدأ التشغيل؛ إيقاف بعد 1.234 ث
+        let script = "1234 Space\n";
+        let (first, _) = run(script, loaded);
+        let (second, _) = run(script, loaded);
+        let at = |s: &SharedState| s.lock().unwrap().get_current_playback_second();
+        assert_eq!(at(&first), at(&second));
+        assert!((at(&first) - 1.2).abs() < 0.11);
+    }
+
+    #[test]
+    fn test_replay_seek_without_data() {
+        // Seeking and marking before anything was loaded must not panic
+        // التنقل والتحديد قبل تحميل أي بيانات يجب ألا يسبب انهياراً
+        let script = "0 Right\n10 End\n20 Home\n30 [\n40 ]\n50 i\n60 F5\n70 L\n80 q\n";
+        let (state, _) = run(script, |_| {});
+        assert!(!state.lock().unwrap().playback_mode);
+    }
+}
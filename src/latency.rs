@@ -0,0 +1,205 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 latency.rs - Per-Frame Processing Latency Budget
+// ═══════════════════════════════════════════════════════════════════════════════
+// Times each pipeline stage so slow machines show why frames fall behind.
+// Features:
+// - Rolling one-second maxima for parse, lock wait, detectors and draw
+// - Combined budget compared against the current inter-frame interval
+// - Warning only after the budget has been exceeded for over a second
+// - Two `Instant::now` calls per stage, cheap enough to leave on
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::time::{Duration, Instant};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Length of one rolling-max window / طول نافذة القيمة القصوى المتدحرجة
+pub const LATENCY_WINDOW: Duration = Duration::from_secs(1);
+
+/// How long the budget must be exceeded before warning / مدة التجاوز قبل التحذير
+pub const OVER_BUDGET_GRACE: Duration = Duration::from_secs(1);
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Pipeline Stages / مراحل المعالجة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Timed stage of the frame pipeline / مرحلة مؤقتة من مسار الإطار
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Parsing one serial block / تحليل كتلة تسلسلية واحدة
+    Parse,
+
+    /// Waiting for the shared state lock, or in the UI's frame queue
+    /// انتظار قفل الحالة المشتركة أو في طابور إطارات الواجهة
+    LockWait,
+
+    /// Running the detectors once / تشغيل الكاشفات مرة واحدة
+    Detect,
+
+    /// Drawing one UI frame / رسم إطار واجهة واحد
+    Draw,
+}
+
+impl Stage {
+    /// All stages in pipeline order / جميع المراحل بترتيب المسار
+    pub const ALL: [Stage; 4] = [Stage::Parse, Stage::LockWait, Stage::Detect, Stage::Draw];
+
+    /// Short label for the UI / تسمية قصيرة للواجهة
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::LockWait => "lock",
+            Stage::Detect => "detect",
+            Stage::Draw => "draw",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Latency Monitor / مراقب زمن المعالجة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rolling per-stage maxima and the budget warning state
+/// القيم القصوى المتدحرجة لكل مرحلة وحالة تحذير الميزانية
+///
+/// Maxima are kept for the current and the previous window, so a reading
+/// always covers between one and two seconds of samples.
+#[derive(Debug, Clone, Default)]
+pub struct LatencyMonitor {
+    /// Max per stage in the current window / الأقصى لكل مرحلة في النافذة الحالية
+    current: [Duration; 4],
+
+    /// Max per stage in the previous window / الأقصى لكل مرحلة في النافذة السابقة
+    previous: [Duration; 4],
+
+    /// Start of the current window / بداية النافذة الحالية
+    window_start: Option<Instant>,
+
+    /// When the budget was first exceeded / وقت أول تجاوز للميزانية
+    over_since: Option<Instant>,
+
+    /// Inter-frame interval of the last check (ms) / الفاصل بين الإطارات عند آخر فحص
+    budget_ms: Option<f64>,
+}
+
+impl LatencyMonitor {
+    /// Record one stage timing / تسجيل توقيت مرحلة
+    pub fn record(&mut self, stage: Stage, elapsed: Duration, now: Instant) {
+        self.roll(now);
+        let slot = &mut self.current[stage.index()];
+        *slot = (*slot).max(elapsed);
+    }
+
+    /// Rolling max of one stage / القيمة القصوى المتدحرجة لمرحلة
+    pub fn max(&self, stage: Stage) -> Duration {
+        self.current[stage.index()].max(self.previous[stage.index()])
+    }
+
+    /// Sum of the stage maxima: worst case cost of one frame
+    /// مجموع القيم القصوى: أسوأ تكلفة لإطار واحد
+    pub fn total(&self) -> Duration {
+        Stage::ALL.iter().map(|&s| self.max(s)).sum()
+    }
+
+    /// Compare the total against the inter-frame interval (ms)
+    /// مقارنة المجموع بالفاصل بين الإطارات
+    ///
+    /// No interval (no live stream yet) clears the warning.
+    pub fn check_budget(&mut self, frame_interval_ms: Option<f64>, now: Instant) {
+        self.roll(now);
+        self.budget_ms = frame_interval_ms;
+        let over = frame_interval_ms.is_some_and(|budget| ms(self.total()) > budget);
+        self.over_since = if over { self.over_since.or(Some(now)) } else { None };
+    }
+
+    /// Whether the budget has been exceeded for longer than the grace period
+    /// هل تجاوزت الميزانية لأكثر من فترة السماح
+    pub fn over_budget(&self, now: Instant) -> bool {
+        self.over_since.is_some_and(|since| now.duration_since(since) > OVER_BUDGET_GRACE)
+    }
+
+    /// Inter-frame interval used by the last check (ms) / الفاصل المستخدم في آخر فحص
+    pub fn budget_ms(&self) -> Option<f64> {
+        self.budget_ms
+    }
+
+    /// Start a new window once the current one is over
+    /// بدء نافذة جديدة عند انتهاء الحالية
+    fn roll(&mut self, now: Instant) {
+        let Some(start) = self.window_start else {
+            self.window_start = Some(now);
+            return;
+        };
+        let age = now.saturating_duration_since(start);
+        if age < LATENCY_WINDOW {
+            return;
+        }
+        // A silent window in between leaves nothing worth keeping
+        // نافذة صامتة بينهما لا تترك شيئاً يستحق الاحتفاظ
+        self.previous = if age < 2 * LATENCY_WINDOW { self.current } else { [Duration::ZERO; 4] };
+        self.current = [Duration::ZERO; 4];
+        self.window_start = Some(now);
+    }
+}
+
+/// Duration in fractional milliseconds / المدة بالميلي ثانية العشرية
+pub fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_rolling_max_expires() {
+        let t0 = Instant::now();
+        let mut monitor = LatencyMonitor::default();
+        monitor.record(Stage::Draw, millis(8), t0);
+        monitor.record(Stage::Draw, millis(3), t0 + millis(500));
+        monitor.record(Stage::Parse, millis(1), t0 + millis(600));
+        assert_eq!(monitor.max(Stage::Draw), millis(8));
+        assert_eq!(monitor.total(), millis(9));
+
+        // Still visible one window later, gone after two / تبقى نافذة ثم تختفي
+        monitor.record(Stage::Draw, millis(2), t0 + millis(1_100));
+        assert_eq!(monitor.max(Stage::Draw), millis(8));
+        monitor.record(Stage::Draw, millis(2), t0 + millis(2_200));
+        assert_eq!(monitor.max(Stage::Draw), millis(2));
+        assert_eq!(monitor.max(Stage::Parse), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_budget_warning_needs_a_second() {
+        let t0 = Instant::now();
+        let mut monitor = LatencyMonitor::default();
+        monitor.record(Stage::Detect, millis(15), t0);
+
+        // 15 ms of work against 10 ms frames / 15 مللي ثانية عمل مقابل إطارات كل 10
+        monitor.check_budget(Some(10.0), t0);
+        assert!(!monitor.over_budget(t0 + millis(900)));
+        monitor.check_budget(Some(10.0), t0 + millis(900));
+        assert!(monitor.over_budget(t0 + millis(1_100)));
+
+        // Slower stream brings it back under budget / تدفق أبطأ يعيدها ضمن الميزانية
+        monitor.check_budget(Some(20.0), t0 + millis(1_200));
+        assert!(!monitor.over_budget(t0 + millis(1_200)));
+        monitor.check_budget(None, t0 + millis(1_300));
+        assert!(!monitor.over_budget(t0 + millis(3_000)));
+        assert_eq!(monitor.budget_ms(), None);
+    }
+}
@@ -0,0 +1,84 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 lib.rs - CSI Library
+// ═══════════════════════════════════════════════════════════════════════════════
+// TUI-free core shared by the interactive binary and external analysis tools.
+// Features:
+// - CSI parsing with automatic format detection
+// - Frame / detection data types and shared application state
+// - Motion, presence, door and breathing-rate detectors
+// - Input quality floors that mark detections as uncertain
+// - CSV loading and logging (with optional integrity sidecar)
+// - Detector settings saved next to each recording
+// - Following a CSV log that is still being written
+// - A/B interval statistics over a loaded recording
+// - Optional log of serial blocks the parser rejected
+// - Per-stage processing latency against the frame interval
+// - Live frame rate with a rolling average
+// - Bounded reader → UI frame queue with a drop counter
+// - Frame timestamps from the ESP's own timer
+// - Nexmon CSI packets received over UDP
+// - Named sources (one receiver per room) with their own detections
+// - Resettable session statistics (uptime, events, peaks)
+// - Per-subcarrier gain / phase calibration loaded from a file
+// ═══════════════════════════════════════════════════════════════════════════════
+
+//! Wi-Fi CSI parsing, detection and CSV I/O.
+//!
+//! Parse a block from the ESP32 serial output and run the detectors:
+//!
+//! ```
+//! use csi_tui::{extract_csi_block, quick_detect, CsiFrame, CsiParser};
+//!
+//! let parser = CsiParser::new();
+//! let frames: Vec<CsiFrame> = (0..12)
+//!     .filter_map(|i| {
+//!         let line = format!("CSI_DATA,... csi_data:[{},-5,{},-10,15,8]", 10 + i, 20 + i * 3);
+//!         let parsed = parser.parse(extract_csi_block(&line)?)?;
+//!         Some(CsiFrame::new(i * 100, parsed.mags, parsed.pairs, parsed.format, parsed.rssi))
+//!     })
+//!     .collect();
+//!
+//! let results = quick_detect(&frames);
+//! assert!(results.presence_value > 0.0);
+//! ```
+
+pub mod binary_packet;
+pub mod calibration;
+pub mod csv_follower;
+pub mod csv_loader;
+pub mod csv_logger;
+pub mod csv_writer;
+pub mod detection_logger;
+pub mod detector_config;
+pub mod detectors;
+pub mod dynamic;
+pub mod error;
+pub mod esp_clock;
+pub mod event_log;
+pub mod export;
+pub mod features;
+pub mod frame_buffer;
+pub mod frame_queue;
+pub mod frame_rate;
+pub mod integrity;
+pub mod interval_stats;
+pub mod jitter;
+pub mod latency;
+pub mod mac_stats;
+pub mod nexmon;
+pub mod parse_failure_log;
+pub mod parser;
+pub mod quality;
+pub mod report;
+pub mod session_stats;
+pub mod sources;
+pub mod spectrum;
+pub mod state;
+pub mod subcarriers;
+
+pub use csv_loader::CsvLoader;
+pub use csv_logger::CsvLogger;
+pub use detectors::quick_detect;
+pub use error::{CsiError, Result};
+pub use parser::{extract_csi_block, extract_mac, extract_rssi, CsiParser, ParseResult, ParsedBlock};
+pub use state::{CsiFormat, CsiFrame, DetectionResults};
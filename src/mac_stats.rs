@@ -0,0 +1,148 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 mac_stats.rs - Per-Transmitter Statistics
+// ═══════════════════════════════════════════════════════════════════════════════
+// Tracks every MAC seen on the serial link so a filter target can be chosen.
+// Features:
+// - Frame count, rate and last RSSI per MAC
+// - Sliding one-minute window of arrival times
+// - Entries pruned after a minute of inactivity
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::{HashMap, VecDeque};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Stats window and inactivity timeout in ms / نافذة الإحصائيات ومهلة الخمول بالميلي ثانية
+pub const MAC_WINDOW_MS: i64 = 60_000;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Per-MAC Entry / إدخال لكل عنوان MAC
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Statistics for one transmitter
+/// إحصائيات مرسل واحد
+#[derive(Debug, Clone, Default)]
+pub struct MacEntry {
+    /// Arrival times inside the window, oldest first / أوقات الوصول داخل النافذة، الأقدم أولاً
+    arrivals: VecDeque<i64>,
+
+    /// Last reported RSSI (dBm) / آخر قيمة RSSI مبلغ عنها
+    pub last_rssi: Option<i32>,
+}
+
+impl MacEntry {
+    /// Frames received in the last minute / الإطارات المستلمة في آخر دقيقة
+    pub fn frame_count(&self) -> usize {
+        self.arrivals.len()
+    }
+
+    /// Timestamp of the latest frame / الطابع الزمني لآخر إطار
+    pub fn last_seen(&self) -> i64 {
+        self.arrivals.back().copied().unwrap_or(0)
+    }
+
+    /// Frames per second over the window / الإطارات في الثانية عبر النافذة
+    pub fn rate(&self) -> f64 {
+        match (self.arrivals.front(), self.arrivals.back()) {
+            (Some(&first), Some(&last)) if last > first => {
+                (self.arrivals.len() - 1) as f64 * 1000.0 / (last - first) as f64
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn drop_before(&mut self, cutoff: i64) {
+        while self.arrivals.front().is_some_and(|&t| t < cutoff) {
+            self.arrivals.pop_front();
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 MAC Table / جدول عناوين MAC
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// All transmitters seen in the last minute
+/// جميع المرسلين الذين ظهروا في آخر دقيقة
+#[derive(Debug, Clone, Default)]
+pub struct MacTable {
+    entries: HashMap<String, MacEntry>,
+}
+
+impl MacTable {
+    /// Record one frame from `mac` / تسجيل إطار واحد من `mac`
+    pub fn record(&mut self, mac: &str, rssi: Option<i32>, now: i64) {
+        let entry = self.entries.entry(mac.to_string()).or_default();
+        entry.arrivals.push_back(now);
+        entry.drop_before(now - MAC_WINDOW_MS);
+        if rssi.is_some() {
+            entry.last_rssi = rssi;
+        }
+    }
+
+    /// Drop old arrivals and inactive transmitters
+    /// حذف الوصولات القديمة والمرسلين غير النشطين
+    pub fn prune(&mut self, now: i64) {
+        let cutoff = now - MAC_WINDOW_MS;
+        self.entries.retain(|_, entry| {
+            entry.drop_before(cutoff);
+            !entry.arrivals.is_empty()
+        });
+    }
+
+    /// Entries sorted by frame count, busiest first
+    /// الإدخالات مرتبة حسب عدد الإطارات، الأكثر نشاطاً أولاً
+    pub fn sorted(&self) -> Vec<(&str, &MacEntry)> {
+        let mut rows: Vec<(&str, &MacEntry)> =
+            self.entries.iter().map(|(mac, e)| (mac.as_str(), e)).collect();
+        rows.sort_by(|a, b| b.1.frame_count().cmp(&a.1.frame_count()).then(a.0.cmp(b.0)));
+        rows
+    }
+
+    /// Number of transmitters tracked / عدد المرسلين المتتبعين
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if no transmitter is tracked / صحيح إذا لم يتم تتبع أي مرسل
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_rate_and_rssi() {
+        let mut table = MacTable::default();
+        for i in 0..11 {
+            table.record("AA:BB:CC:DD:EE:01", Some(-40 - i), i as i64 * 100);
+        }
+        table.record("AA:BB:CC:DD:EE:02", None, 500);
+
+        let rows = table.sorted();
+        assert_eq!(rows[0].0, "AA:BB:CC:DD:EE:01");
+        assert_eq!(rows[0].1.frame_count(), 11);
+        assert!((rows[0].1.rate() - 10.0).abs() < 1e-9);
+        assert_eq!(rows[0].1.last_rssi, Some(-50));
+        assert_eq!(rows[1].1.last_rssi, None);
+    }
+
+    #[test]
+    fn test_prune_inactive() {
+        let mut table = MacTable::default();
+        table.record("old", Some(-70), 0);
+        table.record("new", Some(-40), 50_000);
+        table.prune(MAC_WINDOW_MS + 1);
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.sorted()[0].0, "new");
+    }
+}
@@ -1,53 +1,268 @@
-﻿// main.rs - Application Entry Point
+﻿// main.rs - Application Entry Point (interactive TUI on top of the csi_tui library)
 mod app;
-mod csv_loader;
-mod csv_logger;
-mod detectors;
+mod config;
 mod esp_terminal;
+mod key_script;
 mod menu;
-mod parser;
+mod network_reader;
 mod serial_reader;
-mod state;
+mod suspend;
 mod ui;
 
-use std::io;
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 use crate::app::App;
+use crate::config::Config;
+use crate::ui::ColorDepth;
+use csi_tui::calibration::Calibration;
+use csi_tui::csv_loader::CsvLoader;
+use csi_tui::csv_logger::{find_interrupted_logs, recover_log, RecoveryOutcome};
+use csi_tui::detection_logger::DetectionLogger;
+use csi_tui::detectors::{Aggregation, DisplayScale, PrimaryDetector, DEFAULT_OUTLIER_SIGMA};
+use csi_tui::dynamic::{DynamicFilter, DEFAULT_MEDIAN_WINDOW_SECS};
+use csi_tui::error::{CsiError, Result};
 use crate::esp_terminal::run_esp_terminal;
+use crate::key_script::{replay, screen_text, KeyRecorder, KeyScript, REPLAY_SIZE};
+use csi_tui::event_log::{EventLog, DEFAULT_MAX_EVENTS_PER_SEC};
+use csi_tui::features::FeatureLogger;
+use csi_tui::integrity::{verify_file, VerifyOutcome};
+use csi_tui::nexmon::DEFAULT_NEXMON_ADDR;
+use csi_tui::parser::DEFAULT_MIN_SUBCARRIERS;
+use csi_tui::report::ReportOptions;
+use csi_tui::quality::{
+    QualityFloors, DEFAULT_MAX_CLIP_FRACTION, DEFAULT_MIN_FORMAT_CONFIDENCE, DEFAULT_MIN_QUALITY_SUBCARRIERS,
+};
 use crate::menu::{show_menu, MenuChoice};
-use crate::state::create_shared_state;
+use crate::serial_reader::{start_recording, SerialReader};
+use crate::suspend::{enter_tui, is_foreground, leave_tui, new_terminal, JobControl, Tui};
+use csi_tui::state::{
+    create_shared_state, ReceiverState, SharedState, DEFAULT_BAUD_RATE, DEFAULT_REFRESH_FPS, DEFAULT_SEEK_SETTLE, DEFAULT_SILENCE_TIMEOUTS,
+    DEFAULT_WARMUP_SECS, DEFAULT_WINDOW_SECS, REFRESH_RATE_STEPS,
+};
+
+fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // csi-tui verify <file> - check a logged CSV against its sidecar
+    if args.first().map(String::as_str) == Some("verify") {
+        let code = match args.get(1) {
+            Some(path) => run_verify(path),
+            None => {
+                let e = CsiError::InvalidInput("usage: csi-tui verify <file.csv>".to_string());
+                eprintln!("Error: {}", e);
+                e.exit_code()
+            }
+        };
+        std::process::exit(code);
+    }
+
+    // --log-csv - start recording frames to CSV with the live session (W toggles)
+    // --integrity - write `.meta` digest sidecars for new CSV logs
+    // --log-rotate-mb N - continue CSV logs in a new `_partNNN` file every N MB (chained with --integrity)
+    // --event-rate N - max event log entries per second
+    // --median-window SECS - rolling median window for the dynamic chart
+    // --log-detections - append detector samples to detections_<time>.csv
+    // --export-features - write one ML feature row per frame to features_<time>.csv
+    // --log-parse-failures - keep rejected serial blocks in parse_failures_<time>.log
+    // --no-reconnect - stop on a lost serial port instead of reopening it (F6 toggles)
+    // --silence-timeouts N - empty serial reads (100 ms each) before a "no bytes" warning (0 = off)
+    // --compact - start with the compact status layout
+    // --report-no-raw - leave raw serial lines and frames out of report bundles (U)
+    // --report-mask-macs - mask MAC addresses in report bundles
+    // --recalibrate-every MINUTES - recapture the calibration baseline on a schedule
+    // --drop-duplicates - skip serial frames identical to the previous one
+    // --streams N - split each frame into N equal streams, one per antenna (N key cycles)
+    // --binary - serial CSI arrives as length-prefixed int8 I/Q packets
+    // --source NAME=PORT - extra receiver for the per-room summary (repeat for more rooms)
+    // --port PORT - read PORT (or a TCP bridge, tcp://HOST:PORT) instead of auto-detecting
+    // --udp [ADDR] - S listens for nexmon CSI over UDP instead of serial (default 0.0.0.0:5500)
+    // --min-subcarriers N - drop serial frames with fewer subcarriers (default 8)
+    // --calibration FILE - per-subcarrier 'index,gain,phase' corrections for live frames
+    // --floor-confidence F - format confidence (0-1) below which detections are uncertain
+    // --floor-subcarriers N - subcarrier count below which detections are uncertain
+    // --floor-clip F - clipped-value fraction (0-1) above which detections are uncertain
+    // --keep-null-subcarriers - analyze guard/DC subcarriers instead of stripping them
+    // --primary-detector motion|presence|door - analysis range shown in stats
+    // --warmup SECS - time after serial start before detections are trusted
+    // --seek-settle SECS - quiet period for detection events after a playback seek
+    // --aggregation mean|median|trimmed - magnitude summary for chart and detectors
+    // --outlier-sigma N - clamp detector magnitudes beyond N deviations of the frame median (default 4, 0 = off)
+    // --display-scale threshold|raw - detector values in the panel and chart
+    // --follow FILE - tail a growing CSV log as live data
+    // --fps N - maximum UI redraws per second (2-30)
+    // --fps-floor N - frame rate below which the Rate line turns yellow/red
+    // --idle-animation - animate the empty chart while waiting for data
+    // --pause-unfocused - stop redrawing while the terminal window is unfocused
+    // --colors auto|full|8|mono - override the detected terminal color depth
+    // --record-keys FILE - write every key press to a replayable script
+    // --replay-keys FILE [--csv DATA] - replay a key script headless and print the screen
+    // --record --duration SECS [--port PORT] [--baud N] [--out FILE] - log serial CSI headless
+    let primary_detector = parse_flag(&args, "--primary-detector", PrimaryDetector::default());
+    let aggregation = parse_flag(&args, "--aggregation", Aggregation::default());
+    let display_scale = parse_flag(&args, "--display-scale", DisplayScale::default());
+    let colors = match arg_value(&args, "--colors") {
+        None | Some("auto") => ColorDepth::detect(),
+        Some(_) => parse_flag(&args, "--colors", ColorDepth::Full),
+    };
+    let calibration = match arg_value(&args, "--calibration") {
+        Some(path) => match Calibration::load(Path::new(path)) {
+            Ok(c) => Some(c),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(e.exit_code());
+            }
+        },
+        None => None,
+    };
+    let non_negative = |x: &f64| x.is_finite() && *x >= 0.0;
+    let fraction = |x: &f64| (0.0..=1.0).contains(x);
+    let mut options = ViewerOptions {
+        log_csv: args.iter().any(|a| a == "--log-csv"),
+        integrity: args.iter().any(|a| a == "--integrity"),
+        log_rotate_bytes: arg_value(&args, "--log-rotate-mb").map(|_| {
+            let mb = number_flag(&args, "--log-rotate-mb", 0.0, |mb: &f64| mb.is_finite() && *mb > 0.0);
+            (mb * 1024.0 * 1024.0) as u64
+        }),
+        idle_animation: args.iter().any(|a| a == "--idle-animation"),
+        compact: args.iter().any(|a| a == "--compact"),
+        report_options: ReportOptions {
+            include_raw: !args.iter().any(|a| a == "--report-no-raw"),
+            mask_macs: args.iter().any(|a| a == "--report-mask-macs"),
+        },
+        pause_unfocused: args.iter().any(|a| a == "--pause-unfocused"),
+        colors,
+        log_detections: args.iter().any(|a| a == "--log-detections"),
+        export_features: args.iter().any(|a| a == "--export-features"),
+        log_parse_failures: args.iter().any(|a| a == "--log-parse-failures"),
+        auto_reconnect: !args.iter().any(|a| a == "--no-reconnect"),
+        silence_timeouts: number_flag(&args, "--silence-timeouts", DEFAULT_SILENCE_TIMEOUTS, |_| true),
+        binary: args.iter().any(|a| a == "--binary"),
+        sources: args
+            .iter()
+            .zip(args.iter().skip(1))
+            .filter(|(flag, _)| *flag == "--source")
+            .filter_map(|(_, value)| value.split_once('='))
+            .map(|(name, port)| (name.to_string(), port.to_string()))
+            .collect(),
+        port: arg_value(&args, "--port").map(str::to_string),
+        udp_source: args.iter().any(|a| a == "--udp").then(|| {
+            arg_value(&args, "--udp")
+                .filter(|v| !v.starts_with("--"))
+                .unwrap_or(DEFAULT_NEXMON_ADDR)
+                .to_string()
+        }),
+        drop_duplicates: args.iter().any(|a| a == "--drop-duplicates"),
+        strip_null_subcarriers: !args.iter().any(|a| a == "--keep-null-subcarriers"),
+        min_subcarriers: number_flag(&args, "--min-subcarriers", DEFAULT_MIN_SUBCARRIERS, |_| true),
+        calibration,
+        quality_floors: QualityFloors {
+            min_format_confidence: number_flag(&args, "--floor-confidence", DEFAULT_MIN_FORMAT_CONFIDENCE, fraction),
+            min_subcarriers: number_flag(&args, "--floor-subcarriers", DEFAULT_MIN_QUALITY_SUBCARRIERS, |_| true),
+            max_clip_fraction: number_flag(&args, "--floor-clip", DEFAULT_MAX_CLIP_FRACTION, fraction),
+        },
+        max_events_per_sec: number_flag(&args, "--event-rate", DEFAULT_MAX_EVENTS_PER_SEC, |&n| n > 0),
+        median_window_secs: number_flag(&args, "--median-window", DEFAULT_MEDIAN_WINDOW_SECS, |secs: &f64| {
+            secs.is_finite() && *secs > 0.0
+        }),
+        primary_detector,
+        aggregation,
+        outlier_sigma: number_flag(&args, "--outlier-sigma", DEFAULT_OUTLIER_SIGMA, non_negative),
+        display_scale,
+        warmup_secs: number_flag(&args, "--warmup", DEFAULT_WARMUP_SECS, non_negative),
+        seek_settle: Duration::from_secs_f64(number_flag(
+            &args,
+            "--seek-settle",
+            DEFAULT_SEEK_SETTLE.as_secs_f64(),
+            |secs: &f64| Duration::try_from_secs_f64(*secs).is_ok(),
+        )),
+        resume_log: None,
+        follow: arg_value(&args, "--follow").map(PathBuf::from),
+        refresh_fps: number_flag(&args, "--fps", DEFAULT_REFRESH_FPS, |fps| {
+            (REFRESH_RATE_STEPS[0]..=REFRESH_RATE_STEPS[4]).contains(fps)
+        }),
+        fps_floor: number_flag(&args, "--fps-floor", 0.0, non_negative),
+        record_keys: arg_value(&args, "--record-keys").map(PathBuf::from),
+        recalibrate_every_secs: arg_value(&args, "--recalibrate-every").map(|_| {
+            number_flag(&args, "--recalibrate-every", 0, |&minutes: &u64| minutes > 0 && minutes.checked_mul(60).is_some()) * 60
+        }),
+        stream_split: number_flag(&args, "--streams", 0, |&n| n <= MAX_STREAM_SPLIT),
+        window_secs: DEFAULT_WINDOW_SECS,
+    };
+
+    // Headless replay skips the menu and the real terminal
+    // الإعادة بدون شاشة تتخطى القائمة والطرفية الحقيقية
+    if let Some(script) = arg_value(&args, "--replay-keys") {
+        let code = match run_replay(&options, Path::new(script), arg_value(&args, "--csv").map(Path::new)) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                e.exit_code()
+            }
+        };
+        std::process::exit(code);
+    }
+
+    // Headless recording skips the menu and the TUI
+    // التسجيل بدون شاشة يتخطى القائمة والواجهة
+    if args.iter().any(|a| a == "--record") {
+        let code = match record_options(&args).and_then(|record| run_record(&options, &record)) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                e.exit_code()
+            }
+        };
+        std::process::exit(code);
+    }
+
+    // Offer to repair logs left open by a crash / عرض إصلاح السجلات المتروكة بعد انهيار
+    options.resume_log = recover_interrupted_logs();
+
+    // Last session's settings; missing or corrupt files give the defaults
+    // إعدادات الجلسة السابقة؛ الملفات المفقودة أو التالفة تعطي القيم الافتراضية
+    let mut config = Config::load();
+    options.window_secs = config.window_secs;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
     loop {
         // Small delay to ensure terminal is ready
         std::thread::sleep(std::time::Duration::from_millis(100));
         
-        let choice = match show_menu() {
+        let choice = match show_menu(&config) {
             Ok(c) => c,
             Err(e) => {
-                eprintln!("Error: {}", e);
+                eprintln!("{}", e.status_message());
                 continue;
             }
         };
         
         match choice {
-            MenuChoice::SetEsp { port, baud } => {
-                if let Err(e) = run_esp_terminal(&port, baud) {
-                    eprintln!("Error: {}", e);
+            MenuChoice::SetEsp { port, baud } => match run_esp_terminal(&port, baud) {
+                Ok(()) => {
+                    // Remember settings that worked / تذكر الإعدادات التي نجحت
+                    config.port = port;
+                    config.baud = baud;
+                    save_config(&config);
+                }
+                Err(e) => {
+                    eprintln!("{}", e.status_message());
                     println!("Press Enter to continue...");
                     let mut input = String::new();
                     let _ = io::stdin().read_line(&mut input);
                 }
-            }
+            },
             MenuChoice::ViewCsiOutput => {
-                if let Err(e) = run_csi_viewer() {
-                    eprintln!("Error: {}", e);
+                match run_csi_viewer(&options) {
+                    Ok(window_secs) if window_secs != config.window_secs => {
+                        config.window_secs = window_secs;
+                        options.window_secs = window_secs;
+                        save_config(&config);
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("{}", e.status_message()),
                 }
+                options.resume_log = None;
             }
             MenuChoice::Quit => {
                 println!("Goodbye!");
@@ -58,62 +273,400 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn run_csi_viewer() -> Result<(), Box<dyn std::error::Error>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    terminal.clear()?;
+/// Save settings, reporting (but not failing on) errors
+/// حفظ الإعدادات مع الإبلاغ عن الأخطاء دون الفشل
+fn save_config(config: &Config) {
+    if let Err(e) = config.save() {
+        eprintln!("{}", e.status_message());
+    }
+}
+
+/// Command-line options applied to each CSI viewer session
+struct ViewerOptions {
+    log_csv: bool,
+    integrity: bool,
+    log_rotate_bytes: Option<u64>,
+    idle_animation: bool,
+    compact: bool,
+    report_options: ReportOptions,
+    pause_unfocused: bool,
+    colors: ColorDepth,
+    log_detections: bool,
+    export_features: bool,
+    log_parse_failures: bool,
+    auto_reconnect: bool,
+    silence_timeouts: u32,
+    binary: bool,
+    sources: Vec<(String, String)>,
+    port: Option<String>,
+    udp_source: Option<String>,
+    drop_duplicates: bool,
+    strip_null_subcarriers: bool,
+    min_subcarriers: usize,
+    calibration: Option<Calibration>,
+    quality_floors: QualityFloors,
+    max_events_per_sec: usize,
+    median_window_secs: f64,
+    primary_detector: PrimaryDetector,
+    aggregation: Aggregation,
+    outlier_sigma: f64,
+    display_scale: DisplayScale,
+    warmup_secs: f64,
+    seek_settle: Duration,
+    resume_log: Option<PathBuf>,
+    follow: Option<PathBuf>,
+    refresh_fps: u32,
+    fps_floor: f64,
+    record_keys: Option<PathBuf>,
+    recalibrate_every_secs: Option<u64>,
+    stream_split: usize,
+    window_secs: u64,
+}
+
+/// Settings for a headless `--record` capture
+struct RecordOptions {
+    port: Option<String>,
+    baud_rate: u32,
+    duration: Duration,
+    out: Option<PathBuf>,
+}
+
+/// Read the `--record` flags; the duration is required
+fn record_options(args: &[String]) -> Result<RecordOptions> {
+    let duration = arg_value(args, "--duration")
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|&secs| secs > 0.0)
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| CsiError::InvalidInput("--record needs --duration SECS (> 0)".to_string()))?;
+    let baud_rate = match arg_value(args, "--baud") {
+        Some(v) => v
+            .parse()
+            .map_err(|_| CsiError::InvalidInput(format!("invalid baud rate '{}'", v)))?,
+        None => DEFAULT_BAUD_RATE,
+    };
+    Ok(RecordOptions {
+        port: arg_value(args, "--port").map(str::to_string),
+        baud_rate,
+        duration,
+        out: arg_value(args, "--out").map(PathBuf::from),
+    })
+}
+
+/// Largest `--streams` split (one stream per antenna) / أكبر تقسيم للتدفقات
+const MAX_STREAM_SPLIT: usize = 8;
+
+/// Value following a `--flag` argument
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Parsed value of a `--flag`, or `default` when absent; a bad value exits
+/// قيمة `--flag` بعد التحليل، أو الافتراضية عند غيابها؛ القيمة الخاطئة تنهي البرنامج
+fn parse_flag<T: FromStr<Err = CsiError>>(args: &[String], name: &str, default: T) -> T {
+    arg_value(args, name).map_or(default, |v| v.parse().unwrap_or_else(|e| exit_with(e)))
+}
+
+/// Numeric `--flag`, or `default` when absent; unparsable or rejected values exit
+/// قيمة `--flag` الرقمية، أو الافتراضية عند غيابها؛ القيم غير الصالحة تنهي البرنامج
+fn number_flag<T: FromStr>(args: &[String], name: &str, default: T, valid: impl Fn(&T) -> bool) -> T {
+    arg_value(args, name).map_or(default, |v| {
+        v.parse().ok().filter(|value| valid(value)).unwrap_or_else(|| {
+            exit_with(CsiError::InvalidInput(format!("invalid value '{}' for {}", v, name)))
+        })
+    })
+}
+
+/// Print a fatal CLI error and exit with its code / طباعة خطأ قاتل والخروج برمزه
+fn exit_with(e: CsiError) -> ! {
+    eprintln!("Error: {}", e);
+    std::process::exit(e.exit_code())
+}
+
+/// Ask a y/n question on stdin / طرح سؤال نعم/لا
+fn confirm(question: &str) -> bool {
+    print!("{} [y/n] ", question);
+    let _ = io::stdout().flush();
+    let mut input = String::new();
+    let _ = io::stdin().read_line(&mut input);
+    matches!(input.trim(), "y" | "Y" | "yes")
+}
+
+/// Finalize interrupted logs in the working directory, returning one to resume
+/// إنهاء السجلات المنقطعة في مجلد العمل وإرجاع سجل للمتابعة
+fn recover_interrupted_logs() -> Option<PathBuf> {
+    let mut resume = None;
+
+    for path in find_interrupted_logs(Path::new(".")) {
+        println!("⚠️ {} was not closed cleanly (interrupted session).", path.display());
+        if !confirm("Finalize it now?") {
+            continue;
+        }
+
+        match recover_log(&path) {
+            Ok(RecoveryOutcome::Repaired { torn_bytes, rows }) => {
+                println!("✅ Recovered {} rows ({} torn bytes removed)", rows, torn_bytes);
+            }
+            Ok(RecoveryOutcome::AlreadyClean) => println!("✅ Already clean"),
+            Err(e) => {
+                eprintln!("{}", e.status_message());
+                continue;
+            }
+        }
+
+        if resume.is_none() && confirm("Resume appending to it in the next capture?") {
+            resume = Some(path);
+        }
+    }
+
+    resume
+}
+
+/// Verify a logged CSV and return the process exit code
+fn run_verify(path: &str) -> i32 {
+    match verify_file(Path::new(path)) {
+        Ok(VerifyOutcome::Intact { bytes, rows, finalized }) => {
+            println!("✅ {}: intact ({} bytes, {} rows)", path, bytes, rows);
+            if !finalized {
+                println!("⚠️ Log was not finalized (session may have been interrupted)");
+            }
+            0
+        }
+        Ok(VerifyOutcome::Diverged { offset, reason }) => {
+            println!("❌ {}: diverges at byte {} - {}", path, offset, reason);
+            1
+        }
+        Err(e) => {
+            eprintln!("{}", e.status_message());
+            e.exit_code()
+        }
+    }
+}
+
+/// Apply the command-line options to a fresh viewer state
+fn configure_state(state: &SharedState, options: &ViewerOptions) {
+    if let Ok(mut state_guard) = state.lock() {
+        state_guard.integrity_logging = options.integrity;
+        state_guard.log_rotate_bytes = options.log_rotate_bytes;
+        state_guard.window_secs = options.window_secs;
+        state_guard.recalibrate_every_secs = options.recalibrate_every_secs;
+        state_guard.stream_split = options.stream_split;
+        state_guard.parse_failure_logging = options.log_parse_failures;
+        state_guard.auto_reconnect = options.auto_reconnect;
+        state_guard.silence_timeouts = options.silence_timeouts;
+        state_guard.min_subcarriers = options.min_subcarriers;
+        state_guard.calibration = options.calibration.clone();
+        state_guard.strip_null_subcarriers = options.strip_null_subcarriers;
+        state_guard.binary_serial = options.binary;
+        state_guard.udp_source = options.udp_source.clone();
+        if let Some(port) = &options.port {
+            state_guard.port_name = port.clone();
+            state_guard.port_chosen = true;
+        }
+        for (name, port) in &options.sources {
+            state_guard.sources.add(name, port);
+        }
+        state_guard.drop_duplicate_frames = options.drop_duplicates;
+        state_guard.quality_floors = options.quality_floors;
+        state_guard.event_log = EventLog::new(options.max_events_per_sec);
+        state_guard.dynamic = DynamicFilter::new(options.median_window_secs);
+        state_guard.primary_detector = options.primary_detector;
+        state_guard.warmup_secs = options.warmup_secs;
+        state_guard.seek_settle = options.seek_settle;
+        state_guard.aggregation = options.aggregation;
+        state_guard.detector_windows.outlier_sigma = options.outlier_sigma;
+        state_guard.display_scale = options.display_scale;
+        state_guard.resume_log = options.resume_log.clone();
+        // A log chosen for resuming is recorded into right away / السجل المختار للمتابعة يُسجل فيه مباشرة
+        state_guard.record_csv = options.log_csv || options.resume_log.is_some();
+        state_guard.ui_refresh_fps = options.refresh_fps;
+        state_guard.fps_floor = options.fps_floor;
+        state_guard.idle_animation = options.idle_animation;
+        state_guard.compact_status = options.compact;
+        state_guard.report_options = options.report_options;
+        if options.log_detections {
+            match DetectionLogger::new_with_timestamp() {
+                Ok(logger) => state_guard.detection_logger = Some(logger),
+                Err(e) => state_guard.status_message = e.status_message(),
+            }
+        }
+        if options.export_features {
+            match FeatureLogger::new_with_timestamp() {
+                Ok(logger) => state_guard.feature_logger = Some(logger),
+                Err(e) => state_guard.status_message = e.status_message(),
+            }
+        }
+    }
+}
+
+/// Replay a key script against CSV data (or a followed file) without a terminal
+/// إعادة نص مفاتيح على بيانات CSV (أو ملف متابع) بدون طرفية
+fn run_replay(options: &ViewerOptions, script: &Path, csv: Option<&Path>) -> Result<()> {
+    let script = KeyScript::load(script)?;
+    let state = create_shared_state();
+    configure_state(&state, options);
+
+    let mut app = App::new(state.clone());
+    if let Some(path) = csv {
+        let summary = CsvLoader::new().load_into_state(path, &state)?;
+        for row in &summary.skipped {
+            eprintln!("⚠️ Skipped line {}: {}", row.line, row.reason);
+        }
+    } else if let Some(path) = &options.follow {
+        app.follow_csv(path)?;
+    }
+    let terminal = replay(&mut app, &state, &script, REPLAY_SIZE, options.colors)?;
+
+    println!("{}", screen_text(&terminal));
+    println!("✅ Replayed {} keys: {}", script.len(), state.lock()?.status_message);
+    Ok(())
+}
+
+/// Log serial CSI to a CSV for a fixed time without a terminal UI
+/// تسجيل CSI التسلسلي في CSV لمدة محددة بدون واجهة
+///
+/// Returns the process exit code: 0, or 3 when the port failed.
+fn run_record(options: &ViewerOptions, record: &RecordOptions) -> Result<i32> {
+    let state = create_shared_state();
+    configure_state(&state, options);
+    {
+        let mut state_guard = state.lock()?;
+        state_guard.record_csv = true;
+        state_guard.baud_rate = record.baud_rate;
+    }
+    let path = start_recording(&state, record.out.clone())?;
+
+    let mut reader = SerialReader::new(state.clone()).with_binary(options.binary);
+    if let Some(port) = &record.port {
+        reader = reader.with_port(port);
+    }
+    reader.start()?;
+    let port_name = state.lock()?.port_name.clone();
+    println!(
+        "⏺️ Recording {} @ {} baud for {:.1}s to {}",
+        port_name,
+        record.baud_rate,
+        record.duration.as_secs_f64(),
+        path.display()
+    );
+
+    let started = Instant::now();
+    let mut failure = None;
+    while started.elapsed() < record.duration {
+        std::thread::sleep(Duration::from_millis(100));
+        let state_guard = state.lock()?;
+        if state_guard.receiver == ReceiverState::Error {
+            failure = Some(state_guard.status_message.clone());
+            break;
+        }
+    }
+    let elapsed = started.elapsed();
+    reader.stop();
+
+    let frames = state.lock()?.session.frames;
+    println!("✅ Captured {} frames in {:.1}s to {}", frames, elapsed.as_secs_f64(), path.display());
+    match failure {
+        Some(message) => {
+            eprintln!("{}", message);
+            Ok(3)
+        }
+        None => Ok(0),
+    }
+}
+
+/// Run one viewer session; returns the live window it ended with
+fn run_csi_viewer(options: &ViewerOptions) -> Result<u64> {
+    let mut terminal = new_terminal()?;
+    enter_tui(&mut terminal, options.pause_unfocused)?;
 
     let state = create_shared_state();
+    configure_state(&state, options);
     let mut app = App::new(state.clone());
-    let result = run_app_loop(&mut terminal, &mut app, &state);
+    app.pause_when_unfocused(options.pause_unfocused);
+    if let Some(path) = &options.record_keys {
+        match KeyRecorder::create(path) {
+            Ok(recorder) => app.record_keys(recorder),
+            Err(e) => state.lock()?.status_message = e.status_message(),
+        }
+    }
+    if let Some(path) = &options.follow {
+        app.follow_csv(path)?;
+    }
+    let result = run_app_loop(&mut terminal, &mut app, &state, options);
 
     // Cleanup - important to do in correct order!
     // تنظيف - مهم بالترتيب الصحيح!
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-    terminal.show_cursor()?;
+    leave_tui(&mut terminal, options.pause_unfocused)?;
     
     // Clear any pending events
     // تنظيف الأحداث المعلقة
-    while crossterm::event::poll(std::time::Duration::from_millis(10))? {
+    while crossterm::event::poll(Duration::from_millis(10)).map_err(CsiError::Terminal)? {
         let _ = crossterm::event::read();
     }
     
-    result.map_err(|e| e.into())
+    // The window the session ended with is saved for next time
+    // النافذة التي انتهت بها الجلسة تُحفظ للمرة القادمة
+    result?;
+    let window_secs = state.lock()?.window_secs;
+    Ok(window_secs)
 }
 
 fn run_app_loop(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    terminal: &mut Tui,
     app: &mut App,
-    state: &state::SharedState,
-) -> Result<(), String> {
+    state: &SharedState,
+    options: &ViewerOptions,
+) -> Result<()> {
+    let (focus_events, colors) = (options.pause_unfocused, options.colors);
+    let job_control = JobControl::register()?;
+    let mut in_background = false;
+    let mut last_tick = std::time::Instant::now();
     loop {
-        {
-            let mut state_guard = state.lock().map_err(|e| e.to_string())?;
-            if state_guard.playback_mode && state_guard.playback_playing {
-                if let Some(frame) = state_guard.advance_playback() {
-                    if frame.subcarrier_count() > state_guard.max_sc {
-                        state_guard.max_sc = frame.subcarrier_count();
-                    }
-                    state_guard.frames.push(frame);
-                    if state_guard.frames.len() > 100 {
-                        state_guard.frames.remove(0);
-                    }
-                    state_guard.status_message = format!("Playing: {:.1}s / {:.1}s",
-                        state_guard.get_current_playback_second(),
-                        state_guard.playback_duration_secs
-                    );
-                }
+        // Ctrl+Z (a key in raw mode) or SIGTSTP: hand the terminal back, then stop
+        // Ctrl+Z أو SIGTSTP: إعادة الطرفية ثم الإيقاف
+        if !in_background && (app.take_suspend_request() || job_control.take_stop()) {
+            leave_tui(terminal, focus_events)?;
+            job_control.stop_process();
+            in_background = true;
+        }
+
+        // After `fg` (or SIGCONT) re-enter the TUI; after `bg` keep ingesting off-screen
+        // بعد `fg` نعود للواجهة؛ بعد `bg` نتابع الاستقبال بدون شاشة
+        if job_control.take_continue() || in_background {
+            if is_foreground() {
+                enter_tui(terminal, focus_events)?;
+                app.force_redraw();
+                in_background = false;
+            } else {
+                in_background = true;
             }
         }
-        app.run_detectors()?;
-        terminal.draw(|frame| { ui::render(frame, state); }).map_err(|e| format!("Draw error: {}", e))?;
+
+        // Playback is paced by wall time, not by loop iterations
+        // التشغيل يعتمد على الوقت الفعلي وليس عدد الدورات
+        let elapsed_ms = last_tick.elapsed().as_secs_f64() * 1000.0;
+        last_tick = std::time::Instant::now();
+        app.tick(elapsed_ms)?;
+
+        // In the background, reading keys or drawing would stop the process again
+        // في الخلفية، قراءة المفاتيح أو الرسم توقف العملية مجدداً
+        if in_background {
+            std::thread::sleep(Duration::from_millis(250));
+            continue;
+        }
+
+        // Redraw at most at the configured rate; input is polled regardless
+        // إعادة الرسم بحد أقصى حسب المعدل المضبوط؛ فحص الإدخال مستمر
+        if app.draw_due() {
+            let draw_started = std::time::Instant::now();
+            terminal.draw(|frame| { ui::render(frame, state, colors); }).map_err(CsiError::Terminal)?;
+            app.mark_drawn(draw_started.elapsed());
+        }
         if app.handle_events()? { break; }
         {
-            let state_guard = state.lock().map_err(|e| e.to_string())?;
+            let state_guard = state.lock()?;
             if state_guard.should_quit { break; }
         }
     }
@@ -2,6 +2,7 @@
 // 📦 menu.rs - Main Menu (Simple)
 // ═══════════════════════════════════════════════════════════════════════════════
 // قائمة بسيطة: Set ESP أو View CSI Output
+// Port and baud prompts default to the saved settings
 // ═══════════════════════════════════════════════════════════════════════════════
 
 use std::io::{self, Write};
@@ -13,6 +14,9 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, Clear, ClearType},
 };
 
+use crate::config::Config;
+use csi_tui::error::{CsiError, Result};
+
 /// Menu choice
 #[derive(Debug, Clone, PartialEq)]
 pub enum MenuChoice {
@@ -22,14 +26,14 @@ pub enum MenuChoice {
 }
 
 /// Show main menu and get choice
-pub fn show_menu() -> Result<MenuChoice, String> {
+pub fn show_menu(config: &Config) -> Result<MenuChoice> {
     // Make sure terminal is in normal mode first
     let _ = disable_raw_mode();
     
     let mut stdout = io::stdout();
     
     // Clear screen
-    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).map_err(|e| e.to_string())?;
+    execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)).map_err(CsiError::Terminal)?;
     
     // Print menu
     println!();
@@ -53,10 +57,10 @@ pub fn show_menu() -> Result<MenuChoice, String> {
     
     println!();
     println!("  Press 1, 2, or Q:");
-    stdout.flush().map_err(|e| e.to_string())?;
+    stdout.flush().map_err(CsiError::Terminal)?;
     
     // Enable raw mode for key detection
-    enable_raw_mode().map_err(|e| e.to_string())?;
+    enable_raw_mode().map_err(CsiError::Terminal)?;
     
     // Clear any pending events
     while event::poll(Duration::from_millis(100)).unwrap_or(false) {
@@ -65,7 +69,7 @@ pub fn show_menu() -> Result<MenuChoice, String> {
     
     // Wait for valid key
     let choice = loop {
-        if event::poll(Duration::from_millis(100)).map_err(|e| e.to_string())? {
+        if event::poll(Duration::from_millis(100)).map_err(CsiError::Terminal)? {
             if let Ok(Event::Key(key)) = event::read() {
                 // Only handle Press events (not Release)
                 if key.kind != KeyEventKind::Press {
@@ -82,11 +86,11 @@ pub fn show_menu() -> Result<MenuChoice, String> {
     };
     
     // Back to normal mode
-    disable_raw_mode().map_err(|e| e.to_string())?;
+    disable_raw_mode().map_err(CsiError::Terminal)?;
     
     match choice {
         1 => {
-            let (port, baud) = get_port_settings()?;
+            let (port, baud) = get_port_settings(config)?;
             Ok(MenuChoice::SetEsp { port, baud })
         }
         2 => Ok(MenuChoice::ViewCsiOutput),
@@ -94,8 +98,8 @@ pub fn show_menu() -> Result<MenuChoice, String> {
     }
 }
 
-/// Get port settings from user
-fn get_port_settings() -> Result<(String, u32), String> {
+/// Get port settings from user; Enter keeps the saved value
+fn get_port_settings(config: &Config) -> Result<(String, u32)> {
     let mut stdout = io::stdout();
     
     println!();
@@ -108,31 +112,32 @@ fn get_port_settings() -> Result<(String, u32), String> {
     
     // Get port name
     println!();
-    print!("  Enter port name (e.g., COM3): ");
-    stdout.flush().map_err(|e| e.to_string())?;
+    print!("  Enter port name [{}]: ", config.port);
+    stdout.flush().map_err(CsiError::Terminal)?;
     
     let mut port = String::new();
-    io::stdin().read_line(&mut port).map_err(|e| e.to_string())?;
-    let port = port.trim().to_string();
-    
-    if port.is_empty() {
-        return Err("Port name cannot be empty".to_string());
-    }
+    io::stdin().read_line(&mut port).map_err(CsiError::Terminal)?;
+    let port = match port.trim() {
+        "" => config.port.clone(),
+        typed => typed.to_string(),
+    };
     
     // Get baud rate
     println!();
     println!("  Common baud rates: 9600, 115200, 460800, 921600");
-    print!("  Enter baud rate [115200]: ");
-    stdout.flush().map_err(|e| e.to_string())?;
+    print!("  Enter baud rate [{}]: ", config.baud);
+    stdout.flush().map_err(CsiError::Terminal)?;
     
     let mut baud_str = String::new();
-    io::stdin().read_line(&mut baud_str).map_err(|e| e.to_string())?;
+    io::stdin().read_line(&mut baud_str).map_err(CsiError::Terminal)?;
     let baud_str = baud_str.trim();
     
     let baud: u32 = if baud_str.is_empty() {
-        115200
+        config.baud
     } else {
-        baud_str.parse().map_err(|_| "Invalid baud rate")?
+        baud_str
+            .parse()
+            .map_err(|_| CsiError::InvalidInput(format!("invalid baud rate: {}", baud_str)))?
     };
     
     println!();
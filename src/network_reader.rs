@@ -0,0 +1,214 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 network_reader.rs - UDP CSI Reader (nexmon)
+// ═══════════════════════════════════════════════════════════════════════════════
+// Receives CSI from nexmon_csi (e.g. a Raspberry Pi) over UDP instead of serial.
+// Features:
+// - Runs in background thread, like the serial reader
+// - Binds a configurable address (default 0.0.0.0:5500)
+// - Decodes each datagram as a nexmon CSI packet
+// - Same downstream path as serial: MAC filter, CSV logging, AppState
+// - Counts datagrams that are not nexmon packets as corrupt
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use csi_tui::error::{CsiError, Result};
+use csi_tui::frame_queue::{FrameSender, ReceivedFrame};
+use csi_tui::nexmon::decode_packet;
+use csi_tui::state::{ReceiverState, SharedState};
+
+use crate::serial_reader::{deliver, open_recording, READ_TIMEOUT_MS};
+
+/// Largest UDP datagram / أكبر حزمة UDP
+const MAX_DATAGRAM_LEN: usize = 65_536;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Network Reader Structure / هيكل قارئ الشبكة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// UDP reader for nexmon CSI packets
+/// قارئ UDP لحزم CSI من nexmon
+pub struct NetworkReader {
+    /// Address to bind (e.g., "0.0.0.0:5500") / العنوان المطلوب ربطه
+    addr: String,
+
+    /// Shared application state / حالة التطبيق المشتركة
+    state: SharedState,
+
+    /// Flag to stop the reader thread / علامة لإيقاف خيط القارئ
+    stop_flag: Arc<AtomicBool>,
+
+    /// Handle to the reader thread / مقبض خيط القارئ
+    thread_handle: Option<JoinHandle<()>>,
+
+    /// Queue frames for the UI instead of locking the state per frame
+    /// إرسال الإطارات عبر طابور الواجهة بدلاً من قفل الحالة لكل إطار
+    queue: Option<FrameSender>,
+}
+
+impl NetworkReader {
+    /// Create a reader for `addr` / إنشاء قارئ للعنوان `addr`
+    pub fn new(state: SharedState, addr: &str) -> Self {
+        Self {
+            addr: addr.to_string(),
+            state,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            thread_handle: None,
+            queue: None,
+        }
+    }
+
+    /// Send frames through `queue`; the UI stores them once per tick
+    /// إرسال الإطارات عبر `queue`؛ الواجهة تحفظها مرة كل نبضة
+    pub fn with_queue(mut self, queue: FrameSender) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Bind the socket and start the reader thread
+    /// ربط المقبس وبدء خيط القارئ
+    pub fn start(&mut self) -> Result<()> {
+        if self.thread_handle.is_some() {
+            return Err(CsiError::ReaderRunning);
+        }
+        self.stop_flag.store(false, Ordering::SeqCst);
+
+        let network_error = |source| CsiError::Network { addr: self.addr.clone(), source };
+        let socket = UdpSocket::bind(&self.addr).map_err(network_error)?;
+        socket
+            .set_read_timeout(Some(Duration::from_millis(READ_TIMEOUT_MS)))
+            .map_err(network_error)?;
+
+        // Show the bound address (port 0 resolves here) / عرض العنوان المربوط
+        let bound = socket.local_addr().map(|a| a.to_string()).unwrap_or_else(|_| self.addr.clone());
+        {
+            let mut guard = self.state.lock()?;
+            guard.port_name = format!("udp:{}", bound);
+            guard.receiver = ReceiverState::Connected;
+            guard.start_warmup(chrono::Utc::now().timestamp_millis());
+            guard.status_message = format!("✅ Listening on udp:{}", bound);
+        }
+
+        let state = Arc::clone(&self.state);
+        let stop_flag = Arc::clone(&self.stop_flag);
+        let queue = self.queue.clone();
+        let handle = thread::spawn(move || {
+            run_network_reader(&socket, queue.as_ref(), &state, &stop_flag);
+        });
+
+        self.thread_handle = Some(handle);
+        Ok(())
+    }
+
+    /// Stop the reader thread / إيقاف خيط القارئ
+    pub fn stop(&mut self) {
+        self.stop_flag.store(true, Ordering::SeqCst);
+        let Some(handle) = self.thread_handle.take() else { return; };
+        let _ = handle.join();
+
+        if let Ok(mut state_guard) = self.state.lock() {
+            state_guard.receiver = ReceiverState::Idle;
+            state_guard.status_message = "⏹️ UDP reader stopped".to_string();
+        }
+    }
+}
+
+impl Drop for NetworkReader {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Network Reader Thread Function / دالة خيط قارئ الشبكة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Main function that runs in the network reader thread
+/// الدالة الرئيسية التي تعمل في خيط قارئ الشبكة
+fn run_network_reader(socket: &UdpSocket, queue: Option<&FrameSender>, state: &SharedState, stop_flag: &AtomicBool) {
+    open_recording(state);
+    let mut datagram = vec![0u8; MAX_DATAGRAM_LEN];
+
+    while !stop_flag.load(Ordering::SeqCst) {
+        match socket.recv_from(&mut datagram) {
+            Ok((len, _)) => handle_datagram(&datagram[..len], queue, state),
+            Err(ref e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {
+                // Timeout is normal, continue / المهلة طبيعية، متابعة
+            }
+            Err(e) => {
+                if let Ok(mut state_guard) = state.lock() {
+                    let addr = socket.local_addr().map(|a| a.to_string()).unwrap_or_default();
+                    state_guard.receiver = ReceiverState::Error;
+                    state_guard.status_message = CsiError::Network { addr, source: e }.status_message();
+                }
+                break;
+            }
+        }
+    }
+
+    // The session's recording ends with it / تسجيل الجلسة ينتهي معها
+    if let Ok(mut state_guard) = state.lock() {
+        state_guard.stop_recording();
+    }
+}
+
+/// Decode one datagram and deliver it as a frame
+/// فك حزمة واحدة وتسليمها كإطار
+fn handle_datagram(datagram: &[u8], queue: Option<&FrameSender>, state: &SharedState) {
+    let parse_started = Instant::now();
+    if let Ok(mut state_guard) = state.lock() {
+        state_guard.frame_rate.record_bytes(parse_started, datagram.len());
+    }
+    match decode_packet(datagram) {
+        Some(packet) => {
+            let frame = ReceivedFrame::new(packet.result, packet.meta, None, None, parse_started.elapsed());
+            deliver(frame.counted(), queue, state);
+        }
+        None => {
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.corrupt_packets += 1;
+            }
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csi_tui::state::create_shared_state;
+
+    #[test]
+    fn test_datagrams_become_frames() {
+        let state = create_shared_state();
+        let mut packet = vec![0x11, 0x11, (-60i8) as u8, 0x08, 1, 2, 3, 4, 5, 6, 0, 0, 0, 0, 6, 0x10, 0, 0];
+        for i in 0..64i16 {
+            packet.extend((i * 3).to_le_bytes());
+            packet.extend((-i).to_le_bytes());
+        }
+
+        handle_datagram(&packet, None, &state);
+        handle_datagram(b"not nexmon", None, &state);
+
+        let s = state.lock().unwrap();
+        assert_eq!(s.frames.len(), 1);
+        assert_eq!(s.frames[0].subcarrier_count(), 64);
+        assert_eq!(s.frames[0].mac.as_deref(), Some("01:02:03:04:05:06"));
+        assert_eq!(s.frames[0].rssi, Some(-60));
+        assert_eq!(s.radio_meta.channel_label().as_deref(), Some("ch 6"));
+        assert_eq!(s.corrupt_packets, 1);
+    }
+
+    #[test]
+    fn test_bad_address_reports_network_error() {
+        let mut reader = NetworkReader::new(create_shared_state(), "not-an-address");
+        assert!(matches!(reader.start(), Err(CsiError::Network { .. })));
+    }
+}
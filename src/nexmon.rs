@@ -0,0 +1,149 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 nexmon.rs - Nexmon CSI Packet Decoder
+// ═══════════════════════════════════════════════════════════════════════════════
+// Decodes the UDP packets nexmon_csi sends from Broadcom chips (Raspberry Pi).
+// Features:
+// - 18-byte header: magic, RSSI, frame control, source MAC, sequence number,
+//   core / spatial stream, chanspec, chip version
+// - int16 little-endian I/Q pairs after the header
+// - Channel and bandwidth from the chanspec
+// - Produces the same ParseResult as the text parser
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use crate::parser::{phases_of, ParseResult};
+use crate::state::{CsiFormat, CsiMeta};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Magic number that starts every packet / الرقم المميز في بداية كل حزمة
+pub const NEXMON_MAGIC: u16 = 0x1111;
+
+/// Header bytes before the I/Q data / بايتات الترويسة قبل بيانات I/Q
+pub const NEXMON_HEADER_LEN: usize = 18;
+
+/// Port nexmon_csi sends to by default / المنفذ الافتراضي لإرسال nexmon_csi
+pub const DEFAULT_NEXMON_ADDR: &str = "0.0.0.0:5500";
+
+/// Chanspec bandwidth field (d11ac) / حقل عرض القناة في chanspec
+const CHANSPEC_BW_MASK: u16 = 0x3800;
+const CHANSPEC_BW_SHIFT: u16 = 11;
+const CHANSPEC_BW_20: u16 = 2;
+const CHANSPEC_BW_40: u16 = 3;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Decoding / فك الترميز
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One decoded nexmon packet / حزمة nexmon واحدة بعد فك الترميز
+#[derive(Debug, Clone)]
+pub struct NexmonPacket {
+    /// CSI values with RSSI and source MAC filled in / قيم CSI مع RSSI وعنوان المصدر
+    pub result: ParseResult,
+
+    /// Channel and bandwidth / القناة وعرض النطاق
+    pub meta: CsiMeta,
+
+    /// Receive core and spatial stream / نواة الاستقبال والتدفق المكاني
+    pub core: u8,
+    pub spatial_stream: u8,
+
+    /// 802.11 sequence number / رقم التسلسل
+    pub sequence: u16,
+}
+
+/// Decode one UDP payload; None when it is not a nexmon CSI packet
+/// فك حمولة UDP واحدة؛ None إن لم تكن حزمة CSI من nexmon
+pub fn decode_packet(packet: &[u8]) -> Option<NexmonPacket> {
+    if packet.len() < NEXMON_HEADER_LEN + 4 || u16_at(packet, 0) != NEXMON_MAGIC {
+        return None;
+    }
+    let data = &packet[NEXMON_HEADER_LEN..];
+    if !data.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let pairs: Vec<(i32, i32)> = data
+        .chunks_exact(4)
+        .map(|iq| (i16_at(iq, 0) as i32, i16_at(iq, 2) as i32))
+        .collect();
+    let mags = pairs.iter().map(|&(r, i)| ((r as f64).powi(2) + (i as f64).powi(2)).sqrt()).collect();
+    let mac = packet[4..10].iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":");
+
+    let core_stream = u16_at(packet, 12);
+    let chanspec = u16_at(packet, 14);
+    let bandwidth = match (chanspec & CHANSPEC_BW_MASK) >> CHANSPEC_BW_SHIFT {
+        CHANSPEC_BW_20 => Some(0),
+        CHANSPEC_BW_40 => Some(1),
+        _ => None,
+    };
+
+    Some(NexmonPacket {
+        result: ParseResult {
+            format: CsiFormat::RealImag,
+            phases: phases_of(&pairs),
+            pairs,
+            mags,
+            rssi: Some(packet[2] as i8 as i32),
+            mac: Some(mac),
+        },
+        meta: CsiMeta {
+            channel: Some((chanspec & 0xFF) as u8),
+            bandwidth,
+            ..CsiMeta::default()
+        },
+        core: (core_stream & 0x7) as u8,
+        spatial_stream: ((core_stream >> 3) & 0x7) as u8,
+        sequence: u16_at(packet, 10),
+    })
+}
+
+/// Little-endian u16 at `at` / قيمة u16 بترتيب little-endian
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+/// Little-endian i16 at `at` / قيمة i16 بترتيب little-endian
+fn i16_at(bytes: &[u8], at: usize) -> i16 {
+    i16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_nexmon_packet() {
+        let mut packet = vec![0x11, 0x11, (-58i8) as u8, 0x08];
+        packet.extend([0xDC, 0xA6, 0x32, 0x01, 0x02, 0x03]);
+        packet.extend(42u16.to_le_bytes());
+        packet.extend((1u16 | 2 << 3).to_le_bytes());
+        packet.extend((0x1000u16 | 36).to_le_bytes()); // 20MHz, channel 36
+        packet.extend(0x4345u16.to_le_bytes());
+        for (r, i) in [(300i16, -400i16), (-3, 4)] {
+            packet.extend(r.to_le_bytes());
+            packet.extend(i.to_le_bytes());
+        }
+
+        let decoded = decode_packet(&packet).unwrap();
+        assert_eq!(decoded.result.pairs, vec![(300, -400), (-3, 4)]);
+        assert_eq!(decoded.result.mags, vec![500.0, 5.0]);
+        assert_eq!(decoded.result.rssi, Some(-58));
+        assert_eq!(decoded.result.mac.as_deref(), Some("DC:A6:32:01:02:03"));
+        assert_eq!((decoded.core, decoded.spatial_stream, decoded.sequence), (1, 2, 42));
+        assert_eq!(decoded.meta.channel_label().as_deref(), Some("ch 36"));
+        assert_eq!(decoded.meta.bandwidth, Some(0));
+
+        // Wrong magic, or I/Q cut mid-pair / رقم مميز خاطئ أو زوج مقطوع
+        packet[0] = 0x12;
+        assert!(decode_packet(&packet).is_none());
+        packet[0] = 0x11;
+        packet.pop();
+        assert!(decode_packet(&packet).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 parse_failure_log.rs - Parse Failure Log
+// ═══════════════════════════════════════════════════════════════════════════════
+// Keeps serial blocks the parser rejected so they can be studied later.
+// Features:
+// - One line per rejected block: timestamp, reason, raw block
+// - Long blocks truncated, line breaks escaped so each failure stays on one line
+// - Flushed per entry; failures are rare and most useful right before a crash
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use chrono::Utc;
+
+use crate::error::{CsiError, Result};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Characters of a raw block kept per entry / عدد أحرف الكتلة الخام المحفوظة لكل سجل
+pub const MAX_LOGGED_BLOCK_CHARS: usize = 256;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Parse Failure Log / سجل فشل التحليل
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Appends rejected raw blocks to a text file
+/// يضيف الكتل الخام المرفوضة إلى ملف نصي
+pub struct ParseFailureLog {
+    /// Buffered file writer / كاتب الملف المخزن
+    writer: BufWriter<File>,
+
+    /// Path of the log file / مسار ملف السجل
+    file_path: PathBuf,
+
+    /// Entries written so far / عدد السجلات المكتوبة
+    entries: usize,
+}
+
+impl ParseFailureLog {
+    /// Create a new failure log / إنشاء سجل فشل جديد
+    pub fn new(file_path: PathBuf) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&file_path)
+            .map_err(|e| CsiError::CsvLog(format!("failed to create file: {}", e)))?;
+
+        Ok(Self { writer: BufWriter::new(file), file_path, entries: 0 })
+    }
+
+    /// Create a new failure log with auto-generated filename
+    /// إنشاء سجل فشل جديد باسم ملف تلقائي
+    pub fn new_with_timestamp() -> Result<Self> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        Self::new(PathBuf::from(format!("parse_failures_{}.log", timestamp)))
+    }
+
+    /// Append one rejected block: `timestamp<TAB>reason<TAB>block`
+    /// إضافة كتلة مرفوضة واحدة
+    pub fn log_failure(&mut self, timestamp: i64, block: &str, reason: &str) -> Result<()> {
+        writeln!(self.writer, "{}\t{}\t{}", timestamp, reason, escape_block(block))
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| CsiError::CsvLog(format!("failed to write {}: {}", self.file_path.display(), e)))?;
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Entries written so far / عدد السجلات المكتوبة
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+}
+
+/// Truncate a block and escape line breaks and tabs
+/// اقتطاع الكتلة وتهريب فواصل الأسطر وعلامات الجدولة
+fn escape_block(block: &str) -> String {
+    let mut out = String::new();
+    for c in block.chars().take(MAX_LOGGED_BLOCK_CHARS) {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    if block.chars().count() > MAX_LOGGED_BLOCK_CHARS {
+        out.push_str("...");
+    }
+    out
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_failures_are_one_line_each() {
+        let path = PathBuf::from("test_parse_failures.log");
+        let mut log = ParseFailureLog::new(path.clone()).unwrap();
+
+        log.log_failure(1000, "mac:AA rssi:-40\r\nboot: ok\n", "no CSI array").unwrap();
+        log.log_failure(1100, &"7,".repeat(400), "no values").unwrap();
+        assert_eq!(log.entries(), 2);
+
+        // Readable before the log is dropped / قابل للقراءة قبل إسقاط السجل
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "1000\tno CSI array\tmac:AA rssi:-40\\r\\nboot: ok\\n");
+        assert!(lines[1].ends_with("..."));
+        assert_eq!(lines[1].split('\t').nth(2).unwrap().len(), MAX_LOGGED_BLOCK_CHARS + 3);
+
+        drop(log);
+        let _ = fs::remove_file(path);
+    }
+}
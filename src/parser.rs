@@ -2,12 +2,33 @@
 // 📦 parser.rs - CSI Data Parser
 // ═══════════════════════════════════════════════════════════════════════════════
 // This module parses raw CSI data from ESP32 firmware.
-// Automatically detects format: Real/Imag pairs or Amplitude-only.
-// Extracts numbers and computes magnitudes.
+// Automatically detects format: Real/Imag pairs or Amplitude-only,
+// unless a format is forced.
+// Extracts numbers with a single-pass byte scanner (regex kept as a fallback)
+// and computes magnitudes.
+// Reads radio metadata (RSSI, channel, noise floor) from the block header.
+// Rejects serial blocks with too few subcarriers to be a real frame.
+// Frames the serial stream into "mac:" blocks or ESP-IDF "CSI_DATA" lines.
+// Removes interleaved ESP-IDF log lines and catches arrays whose length jumps.
 // ═══════════════════════════════════════════════════════════════════════════════
 
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Range;
+
 use regex::Regex;
-use crate::state::CsiFormat;
+use crate::state::{CsiFormat, CsiMeta};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Fewest subcarriers a live frame needs by default / أقل عدد ناقلات فرعية افتراضياً
+///
+/// The smallest real ESP32 capture (HT20 LLTF) has far more; anything below
+/// this is firmware debug output or a torn line.
+pub const DEFAULT_MIN_SUBCARRIERS: usize = 8;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Parse Result Structure / هيكل نتيجة التحليل
@@ -25,8 +46,131 @@ pub struct ParseResult {
     
     /// Computed magnitudes / السعات المحسوبة
     pub mags: Vec<f64>,
+    
+    /// Phase per subcarrier in radians (RealImag only) / الطور لكل ناقل فرعي بالراديان
+    pub phases: Vec<f64>,
+    
+    /// RSSI when the data still carries the header (`rssi:-45`) / قيمة RSSI إن وجدت الترويسة
+    pub rssi: Option<i32>,
+    
+    /// Transmitter MAC from the block header / عنوان MAC للمرسل من ترويسة الكتلة
+    pub mac: Option<String>,
+}
+
+impl ParseResult {
+    /// Integers the CSI array held / عدد الأعداد في مصفوفة CSI
+    pub fn value_count(&self) -> usize {
+        match self.format {
+            CsiFormat::RealImag => self.pairs.len() * 2,
+            _ => self.pairs.len(),
+        }
+    }
+}
+
+/// CSI array plus the radio metadata from the block header
+/// مصفوفة CSI مع بيانات الراديو الوصفية من ترويسة الكتلة
+#[derive(Debug, Clone)]
+pub struct ParsedBlock {
+    /// Parsed CSI array (RSSI and MAC filled in) / مصفوفة CSI المحللة
+    pub result: ParseResult,
+
+    /// Received signal strength (dBm) / قوة الإشارة المستقبلة
+    pub rssi: Option<i32>,
+
+    /// Channel, bandwidth and signal mode / القناة وعرض النطاق ونمط الإشارة
+    pub meta: CsiMeta,
+
+    /// Noise floor (dBm) / أرضية الضجيج
+    pub noise_floor: Option<i32>,
+
+    /// ESP local timer when the packet arrived (µs) / مؤقت ESP المحلي عند وصول الحزمة
+    pub esp_timestamp_us: Option<u64>,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 ESP-IDF Header Layout / تخطيط ترويسة ESP-IDF
+// ═══════════════════════════════════════════════════════════════════════════════
+//
+// `CSI_DATA,id,mac,rssi,rate,sig_mode,mcs,cwb,smoothing,not_sounding,aggregation,
+//  stbc,fec_coding,sgi,noise_floor,ampdu_cnt,channel,secondary_channel,timestamp,
+//  ant,sig_len,rx_state,len,first_word,"[...]"`
+
+/// Field positions in a `CSI_DATA,` line / مواقع الحقول في سطر CSI_DATA
+const ESP_IDF_MAC_FIELD: usize = 2;
+const ESP_IDF_RSSI_FIELD: usize = 3;
+const ESP_IDF_SIG_MODE_FIELD: usize = 5;
+const ESP_IDF_CWB_FIELD: usize = 7;
+const ESP_IDF_NOISE_FLOOR_FIELD: usize = 14;
+const ESP_IDF_CHANNEL_FIELD: usize = 16;
+const ESP_IDF_SECONDARY_CHANNEL_FIELD: usize = 17;
+const ESP_IDF_TIMESTAMP_FIELD: usize = 18;
+
+/// Why a serial block did not become a frame / سبب عدم تحول الكتلة إلى إطار
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseFailure {
+    /// No bracketed or delimited array / لا توجد مصفوفة
+    NoArray,
+
+    /// Array without any integers / مصفوفة بلا أرقام
+    NoValues,
+
+    /// Fewer subcarriers than the minimum / ناقلات فرعية أقل من الحد الأدنى
+    TooFewSubcarriers,
+}
+
+impl ParseFailure {
+    /// Short reason for logs / سبب مختصر للسجلات
+    pub fn reason(self) -> &'static str {
+        match self {
+            ParseFailure::NoArray => "no CSI array",
+            ParseFailure::NoValues => "no values in CSI array",
+            ParseFailure::TooFewSubcarriers => "too few subcarriers",
+        }
+    }
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.reason())
+    }
+}
+
+/// Running counts of serial blocks and how they fared
+/// عدادات جارية لكتل التسلسل ونتائجها
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseStats {
+    /// Complete blocks framed from the stream / الكتل الكاملة المقتطعة من التدفق
+    pub seen: u64,
+
+    /// Blocks that became frames / الكتل التي أصبحت إطارات
+    pub parsed: u64,
+
+    /// Blocks the parser rejected / الكتل التي رفضها المحلل
+    pub rejected: u64,
+
+    /// Why the newest rejected block failed / سبب رفض أحدث كتلة
+    pub last_failure: Option<ParseFailure>,
+}
+
+impl ParseStats {
+    /// Count a block that parsed / عد كتلة تم تحليلها
+    pub fn record_parsed(&mut self) {
+        self.seen += 1;
+        self.parsed += 1;
+    }
+
+    /// Count a rejected block / عد كتلة مرفوضة
+    pub fn record_rejected(&mut self, failure: ParseFailure) {
+        self.seen += 1;
+        self.rejected += 1;
+        self.last_failure = Some(failure);
+    }
 }
 
+/// Format, pairs, magnitudes and phases of one CSI array
+/// الصيغة والأزواج والسعات والأطوار لمصفوفة CSI واحدة
+type ParsedValues = (CsiFormat, Vec<(i32, i32)>, Vec<f64>, Vec<f64>);
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 CSI Parser / محلل CSI
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -34,20 +178,55 @@ pub struct ParseResult {
 /// Main CSI parser with automatic format detection
 /// محلل CSI الرئيسي مع كشف تلقائي للصيغة
 pub struct CsiParser {
-    /// Regex pattern to extract numbers from CSI data
-    /// نمط التعبير النمطي لاستخراج الأرقام من بيانات CSI
-    number_regex: Regex,
+    /// Regex used instead of the byte scanner when set
+    /// التعبير النمطي المستخدم بدلاً من الماسح البايتي عند تعيينه
+    number_regex: Option<Regex>,
+
+    /// Blocks with fewer subcarriers are rejected / الكتل ذات الناقلات الأقل تُرفض
+    min_subcarriers: usize,
+
+    /// Format used for every array; None detects it per array
+    /// الصيغة المستخدمة لكل مصفوفة؛ لا شيء يعني الكشف لكل مصفوفة
+    forced_format: Option<CsiFormat>,
 }
 
 impl CsiParser {
     /// Create a new CSI parser instance
     /// إنشاء مثيل محلل CSI جديد
     pub fn new() -> Self {
+        Self { number_regex: None, min_subcarriers: 1, forced_format: None }
+    }
+
+    /// Extract numbers with the regex instead of the byte scanner
+    /// استخراج الأرقام بالتعبير النمطي بدلاً من الماسح البايتي
+    ///
+    /// Slower; kept as a reference for the scanner.
+    pub fn with_regex_scanner(mut self) -> Self {
         // Pattern matches integers (positive and negative)
         // النمط يطابق الأعداد الصحيحة (موجبة وسالبة)
-        let number_regex = Regex::new(r"-?\d+").expect("Failed to compile regex");
-        
-        Self { number_regex }
+        self.number_regex = Some(Regex::new(r"-?\d+").expect("Failed to compile regex"));
+        self
+    }
+
+    /// Create a parser that reads every array as `format`
+    /// إنشاء محلل يقرأ كل مصفوفة بالصيغة `format`
+    ///
+    /// The sign heuristic is skipped entirely; `CsiFormat::Unknown` keeps
+    /// automatic detection.
+    pub fn with_format(format: CsiFormat) -> Self {
+        Self {
+            forced_format: (format != CsiFormat::Unknown).then_some(format),
+            ..Self::new()
+        }
+    }
+
+    /// Reject blocks with fewer than `min` subcarriers in `parse_block`
+    /// رفض الكتل التي تحوي أقل من `min` ناقل فرعي
+    ///
+    /// `parse` on a bare array is not affected.
+    pub fn with_min_subcarriers(mut self, min: usize) -> Self {
+        self.min_subcarriers = min.max(1);
+        self
     }
 
     /// Parse a CSI data block and return parsed result
@@ -69,23 +248,99 @@ impl CsiParser {
         }
 
         // Detect format and parse accordingly / كشف الصيغة والتحليل وفقاً لها
-        let (format, pairs, mags) = self.detect_and_parse(&numbers);
+        let (format, pairs, mags, phases) = self.detect_and_parse(&numbers);
         
         // Return None if no valid data was parsed
         if mags.is_empty() {
             return None;
         }
 
-        Some(ParseResult { format, pairs, mags })
+        Some(ParseResult { format, pairs, mags, phases, rssi: extract_rssi(data), mac: None })
+    }
+
+    /// Parse a whole serial block: CSI array plus header fields (RSSI, MAC)
+    /// تحليل كتلة تسلسلية كاملة: مصفوفة CSI وحقول الترويسة (RSSI، MAC)
+    pub fn parse_block(&self, block: &str) -> Option<ParseResult> {
+        self.parse_with_metadata(block).map(|parsed| parsed.result)
+    }
+
+    /// Parse a block, keeping the header metadata out of the CSI values
+    /// تحليل كتلة مع إبقاء البيانات الوصفية للترويسة خارج قيم CSI
+    ///
+    /// Numbers are only read inside the bracketed array; the header before it
+    /// is read either as an ESP-IDF `CSI_DATA,` line or as `key:value` labels.
+    pub fn parse_with_metadata(&self, block: &str) -> Option<ParsedBlock> {
+        let (header, array) = split_block(block)?;
+        let mut result = self.parse(array)?;
+        if result.mags.len() < self.min_subcarriers {
+            return None;
+        }
+
+        let mut parsed = match header.find("CSI_DATA,") {
+            Some(start) => {
+                let fields: Vec<&str> = header[start..].split(',').map(str::trim).collect();
+                let field = |i: usize| fields.get(i).and_then(|f| f.parse().ok());
+                result.mac = fields
+                    .get(ESP_IDF_MAC_FIELD)
+                    .filter(|mac| mac.len() == 17)
+                    .map(|mac| mac.to_string());
+                let small = |i: usize| fields.get(i).and_then(|f| f.parse().ok());
+                ParsedBlock {
+                    rssi: field(ESP_IDF_RSSI_FIELD),
+                    meta: CsiMeta {
+                        channel: small(ESP_IDF_CHANNEL_FIELD),
+                        secondary_channel: small(ESP_IDF_SECONDARY_CHANNEL_FIELD),
+                        sig_mode: small(ESP_IDF_SIG_MODE_FIELD),
+                        bandwidth: small(ESP_IDF_CWB_FIELD),
+                    },
+                    noise_floor: field(ESP_IDF_NOISE_FLOOR_FIELD),
+                    esp_timestamp_us: fields.get(ESP_IDF_TIMESTAMP_FIELD).and_then(|f| f.parse().ok()),
+                    result,
+                }
+            }
+            None => {
+                result.mac = extract_mac(header).map(str::to_string);
+                let small = |label: &str| extract_labeled_int::<u8>(header, label);
+                ParsedBlock {
+                    rssi: extract_rssi(header),
+                    meta: CsiMeta {
+                        channel: small("channel"),
+                        secondary_channel: small("secondary_channel"),
+                        sig_mode: small("sig_mode"),
+                        bandwidth: small("bandwidth").or_else(|| small("cwb")),
+                    },
+                    noise_floor: extract_labeled_int(header, "noise_floor"),
+                    esp_timestamp_us: extract_labeled_int(header, "local_timestamp")
+                        .or_else(|| extract_labeled_int(header, "timestamp")),
+                    result,
+                }
+            }
+        };
+        parsed.result.rssi = parsed.rssi;
+        Some(parsed)
+    }
+
+    /// Why `parse_block` rejected a block / سبب رفض `parse_block` للكتلة
+    pub fn diagnose(&self, block: &str) -> ParseFailure {
+        let Some((_, array)) = split_block(block) else {
+            return ParseFailure::NoArray;
+        };
+        match self.parse(array) {
+            None => ParseFailure::NoValues,
+            Some(_) => ParseFailure::TooFewSubcarriers,
+        }
     }
 
     /// Extract all integers from a string
     /// استخراج جميع الأعداد الصحيحة من نص
     fn extract_numbers(&self, data: &str) -> Vec<i32> {
-        self.number_regex
-            .find_iter(data)
-            .filter_map(|m| m.as_str().parse::<i32>().ok())
-            .collect()
+        match &self.number_regex {
+            Some(regex) => regex
+                .find_iter(data)
+                .filter_map(|m| m.as_str().parse::<i32>().ok())
+                .collect(),
+            None => scan_integers(data),
+        }
     }
 
     /// Detect CSI format and parse numbers accordingly
@@ -95,29 +350,32 @@ impl CsiParser {
     /// - If numbers come in pairs where second value is often similar magnitude
     ///   to first but with different sign pattern → Real/Imag
     /// - If numbers are all positive or mostly single-value pattern → Amplitude
-    fn detect_and_parse(&self, numbers: &[i32]) -> (CsiFormat, Vec<(i32, i32)>, Vec<f64>) {
-        // Heuristic: Check if this looks like Real/Imag pairs
-        // استدلال: التحقق مما إذا كان هذا يشبه أزواج حقيقي/تخيلي
-        let format = self.detect_format(numbers);
+    fn detect_and_parse(&self, numbers: &[i32]) -> ParsedValues {
+        // Heuristic: Check if this looks like Real/Imag pairs, unless forced
+        // استدلال: التحقق مما إذا كان هذا يشبه أزواج حقيقي/تخيلي، إلا إذا فُرضت الصيغة
+        let format = match self.forced_format {
+            Some(format) => format,
+            None => self.detect_format(numbers),
+        };
         
         match format {
             CsiFormat::RealImag => {
-                let (pairs, mags) = self.parse_real_imag(numbers);
-                (format, pairs, mags)
+                let (pairs, mags, phases) = self.parse_real_imag(numbers);
+                (format, pairs, mags, phases)
             }
             CsiFormat::AmplitudeOnly => {
                 let (pairs, mags) = self.parse_amplitude_only(numbers);
-                (format, pairs, mags)
+                (format, pairs, mags, Vec::new())
             }
             CsiFormat::Unknown => {
                 // Default to Real/Imag if even count, else Amplitude
                 // افتراضياً استخدم حقيقي/تخيلي إذا كان العدد زوجي، وإلا سعة
-                if numbers.len() % 2 == 0 {
-                    let (pairs, mags) = self.parse_real_imag(numbers);
-                    (CsiFormat::RealImag, pairs, mags)
+                if numbers.len().is_multiple_of(2) {
+                    let (pairs, mags, phases) = self.parse_real_imag(numbers);
+                    (CsiFormat::RealImag, pairs, mags, phases)
                 } else {
                     let (pairs, mags) = self.parse_amplitude_only(numbers);
-                    (CsiFormat::AmplitudeOnly, pairs, mags)
+                    (CsiFormat::AmplitudeOnly, pairs, mags, Vec::new())
                 }
             }
         }
@@ -136,7 +394,7 @@ impl CsiParser {
         // - Pairs often have similar absolute values
         
         let has_negatives = numbers.iter().any(|&n| n < 0);
-        let even_count = numbers.len() % 2 == 0;
+        let even_count = numbers.len().is_multiple_of(2);
         
         // Count how many numbers are negative
         let negative_count = numbers.iter().filter(|&&n| n < 0).count();
@@ -157,11 +415,12 @@ impl CsiParser {
         CsiFormat::Unknown
     }
 
-    /// Parse numbers as Real/Imag pairs and compute magnitudes
-    /// تحليل الأرقام كأزواج حقيقي/تخيلي وحساب السعات
-    fn parse_real_imag(&self, numbers: &[i32]) -> (Vec<(i32, i32)>, Vec<f64>) {
+    /// Parse numbers as Real/Imag pairs and compute magnitudes and phases
+    /// تحليل الأرقام كأزواج حقيقي/تخيلي وحساب السعات والأطوار
+    fn parse_real_imag(&self, numbers: &[i32]) -> (Vec<(i32, i32)>, Vec<f64>, Vec<f64>) {
         let mut pairs = Vec::new();
         let mut mags = Vec::new();
+        let mut phases = Vec::new();
         
         // Process pairs (real, imag)
         // معالجة الأزواج (حقيقي، تخيلي)
@@ -176,10 +435,13 @@ impl CsiParser {
                 // حساب السعة: الجذر التربيعي (حقيقي² + تخيلي²)
                 let mag = ((real as f64).powi(2) + (imag as f64).powi(2)).sqrt();
                 mags.push(mag);
+
+                // Phase: atan2(imag, real) / الطور
+                phases.push(phase_of(real, imag));
             }
         }
         
-        (pairs, mags)
+        (pairs, mags, phases)
     }
 
     /// Parse numbers as amplitude-only values
@@ -206,10 +468,64 @@ impl Default for CsiParser {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Block Framing / تقطيع الكتل
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Start of an ESP-IDF csi_recv line / بداية سطر csi_recv من ESP-IDF
+const ESP_IDF_MARKER: &str = "CSI_DATA";
+
+/// Markers that open a block / العلامات التي تبدأ كتلة
+const BLOCK_MARKERS: [&str; 2] = ["mac:", ESP_IDF_MARKER];
+
+/// Byte range of the first complete block in a serial buffer
+/// نطاق أول كتلة مكتملة في مخزن تسلسلي
+///
+/// Whichever marker comes first decides the format, so both firmwares can
+/// share a session. A `CSI_DATA` line ends at its newline; a `mac:` block
+/// runs until the next marker of either kind, or - when it is the last one in
+/// the buffer - through the newline after its closing `]`. None while still
+/// incomplete.
+pub fn find_block(buffer: &str) -> Option<Range<usize>> {
+    let (start, marker) = BLOCK_MARKERS
+        .iter()
+        .filter_map(|&m| buffer.find(m).map(|i| (i, m)))
+        .min_by_key(|&(i, _)| i)?;
+    let body = start + marker.len();
+    let end = if marker == ESP_IDF_MARKER {
+        body + buffer[body..].find('\n')? + 1
+    } else {
+        match BLOCK_MARKERS.iter().filter_map(|m| buffer[body..].find(m)).min() {
+            Some(next) => body + next,
+            None => body + terminated_array_end(&buffer[body..])?,
+        }
+    };
+    Some(start..end)
+}
+
+/// End of a `[...]` array followed by a newline, past that newline
+/// نهاية مصفوفة `[...]` يتبعها سطر جديد، بعد ذلك السطر
+///
+/// Lets the last `mac:` block of a chunk through without waiting for the
+/// next marker; a block still missing its `]` or newline stays buffered.
+fn terminated_array_end(body: &str) -> Option<usize> {
+    let open = body.find('[')?;
+    let close = open + body[open..].find(']')?;
+    Some(close + body[close..].find('\n')? + 1)
+}
+
+/// Start of the last block in a buffer, of either format
+/// بداية آخر كتلة في المخزن من أي صيغة
+pub fn last_block_start(buffer: &str) -> Option<usize> {
+    BLOCK_MARKERS.iter().filter_map(|m| buffer.rfind(m)).max()
+}
+
 /// Extract CSI block from raw serial data
 /// استخراج كتلة CSI من بيانات التسلسل الخام
 /// 
-/// Looks for data between square brackets [...]
+/// Looks for data between square brackets [...]; without brackets, falls back
+/// to the trailing run of space- or tab-delimited integers
+/// (e.g. "mac:AA:BB:CC:DD:EE:FF csi_data: 10 -5 20 -10")
 /// يبحث عن البيانات بين الأقواس المربعة [...]
 pub fn extract_csi_block(data: &str) -> Option<&str> {
     // Find the CSI data array in the format: csi_data:[...]
@@ -221,7 +537,245 @@ pub fn extract_csi_block(data: &str) -> Option<&str> {
             }
         }
     }
-    None
+    extract_delimited_block(data)
+}
+
+/// Split a block into its header and its CSI array
+/// تقسيم الكتلة إلى ترويستها ومصفوفة CSI
+fn split_block(block: &str) -> Option<(&str, &str)> {
+    match (block.find('['), block.rfind(']')) {
+        (Some(start), Some(end)) if end > start => Some((&block[..start], &block[start..=end])),
+        _ => Some((block, extract_delimited_block(block)?)),
+    }
+}
+
+/// Minimum integers for a bracketless block / أقل عدد أرقام لكتلة بدون أقواس
+const MIN_DELIMITED_VALUES: usize = 2;
+
+/// Bracketless fallback: integers after the last label, up to the end of the line
+/// البديل بدون أقواس: الأرقام بعد آخر وسم حتى نهاية السطر
+fn extract_delimited_block(data: &str) -> Option<&str> {
+    // Skip a "csi_data:" / "csi:" label if present / تخطي وسم csi_data: إن وجد
+    let offset = ["csi_data:", "csi:"]
+        .iter()
+        .find_map(|label| data.rfind(label).map(|i| i + label.len()))
+        .unwrap_or(0);
+    let data = data[offset..].trim_end();
+    let bytes = data.as_bytes();
+    let is_space = |b: u8| b == b' ' || b == b'\t';
+
+    // Walk back over whitespace-separated integer tokens
+    // الرجوع عبر الرموز الرقمية المفصولة بمسافات
+    let mut pos = data.len();
+    let mut start = None;
+    let mut count = 0;
+    loop {
+        while pos > 0 && is_space(bytes[pos - 1]) {
+            pos -= 1;
+        }
+        let end = pos;
+        while pos > 0 && !is_space(bytes[pos - 1]) {
+            pos -= 1;
+        }
+        let digits = data[pos..end].strip_prefix('-').unwrap_or(&data[pos..end]);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            break;
+        }
+        start = Some(pos);
+        count += 1;
+    }
+
+    start.filter(|_| count >= MIN_DELIMITED_VALUES).map(|i| &data[i..])
+}
+
+/// Phase of each (real, imag) pair: atan2(imag, real) in (-π, π]
+/// طور كل زوج (حقيقي، تخيلي) بالراديان
+pub fn phases_of(pairs: &[(i32, i32)]) -> Vec<f64> {
+    pairs.iter().map(|&(real, imag)| phase_of(real, imag)).collect()
+}
+
+/// Phase of one pair; the zero-zero pair has no direction and reads 0.0
+/// (atan2(0, 0) = 0)
+/// طور زوج واحد؛ الزوج صفر-صفر بلا اتجاه ويُقرأ 0.0
+pub fn phase_of(real: i32, imag: i32) -> f64 {
+    (imag as f64).atan2(real as f64)
+}
+
+/// Extract the transmitter MAC from a raw block ("mac:AA:BB:CC:DD:EE:FF ...")
+/// استخراج عنوان MAC للمرسل من كتلة خام
+pub fn extract_mac(data: &str) -> Option<&str> {
+    let start = data.find("mac:")? + 4;
+    let rest = data[start..].trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_ascii_hexdigit() || c == ':'))
+        .unwrap_or(rest.len());
+    let mac = &rest[..end];
+    (mac.len() == 17).then_some(mac)
+}
+
+/// Extract the RSSI (dBm) from a raw block ("rssi:-45" or "rssi=-45")
+/// استخراج قيمة RSSI من كتلة خام
+pub fn extract_rssi(data: &str) -> Option<i32> {
+    extract_labeled_int(data, "rssi")
+}
+
+/// Extract a labelled integer ("channel:6", "noise_floor=-95")
+/// استخراج عدد صحيح موسوم
+///
+/// The label must start a word, so "channel" does not match "secondary_channel".
+/// Values that don't fit `T` are None.
+fn extract_labeled_int<T: std::str::FromStr>(data: &str, label: &str) -> Option<T> {
+    let start = data
+        .match_indices(label)
+        .map(|(i, _)| i)
+        .find(|&i| !data[..i].ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_'))?
+        + label.len();
+    let rest = data[start..].trim_start_matches([':', '=', ' ']);
+    let end = rest
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && c == '-')))
+        .map_or(rest.len(), |(i, _)| i);
+    rest[..end].parse().ok()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Log Line Filtering / تصفية أسطر السجل
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// ESP-IDF log levels (error, warning, info, debug, verbose) / مستويات سجل ESP-IDF
+const ESP_LOG_LEVELS: &[u8] = b"EWIDV";
+
+/// Recent array lengths kept for the median / أطوال المصفوفات الأخيرة المحفوظة للوسيط
+const LENGTH_HISTORY: usize = 16;
+
+/// Lengths seen before the guard rejects anything / الأطوال اللازمة قبل أن يرفض الحارس أي شيء
+const MIN_LENGTH_HISTORY: usize = 5;
+
+/// Largest jump from the recent median still accepted (values)
+/// أكبر قفزة عن الوسيط الأخير ما زالت مقبولة (بعدد القيم)
+pub const MAX_LENGTH_DEVIATION: usize = 4;
+
+/// Byte offset where an ESP-IDF log line (`I (1234) wifi: ...`) starts in `line`
+/// موضع بداية سطر سجل ESP-IDF داخل السطر
+///
+/// A color code right before the level letter belongs to the log line.
+fn log_start(line: &str) -> Option<usize> {
+    let bytes = line.as_bytes();
+    (0..bytes.len()).find_map(|i| {
+        let level = ESP_LOG_LEVELS.contains(&bytes[i])
+            && bytes[i + 1..].starts_with(b" (")
+            && {
+                let digits = bytes[i + 3..].iter().take_while(|b| b.is_ascii_digit()).count();
+                digits > 0 && bytes[i + 3 + digits..].starts_with(b") ")
+            };
+        if !level {
+            return None;
+        }
+        match color_code_before(line, i) {
+            Some(color) => Some(color),
+            None if i == 0 || !bytes[i - 1].is_ascii_alphanumeric() => Some(i),
+            None => None,
+        }
+    })
+}
+
+/// Start of a "\x1b[0;32m" color code ending at `at` / بداية رمز لون ينتهي عند `at`
+fn color_code_before(line: &str, at: usize) -> Option<usize> {
+    line[..at].rfind('\x1b').filter(|&e| {
+        let code = &line[e + 1..at];
+        code.len() >= 2
+            && code.starts_with('[')
+            && code.ends_with('m')
+            && code[1..code.len() - 1].bytes().all(|b| b.is_ascii_digit() || b == b';')
+    })
+}
+
+/// Block with its ESP-IDF log lines removed, and how many were removed
+/// الكتلة بعد حذف أسطر سجل ESP-IDF، وعدد الأسطر المحذوفة
+///
+/// A log line that landed mid-array takes only the rest of its own line,
+/// so the numbers before it join up with the ones after it.
+pub fn strip_log_lines(block: &str) -> (Cow<'_, str>, usize) {
+    if log_start(block).is_none() {
+        return (Cow::Borrowed(block), 0);
+    }
+    let mut kept = String::with_capacity(block.len());
+    let mut removed = 0;
+    for line in block.split_inclusive('\n') {
+        match log_start(line) {
+            Some(start) => {
+                kept.push_str(&line[..start]);
+                removed += 1;
+            }
+            None => kept.push_str(line),
+        }
+    }
+    (Cow::Owned(kept), removed)
+}
+
+/// Rejects arrays whose length jumps away from the recent median
+/// يرفض المصفوفات التي يقفز طولها بعيداً عن الوسيط الأخير
+///
+/// Every length is remembered, accepted or not, so a real layout change
+/// (e.g. HT20 → HT40) is accepted once it becomes the median.
+#[derive(Debug, Clone, Default)]
+pub struct ArrayLengthGuard {
+    recent: VecDeque<usize>,
+}
+
+impl ArrayLengthGuard {
+    /// Record an array of `values` integers; false when it looks contaminated
+    /// تسجيل مصفوفة من `values` عدداً؛ false إن بدت ملوثة
+    pub fn accept(&mut self, values: usize) -> bool {
+        let accepted = self.recent.len() < MIN_LENGTH_HISTORY || {
+            let mut sorted: Vec<usize> = self.recent.iter().copied().collect();
+            sorted.sort_unstable();
+            sorted[sorted.len() / 2].abs_diff(values) <= MAX_LENGTH_DEVIATION
+        };
+        self.recent.push_back(values);
+        if self.recent.len() > LENGTH_HISTORY {
+            self.recent.pop_front();
+        }
+        accepted
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Number Scanner / ماسح الأرقام
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Integers in `data`, read in one pass over the bytes
+/// الأعداد الصحيحة في `data` بقراءة واحدة للبايتات
+///
+/// Same matches as `-?\d+` with ASCII digits: a `-` counts only directly
+/// before a digit, and values outside `i32` are skipped.
+fn scan_integers(data: &str) -> Vec<i32> {
+    let bytes = data.as_bytes();
+    let mut numbers = Vec::with_capacity(bytes.len() / 4);
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        if negative {
+            i += 1;
+        }
+
+        // Saturates far above i32, so overlong runs fail the conversion below
+        // يتشبع فوق i32 بكثير فتفشل السلاسل الطويلة في التحويل أدناه
+        let mut value: i64 = 0;
+        while let Some(&digit) = bytes.get(i).filter(|b| b.is_ascii_digit()) {
+            value = value.saturating_mul(10).saturating_add((digit - b'0') as i64);
+            i += 1;
+        }
+        if let Ok(number) = i32::try_from(if negative { -value } else { value }) {
+            numbers.push(number);
+        }
+    }
+    numbers
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -255,6 +809,43 @@ mod tests {
         assert_eq!(result.mags.len(), 5);
     }
 
+    #[test]
+    fn test_scanner_matches_regex() {
+        let regex = CsiParser::new().with_regex_scanner();
+        let inputs = [
+            "[10, -5, 20, -10, 15, 8]",
+            "--5 5-3 - -x 7- 007",
+            "2147483647 -2147483648 2147483648 -2147483649 99999999999999999999999",
+            "rssi:-45 [1,2,3]\r\n",
+            "",
+        ];
+        for input in inputs {
+            assert_eq!(scan_integers(input), regex.extract_numbers(input), "{:?}", input);
+        }
+        assert_eq!(scan_integers("--5 5-3 -"), vec![-5, 5, -3]);
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_number_scanners() {
+        let block = format!(
+            "[{}]",
+            (0..512).map(|i| ((i * 37) % 255 - 127).to_string()).collect::<Vec<_>>().join(",")
+        );
+        let time = |parser: &CsiParser| {
+            let started = std::time::Instant::now();
+            for _ in 0..2_000 {
+                assert_eq!(parser.extract_numbers(std::hint::black_box(&block)).len(), 512);
+            }
+            started.elapsed()
+        };
+        let regex = time(&CsiParser::new().with_regex_scanner());
+        let scanner = time(&CsiParser::new());
+        eprintln!("512 numbers x 2000: regex {:?}, scanner {:?}", regex, scanner);
+        assert!(scanner < regex);
+    }
+
     #[test]
     fn test_extract_csi_block() {
         let raw = "mac:AA:BB:CC:DD:EE:FF csi_data:[1,2,3,4,5]";
@@ -262,4 +853,240 @@ mod tests {
         
         assert_eq!(block, "[1,2,3,4,5]");
     }
+
+    #[test]
+    fn test_extract_delimited_block() {
+        let parser = CsiParser::new();
+
+        // Space-delimited after the MAC / مفصولة بمسافات بعد عنوان MAC
+        let raw = "mac:AA:BB:CC:DD:EE:FF rssi:-47 10 -5 20 -10\r\n";
+        let block = extract_csi_block(raw).unwrap();
+        assert_eq!(block, "10 -5 20 -10");
+        let result = parser.parse(block).unwrap();
+        assert_eq!(result.format, CsiFormat::RealImag);
+        assert_eq!(result.pairs, vec![(10, -5), (20, -10)]);
+
+        // Tab-delimited after a label / مفصولة بمسافات جدولة بعد وسم
+        let raw = "mac:AA:BB:CC:DD:EE:FF\tcsi_data:\t100\t150\t\t120\n";
+        let block = extract_csi_block(raw).unwrap();
+        assert_eq!(block, "100\t150\t\t120");
+        assert_eq!(parser.parse(block).unwrap().mags, vec![100.0, 150.0, 120.0]);
+
+        // Not enough numbers / أرقام غير كافية
+        assert_eq!(extract_csi_block("mac:AA:BB:CC:DD:EE:FF rssi:-47 7"), None);
+        assert_eq!(extract_csi_block("mac:AA:BB:CC:DD:EE:FF"), None);
+    }
+
+    #[test]
+    fn test_parse_block_rssi() {
+        let parser = CsiParser::new();
+        let with = parser.parse_block("mac:AA:BB:CC:DD:EE:FF rssi:-61 csi_data:[10,-5,20,-10]").unwrap();
+        assert_eq!(with.rssi, Some(-61));
+        assert_eq!(with.pairs.len(), 2);
+
+        // Missing RSSI is tolerated / غياب RSSI مقبول
+        let without = parser.parse_block("mac:AA:BB:CC:DD:EE:FF csi_data:[10,-5,20,-10]").unwrap();
+        assert_eq!(without.rssi, None);
+        assert_eq!(without.mac.as_deref(), Some("AA:BB:CC:DD:EE:FF"));
+
+        // Malformed MAC: block still parses / MAC تالف: الكتلة تُحلل رغم ذلك
+        let torn = parser.parse_block("mac:AA:BB:C rssi:-61 csi_data:[10,-5,20,-10]").unwrap();
+        assert_eq!(torn.mac, None);
+        assert_eq!(torn.pairs.len(), 2);
+    }
+
+    #[test]
+    fn test_forced_format_bypasses_heuristic() {
+        // Quiet period: mostly positive I/Q looks like amplitude
+        // فترة هادئة: I/Q موجبة غالباً تبدو كسعة
+        let quiet = "[4, 3, 5, 2, 6, 1, 3, 4]";
+        assert_eq!(CsiParser::new().parse(quiet).unwrap().format, CsiFormat::AmplitudeOnly);
+
+        let forced = CsiParser::with_format(CsiFormat::RealImag).parse(quiet).unwrap();
+        assert_eq!(forced.format, CsiFormat::RealImag);
+        assert_eq!(forced.pairs, vec![(4, 3), (5, 2), (6, 1), (3, 4)]);
+        assert_eq!(forced.phases.len(), 4);
+
+        // Forced amplitude keeps every value, even balanced signs
+        // السعة المفروضة تبقي كل القيم حتى مع إشارات متوازنة
+        let amplitude = CsiParser::with_format(CsiFormat::AmplitudeOnly).parse("[10, -5, 20, -10]").unwrap();
+        assert_eq!(amplitude.format, CsiFormat::AmplitudeOnly);
+        assert_eq!(amplitude.mags, vec![10.0, 5.0, 20.0, 10.0]);
+
+        // Unknown means automatic / Unknown يعني تلقائي
+        let auto = CsiParser::with_format(CsiFormat::Unknown).parse(quiet).unwrap();
+        assert_eq!(auto.format, CsiFormat::AmplitudeOnly);
+    }
+
+    #[test]
+    fn test_parse_esp_idf_metadata() {
+        // Line as printed by the ESP-IDF csi_recv example (array shortened)
+        // سطر كما يطبعه مثال csi_recv من ESP-IDF (المصفوفة مختصرة)
+        let line = "CSI_DATA,12,1a:2b:3c:4d:5e:6f,-38,11,1,6,1,0,1,0,0,0,0,-97,0,11,2,1702563,0,68,0,8,1,\"[87,-96,6,0,-12,33,40,-21]\"";
+        let parser = CsiParser::new();
+        let parsed = parser.parse_with_metadata(line).unwrap();
+        assert_eq!(parsed.rssi, Some(-38));
+        assert_eq!(parsed.meta, CsiMeta { channel: Some(11), secondary_channel: Some(2), sig_mode: Some(1), bandwidth: Some(1) });
+        assert_eq!(parsed.meta.wifi_standard().as_deref(), Some("Wi-Fi 4 (40MHz)"));
+        assert_eq!(parsed.meta.channel_label().as_deref(), Some("ch 11-"));
+        assert_eq!(parsed.noise_floor, Some(-97));
+        assert_eq!(parsed.esp_timestamp_us, Some(1_702_563));
+
+        // Metadata integers do not leak into the CSI values
+        // أرقام البيانات الوصفية لا تتسرب إلى قيم CSI
+        let result = &parsed.result;
+        assert_eq!(result.format, CsiFormat::RealImag);
+        assert_eq!(result.pairs, vec![(87, -96), (6, 0), (-12, 33), (40, -21)]);
+        assert_eq!(result.rssi, Some(-38));
+        assert_eq!(result.mac.as_deref(), Some("1a:2b:3c:4d:5e:6f"));
+        assert_eq!(parser.parse_block(line).unwrap().pairs.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_labeled_metadata() {
+        let parser = CsiParser::new();
+        let block = "mac:AA:BB:CC:DD:EE:FF rssi:-52 secondary_channel:1 channel:6 noise_floor=-93 csi_data:[10,-5,20,-10]";
+        let parsed = parser.parse_with_metadata(block).unwrap();
+        assert_eq!(parsed.rssi, Some(-52));
+        assert_eq!(parsed.meta.channel, Some(6));
+        assert_eq!(parsed.meta.secondary_channel, Some(1));
+        assert_eq!(parsed.noise_floor, Some(-93));
+        assert_eq!(parsed.result.mac.as_deref(), Some("AA:BB:CC:DD:EE:FF"));
+        assert_eq!(parsed.result.pairs, vec![(10, -5), (20, -10)]);
+
+        // Missing fields stay empty / الحقول الغائبة تبقى فارغة
+        let bare = parser.parse_with_metadata("mac:AA:BB:CC:DD:EE:FF 10 -5 20 -10").unwrap();
+        assert_eq!((bare.rssi, bare.noise_floor), (None, None));
+        assert!(bare.meta.is_empty());
+        assert_eq!(bare.esp_timestamp_us, None);
+        assert_eq!(bare.result.pairs.len(), 2);
+    }
+
+    #[test]
+    fn test_phase_quadrants() {
+        use std::f64::consts::{FRAC_PI_4, PI};
+
+        let phases = phases_of(&[(1, 1), (-1, 1), (-1, -1), (1, -1), (-1, 0), (0, 2), (0, 0)]);
+        let expected = [FRAC_PI_4, 3.0 * FRAC_PI_4, -3.0 * FRAC_PI_4, -FRAC_PI_4, PI, PI / 2.0, 0.0];
+        for (got, want) in phases.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-12, "{} != {}", got, want);
+        }
+
+        // RealImag data carries phases, amplitude-only does not
+        // بيانات حقيقي/تخيلي تحمل الطور، وبيانات السعة لا
+        let parser = CsiParser::new();
+        let real_imag = parser.parse("[10,-5,-20,10]").unwrap();
+        assert_eq!(real_imag.format, CsiFormat::RealImag);
+        assert_eq!(real_imag.phases.len(), 2);
+        assert!((real_imag.phases[1] - 10f64.atan2(-20.0)).abs() < 1e-12);
+        let amplitude = parser.parse("[10,20,30,40,50]").unwrap();
+        assert_eq!(amplitude.format, CsiFormat::AmplitudeOnly);
+        assert!(amplitude.phases.is_empty());
+    }
+
+    #[test]
+    fn test_find_block_mixed_formats() {
+        let esp = "CSI_DATA,0,1a:2b:3c:4d:5e:6f,-52,11,1,6,1,0,1,0,0,0,0,-97,0,11,2,1702563,0,68,0,8,1,\"[87 -96 6 0]\"\r\n";
+        let mac = "mac:AA:BB:CC:DD:EE:FF rssi:-40 csi_data:[10,-5,20,-10]\n";
+        let buffer = format!("boot noise\n{}{}{}", esp, mac, esp);
+
+        // ESP-IDF line ends at its newline / سطر ESP-IDF ينتهي عند السطر الجديد
+        let first = find_block(&buffer).unwrap();
+        assert_eq!(&buffer[first.clone()], esp);
+
+        // mac: block runs until the next CSI_DATA line / كتلة mac تمتد حتى السطر التالي
+        let rest = &buffer[first.end..];
+        assert_eq!(&rest[find_block(rest).unwrap()], mac);
+
+        // Last line is complete, a half-line is not / السطر الأخير مكتمل، ونصف السطر لا
+        let tail = &buffer[first.end + mac.len()..];
+        assert_eq!(find_block(tail), Some(0..esp.len()));
+        assert_eq!(find_block(&esp[..esp.len() - 2]), None);
+        assert_eq!(last_block_start(&buffer), Some(buffer.len() - esp.len()));
+
+        // Trailing mac: block ends at the newline after its array
+        // كتلة mac الأخيرة تنتهي عند السطر الجديد بعد مصفوفتها
+        assert_eq!(find_block(mac), Some(0..mac.len()));
+        let two = format!("{}{}", mac, mac);
+        assert_eq!(find_block(&two), Some(0..mac.len()));
+        assert_eq!(find_block(&two[mac.len()..]), Some(0..mac.len()));
+
+        // Partial trailing blocks stay buffered / الكتل الأخيرة الجزئية تبقى في المخزن
+        assert_eq!(find_block(&mac[..mac.len() - 1]), None);
+        assert_eq!(find_block("mac:AA:BB:CC:DD:EE:FF csi_data:[10,-5,\n20"), None);
+        assert_eq!(find_block("mac:AA:BB:CC:DD:EE:FF rssi:-40\n"), None);
+
+        // Space-separated array with fixed-column RSSI and MAC / مصفوفة بمسافات
+        let parsed = CsiParser::new().parse_block(esp).unwrap();
+        assert_eq!(parsed.pairs, vec![(87, -96), (6, 0)]);
+        assert_eq!(parsed.rssi, Some(-52));
+        assert_eq!(parsed.mac.as_deref(), Some("1a:2b:3c:4d:5e:6f"));
+    }
+
+    #[test]
+    fn test_tiny_blocks_rejected() {
+        let parser = CsiParser::new().with_min_subcarriers(DEFAULT_MIN_SUBCARRIERS);
+        let full = format!("mac:AA:BB:CC:DD:EE:FF csi_data:[{}]", ["10,-5"; 8].join(","));
+        assert_eq!(parser.parse_block(&full).unwrap().mags.len(), 8);
+
+        // One value, empty array, no array / قيمة واحدة، مصفوفة فارغة، بلا مصفوفة
+        let single = "mac:AA:BB:CC:DD:EE:FF csi_data:[5]";
+        assert!(parser.parse_block(single).is_none());
+        assert_eq!(parser.diagnose(single), ParseFailure::TooFewSubcarriers);
+        assert_eq!(parser.diagnose("mac:AA:BB:CC:DD:EE:FF csi_data:[]"), ParseFailure::NoValues);
+        assert_eq!(parser.diagnose("mac:AA:BB:CC:DD:EE:FF"), ParseFailure::NoArray);
+
+        // Bare arrays and the default parser are unaffected / المصفوفات المجردة والمحلل الافتراضي بلا تغيير
+        assert_eq!(parser.parse("[5]").unwrap().mags, vec![5.0]);
+        assert!(CsiParser::new().parse_block(single).is_some());
+    }
+
+    #[test]
+    fn test_extract_mac_and_rssi() {
+        let raw = "mac:AA:BB:CC:DD:EE:FF rssi:-47 csi_data:[1,2,3,4,5]";
+        assert_eq!(extract_mac(raw), Some("AA:BB:CC:DD:EE:FF"));
+        assert_eq!(extract_rssi(raw), Some(-47));
+        assert_eq!(extract_rssi("mac:AA:BB:CC:DD:EE:FF rssi=-60,"), Some(-60));
+        assert_eq!(extract_mac("mac:AA:BB csi_data:[1]"), None);
+        assert_eq!(extract_rssi("csi_data:[1,2]"), None);
+    }
+
+    #[test]
+    fn test_log_lines_removed() {
+        let parser = CsiParser::new();
+        let clean = "mac:24:0A:C4:00:00:01 rssi:-45\ncsi_data:[10,-5,20,-10,30,-15,40,-20,50,-25,60,-30,70,-35,80,-40]\n";
+        let expected = parser.parse_with_metadata(clean).unwrap().result.pairs;
+
+        // Own line, mid-array, and with a color code / سطر مستقل، ووسط المصفوفة، ومع رمز لون
+        let noisy = [
+            "mac:24:0A:C4:00:00:01 rssi:-45\nI (1234) wifi: new:<6,0>, old:<6,0>\ncsi_data:[10,-5,20,-10,30,-15,40,-20,50,-25,60,-30,70,-35,80,-40]\n",
+            "mac:24:0A:C4:00:00:01 rssi:-45\ncsi_data:[10,-5,20,-10,30,W (99812) wifi: beacon timeout 3\n-15,40,-20,50,-25,60,-30,70,-35,80,-40]\n",
+            "mac:24:0A:C4:00:00:01 rssi:-45\ncsi_data:[10,-5,20,-10,30,-15,40,-20,\x1b[0;32mI (5) phy: 2 4\x1b[0m\n50,-25,60,-30,70,-35,80,-40]\n",
+        ];
+        for block in noisy {
+            let (stripped, removed) = strip_log_lines(block);
+            assert_eq!(removed, 1, "{:?}", block);
+            assert_eq!(parser.parse_with_metadata(&stripped).unwrap().result.pairs, expected);
+        }
+
+        // Clean blocks pass through untouched / الكتل النظيفة تمر كما هي
+        assert!(matches!(strip_log_lines(clean), (Cow::Borrowed(_), 0)));
+        assert!(log_start("mac:DE:AD:BE:EF:00:01 RSSI (45) ").is_none());
+    }
+
+    #[test]
+    fn test_length_guard() {
+        let mut guard = ArrayLengthGuard::default();
+        for _ in 0..MIN_LENGTH_HISTORY {
+            assert!(guard.accept(128));
+        }
+        assert!(guard.accept(128 + MAX_LENGTH_DEVIATION));
+        assert!(!guard.accept(128 + MAX_LENGTH_DEVIATION + 1));
+        assert!(!guard.accept(120));
+
+        // A lasting change wins the median / التغيير الدائم يكسب الوسيط
+        let accepted = (0..LENGTH_HISTORY).filter(|_| guard.accept(256)).count();
+        assert!(accepted > 0 && accepted < LENGTH_HISTORY);
+        assert!(guard.accept(256));
+    }
 }
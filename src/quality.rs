@@ -0,0 +1,211 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 quality.rs - Detection Input Quality
+// ═══════════════════════════════════════════════════════════════════════════════
+// Rates how far the detectors can be trusted on the current input.
+// Features:
+// - Format-detection confidence from the sign balance of the raw values
+// - Subcarrier count and fraction of railed (clipped) values
+// - Configurable floors; any signal past its floor marks detections uncertain
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use crate::state::{CsiFormat, CsiFrame};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Recent frames the quality is judged on / الإطارات الحديثة التي تقيّم الجودة
+pub const QUALITY_WINDOW_FRAMES: usize = 20;
+
+/// Raw value treated as railed (ESP32 CSI is 8-bit) / القيمة الخام المعتبرة مشبعة
+pub const CLIP_LEVEL: i32 = 127;
+
+/// Default floors / الحدود الافتراضية
+pub const DEFAULT_MIN_FORMAT_CONFIDENCE: f64 = 0.5;
+pub const DEFAULT_MIN_QUALITY_SUBCARRIERS: usize = 32;
+pub const DEFAULT_MAX_CLIP_FRACTION: f64 = 0.05;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Quality Floors / حدود الجودة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Limits below which detections are shown as uncertain
+/// الحدود التي تعرض الكشوفات دونها كغير مؤكدة
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityFloors {
+    /// Lowest format-detection confidence (0-1) / أدنى ثقة في كشف الصيغة
+    pub min_format_confidence: f64,
+
+    /// Fewest subcarriers / أقل عدد ناقلات فرعية
+    pub min_subcarriers: usize,
+
+    /// Highest fraction of clipped values (0-1) / أعلى نسبة قيم مشبعة
+    pub max_clip_fraction: f64,
+}
+
+impl Default for QualityFloors {
+    fn default() -> Self {
+        Self {
+            min_format_confidence: DEFAULT_MIN_FORMAT_CONFIDENCE,
+            min_subcarriers: DEFAULT_MIN_QUALITY_SUBCARRIERS,
+            max_clip_fraction: DEFAULT_MAX_CLIP_FRACTION,
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Quality / الجودة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Input quality signals and the verdict against the floors
+/// إشارات جودة الإدخال والحكم مقارنة بالحدود
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quality {
+    /// Mean format-detection confidence (0-1) / متوسط الثقة في كشف الصيغة
+    pub format_confidence: f64,
+
+    /// Subcarriers of the newest frame / ناقلات أحدث إطار
+    pub subcarriers: usize,
+
+    /// Fraction of clipped raw values (0-1) / نسبة القيم الخام المشبعة
+    pub clip_fraction: f64,
+
+    /// Any signal past its floor / أي إشارة تجاوزت حدها
+    pub uncertain: bool,
+}
+
+impl Quality {
+    /// Short reason for the UI, None when certain / سبب مختصر للواجهة
+    pub fn reason(&self, floors: &QualityFloors) -> Option<String> {
+        if !self.uncertain {
+            None
+        } else if self.subcarriers < floors.min_subcarriers {
+            Some(format!("{} SC", self.subcarriers))
+        } else if self.clip_fraction > floors.max_clip_fraction {
+            Some(format!("{:.0}% clipped", self.clip_fraction * 100.0))
+        } else {
+            Some(format!("format {:.0}%", self.format_confidence * 100.0))
+        }
+    }
+}
+
+/// Confidence (0-1) that a frame's format was detected correctly
+/// الثقة في أن صيغة الإطار اكتُشفت بشكل صحيح
+///
+/// Mirrors the parser heuristics: Real/Imag data is about half negative,
+/// amplitude data has (almost) no negatives. Confidence falls to 0 at the
+/// ratio where the parser would have chosen differently.
+pub fn format_confidence(frame: &CsiFrame) -> f64 {
+    let values: Vec<i32> = match frame.format {
+        CsiFormat::RealImag => frame.pairs.iter().flat_map(|&(r, i)| [r, i]).collect(),
+        CsiFormat::AmplitudeOnly => frame.pairs.iter().map(|&(r, _)| r).collect(),
+        CsiFormat::Unknown => return 0.0,
+    };
+    if values.is_empty() {
+        return 0.0;
+    }
+    let negative = values.iter().filter(|&&v| v < 0).count() as f64 / values.len() as f64;
+
+    let confidence = match frame.format {
+        CsiFormat::RealImag => 1.0 - (negative - 0.5).abs() / 0.35,
+        _ => 1.0 - negative / 0.1,
+    };
+    confidence.clamp(0.0, 1.0)
+}
+
+/// Assess the newest QUALITY_WINDOW_FRAMES frames against `floors`
+/// تقييم أحدث الإطارات مقارنة بالحدود
+pub fn assess(frames: &[CsiFrame], floors: &QualityFloors) -> Quality {
+    let recent = &frames[frames.len().saturating_sub(QUALITY_WINDOW_FRAMES)..];
+    let subcarriers = recent.last().map_or(0, |f| f.subcarrier_count());
+
+    let format_confidence = if recent.is_empty() {
+        0.0
+    } else {
+        recent.iter().map(format_confidence).sum::<f64>() / recent.len() as f64
+    };
+
+    let (mut clipped, mut total) = (0usize, 0usize);
+    for frame in recent {
+        let components: Vec<i32> = match frame.format {
+            CsiFormat::RealImag => frame.pairs.iter().flat_map(|&(r, i)| [r, i]).collect(),
+            _ => frame.pairs.iter().map(|&(r, _)| r).collect(),
+        };
+        clipped += components.iter().filter(|v| v.abs() >= CLIP_LEVEL).count();
+        total += components.len();
+    }
+    let clip_fraction = if total == 0 { 0.0 } else { clipped as f64 / total as f64 };
+
+    Quality {
+        format_confidence,
+        subcarriers,
+        clip_fraction,
+        uncertain: format_confidence < floors.min_format_confidence
+            || subcarriers < floors.min_subcarriers
+            || clip_fraction > floors.max_clip_fraction,
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{AppState, WindowClock};
+
+    fn real_imag(sc: usize, value: i32) -> CsiFrame {
+        let pairs: Vec<(i32, i32)> = (0..sc).map(|i| if i % 2 == 0 { (value, -value) } else { (-value, value) }).collect();
+        let mags = pairs.iter().map(|&(r, i)| ((r * r + i * i) as f64).sqrt()).collect();
+        CsiFrame::new(0, mags, pairs, CsiFormat::RealImag, None)
+    }
+
+    #[test]
+    fn test_detection_quality_good_and_degraded() {
+        let mut state = AppState::new();
+        state.window_clock = WindowClock::NewestFrame;
+        for _ in 0..30 {
+            state.push_frame(real_imag(64, 20));
+        }
+        let good = state.detection_quality();
+        assert!(!good.uncertain);
+        assert_eq!(good.subcarriers, 64);
+        assert_eq!(good.format_confidence, 1.0);
+        assert_eq!(good.reason(&state.quality_floors), None);
+
+        // Too few subcarriers / ناقلات فرعية قليلة
+        state.clear_frames();
+        state.push_frame(real_imag(12, 20));
+        let narrow = state.detection_quality();
+        assert!(narrow.uncertain);
+        assert_eq!(narrow.reason(&state.quality_floors).as_deref(), Some("12 SC"));
+
+        // Railed front end / واجهة مشبعة
+        state.clear_frames();
+        state.push_frame(real_imag(64, 127));
+        let clipped = state.detection_quality();
+        assert!(clipped.uncertain);
+        assert_eq!(clipped.clip_fraction, 1.0);
+
+        // The floors are configurable / الحدود قابلة للضبط
+        state.quality_floors.max_clip_fraction = 1.0;
+        assert!(!state.detection_quality().uncertain);
+    }
+
+    #[test]
+    fn test_format_confidence() {
+        // Balanced signs: confident Real/Imag / إشارات متوازنة
+        assert_eq!(format_confidence(&real_imag(8, 5)), 1.0);
+
+        // Barely Real/Imag: 20% negative / بالكاد حقيقي/تخيلي
+        let pairs = vec![(5, 5), (5, 5), (5, -5), (5, 5), (5, 5)];
+        let marginal = CsiFrame::new(0, vec![7.0; 5], pairs, CsiFormat::RealImag, None);
+        assert!(format_confidence(&marginal) < 0.2);
+
+        // Amplitude with no negatives / سعات بلا قيم سالبة
+        let amplitude = CsiFrame::new(0, vec![3.0; 4], vec![(3, 0); 4], CsiFormat::AmplitudeOnly, None);
+        assert_eq!(format_confidence(&amplitude), 1.0);
+        assert_eq!(assess(&[], &QualityFloors::default()).subcarriers, 0);
+    }
+}
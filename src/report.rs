@@ -0,0 +1,287 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 report.rs - Issue Report Bundle
+// ═══════════════════════════════════════════════════════════════════════════════
+// Gathers what a bug report needs into one timestamped directory.
+// Features:
+// - Bounded rings for raw serial lines and status-message history
+// - Summary: version, OS/terminal, current config and parser/drop counters
+// - Recent raw lines and frames, optionally left out for privacy
+// - Sanitized text, with MAC addresses optionally masked
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone, Utc};
+use regex::Regex;
+
+use crate::error::{CsiError, Result};
+use crate::state::AppState;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Raw serial lines kept for a report / أسطر التسلسل الخام المحفوظة للتقرير
+pub const RAW_LINE_CAPACITY: usize = 2_000;
+
+/// Status messages kept for a report / رسائل الحالة المحفوظة للتقرير
+pub const MESSAGE_HISTORY_CAPACITY: usize = 100;
+
+/// Newest frames written to a report / أحدث الإطارات المكتوبة في التقرير
+pub const REPORT_FRAMES: usize = 20;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Line Ring / حلقة الأسطر
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Fixed-capacity ring of text lines, oldest first
+/// حلقة أسطر نصية بسعة ثابتة، الأقدم أولاً
+#[derive(Debug, Clone)]
+pub struct LineRing {
+    /// Stored lines / الأسطر المخزنة
+    lines: VecDeque<String>,
+
+    /// Maximum lines kept / أقصى عدد أسطر
+    capacity: usize,
+}
+
+impl LineRing {
+    /// Create an empty ring / إنشاء حلقة فارغة
+    pub fn new(capacity: usize) -> Self {
+        Self { lines: VecDeque::new(), capacity: capacity.max(1) }
+    }
+
+    /// Append a line, dropping the oldest when full / إضافة سطر وحذف الأقدم عند الامتلاء
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    /// Replace the newest line / استبدال أحدث سطر
+    pub fn replace_last(&mut self, line: String) {
+        match self.lines.back_mut() {
+            Some(last) => *last = line,
+            None => self.lines.push_back(line),
+        }
+    }
+
+    /// Stored lines, oldest first / الأسطر المخزنة، الأقدم أولاً
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.lines.iter()
+    }
+
+    /// Number of stored lines / عدد الأسطر المخزنة
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// True when nothing is stored / صحيح عند عدم وجود أسطر
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Redaction / التنقيح
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// What goes into a report / ما يدخل في التقرير
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportOptions {
+    /// Include raw serial lines and recent frames / تضمين الأسطر الخام والإطارات الحديثة
+    pub include_raw: bool,
+
+    /// Mask MAC addresses everywhere / إخفاء عناوين MAC في كل مكان
+    pub mask_macs: bool,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self { include_raw: true, mask_macs: false }
+    }
+}
+
+/// Drop control characters and trailing whitespace / حذف محارف التحكم والمسافات اللاحقة
+pub fn sanitize_line(line: &str) -> String {
+    line.chars()
+        .filter(|c| *c == '\t' || !c.is_control())
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/// Replace all but the last octet of each MAC address
+/// استبدال كل بايتات عنوان MAC عدا الأخير
+///
+/// Keeping one octet lets a reader still tell transmitters apart.
+pub fn mask_macs(text: &str) -> String {
+    let mac = Regex::new(r"\b(?:[0-9A-Fa-f]{2}[:-]){5}([0-9A-Fa-f]{2})\b").expect("Failed to compile regex");
+    mac.replace_all(text, "**:**:**:**:**:$1").into_owned()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Bundle Writer / كاتب الحزمة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Write `report_<time>/` under `root` and return its path
+/// كتابة `report_<time>/` داخل `root` وإرجاع مساره
+pub fn write_report(root: &Path, state: &AppState, options: &ReportOptions) -> Result<PathBuf> {
+    let dir = root.join(format!("report_{}", Utc::now().format("%Y%m%d_%H%M%S")));
+    fs::create_dir_all(&dir).map_err(|e| CsiError::Report(format!("failed to create {}: {}", dir.display(), e)))?;
+
+    let redact = |text: String| if options.mask_macs { mask_macs(&text) } else { text };
+    let write = |name: &str, text: String| {
+        fs::write(dir.join(name), redact(text)).map_err(|e| CsiError::Report(format!("failed to write {}: {}", name, e)))
+    };
+
+    write("summary.txt", summary(state, options))?;
+    write("messages.txt", lines_of(state.message_history.iter()))?;
+    if options.include_raw {
+        write("raw_serial.txt", lines_of(state.raw_lines.iter()))?;
+        write("frames.csv", recent_frames(state))?;
+    }
+    Ok(dir)
+}
+
+/// Join sanitized lines / ضم الأسطر المنقحة
+fn lines_of<'a>(lines: impl Iterator<Item = &'a String>) -> String {
+    lines.map(|l| sanitize_line(l) + "\n").collect()
+}
+
+/// Version, environment, config and counters / الإصدار والبيئة والإعدادات والعدادات
+fn summary(state: &AppState, options: &ReportOptions) -> String {
+    let env = |key: &str| std::env::var(key).unwrap_or_else(|_| "--".to_string());
+    let floors = &state.quality_floors;
+    let fields = [
+        ("version", env!("CARGO_PKG_VERSION").to_string()),
+        ("os", format!("{} {}", std::env::consts::OS, std::env::consts::ARCH)),
+        ("term", env("TERM")),
+        ("term_program", env("TERM_PROGRAM")),
+        ("colorterm", env("COLORTERM")),
+        ("port", state.port_name.clone()),
+        ("receiver", state.receiver.label().to_string()),
+        ("binary_serial", state.binary_serial.to_string()),
+        ("csi_format", state.format_mode_label().to_string()),
+        ("strip_null_subcarriers", state.strip_null_subcarriers.to_string()),
+        ("min_subcarriers", state.min_subcarriers.to_string()),
+        ("window_secs", state.window_secs.to_string()),
+        ("primary_detector", state.primary_detector.label().to_string()),
+        ("aggregation", state.aggregation.label().to_string()),
+        ("display_scale", format!("{:?}", state.display_scale)),
+        ("warmup_secs", state.warmup_secs.to_string()),
+        ("mac_filter", state.mac_filter.clone().unwrap_or_else(|| "--".to_string())),
+        (
+            "quality_floors",
+            format!(
+                "confidence {} / subcarriers {} / clip {}",
+                floors.min_format_confidence, floors.min_subcarriers, floors.max_clip_fraction
+            ),
+        ),
+        ("frames_in_window", state.frame_count().to_string()),
+        ("frames_total", state.frame_seq.to_string()),
+        ("max_subcarriers", state.max_sc.to_string()),
+        ("null_subcarriers", state.null_subcarriers.to_string()),
+        ("blocks_seen", state.parse_stats.seen.to_string()),
+        ("blocks_parsed", state.parse_stats.parsed.to_string()),
+        ("blocks_rejected", state.parse_stats.rejected.to_string()),
+        (
+            "last_rejection",
+            state.parse_stats.last_failure.map_or("--".to_string(), |f| f.to_string()),
+        ),
+        ("tiny_frames_rejected", state.tiny_frames_rejected.to_string()),
+        ("contaminated_frames", state.contaminated_frames.to_string()),
+        ("corrupt_packets", state.corrupt_packets.to_string()),
+        ("drop_duplicate_frames", state.drop_duplicate_frames.to_string()),
+        ("duplicates_dropped", state.duplicates_dropped.to_string()),
+        ("raw_data", if options.include_raw { "included" } else { "excluded" }.to_string()),
+    ];
+    fields.iter().map(|(key, value)| format!("{}: {}\n", key, sanitize_line(value))).collect()
+}
+
+/// Newest frames as CSV (magnitudes joined by ';') / أحدث الإطارات بصيغة CSV
+fn recent_frames(state: &AppState) -> String {
+    let frames = &state.frames;
+    let mut csv = String::from("timestamp,time,rssi,mac,subcarriers,mags\n");
+    for frame in frames.iter().skip(frames.len().saturating_sub(REPORT_FRAMES)) {
+        let time = Local
+            .timestamp_millis_opt(frame.timestamp)
+            .single()
+            .map(|t| t.format("%H:%M:%S%.3f").to_string())
+            .unwrap_or_default();
+        let mags: Vec<String> = frame.mags.iter().map(|m| format!("{:.1}", m)).collect();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            frame.timestamp,
+            time,
+            frame.rssi.map(|r| r.to_string()).unwrap_or_default(),
+            frame.mac.as_deref().unwrap_or(""),
+            frame.subcarrier_count(),
+            mags.join(";"),
+        ));
+    }
+    csv
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{CsiFormat, CsiFrame};
+
+    #[test]
+    fn test_line_ring_and_redaction() {
+        let mut ring = LineRing::new(2);
+        for line in ["a", "b", "c"] {
+            ring.push(line.to_string());
+        }
+        ring.replace_last("d".to_string());
+        assert_eq!(ring.iter().cloned().collect::<Vec<_>>(), vec!["b", "d"]);
+
+        assert_eq!(sanitize_line("mac:1\x1b[0m\r\n"), "mac:1[0m");
+        assert_eq!(
+            mask_macs("CSI_DATA,1,1a:2b:3c:4d:5e:6f,-38 mac:AA-BB-CC-DD-EE-FF"),
+            "CSI_DATA,1,**:**:**:**:**:6f,-38 mac:**:**:**:**:**:FF"
+        );
+    }
+
+    #[test]
+    fn test_report_bundle_contents() {
+        let root = std::env::temp_dir().join(format!("csi_report_test_{}", std::process::id()));
+        let mut state = AppState::new();
+        state.raw_lines.push("mac:AA:BB:CC:DD:EE:01 csi_data:[1,2,3,4]".to_string());
+        state.message_history.push("12:00:00 ✅ Connected to COM3".to_string());
+        state.push_frame(
+            CsiFrame::new(chrono::Utc::now().timestamp_millis(), vec![5.0; 4], vec![], CsiFormat::AmplitudeOnly, Some(-40))
+                .with_mac(Some("AA:BB:CC:DD:EE:01".to_string())),
+        );
+
+        // Everything, MACs masked / كل شيء مع إخفاء MAC
+        let options = ReportOptions { include_raw: true, mask_macs: true };
+        let dir = write_report(&root, &state, &options).unwrap();
+        let summary = fs::read_to_string(dir.join("summary.txt")).unwrap();
+        assert!(summary.contains(&format!("version: {}", env!("CARGO_PKG_VERSION"))));
+        assert!(summary.contains("tiny_frames_rejected: 0"));
+        assert!(fs::read_to_string(dir.join("messages.txt")).unwrap().contains("Connected to COM3"));
+        let raw = fs::read_to_string(dir.join("raw_serial.txt")).unwrap();
+        assert!(raw.contains("**:**:**:**:**:01") && !raw.contains("AA:BB"));
+        let frames = fs::read_to_string(dir.join("frames.csv")).unwrap();
+        assert_eq!(frames.lines().count(), 2);
+        assert!(!frames.contains("AA:BB"));
+        fs::remove_dir_all(&dir).unwrap();
+
+        // Privacy: no raw data files / الخصوصية: بلا ملفات بيانات خام
+        let options = ReportOptions { include_raw: false, mask_macs: false };
+        let dir = write_report(&root, &state, &options).unwrap();
+        assert!(dir.join("summary.txt").exists());
+        assert!(!dir.join("raw_serial.txt").exists());
+        assert!(!dir.join("frames.csv").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -4,41 +4,102 @@
 // This module handles reading CSI data from ESP32 via serial port.
 // Features:
 // - Runs in background thread
-// - Detects CSI blocks by "mac:" delimiter
-// - Uses parser to decode data
-// - Pushes frames into AppState
+// - Auto-selects a port, preferring ESP bridge chips by USB vendor ID
+// - Detects CSI blocks by "mac:" delimiter or ESP-IDF "CSI_DATA" lines
+// - Optional binary mode for firmware that streams packed I/Q packets
+// - Uses parser to decode data, with the format forced from the UI if set
+// - Optionally skips frames that repeat the previous one
+// - Tracks per-MAC stats and applies the MAC filter
+// - Pushes frames into AppState, or through the UI's frame queue when given one
 // - Maintains last 60 seconds of data
 // - Logs to CSV if logger is active
+// - Optionally keeps blocks the parser rejects for later study
+// - Keeps the last raw text lines for report bundles
+// - Reconnects with exponential backoff when the port drops or fails to open
+// - Warns when the link goes silent, or sends bytes that never parse as CSI
+// - Named source readers on a fixed port tag their frames for the room view
+// - A tcp://host:port "port" reads a serial stream forwarded over the network
 // ═══════════════════════════════════════════════════════════════════════════════
 
-use std::io::Read;
+use std::io::{self, Read};
+use std::net::TcpStream;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
 
-use crate::csv_logger::CsvLogger;
-use crate::parser::{extract_csi_block, CsiParser};
-use crate::state::{CsiFrame, SharedState};
-use serialport::{available_ports, SerialPortType};
+use csi_tui::binary_packet::{take_packet, Packet};
+use csi_tui::csv_logger::CsvLogger;
+use csi_tui::csv_writer::CsvWriter;
+use csi_tui::detector_config::DetectorConfig;
+use csi_tui::error::{CsiError, Result};
+use csi_tui::frame_queue::{FrameSender, ReceivedFrame};
+use csi_tui::latency::Stage;
+use csi_tui::parse_failure_log::ParseFailureLog;
+use csi_tui::parser::{find_block, last_block_start, strip_log_lines, ArrayLengthGuard, CsiParser, ParseFailure, ParsedBlock, DEFAULT_MIN_SUBCARRIERS};
+use csi_tui::state::{AppState, CsiFormat, CsiFrame, CsiMeta, PortChoice, ReceiverState, SharedState};
+use serialport::{available_ports, SerialPortInfo, SerialPortType};
 
-/// Automatically chooses the first available USB serial port.
+/// USB vendor IDs of the bridges ESP boards use / معرفات مصنعي الجسور المستخدمة في لوحات ESP
+/// (Silicon Labs CP210x, WCH CH340, FTDI, Espressif native USB)
+const ESP_BRIDGE_VIDS: [u16; 4] = [0x10C4, 0x1A86, 0x0403, 0x303A];
+
+/// Automatically chooses the best-ranked USB serial port.
 pub fn auto_select_port() -> Option<String> {
-    let ports = available_ports().ok()?;
+    ranked_ports().into_iter().next()
+}
+
+/// USB serial ports, best candidate first / منافذ USB التسلسلية، الأفضل أولاً
+pub fn ranked_ports() -> Vec<String> {
+    rank_ports(&available_ports().unwrap_or_default())
+}
+
+/// Known ESP bridge chips first, then other USB ports, each in listed order;
+/// non-USB ports are never candidates
+/// شرائح جسور ESP المعروفة أولاً ثم منافذ USB الأخرى، كل منها بترتيب القائمة
+fn rank_ports(ports: &[SerialPortInfo]) -> Vec<String> {
+    let mut usb: Vec<(bool, &str)> = ports
+        .iter()
+        .filter_map(|p| match &p.port_type {
+            SerialPortType::UsbPort(info) => Some((!ESP_BRIDGE_VIDS.contains(&info.vid), p.port_name.as_str())),
+            _ => None,
+        })
+        .collect();
+    // Stable: equal ranks keep the system's order / ترتيب مستقر
+    usb.sort_by_key(|&(other, _)| other);
+    usb.into_iter().map(|(_, name)| name.to_string()).collect()
+}
 
-    for p in ports {
-        match &p.port_type {
-            SerialPortType::UsbPort(_) => {
-                // First USB serial device → most likely the ESP32-C3
-                return Some(p.port_name.clone());
+/// Every serial port with a readable description, for the port picker
+/// كل المنافذ التسلسلية مع وصف مقروء لنافذة اختيار المنفذ
+pub fn list_ports() -> Vec<PortChoice> {
+    available_ports()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|p| PortChoice { description: port_description(&p.port_type), name: p.port_name })
+        .collect()
+}
+
+/// USB product and manufacturer with VID:PID, or the port type
+/// منتج USB والمصنع مع VID:PID، أو نوع المنفذ
+fn port_description(port_type: &SerialPortType) -> String {
+    match port_type {
+        SerialPortType::UsbPort(usb) => {
+            let names: Vec<&str> = [usb.product.as_deref(), usb.manufacturer.as_deref()].into_iter().flatten().collect();
+            let ids = format!("{:04x}:{:04x}", usb.vid, usb.pid);
+            if names.is_empty() {
+                format!("USB {}", ids)
+            } else {
+                format!("{} ({})", names.join(" - "), ids)
             }
-            _ => {}
         }
+        SerialPortType::PciPort => "PCI".to_string(),
+        SerialPortType::BluetoothPort => "Bluetooth".to_string(),
+        SerialPortType::Unknown => "Unknown".to_string(),
     }
-
-    None
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -49,12 +110,22 @@ pub fn auto_select_port() -> Option<String> {
 /// Used as a fallback if auto-detection fails.
 pub const DEFAULT_PORT: &str = "COM3";
 
-/// Default baud rate / معدل البود الافتراضي
-pub const DEFAULT_BAUD_RATE: u32 = 115_200;
-
 /// Read timeout in milliseconds / مهلة القراءة بالميلي ثانية
 pub const READ_TIMEOUT_MS: u64 = 100;
 
+/// How often a pending open checks for cancel (ms) / تكرار فحص الإلغاء أثناء الفتح
+const OPEN_POLL_MS: u64 = 50;
+
+/// Pause before the first reopen attempt (ms) / المهلة قبل أول محاولة إعادة فتح
+const RECONNECT_INITIAL_DELAY_MS: u64 = 250;
+
+/// Longest pause between reopen attempts (ms) / أطول مهلة بين محاولات إعادة الفتح
+const RECONNECT_MAX_DELAY_MS: u64 = 5_000;
+
+/// Bytes received without a parsed frame before warning of a format mismatch
+/// البايتات المستلمة دون إطار محلل قبل التحذير من عدم تطابق الصيغة
+const NO_FRAME_BYTES: usize = 4_096;
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Serial Reader Structure / هيكل قارئ التسلسل
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -65,9 +136,6 @@ pub struct SerialReader {
     /// Port name (e.g., "COM3") / اسم المنفذ (مثل "COM3")
     port_name: String,
 
-    /// Baud rate / معدل البود
-    baud_rate: u32,
-
     /// Shared application state / حالة التطبيق المشتركة
     state: SharedState,
 
@@ -76,6 +144,21 @@ pub struct SerialReader {
 
     /// Handle to the reader thread / مقبض خيط القارئ
     thread_handle: Option<JoinHandle<()>>,
+
+    /// Decode binary packets instead of text / فك الحزم الثنائية بدلاً من النص
+    binary: bool,
+
+    /// Source name for a named reader on a fixed port; None for the primary reader
+    /// اسم المصدر لقارئ مسمى على منفذ ثابت؛ None للقارئ الأساسي
+    source: Option<String>,
+
+    /// Detect the port on start instead of using `port_name` as given
+    /// اكتشاف المنفذ عند البدء بدلاً من استخدام `port_name` كما هو
+    auto_port: bool,
+
+    /// Queue frames for the UI instead of locking the state per frame
+    /// إرسال الإطارات عبر طابور الواجهة بدلاً من قفل الحالة لكل إطار
+    queue: Option<FrameSender>,
 }
 
 impl SerialReader {
@@ -87,43 +170,93 @@ impl SerialReader {
 
         Self {
             port_name: detected,
-            baud_rate: DEFAULT_BAUD_RATE,
             state,
             stop_flag: Arc::new(AtomicBool::new(false)),
             thread_handle: None,
+            binary: false,
+            source: None,
+            auto_port: true,
+            queue: None,
         }
     }
 
+    /// Read `port` as the named source `name` / قراءة `port` كمصدر مسمى `name`
+    ///
+    /// The port is used as given (no auto-detection) and frames are tagged
+    /// with the name; they share the primary reader's recording under that
+    /// name, while the main view and port stay with the primary reader.
+    pub fn with_source(mut self, name: &str, port: &str) -> Self {
+        self.source = Some(name.to_string());
+        self.with_port(port)
+    }
+
+    /// Open `port` as given, e.g. the one chosen in the port picker
+    /// فتح `port` كما هو، مثل المنفذ المختار من نافذة الاختيار
+    pub fn with_port(mut self, port: &str) -> Self {
+        self.port_name = port.to_string();
+        self.auto_port = false;
+        self
+    }
+
+    /// Send frames through `queue`; the UI stores them once per tick
+    /// إرسال الإطارات عبر `queue`؛ الواجهة تحفظها مرة كل نبضة
+    pub fn with_queue(mut self, queue: FrameSender) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+
+    /// Read length-prefixed binary packets instead of text
+    /// قراءة حزم ثنائية مسبوقة بالطول بدلاً من النص
+    pub fn with_binary(mut self, binary: bool) -> Self {
+        self.binary = binary;
+        self
+    }
+
     /// Start the serial reader thread
     /// بدء خيط قارئ التسلسل
-    pub fn start(&mut self) -> Result<(), String> {
+    pub fn start(&mut self) -> Result<()> {
         // Check if already running
         if self.thread_handle.is_some() {
-            return Err("Serial reader already running".to_string());
+            return Err(CsiError::ReaderRunning);
         }
 
         // Reset stop flag
         self.stop_flag.store(false, Ordering::SeqCst);
 
-        // 🔍 Detect serial port on startup
-        let detected_port = auto_select_port().unwrap_or(self.port_name.clone());
-        self.port_name = detected_port.clone();
+        // 🔍 Detect serial port on startup (fixed ports are kept)
+        let mut skipped = Vec::new();
+        if self.auto_port {
+            if let Some((best, rest)) = ranked_ports().split_first() {
+                self.port_name = best.clone();
+                skipped = rest.to_vec();
+            }
+        }
 
-        let port_name = detected_port;
-        let baud_rate = self.baud_rate;
+        let port_name = self.port_name.clone();
+        // Rate chosen in the viewer (F8) / المعدل المختار في العارض
+        let baud_rate = self.state.lock()?.baud_rate;
+        let binary = self.binary;
+        let source = self.source.clone();
+        let queue = self.queue.clone();
         let state = Arc::clone(&self.state);
         let stop_flag = Arc::clone(&self.stop_flag);
 
         // 🔥 UPDATE AppState.port_name SO UI CAN DISPLAY REAL PORT
         {
-            let mut guard = state.lock().map_err(|e| e.to_string())?;
-            guard.port_name = port_name.clone();   // <-- IMPORTANT LINE
-            guard.status_message = format!("🔄 Connecting to {}...", port_name);
+            let mut guard = state.lock()?;
+            if source.is_none() {
+                guard.port_name = port_name.clone();   // <-- IMPORTANT LINE
+            }
+            set_receiver(&mut guard, source.as_deref(), ReceiverState::Connecting);
+            guard.status_message = format!("🔄 Connecting to {}… (press X to cancel)", port_name);
+            if !skipped.is_empty() {
+                guard.status_message.push_str(&format!(" - skipped {}", skipped.join(", ")));
+            }
         }
 
         // Spawn the reader thread
         let handle = thread::spawn(move || {
-            run_serial_reader(&port_name, baud_rate, &state, &stop_flag);
+            run_serial_reader(&port_name, baud_rate, binary, source.as_deref(), queue.as_ref(), &state, &stop_flag);
         });
 
         self.thread_handle = Some(handle);
@@ -136,16 +269,26 @@ impl SerialReader {
     pub fn stop(&mut self) {
         // Set stop flag / تعيين علامة الإيقاف
         self.stop_flag.store(true, Ordering::SeqCst);
+        let cancelled = self
+            .state
+            .lock()
+            .map(|g| receiver_of(&g, self.source.as_deref()).is_connecting())
+            .unwrap_or(false);
 
-        // Wait for thread to finish / انتظار انتهاء الخيط
+        // Wait for thread to finish; a pending open is abandoned within OPEN_POLL_MS
+        // انتظار انتهاء الخيط؛ الفتح المعلق يُترك خلال OPEN_POLL_MS
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
 
         // Update state / تحديث الحالة
         if let Ok(mut state_guard) = self.state.lock() {
-            state_guard.receiver_active = false;
-            state_guard.status_message = "⏹️ Serial reader stopped".to_string();
+            set_receiver(&mut state_guard, self.source.as_deref(), ReceiverState::Idle);
+            state_guard.status_message = if cancelled {
+                format!("⏹️ Connection to {} cancelled", self.port_name)
+            } else {
+                "⏹️ Serial reader stopped".to_string()
+            };
         }
     }
 }
@@ -165,79 +308,436 @@ impl Drop for SerialReader {
 fn run_serial_reader(
     port_name: &str,
     baud_rate: u32,
+    binary: bool,
+    source: Option<&str>,
+    queue: Option<&FrameSender>,
     state: &SharedState,
     stop_flag: &Arc<AtomicBool>,
     //
 ) {
-    // Try to open the serial port / محاولة فتح المنفذ التسلسلي
-    let port_result = serialport::new(port_name, baud_rate)
-        .timeout(Duration::from_millis(READ_TIMEOUT_MS))
-        .open();
+    // The port may come back under another name after a replug
+    // قد يعود المنفذ باسم آخر بعد إعادة التوصيل
+    let mut port_name = port_name.to_string();
 
-    let mut port = match port_result {
-        Ok(p) => {
+    // Set after a reconnect until the first bytes arrive / يُعين بعد إعادة الاتصال حتى وصول أول البايتات
+    let mut awaiting_data = false;
+
+    // Try to open the serial port; X cancels while it blocks
+    // محاولة فتح المنفذ التسلسلي؛ X يلغي أثناء الانتظار
+    let mut port = match open_cancellable(&port_name, baud_rate, stop_flag) {
+        None => return,
+        Some(Ok(p)) => {
             // Update state to show connected / تحديث الحالة لإظهار الاتصال
             if let Ok(mut state_guard) = state.lock() {
-                state_guard.receiver_active = true;
+                set_receiver(&mut state_guard, source, ReceiverState::Connected);
                 state_guard.status_message = format!("✅ Connected to {}", port_name);
             }
             p
         }
-        Err(e) => {
-            // Update state to show error / تحديث الحالة لإظهار الخطأ
-            if let Ok(mut state_guard) = state.lock() {
-                state_guard.receiver_active = false;
-                state_guard.status_message =
-                    format!("❌ Failed to open {}: {}", port_name, e);
+        Some(Err(e)) => {
+            // Board not there yet: keep trying / اللوحة غير موجودة بعد: متابعة المحاولة
+            let reason = e.to_string();
+            match reconnect(&mut port_name, baud_rate, source, state, stop_flag, &reason) {
+                Some(p) => {
+                    awaiting_data = true;
+                    p
+                }
+                None => return,
             }
-            return;
         }
     };
 
     // Create parser and CSV logger / إنشاء المحلل ومسجل CSV
-    let parser = CsiParser::new();
-    let mut csv_logger = CsvLogger::new_with_timestamp().ok();
+    let min_subcarriers = state.lock().map(|g| g.min_subcarriers).unwrap_or(DEFAULT_MIN_SUBCARRIERS);
+    let mut forced_format = state.lock().map(|g| g.forced_format).unwrap_or(None);
+    let mut parser = parser_for(forced_format, min_subcarriers);
+    let mut length_guard = ArrayLengthGuard::default();
+    // Named sources are not recorded yet / المصادر المسماة لا تُسجل بعد
+    if source.is_none() {
+        open_recording(state);
+    }
+
+    // Rejected blocks are kept only on request / الكتل المرفوضة تحفظ عند الطلب فقط
+    let mut failure_log = None;
+    if state.lock().map(|g| g.parse_failure_logging).unwrap_or(false) {
+        match ParseFailureLog::new_with_timestamp() {
+            Ok(log) => failure_log = Some(log),
+            Err(e) => {
+                if let Ok(mut state_guard) = state.lock() {
+                    state_guard.status_message = e.status_message();
+                }
+            }
+        }
+    }
 
     // Buffer for incoming data / مخزن مؤقت للبيانات الواردة
     let mut text_buffer = String::new();
+    let mut line_buffer = String::new();
+    let mut byte_buffer = Vec::new();
     let mut read_buffer = [0u8; 1024];
+    let tcp = tcp_address(&port_name).is_some();
+    let silence_timeouts = state.lock().map(|g| g.silence_timeouts).unwrap_or(0);
+    let mut link_watch = LinkWatch::new(silence_timeouts);
 
     // Main reading loop / حلقة القراءة الرئيسية
     while !stop_flag.load(Ordering::SeqCst) {
         // Read from serial port / القراءة من المنفذ التسلسلي
-        match port.read(&mut read_buffer) {
+        let read = match port.read(&mut read_buffer) {
+            // A TCP bridge reads 0 bytes once the far end closes / جسر TCP يقرأ 0 بايت عند إغلاق الطرف الآخر
+            Ok(0) if tcp => Err(io::Error::new(io::ErrorKind::ConnectionAborted, "connection closed")),
+            read => read,
+        };
+
+        // Data flows again after a reconnect / عودة تدفق البيانات بعد إعادة الاتصال
+        if awaiting_data && matches!(read, Ok(n) if n > 0) {
+            awaiting_data = false;
+            if let Ok(mut state_guard) = state.lock() {
+                set_receiver(&mut state_guard, source, ReceiverState::Connected);
+                state_guard.status_message = format!("✅ Connected to {}", port_name);
+            }
+        }
+
+        let (bytes_read, frames) = match read {
+            Ok(bytes_read) if bytes_read > 0 && binary => {
+                // Packed bytes must not go through UTF-8 / البايتات المضغوطة لا تمر عبر UTF-8
+                byte_buffer.extend_from_slice(&read_buffer[..bytes_read]);
+                (bytes_read, process_packets(&mut byte_buffer, min_subcarriers, source, queue, state))
+            }
             Ok(bytes_read) if bytes_read > 0 => {
                 // Convert to string and append / التحويل إلى نص والإضافة
                 let text = String::from_utf8_lossy(&read_buffer[..bytes_read]);
                 text_buffer.push_str(&text);
+                if source.is_none() {
+                    record_raw_lines(&mut line_buffer, &text, state);
+                }
+
+                // Follow a format override from the UI / متابعة الصيغة المفروضة من الواجهة
+                let wanted = state.lock().map(|g| g.forced_format).unwrap_or(forced_format);
+                if wanted != forced_format {
+                    forced_format = wanted;
+                    parser = parser_for(forced_format, min_subcarriers);
+                }
 
                 // Process complete CSI blocks / معالجة كتل CSI المكتملة
-                process_buffer(&mut text_buffer, &parser, state, &mut csv_logger);
+                let frames = process_buffer(&mut text_buffer, &parser, &mut length_guard, source, queue, state, &mut failure_log);
+                (bytes_read, frames)
             }
             Ok(_) => {
                 // No data, continue / لا توجد بيانات، متابعة
+                (0, 0)
             }
-            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                // Timeout is normal, continue / المهلة طبيعية، متابعة
+            Err(ref e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => {
+                // Timeout is normal; a last block sent without its newline is due now
+                // المهلة طبيعية؛ آخر كتلة أُرسلت بدون سطرها الجديد تُعالج الآن
+                let frames = if !binary && terminate_trailing_block(&mut text_buffer) {
+                    process_buffer(&mut text_buffer, &parser, &mut length_guard, source, queue, state, &mut failure_log)
+                } else {
+                    0
+                };
+                (0, frames)
             }
             Err(e) => {
-                // Error reading, update state / خطأ في القراءة، تحديث الحالة
-                if let Ok(mut state_guard) = state.lock() {
-                    state_guard.status_message = format!("⚠️ Read error: {}", e);
+                // Port lost (unplugged, reset, bridge closed): save what we have, then reopen
+                // فُقد المنفذ (فصل، إعادة تشغيل، إغلاق الجسر): حفظ ما لدينا ثم إعادة الفتح
+                if source.is_none() {
+                    flush_recording(state);
+                }
+                text_buffer.clear();
+                byte_buffer.clear();
+                line_buffer.clear();
+
+                let reason = read_error(&port_name, e).to_string();
+                match reconnect(&mut port_name, baud_rate, source, state, stop_flag, &reason) {
+                    Some(p) => {
+                        port = p;
+                        awaiting_data = true;
+                        link_watch = LinkWatch::new(silence_timeouts);
+                    }
+                    None => break,
                 }
-                break;
+                continue;
+            }
+        };
+
+        // Throughput of the primary link / إنتاجية الرابط الأساسي
+        if bytes_read > 0 && source.is_none() {
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.frame_rate.record_bytes(Instant::now(), bytes_read);
             }
         }
-    }
 
-    // Flush CSV logger before exiting / تفريغ مسجل CSV قبل الخروج
-    if let Some(ref mut logger) = csv_logger {
-        let _ = logger.flush();
+        // Silent link or unparsable bytes / رابط صامت أو بايتات غير قابلة للتحليل
+        if let Some(health) = link_watch.record(bytes_read, frames) {
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.status_message = health.status_message(&port_name, silence_timeouts);
+            }
+        }
     }
 
-    // Update state to show stopped / تحديث الحالة لإظهار التوقف
+    // Update state to show stopped; a failure keeps its Error state
+    // تحديث الحالة لإظهار التوقف؛ الفشل يبقي حالة الخطأ
     if let Ok(mut state_guard) = state.lock() {
-        state_guard.receiver_active = false;
+        // The session's recording ends with it / تسجيل الجلسة ينتهي معها
+        if source.is_none() {
+            state_guard.stop_recording();
+        }
+        if receiver_of(&state_guard, source) != ReceiverState::Error {
+            set_receiver(&mut state_guard, source, ReceiverState::Idle);
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Link Watch / مراقبة الرابط
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// What the serial link is delivering / ما يوصله الرابط التسلسلي
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkHealth {
+    /// Frames are parsed, or nothing looks wrong yet / الإطارات تُحلل أو لا شيء يبدو خاطئاً بعد
+    Flowing,
+    /// No bytes at all: dead link or halted board / لا بايتات إطلاقاً: رابط معطل أو لوحة متوقفة
+    Silent,
+    /// Bytes arrive but never parse as CSI: format mismatch / بايتات تصل دون أن تُحلل كـ CSI
+    NoFrames,
+}
+
+impl LinkHealth {
+    /// Status line for entering this state / سطر الحالة عند دخول هذه الحالة
+    fn status_message(self, port_name: &str, silence_timeouts: u32) -> String {
+        match self {
+            LinkHealth::Flowing => format!("✅ CSI frames flowing from {}", port_name),
+            LinkHealth::Silent => format!(
+                "⚠️ No bytes received from {} for {:.1}s - is the board running?",
+                port_name,
+                (silence_timeouts as u64 * READ_TIMEOUT_MS) as f64 / 1000.0
+            ),
+            LinkHealth::NoFrames => format!(
+                "⚠️ Bytes arriving from {} but no CSI frames parsed - wrong firmware or format?",
+                port_name
+            ),
+        }
+    }
+}
+
+/// Counts empty reads and bytes since the last frame
+/// عد القراءات الفارغة والبايتات منذ آخر إطار
+#[derive(Debug)]
+struct LinkWatch {
+    /// Empty reads in a row before Silent; 0 turns the watch off / القراءات الفارغة قبل الصمت
+    silence_timeouts: u32,
+
+    /// Empty reads in a row / القراءات الفارغة المتتالية
+    timeouts: u32,
+
+    /// Bytes since the last parsed frame / البايتات منذ آخر إطار محلل
+    bytes_without_frame: usize,
+
+    /// Last reported health / آخر حالة مُبلغ عنها
+    health: LinkHealth,
+}
+
+impl LinkWatch {
+    fn new(silence_timeouts: u32) -> Self {
+        Self { silence_timeouts, timeouts: 0, bytes_without_frame: 0, health: LinkHealth::Flowing }
+    }
+
+    /// Record one read (0 bytes for a timeout); the new health when it changed
+    /// تسجيل قراءة واحدة (0 بايت للمهلة)؛ الحالة الجديدة عند تغيرها
+    fn record(&mut self, bytes: usize, frames: usize) -> Option<LinkHealth> {
+        if self.silence_timeouts == 0 {
+            return None;
+        }
+        if bytes == 0 {
+            self.timeouts = self.timeouts.saturating_add(1);
+        } else {
+            self.timeouts = 0;
+            self.bytes_without_frame = if frames > 0 { 0 } else { self.bytes_without_frame + bytes };
+        }
+
+        let health = if self.timeouts >= self.silence_timeouts {
+            LinkHealth::Silent
+        } else if self.bytes_without_frame >= NO_FRAME_BYTES {
+            LinkHealth::NoFrames
+        } else {
+            LinkHealth::Flowing
+        };
+        (health != self.health).then(|| {
+            self.health = health;
+            health
+        })
+    }
+}
+
+/// Connection state shown for this reader / حالة الاتصال المعروضة لهذا القارئ
+fn receiver_of(state: &AppState, source: Option<&str>) -> ReceiverState {
+    match source {
+        Some(name) => state.sources.get(name).map(|s| s.receiver).unwrap_or_default(),
+        None => state.receiver,
+    }
+}
+
+/// Update the connection state shown for this reader
+/// تحديث حالة الاتصال المعروضة لهذا القارئ
+///
+/// The primary reader also restarts the detector warmup once connected.
+fn set_receiver(state: &mut AppState, source: Option<&str>, receiver: ReceiverState) {
+    match source {
+        Some(name) => state.sources.set_receiver(name, receiver),
+        None => {
+            if receiver == ReceiverState::Connected {
+                state.start_warmup(chrono::Utc::now().timestamp_millis());
+            }
+            state.receiver = receiver;
+        }
+    }
+}
+
+/// Parser for the current format override / محلل لوضع الصيغة الحالي
+fn parser_for(forced_format: Option<CsiFormat>, min_subcarriers: usize) -> CsiParser {
+    CsiParser::with_format(forced_format.unwrap_or_default()).with_min_subcarriers(min_subcarriers)
+}
+
+/// Open the port on a helper thread, giving up as soon as the stop flag is set
+/// فتح المنفذ في خيط مساعد والتخلي عنه فور تعيين علامة الإيقاف
+///
+/// `serialport::open` can block for seconds on a port held by another
+/// program, as can a TCP connect to an unreachable host. None means
+/// cancelled; the helper drops the link if it still opens.
+fn open_cancellable(port_name: &str, baud_rate: u32, stop_flag: &AtomicBool) -> Option<Result<Link>> {
+    let (tx, rx) = mpsc::channel();
+    let port_name = port_name.to_string();
+    thread::spawn(move || {
+        let _ = tx.send(open_link(&port_name, baud_rate));
+    });
+
+    loop {
+        if stop_flag.load(Ordering::SeqCst) {
+            return None;
+        }
+        match rx.recv_timeout(Duration::from_millis(OPEN_POLL_MS)) {
+            Ok(result) => return Some(result),
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+/// Byte stream of an open serial port or TCP bridge
+/// تدفق بايتات منفذ تسلسلي مفتوح أو جسر TCP
+type Link = Box<dyn Read + Send>;
+
+/// Port name prefix of a serial stream forwarded over TCP (e.g. by socat)
+/// بادئة اسم المنفذ لتدفق تسلسلي محول عبر TCP (مثلاً عبر socat)
+pub const TCP_SCHEME: &str = "tcp://";
+
+/// `host:port` of a `tcp://host:port` port name / عنوان `host:port` من اسم منفذ TCP
+fn tcp_address(port_name: &str) -> Option<&str> {
+    port_name.strip_prefix(TCP_SCHEME)
+}
+
+/// Open a serial port, or connect to a TCP bridge; both read with the same timeout
+/// فتح منفذ تسلسلي أو الاتصال بجسر TCP؛ كلاهما يقرأ بنفس المهلة
+fn open_link(port_name: &str, baud_rate: u32) -> Result<Link> {
+    let timeout = Duration::from_millis(READ_TIMEOUT_MS);
+    if let Some(addr) = tcp_address(port_name) {
+        let tcp_error = |source| CsiError::Tcp { addr: addr.to_string(), source };
+        let stream = TcpStream::connect(addr).map_err(tcp_error)?;
+        stream.set_read_timeout(Some(timeout)).map_err(tcp_error)?;
+        return Ok(Box::new(stream));
+    }
+    let port = serialport::new(port_name, baud_rate)
+        .timeout(timeout)
+        .open()
+        .map_err(|e| CsiError::from_serial(port_name, e))?;
+    Ok(Box::new(port))
+}
+
+/// Error for a read that failed on an open link / خطأ قراءة فشلت على رابط مفتوح
+fn read_error(port_name: &str, e: io::Error) -> CsiError {
+    match tcp_address(port_name) {
+        Some(addr) => CsiError::Tcp { addr: addr.to_string(), source: e },
+        None => CsiError::SerialIo(e),
+    }
+}
+
+/// Pause before reopen attempt `attempt` (1-based): doubles up to the cap
+/// المهلة قبل محاولة إعادة الفتح رقم `attempt`: تتضاعف حتى الحد الأقصى
+fn backoff_delay(attempt: u32) -> Duration {
+    let factor = 1u64 << attempt.saturating_sub(1).min(16);
+    Duration::from_millis((RECONNECT_INITIAL_DELAY_MS * factor).min(RECONNECT_MAX_DELAY_MS))
+}
+
+/// Keep reopening the port until it comes back; None when cancelled or
+/// auto-reconnect is off
+/// متابعة إعادة فتح المنفذ حتى يعود؛ لا شيء عند الإلغاء أو إيقاف إعادة الاتصال
+///
+/// Each attempt re-runs port detection, since a replugged board can get a
+/// new name; `port_name` and the UI follow it. Named sources, TCP bridges
+/// and a port the user chose keep their port.
+/// The receiver stays Reconnecting until data flows on the reopened port.
+fn reconnect(
+    port_name: &mut String,
+    baud_rate: u32,
+    source: Option<&str>,
+    state: &SharedState,
+    stop_flag: &AtomicBool,
+    reason: &str,
+) -> Option<Link> {
+    for attempt in 1.. {
+        let delay = backoff_delay(attempt);
+        let mut port_chosen = false;
+        if let Ok(mut state_guard) = state.lock() {
+            port_chosen = state_guard.port_chosen;
+            // Checked every attempt so F6 also ends a retry loop / يفحص كل محاولة ليوقف F6 حلقة الإعادة أيضاً
+            if !state_guard.auto_reconnect {
+                set_receiver(&mut state_guard, source, ReceiverState::Error);
+                state_guard.status_message = format!("❌ {} - auto-reconnect off, press S to retry", reason);
+                return None;
+            }
+            set_receiver(&mut state_guard, source, ReceiverState::Reconnecting);
+            state_guard.status_message = format!(
+                "🔁 Reconnecting (attempt {}) to {} in {:.1}s… {} (press X to cancel)",
+                attempt,
+                port_name,
+                delay.as_secs_f64(),
+                reason
+            );
+        }
+
+        // Sleep in short steps so X stays responsive / النوم بخطوات قصيرة ليبقى X سريع الاستجابة
+        let resume_at = Instant::now() + delay;
+        while Instant::now() < resume_at {
+            if stop_flag.load(Ordering::SeqCst) {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(OPEN_POLL_MS).min(resume_at - Instant::now()));
+        }
+
+        let candidate = reconnect_candidate(port_name, source, port_chosen);
+        if let Ok(port) = open_cancellable(&candidate, baud_rate, stop_flag)? {
+            *port_name = candidate;
+            if let Ok(mut state_guard) = state.lock() {
+                if source.is_none() {
+                    state_guard.port_name = port_name.clone();
+                }
+                state_guard.status_message = format!("🔁 Reopened {}, waiting for data…", port_name);
+            }
+            return Some(port);
+        }
+    }
+    None
+}
+
+/// Port to try on a reconnect attempt: re-detected for the primary serial
+/// reader unless the user picked the port (-p or the port picker)
+/// المنفذ لمحاولة إعادة الاتصال: يعاد اكتشافه للقارئ الأساسي ما لم يختر المستخدم المنفذ
+fn reconnect_candidate(port_name: &str, source: Option<&str>, port_chosen: bool) -> String {
+    match source {
+        None if !port_chosen && tcp_address(port_name).is_none() => {
+            auto_select_port().unwrap_or_else(|| port_name.to_string())
+        }
+        _ => port_name.to_string(),
     }
 }
 
@@ -245,73 +745,295 @@ fn run_serial_reader(
 // 🔹 Buffer Processing / معالجة المخزن المؤقت
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Process the text buffer to extract and parse CSI blocks
-/// معالجة المخزن المؤقت لاستخراج وتحليل كتل CSI
+/// Process the text buffer to extract and parse CSI blocks; returns the
+/// number of blocks parsed
+/// معالجة المخزن المؤقت لاستخراج وتحليل كتل CSI؛ تعيد عدد الكتل المحللة
 fn process_buffer(
     buffer: &mut String,
     parser: &CsiParser,
+    length_guard: &mut ArrayLengthGuard,
+    source: Option<&str>,
+    queue: Option<&FrameSender>,
     state: &SharedState,
-    csv_logger: &mut Option<CsvLogger>,
-) {
-    // Look for complete CSI blocks delimited by "mac:"
-    // البحث عن كتل CSI الكاملة المحددة بـ "mac:"
-    while let Some(start) = buffer.find("mac:") {
-        // Find the next "mac:" to delimit the block
-        // البحث عن "mac:" التالية لتحديد الكتلة
-        if let Some(end_rel) = buffer[start + 4..].find("mac:") {
-            let end = start + 4 + end_rel;
-
-            // Extract the complete block / استخراج الكتلة الكاملة
-            let block = buffer[start..end].to_string();
-
-            // Remove processed block from buffer / إزالة الكتلة المعالجة من المخزن
-            buffer.replace_range(start..end, "");
-
-            // Parse the block / تحليل الكتلة
-            if let Some(csi_data) = extract_csi_block(&block) {
-                if let Some(result) = parser.parse(csi_data) {
-                    // Create frame with current timestamp
-                    // إنشاء إطار بالطابع الزمني الحالي
-                    let timestamp = Utc::now().timestamp_millis();
-                    let frame = CsiFrame::new(
-                        timestamp,
-                        result.mags,
-                        result.pairs,
-                        result.format,
-                    );
-
-                    // Log to CSV if logger exists / تسجيل في CSV إذا وجد المسجل
-                    if let Some(ref mut logger) = csv_logger {
-                        let _ = logger.log_frame(&frame);
-                    }
+    failure_log: &mut Option<ParseFailureLog>,
+) -> usize {
+    let mut parsed = 0;
+    // Look for complete CSI blocks: "mac:" blocks or ESP-IDF "CSI_DATA" lines
+    // البحث عن كتل CSI الكاملة: كتل "mac:" أو أسطر "CSI_DATA"
+    // An incomplete block stays in the buffer until more data arrives
+    // الكتلة غير المكتملة تبقى في المخزن حتى وصول المزيد من البيانات
+    while let Some(range) = find_block(buffer) {
+        // Extract the complete block / استخراج الكتلة الكاملة
+        let block = buffer[range.clone()].to_string();
+
+        // Remove processed block from buffer / إزالة الكتلة المعالجة من المخزن
+        buffer.replace_range(range, "");
+
+        // Firmware log lines mixed into the block / أسطر سجل البرنامج الثابت المختلطة بالكتلة
+        let (block, _) = strip_log_lines(&block);
+
+        // Parse the block / تحليل الكتلة
+        let parse_started = Instant::now();
+        if let Some(ParsedBlock { result, meta, esp_timestamp_us, .. }) = parser.parse_with_metadata(&block) {
+            // Length jumped: log text we could not recognize got into the array
+            // قفزة في الطول: نص سجل لم نتعرف عليه دخل المصفوفة
+            if !length_guard.accept(result.value_count()) {
+                if let Ok(mut state_guard) = state.lock() {
+                    state_guard.contaminated_frames += 1;
+                }
+                continue;
+            }
+            parsed += 1;
+
+            // Counted when stored; firmware repeating its last block when idle is dropped there
+            // يُعد عند الحفظ؛ تكرار البرنامج الثابت لآخر كتلة عند الخمول يُحذف هناك
+            let frame = ReceivedFrame::new(result, meta, esp_timestamp_us, source, parse_started.elapsed());
+            deliver(frame.counted().deduped(), queue, state);
+        } else {
+            let failure = parser.diagnose(&block);
+
+            // Count rejections (tiny frames apart) so flaky wiring is visible
+            // عد الرفض (والإطارات الصغيرة منفصلة) حتى تظهر التوصيلات غير المستقرة
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.parse_stats.record_rejected(failure);
+                if failure == ParseFailure::TooFewSubcarriers {
+                    state_guard.tiny_frames_rejected += 1;
+                }
+            }
 
-                    // Push to state / إضافة للحالة
+            // Keep the rejected block for later study / حفظ الكتلة المرفوضة لدراستها لاحقاً
+            if let Some(log) = failure_log {
+                let timestamp = Utc::now().timestamp_millis();
+                if let Err(e) = log.log_failure(timestamp, &block, failure.reason()) {
+                    *failure_log = None;
                     if let Ok(mut state_guard) = state.lock() {
-                        let sc_count = frame.subcarrier_count();
-                        state_guard.push_frame(frame);
-                        state_guard.status_message = format!(
-                            "📥 Receiving CSI: {} subcarriers, {} frames",
-                            sc_count,
-                            state_guard.frame_count()
-                        );
+                        state_guard.status_message = e.status_message();
                     }
                 }
             }
-        } else {
-            // Incomplete block, wait for more data
-            // كتلة غير مكتملة، انتظار المزيد من البيانات
-            break;
         }
     }
 
     // Prevent buffer from growing too large / منع نمو المخزن بشكل كبير جداً
     if buffer.len() > 10_000 {
-        if let Some(last_mac) = buffer.rfind("mac:") {
-            buffer.replace_range(..last_mac, "");
+        if let Some(last_block) = last_block_start(buffer) {
+            buffer.replace_range(..last_block, "");
         } else {
             buffer.clear();
         }
     }
+    parsed
+}
+
+/// Open the shared CSV recording, unless recording is off or already open
+/// فتح تسجيل CSV المشترك ما لم يكن التسجيل معطلاً أو مفتوحاً مسبقاً
+///
+/// Resumes a recovered log if one was chosen at startup, otherwise starts a
+/// new recording with its detector settings alongside. Returns its path.
+pub(crate) fn open_recording(state: &SharedState) -> Option<PathBuf> {
+    let wanted = state.lock().map(|g| g.record_csv && g.csv_logger.is_none()).unwrap_or(false);
+    if !wanted {
+        return None;
+    }
+    match start_recording(state, None) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            if let Ok(mut state_guard) = state.lock() {
+                state_guard.status_message = e.status_message();
+            }
+            None
+        }
+    }
+}
+
+/// Open the shared CSV recording at `target` (timestamped name when None)
+/// فتح تسجيل CSV المشترك في `target` (اسم بالتوقيت عند None)
+///
+/// A recovered log chosen at startup is resumed instead. Returns its path.
+pub(crate) fn start_recording(state: &SharedState, target: Option<PathBuf>) -> Result<PathBuf> {
+    let resume_log = state.lock()?.resume_log.take();
+    let resuming = resume_log.is_some();
+    let mut logger = match (resume_log, target) {
+        (Some(path), _) => CsvLogger::resume(path)?,
+        (None, Some(path)) => CsvLogger::new(path)?,
+        (None, None) => CsvLogger::new_with_timestamp()?,
+    };
+
+    // A new recording keeps its detector settings alongside / التسجيل الجديد يحفظ إعدادات الكاشفات بجانبه
+    if !resuming {
+        write_config_sidecar(&logger, state);
+    }
+
+    // Enable integrity sidecar if requested / تفعيل ملف السلامة الجانبي إذا طُلب
+    let mut state_guard = state.lock()?;
+    if state_guard.integrity_logging {
+        logger.enable_integrity()?;
+    }
+    logger.set_rotate_bytes(state_guard.log_rotate_bytes);
+
+    let path = logger.file_path().to_path_buf();
+    state_guard.csv_logger = Some(CsvWriter::spawn(logger));
+    Ok(path)
+}
+
+/// Flush the shared CSV recording / تفريغ تسجيل CSV المشترك
+fn flush_recording(state: &SharedState) {
+    if let Ok(state_guard) = state.lock() {
+        if let Some(logger) = &state_guard.csv_logger {
+            logger.flush();
+        }
+    }
+}
+
+/// Save the active detector settings next to a new recording
+/// حفظ إعدادات الكاشفات الحالية بجانب تسجيل جديد
+fn write_config_sidecar(logger: &CsvLogger, state: &SharedState) {
+    let Ok(mut state_guard) = state.lock() else { return; };
+    if let Err(e) = DetectorConfig::from_state(&state_guard).save_sidecar(logger.file_path()) {
+        state_guard.status_message = e.status_message();
+    }
+}
+
+/// Close a trailing block that ends in `]` but never got its newline; true
+/// when one was closed
+/// إغلاق كتلة أخيرة تنتهي بـ `]` دون سطر جديد؛ true عند إغلاقها
+fn terminate_trailing_block(buffer: &mut String) -> bool {
+    let waiting = buffer.trim_end_matches([' ', '\t']).ends_with(']') && find_block(buffer).is_none();
+    if waiting {
+        buffer.push('\n');
+    }
+    waiting
+}
+
+/// Keep each complete raw line in the state's ring for report bundles
+/// حفظ كل سطر خام مكتمل في حلقة الحالة لحزم التقارير
+fn record_raw_lines(line_buffer: &mut String, text: &str, state: &SharedState) {
+    line_buffer.push_str(text);
+    let Some(end) = line_buffer.rfind('\n') else {
+        // A line this long is not text; don't let it grow / سطر بهذا الطول ليس نصاً
+        if line_buffer.len() > 10_000 {
+            line_buffer.clear();
+        }
+        return;
+    };
+    let complete: String = line_buffer.drain(..=end).collect();
+    if let Ok(mut state_guard) = state.lock() {
+        for line in complete.lines().filter(|l| !l.trim().is_empty()) {
+            state_guard.raw_lines.push(line.to_string());
+        }
+    }
+}
+
+/// Decode every complete binary packet in the byte buffer; returns the
+/// number of frames decoded
+/// فك كل حزمة ثنائية مكتملة في مخزن البايتات؛ تعيد عدد الإطارات المفكوكة
+///
+/// Frames under `min_subcarriers` are dropped and counted, as on the text path.
+fn process_packets(
+    buffer: &mut Vec<u8>,
+    min_subcarriers: usize,
+    source: Option<&str>,
+    queue: Option<&FrameSender>,
+    state: &SharedState,
+) -> usize {
+    let mut frames = 0;
+    loop {
+        let parse_started = Instant::now();
+        match take_packet(buffer) {
+            Some(Packet::Frame(result)) if result.mags.len() < min_subcarriers => {
+                if let Ok(mut state_guard) = state.lock() {
+                    state_guard.tiny_frames_rejected += 1;
+                }
+            }
+            Some(Packet::Frame(result)) => {
+                frames += 1;
+                let frame = ReceivedFrame::new(result, CsiMeta::default(), None, source, parse_started.elapsed());
+                deliver(frame, queue, state)
+            }
+            Some(Packet::Corrupt) => {
+                if let Ok(mut state_guard) = state.lock() {
+                    state_guard.corrupt_packets += 1;
+                }
+            }
+            None => break,
+        }
+    }
+    frames
+}
+
+/// Hand a received frame to the UI's queue, or store it right away
+/// تسليم الإطار المستلم لطابور الواجهة أو حفظه مباشرة
+pub(crate) fn deliver(frame: ReceivedFrame, queue: Option<&FrameSender>, state: &SharedState) {
+    // A full queue counts the drop itself / الطابور الممتلئ يعد الإطار المحذوف بنفسه
+    if let Some(queue) = queue {
+        queue.send(frame);
+        return;
+    }
+    let lock_started = Instant::now();
+    if let Ok(mut state_guard) = state.lock() {
+        store_frame(&mut state_guard, frame, lock_started.elapsed());
+    }
+}
+
+/// Turn a received frame into a state frame: MAC filter, CSV log, push to state
+/// تحويل الإطار المستلم إلى إطار في الحالة: مرشح MAC، سجل CSV، إضافة للحالة
+///
+/// `waited` is the lock wait, or the time spent in the queue.
+pub(crate) fn store_frame(state_guard: &mut AppState, received: ReceivedFrame, waited: Duration) {
+    let ReceivedFrame { result, meta, esp_timestamp_us, source, parse_time, host_ms, counted, dedupe, .. } = received;
+    if counted {
+        state_guard.parse_stats.record_parsed();
+    }
+    if dedupe && state_guard.is_duplicate_frame(result.mac.as_deref(), &result.mags) {
+        return;
+    }
+
+    let now = Instant::now();
+    state_guard.latency.record(Stage::Parse, parse_time, now);
+    state_guard.latency.record(Stage::LockWait, waited, now);
+
+    // Host receive time; the ESP timer replaces it when the block carries one.
+    // The ESP clock is anchored on the primary reader only.
+    // وقت الاستقبال على المضيف؛ يحل محله مؤقت ESP إن حملته الكتلة (للقارئ الأساسي فقط)
+    let timestamp = match source {
+        Some(_) => host_ms,
+        None => state_guard.esp_clock.frame_time(esp_timestamp_us, host_ms),
+    };
+    if !state_guard.accept_mac(result.mac.as_deref(), result.rssi, timestamp) {
+        return;
+    }
+
+    let mut frame = CsiFrame::with_phases(
+        timestamp,
+        result.mags,
+        result.pairs,
+        result.phases,
+        result.format,
+        result.rssi,
+    )
+    .with_mac(result.mac)
+    .with_meta(meta)
+    .with_source(source);
+
+    // Log the frame as received (every source, by name), calibrate it, then push to state
+    // تسجيل الإطار كما استُلم (كل المصادر بأسمائها) ثم معايرته ثم إضافته للحالة
+    if let Some(logger) = &state_guard.csv_logger {
+        logger.log_frame(frame.clone());
+    }
+    if let Some(calibration) = &state_guard.calibration {
+        calibration.apply(&mut frame);
+    }
+    let sc_count = frame.subcarrier_count();
+    let sc_change = state_guard.last_sc_change;
+    state_guard.push_frame(frame);
+    // A count change keeps its warning / تغير عدد الناقلات يبقي تحذيره
+    if state_guard.last_sc_change == sc_change {
+        state_guard.status_message = format!(
+            "📥 Receiving CSI: {} subcarriers, {} frames",
+            sc_count,
+            state_guard.frame_count()
+        );
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -321,11 +1043,463 @@ fn process_buffer(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::create_shared_state;
+    use csi_tui::parser::ParseStats;
+    use csi_tui::state::{create_shared_state, DEFAULT_BAUD_RATE};
+
+    #[test]
+    fn test_recording_writes_config_sidecar() {
+        let path = std::env::temp_dir().join(format!("csi_reader_sidecar_{}.csv", std::process::id()));
+        let state = create_shared_state();
+        state.lock().unwrap().detector_windows.motion = 7;
+
+        let logger = CsvLogger::new(path.clone()).unwrap();
+        write_config_sidecar(&logger, &state);
+        drop(logger);
+
+        let loaded = DetectorConfig::load_sidecar(&path).unwrap().unwrap();
+        assert_eq!(loaded, DetectorConfig::from_state(&state.lock().unwrap()));
+        assert_eq!(loaded.motion_window, 7);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(csi_tui::detector_config::sidecar_path(&path));
+    }
+
+    #[test]
+    fn test_trailing_block_not_held_back() {
+        let state = create_shared_state();
+        let parser = CsiParser::new();
+        let mut guard = ArrayLengthGuard::default();
+
+        // Both blocks of the chunk are stored, a partial third is kept
+        // تُحفظ كتلتا الدفعة وتبقى الثالثة الجزئية
+        let mut buffer = "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]\n\
+                          mac:AA:BB:CC:DD:EE:02 csi_data:[11,-5,20,-10]\r\n\
+                          mac:AA:BB:CC:DD:EE:03 csi_data:[12,-5"
+            .to_string();
+        assert_eq!(process_buffer(&mut buffer, &parser, &mut guard, None, None, &state, &mut None), 2);
+        assert_eq!(buffer, "mac:AA:BB:CC:DD:EE:03 csi_data:[12,-5");
+        assert!(!terminate_trailing_block(&mut buffer));
+
+        // Rest arrives without a newline; the read timeout closes it
+        // يصل الباقي بدون سطر جديد؛ مهلة القراءة تغلقه
+        buffer.push_str(",20,-10]");
+        assert_eq!(process_buffer(&mut buffer, &parser, &mut guard, None, None, &state, &mut None), 0);
+        assert!(terminate_trailing_block(&mut buffer));
+        assert_eq!(process_buffer(&mut buffer, &parser, &mut guard, None, None, &state, &mut None), 1);
+        assert!(buffer.is_empty());
+        assert!(!terminate_trailing_block(&mut buffer));
+
+        let s = state.lock().unwrap();
+        assert_eq!(s.frames.len(), 3);
+        assert_eq!(s.frames[2].mac.as_deref(), Some("AA:BB:CC:DD:EE:03"));
+    }
+
+    #[test]
+    fn test_frames_go_to_shared_recording() {
+        let path = std::env::temp_dir().join(format!("csi_reader_shared_{}.csv", std::process::id()));
+        let state = create_shared_state();
+        state.lock().unwrap().record_csv = false;
+        assert!(open_recording(&state).is_none());
+        assert_eq!(start_recording(&state, Some(path.clone())).unwrap(), path);
+
+        let mut buffer = "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]\nmac:".to_string();
+        process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+        // Stopping closes the file; later frames are not written / الإيقاف يغلق الملف ولا تُكتب الإطارات اللاحقة
+        assert_eq!(state.lock().unwrap().stop_recording(), Some(path.clone()));
+        let mut buffer = "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]\nmac:".to_string();
+        process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+        assert!(state.lock().unwrap().csv_logger.is_none());
+        assert_eq!(state.lock().unwrap().frames.len(), 2);
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written.lines().count(), 2);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(csi_tui::csv_logger::open_marker_for(&path));
+    }
+
+    #[test]
+    fn test_port_descriptions() {
+        let usb = |product: Option<&str>, manufacturer: Option<&str>| {
+            SerialPortType::UsbPort(serialport::UsbPortInfo {
+                vid: 0x303a,
+                pid: 0x1001,
+                serial_number: None,
+                manufacturer: manufacturer.map(str::to_string),
+                product: product.map(str::to_string),
+            })
+        };
+        assert_eq!(port_description(&usb(Some("USB JTAG/serial"), Some("Espressif"))), "USB JTAG/serial - Espressif (303a:1001)");
+        assert_eq!(port_description(&usb(None, None)), "USB 303a:1001");
+        assert_eq!(port_description(&SerialPortType::PciPort), "PCI");
+    }
+
+    #[test]
+    fn test_esp_bridges_ranked_first() {
+        let port = |name: &str, port_type: SerialPortType| SerialPortInfo { port_name: name.to_string(), port_type };
+        let usb = |name: &str, vid: u16| {
+            port(
+                name,
+                SerialPortType::UsbPort(serialport::UsbPortInfo {
+                    vid,
+                    pid: 0x0001,
+                    serial_number: None,
+                    manufacturer: None,
+                    product: None,
+                }),
+            )
+        };
+        let ports = [
+            port("/dev/ttyS0", SerialPortType::PciPort),
+            usb("/dev/ttyACM0", 0x2341), // Arduino
+            usb("/dev/ttyUSB0", 0x1A86), // CH340
+            port("/dev/rfcomm0", SerialPortType::BluetoothPort),
+            usb("/dev/ttyACM1", 0x303A), // Espressif native USB
+            usb("/dev/ttyUSB1", 0x10C4), // CP210x
+        ];
+        assert_eq!(rank_ports(&ports), vec!["/dev/ttyUSB0", "/dev/ttyACM1", "/dev/ttyUSB1", "/dev/ttyACM0"]);
+
+        // No known bridge: first USB port, as before / لا جسر معروف: أول منفذ USB كما كان
+        assert_eq!(rank_ports(&ports[..2]), vec!["/dev/ttyACM0"]);
+        assert!(rank_ports(&ports[..1]).is_empty());
+    }
+
+    #[test]
+    fn test_tcp_bridge_reads_and_reconnects() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = format!("{}{}", TCP_SCHEME, listener.local_addr().unwrap());
+        let state = create_shared_state();
+        let mut reader = SerialReader::new(state.clone()).with_port(&port);
+        reader.start().unwrap();
+
+        let wait_for = |done: &dyn Fn(&AppState) -> bool| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !done(&state.lock().unwrap()) {
+                assert!(Instant::now() < deadline, "{}", state.lock().unwrap().status_message);
+                thread::sleep(Duration::from_millis(10));
+            }
+        };
+        let array = vec!["10,-5"; 16].join(",");
+        let send = |block: &str| {
+            use std::io::Write;
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(block.as_bytes()).unwrap();
+        };
+
+        // Same text stream as serial; closing the bridge counts as a lost port
+        // نفس التدفق النصي؛ إغلاق الجسر يُعد فقداناً للمنفذ
+        send(&format!("mac:AA:BB:CC:DD:EE:01 csi_data:[{0}]\nmac:AA:BB:CC:DD:EE:02 csi_data:[{0}]\n", array));
+        wait_for(&|s| s.frames.len() == 2 && s.receiver == ReceiverState::Reconnecting);
+        assert!(state.lock().unwrap().status_message.contains(&port));
+
+        // The bridge comes back on the same address / يعود الجسر على نفس العنوان
+        send(&format!("mac:AA:BB:CC:DD:EE:03 csi_data:[{}]\n", array));
+        wait_for(&|s| s.frames.len() == 3);
+        reader.stop();
+        assert_eq!(state.lock().unwrap().port_name, port);
+    }
 
     #[test]
     fn test_serial_reader_creation() {
         let state = create_shared_state();
         let _reader = SerialReader::new(state);
     }
+
+    #[test]
+    fn test_cancelled_open_returns_promptly() {
+        // Stop already requested: the open is abandoned, not waited for
+        // الإيقاف مطلوب مسبقاً: يُترك الفتح بدل انتظاره
+        let stop = AtomicBool::new(true);
+        let started = std::time::Instant::now();
+        assert!(open_cancellable("/dev/csi-tui-missing", DEFAULT_BAUD_RATE, &stop).is_none());
+        assert!(started.elapsed() < Duration::from_millis(OPEN_POLL_MS * 4));
+
+        // Stopping a reader that is still connecting reports a cancel
+        // إيقاف قارئ ما زال يتصل يُبلغ عن الإلغاء
+        let state = create_shared_state();
+        state.lock().unwrap().receiver = ReceiverState::Connecting;
+        let mut reader = SerialReader::new(state.clone());
+        reader.stop();
+        let guard = state.lock().unwrap();
+        assert_eq!(guard.receiver, ReceiverState::Idle);
+        assert!(guard.status_message.contains("cancelled"));
+    }
+
+    #[test]
+    fn test_reconnect_backoff() {
+        let delays: Vec<u64> = (1..=7).map(|n| backoff_delay(n).as_millis() as u64).collect();
+        assert_eq!(delays, vec![250, 500, 1_000, 2_000, 4_000, 5_000, 5_000]);
+        assert_eq!(backoff_delay(u32::MAX), Duration::from_millis(RECONNECT_MAX_DELAY_MS));
+
+        // Cancelled while waiting: gives up within one poll step
+        // الإلغاء أثناء الانتظار: يتوقف خلال خطوة فحص واحدة
+        let state = create_shared_state();
+        let mut port_name = "/dev/csi-tui-missing".to_string();
+        let started = std::time::Instant::now();
+        let stop = AtomicBool::new(true);
+        assert!(reconnect(&mut port_name, DEFAULT_BAUD_RATE, None, &state, &stop, "unplugged").is_none());
+        assert!(started.elapsed() < Duration::from_millis(OPEN_POLL_MS * 4));
+        let guard = state.lock().unwrap();
+        assert_eq!(guard.receiver, ReceiverState::Reconnecting);
+        assert!(guard.status_message.contains("unplugged"));
+        assert!(guard.status_message.starts_with("🔁 Reconnecting (attempt 1)"));
+    }
+
+    #[test]
+    fn test_reconnect_keeps_chosen_port() {
+        // A chosen port, a named source or a TCP bridge is never swapped
+        // المنفذ المختار أو المصدر المسمى أو جسر TCP لا يُستبدل أبداً
+        let port = "/dev/csi-tui-chosen";
+        assert_eq!(reconnect_candidate(port, None, true), port);
+        assert_eq!(reconnect_candidate(port, Some("left"), false), port);
+        assert_eq!(reconnect_candidate("tcp://127.0.0.1:3333", None, false), "tcp://127.0.0.1:3333");
+
+        // Otherwise detection runs again / وإلا يعاد الاكتشاف
+        let detected = auto_select_port().unwrap_or_else(|| port.to_string());
+        assert_eq!(reconnect_candidate(port, None, false), detected);
+    }
+
+    #[test]
+    fn test_link_watch_transitions() {
+        let mut watch = LinkWatch::new(3);
+
+        // Timeouts: silent on the third in a row / المهل: صمت عند الثالثة على التوالي
+        assert_eq!(watch.record(0, 0), None);
+        assert_eq!(watch.record(0, 0), None);
+        assert_eq!(watch.record(0, 0), Some(LinkHealth::Silent));
+        assert_eq!(watch.record(0, 0), None);
+
+        // Text that never parses: bytes but no frames / نص لا يُحلل: بايتات بلا إطارات
+        assert_eq!(watch.record(1_000, 0), Some(LinkHealth::Flowing));
+        for _ in 0..3 {
+            assert_eq!(watch.record(1_000, 0), None);
+        }
+        assert_eq!(watch.record(1_000, 0), Some(LinkHealth::NoFrames));
+
+        // A quiet moment keeps the mismatch; a frame clears it
+        // لحظة هدوء تبقي عدم التطابق؛ الإطار يزيله
+        assert_eq!(watch.record(0, 0), None);
+        assert_eq!(watch.record(600, 1), Some(LinkHealth::Flowing));
+
+        // Threshold 0 turns the watch off / العتبة 0 تعطل المراقبة
+        let mut off = LinkWatch::new(0);
+        assert!((0..100).all(|_| off.record(0, 0).is_none()));
+    }
+
+    #[test]
+    fn test_reconnect_disabled() {
+        let state = create_shared_state();
+        state.lock().unwrap().auto_reconnect = false;
+        let mut port_name = "/dev/csi-tui-missing".to_string();
+        let stop = AtomicBool::new(false);
+
+        // Gives up at once, without waiting out a backoff / يتوقف فوراً دون انتظار
+        let started = std::time::Instant::now();
+        assert!(reconnect(&mut port_name, DEFAULT_BAUD_RATE, None, &state, &stop, "unplugged").is_none());
+        assert!(started.elapsed() < backoff_delay(1));
+        let guard = state.lock().unwrap();
+        assert_eq!(guard.receiver, ReceiverState::Error);
+        assert!(guard.status_message.contains("auto-reconnect off"));
+    }
+
+    #[test]
+    fn test_rejected_block_goes_to_failure_log() {
+        let path = std::path::PathBuf::from("test_serial_failures.log");
+        let state = create_shared_state();
+        let mut failure_log = Some(ParseFailureLog::new(path.clone()).unwrap());
+        let mut buffer = String::from(
+            "mac:AA:BB:CC:DD:EE:01 rssi:-50 csi_data:[oops]\n\
+             mac:AA:BB:CC:DD:EE:02 rssi:-51 csi_data:[10,-5,20,-10]\n\
+             mac:",
+        );
+
+        process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut failure_log);
+
+        // The good block became a frame, the bad one was logged
+        // الكتلة السليمة أصبحت إطاراً، والتالفة سُجلت
+        assert_eq!(state.lock().unwrap().frame_count(), 1);
+        assert_eq!(failure_log.as_ref().unwrap().entries(), 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\tno values in CSI array\tmac:AA:BB:CC:DD:EE:01 rssi:-50 csi_data:[oops]\\n"));
+
+        drop(failure_log);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_esp_idf_and_mac_blocks_share_a_session() {
+        let state = create_shared_state();
+        let mut buffer = String::from(
+            "CSI_DATA,0,1a:2b:3c:4d:5e:6f,-52,11,1,6,1,0,1,0,0,0,0,-97,0,11,2,1702563,0,68,0,8,1,\"[87 -96 6 0]\"\n\
+             mac:AA:BB:CC:DD:EE:02 rssi:-51 csi_data:[10,-5,20,-10]\n\
+             CSI_DATA,1,1a:2b:3c:4d:5e:6f,-53,11,1,6,1,0,1,0,0,0,0,-97,0,11,2,1702600,0,68,0,8,1,\"[80 -90",
+        );
+
+        process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+        // Two frames; the unfinished line waits for more data
+        // إطاران؛ السطر غير المكتمل ينتظر المزيد من البيانات
+        let state_guard = state.lock().unwrap();
+        assert_eq!(state_guard.frame_count(), 2);
+        assert_eq!(state_guard.frames[0].rssi, Some(-52));
+        assert_eq!(state_guard.frames[0].mac.as_deref(), Some("1a:2b:3c:4d:5e:6f"));
+        assert_eq!(state_guard.frames[1].mac.as_deref(), Some("AA:BB:CC:DD:EE:02"));
+        assert!(buffer.starts_with("CSI_DATA,1,"));
+    }
+
+    #[test]
+    fn test_esp_timestamps_set_frame_times() {
+        // Both blocks arrive in one read / الكتلتان تصلان في قراءة واحدة
+        let state = create_shared_state();
+        let mut buffer = String::new();
+        for (us, first) in [(4_000_000, 87), (4_010_000, 90)] {
+            buffer.push_str(&format!(
+                "CSI_DATA,1,1a:2b:3c:4d:5e:6f,-38,11,1,6,1,0,1,0,0,0,0,-97,0,11,2,{},0,68,0,8,1,\"[{},-96,6,0,-12,33,40,-21]\"\n",
+                us, first
+            ));
+        }
+        process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+        let guard = state.lock().unwrap();
+        assert_eq!(guard.frames.len(), 2);
+        assert_eq!(guard.frames[1].timestamp - guard.frames[0].timestamp, 10);
+    }
+
+    #[test]
+    fn test_tiny_blocks_are_counted() {
+        let state = create_shared_state();
+        let parser = CsiParser::new().with_min_subcarriers(DEFAULT_MIN_SUBCARRIERS);
+        let mut buffer = format!(
+            "mac:AA:BB:CC:DD:EE:01 csi_data:[5]\n\
+             mac:AA:BB:CC:DD:EE:01 csi_data:[]\n\
+             mac:AA:BB:CC:DD:EE:01 csi_data:[{}]\n\
+             mac:",
+            ["10,-5"; 8].join(","),
+        );
+
+        process_buffer(&mut buffer, &parser, &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+        // Only "[5]" counts; "[]" has no values at all / فقط [5] تُحسب
+        let state_guard = state.lock().unwrap();
+        assert_eq!(state_guard.frame_count(), 1);
+        assert_eq!(state_guard.max_sc, 8);
+        assert_eq!(state_guard.tiny_frames_rejected, 1);
+    }
+
+    #[test]
+    fn test_interleaved_log_lines() {
+        let state = create_shared_state();
+        let array = ["10,-5"; 32].join(",");
+        let (head, tail) = array.split_at(40);
+        let mut buffer = String::new();
+        for i in 0..6 {
+            buffer.push_str(&format!("I ({}) wifi: station: 24:0a:c4 join, AID=1, bgn, 40U\n", i * 1000));
+            buffer.push_str(&format!("mac:AA:BB:CC:DD:EE:01 rssi:-50\ncsi_data:[{}]\n", array));
+        }
+        // Log line in the middle of the array / سطر سجل وسط المصفوفة
+        buffer.push_str(&format!("mac:AA:BB:CC:DD:EE:01 csi_data:[{}W (7000) wifi: bcn_timeout 1 2 3\n{}]\n", head, tail));
+        // Unrecognizable text inside the array: caught by its length / نص غير معروف داخل المصفوفة يكشفه الطول
+        buffer.push_str(&format!("mac:AA:BB:CC:DD:EE:01 csi_data:[{}, 1 2 3 4 5 6 {}]\n", head, tail));
+        buffer.push_str("mac:");
+
+        process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+        let state_guard = state.lock().unwrap();
+        assert_eq!(state_guard.frame_count(), 7);
+        assert!(state_guard.frames.iter().all(|f| f.subcarrier_count() == 32));
+        assert_eq!(state_guard.contaminated_frames, 1);
+        assert_eq!(state_guard.parse_stats.rejected, 0);
+    }
+
+    #[test]
+    fn test_block_counters() {
+        let state = create_shared_state();
+        let parser = CsiParser::new().with_min_subcarriers(2);
+        let mut buffer = String::from(
+            "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]\n\
+             mac:AA:BB:CC:DD:EE:01 csi_data:garbage\n\
+             mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]\n\
+             mac:AA:BB:CC:DD:EE:01 csi_data:[oops]\n\
+             mac:AA:BB:CC:DD:EE:01 csi_data:[3,4]\n\
+             mac:",
+        );
+
+        process_buffer(&mut buffer, &parser, &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+        let mut state_guard = state.lock().unwrap();
+        let stats = state_guard.parse_stats;
+        assert_eq!((stats.seen, stats.parsed, stats.rejected), (5, 2, 3));
+        assert_eq!(stats.last_failure, Some(ParseFailure::TooFewSubcarriers));
+        assert_eq!(state_guard.frame_count(), 2);
+
+        // Reset from the UI / التصفير من الواجهة
+        state_guard.reset_parse_stats();
+        assert_eq!(state_guard.parse_stats, ParseStats::default());
+        assert_eq!(state_guard.tiny_frames_rejected, 0);
+    }
+
+    #[test]
+    fn test_duplicate_frames_dropped_when_enabled() {
+        let blocks = [
+            "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]",
+            "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]",
+            "mac:AA:BB:CC:DD:EE:01 csi_data:[10,-5,20,-10]",
+            "mac:AA:BB:CC:DD:EE:01 csi_data:[11,-5,20,-10]",
+            "mac:AA:BB:CC:DD:EE:02 csi_data:[11,-5,20,-10]",
+            "mac:AA:BB:CC:DD:EE:02 csi_data:[11,-5,20,-10]",
+        ];
+        let stream = format!("{}\nmac:", blocks.join("\n"));
+
+        for (enabled, stored, dropped) in [(false, 6, 0), (true, 3, 3)] {
+            let state = create_shared_state();
+            state.lock().unwrap().drop_duplicate_frames = enabled;
+            let mut buffer = stream.clone();
+            process_buffer(&mut buffer, &CsiParser::new(), &mut ArrayLengthGuard::default(), None, None, &state, &mut None);
+
+            // Only the changed frames and the new sender are kept
+            // تبقى الإطارات المتغيرة والمرسل الجديد فقط
+            let state_guard = state.lock().unwrap();
+            assert_eq!(state_guard.frame_count(), stored);
+            assert_eq!(state_guard.duplicates_dropped, dropped);
+        }
+    }
+
+    #[test]
+    fn test_binary_packets_become_frames() {
+        let state = create_shared_state();
+        // Good packet, bad length, good packet, half a packet
+        // حزمة سليمة، طول خاطئ، حزمة سليمة، نصف حزمة
+        let mut buffer = vec![0xC5, 0x1A, 0x04, 0x00, 3, 4, 0xFA, 8];
+        buffer.extend([0xC5, 0x1A, 0x05, 0x00]);
+        buffer.extend([0xC5, 0x1A, 0x02, 0x00, 0x80, 0x7F]);
+        buffer.extend([0xC5, 0x1A, 0x04, 0x00, 1]);
+
+        process_packets(&mut buffer, 1, None, None, &state);
+
+        let state_guard = state.lock().unwrap();
+        assert_eq!(state_guard.frame_count(), 2);
+        assert_eq!(state_guard.frames[0].pairs, vec![(3, 4), (-6, 8)]);
+        assert_eq!(state_guard.frames[0].phases, csi_tui::parser::phases_of(&[(3, 4), (-6, 8)]));
+        assert_eq!(state_guard.frames[1].pairs, vec![(-128, 127)]);
+        assert_eq!(state_guard.corrupt_packets, 1);
+        assert_eq!(buffer.len(), 5);
+    }
+
+    #[test]
+    fn test_tiny_binary_packets_are_counted() {
+        // Two subcarriers, then one: the second is under the minimum
+        // ناقلان ثم ناقل واحد: الثاني أقل من الحد الأدنى
+        let state = create_shared_state();
+        let mut buffer = vec![0xC5, 0x1A, 0x04, 0x00, 3, 4, 0xFA, 8];
+        buffer.extend([0xC5, 0x1A, 0x02, 0x00, 0x80, 0x7F]);
+
+        assert_eq!(process_packets(&mut buffer, 2, None, None, &state), 1);
+
+        let state_guard = state.lock().unwrap();
+        assert_eq!(state_guard.frame_count(), 1);
+        assert_eq!(state_guard.frames[0].pairs, vec![(3, 4), (-6, 8)]);
+        assert_eq!(state_guard.tiny_frames_rejected, 1);
+        assert!(buffer.is_empty());
+    }
 }
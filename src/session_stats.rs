@@ -0,0 +1,102 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 session_stats.rs - Session Statistics
+// ═══════════════════════════════════════════════════════════════════════════════
+// Cumulative counters for the current measurement period.
+// Features:
+// - Uptime since the session (or the last reset) started
+// - Frames received and detection events per detector
+// - Peak detector values
+// - Reset on demand without touching frames or detection state
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::time::{Duration, Instant};
+
+use crate::state::DetectionResults;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Session Statistics / إحصائيات الجلسة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Counters since the session started or was last reset
+/// العدادات منذ بدء الجلسة أو آخر تصفير
+#[derive(Debug, Clone)]
+pub struct SessionStats {
+    /// Start of the measurement period / بداية فترة القياس
+    started: Instant,
+
+    /// Frames received / الإطارات المستلمة
+    pub frames: u64,
+
+    /// Rising edges per detector / عدد مرات بدء الكشف لكل كاشف
+    pub motion_events: u64,
+    pub presence_events: u64,
+    pub door_events: u64,
+
+    /// Highest detector values seen / أعلى قيم الكاشفات
+    pub peak_motion: f64,
+    pub peak_presence: f64,
+    pub peak_door: f64,
+}
+
+impl SessionStats {
+    /// Start a measurement period at `now` / بدء فترة قياس عند `now`
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            frames: 0,
+            motion_events: 0,
+            presence_events: 0,
+            door_events: 0,
+            peak_motion: 0.0,
+            peak_presence: 0.0,
+            peak_door: 0.0,
+        }
+    }
+
+    /// Time since the period started / الوقت منذ بدء الفترة
+    pub fn uptime(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.started)
+    }
+
+    /// Detection events across all detectors / أحداث الكشف لجميع الكاشفات
+    pub fn total_events(&self) -> u64 {
+        self.motion_events + self.presence_events + self.door_events
+    }
+
+    /// Count rising edges and raise the peaks / عد بدايات الكشف ورفع القمم
+    pub fn record_detections(&mut self, previous: &DetectionResults, current: &DetectionResults) {
+        self.motion_events += u64::from(current.motion_detected && !previous.motion_detected);
+        self.presence_events += u64::from(current.human_present && !previous.human_present);
+        self.door_events += u64::from(current.door_open && !previous.door_open);
+        self.peak_motion = self.peak_motion.max(current.motion_value);
+        self.peak_presence = self.peak_presence.max(current.presence_value);
+        self.peak_door = self.peak_door.max(current.door_value);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_and_peaks() {
+        let start = Instant::now();
+        let mut stats = SessionStats::new(start);
+        let quiet = DetectionResults::default();
+        let moving = DetectionResults { motion_detected: true, motion_value: 42.0, ..DetectionResults::default() };
+
+        stats.record_detections(&quiet, &moving);
+        stats.record_detections(&moving, &moving);
+        stats.record_detections(&moving, &quiet);
+        stats.record_detections(&quiet, &DetectionResults { door_open: true, door_value: 7.0, ..quiet.clone() });
+
+        assert_eq!((stats.motion_events, stats.presence_events, stats.door_events), (1, 0, 1));
+        assert_eq!(stats.total_events(), 2);
+        assert_eq!((stats.peak_motion, stats.peak_door), (42.0, 7.0));
+        assert_eq!(stats.uptime(start + Duration::from_secs(90)), Duration::from_secs(90));
+    }
+}
@@ -0,0 +1,206 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 sources.rs - Named CSI Sources
+// ═══════════════════════════════════════════════════════════════════════════════
+// Extra receivers (one ESP per room) whose frames are tagged with a source name.
+// Features:
+// - Frames routed to their source by the tag the reader puts on them
+// - Recent frames, connection state and detections kept per source
+// - Detectors run per source with the shared windows and aggregation
+// - The untagged primary reader keeps the main view to itself
+// - Sources named in a recording are added when it is played back
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+
+use crate::detectors::{quick_detect_with, Aggregation, DetectorWindows};
+use crate::state::{CsiFrame, DetectionResults, ReceiverState};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Frames kept per source, enough for every detector window
+/// الإطارات المحفوظة لكل مصدر، تكفي لكل نوافذ الكاشفات
+pub const SOURCE_FRAME_CAPACITY: usize = 500;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Source State / حالة المصدر
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One named source and its latest detections / مصدر مسمى وآخر نتائج كشفه
+#[derive(Debug, Clone)]
+pub struct SourceState {
+    /// Name shown in the summary rows (e.g. "kitchen") / الاسم المعروض في صفوف الملخص
+    pub name: String,
+
+    /// Port the source reads from / المنفذ الذي يقرأ منه المصدر
+    pub port: String,
+
+    /// Connection state of the source's reader / حالة اتصال قارئ المصدر
+    pub receiver: ReceiverState,
+
+    /// Detector results on the source's frames / نتائج الكاشفات على إطارات المصدر
+    pub detections: DetectionResults,
+
+    /// Recent frames, oldest first / الإطارات الأخيرة، الأقدم أولاً
+    frames: VecDeque<CsiFrame>,
+
+    /// Frames received / الإطارات المستلمة
+    frame_seq: u64,
+
+    /// `frame_seq` at the last detector run / قيمة العداد عند آخر تشغيل للكاشفات
+    detected_seq: u64,
+}
+
+impl SourceState {
+    /// Frames received since the source was added / الإطارات المستلمة منذ إضافة المصدر
+    pub fn frame_count(&self) -> u64 {
+        self.frame_seq
+    }
+
+    /// Up to `count` newest frames, oldest first / آخر `count` إطارات، الأقدم أولاً
+    pub fn recent_frames(&self, count: usize) -> impl Iterator<Item = &CsiFrame> {
+        self.frames.iter().skip(self.frames.len().saturating_sub(count))
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Source Set / مجموعة المصادر
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// All named sources, in the order they were added / كل المصادر المسماة بترتيب إضافتها
+#[derive(Debug, Clone, Default)]
+pub struct SourceSet {
+    sources: Vec<SourceState>,
+}
+
+impl SourceSet {
+    /// Register a source; a repeated name is ignored / تسجيل مصدر؛ الاسم المكرر يُتجاهل
+    pub fn add(&mut self, name: &str, port: &str) {
+        if self.get(name).is_some() {
+            return;
+        }
+        self.sources.push(SourceState {
+            name: name.to_string(),
+            port: port.to_string(),
+            receiver: ReceiverState::Idle,
+            detections: DetectionResults::default(),
+            frames: VecDeque::new(),
+            frame_seq: 0,
+            detected_seq: 0,
+        });
+    }
+
+    /// Source called `name` / المصدر المسمى `name`
+    pub fn get(&self, name: &str) -> Option<&SourceState> {
+        self.sources.iter().find(|s| s.name == name)
+    }
+
+    /// Sources in the order they were added / المصادر بترتيب إضافتها
+    pub fn iter(&self) -> impl Iterator<Item = &SourceState> {
+        self.sources.iter()
+    }
+
+    /// Number of sources / عدد المصادر
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// True when no source was added / صحيح عند عدم إضافة أي مصدر
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Store a frame under its source tag; false when the tag is unknown
+    /// حفظ إطار تحت وسم مصدره؛ false إن كان الوسم غير معروف
+    pub fn route(&mut self, frame: CsiFrame) -> bool {
+        let Some(source) = frame.source.as_deref().and_then(|name| self.get_mut(name)) else {
+            return false;
+        };
+        source.frames.push_back(frame);
+        if source.frames.len() > SOURCE_FRAME_CAPACITY {
+            source.frames.pop_front();
+        }
+        source.frame_seq += 1;
+        true
+    }
+
+    /// Update the connection state of `name` / تحديث حالة اتصال المصدر
+    pub fn set_receiver(&mut self, name: &str, receiver: ReceiverState) {
+        if let Some(source) = self.get_mut(name) {
+            source.receiver = receiver;
+        }
+    }
+
+    /// Run the detectors on every source with new frames
+    /// تشغيل الكاشفات على كل مصدر وصلته إطارات جديدة
+    pub fn run_detectors(&mut self, aggregation: Aggregation, windows: &DetectorWindows) {
+        for source in self.sources.iter_mut().filter(|s| s.frame_seq != s.detected_seq) {
+            let frames: Vec<CsiFrame> = source.frames.iter().cloned().collect();
+            source.detections = quick_detect_with(&frames, aggregation, windows).0;
+            source.detected_seq = source.frame_seq;
+        }
+    }
+
+    /// Forget every source's frames and detections / نسيان إطارات ونتائج كل المصادر
+    pub fn clear_frames(&mut self) {
+        for source in &mut self.sources {
+            source.frames.clear();
+            source.detections = DetectionResults::default();
+            source.detected_seq = source.frame_seq;
+        }
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut SourceState> {
+        self.sources.iter_mut().find(|s| s.name == name)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CsiFormat;
+
+    fn frame(source: &str, i: i64, swing: f64) -> CsiFrame {
+        let level = 20.0 + if i % 2 == 0 { swing } else { -swing };
+        CsiFrame::new(i * 50, vec![level; 32], vec![], CsiFormat::AmplitudeOnly, None)
+            .with_source(Some(source.to_string()))
+    }
+
+    #[test]
+    fn test_frames_routed_to_their_source() {
+        let mut sources = SourceSet::default();
+        sources.add("kitchen", "/dev/ttyUSB1");
+        sources.add("bedroom", "/dev/ttyUSB2");
+        sources.add("kitchen", "/dev/ttyUSB9");
+        assert_eq!(sources.len(), 2);
+
+        // Kitchen swings, bedroom stays flat / المطبخ يتأرجح والغرفة ثابتة
+        for i in 0..60 {
+            assert!(sources.route(frame("kitchen", i, 15.0)));
+            assert!(sources.route(frame("bedroom", i, 0.0)));
+        }
+        assert!(!sources.route(frame("garage", 0, 0.0)));
+        assert!(!sources.route(CsiFrame::new(0, vec![1.0], vec![], CsiFormat::AmplitudeOnly, None)));
+
+        sources.run_detectors(Aggregation::default(), &DetectorWindows::default());
+        let kitchen = sources.get("kitchen").unwrap();
+        let bedroom = sources.get("bedroom").unwrap();
+        assert_eq!((kitchen.frame_count(), bedroom.frame_count()), (60, 60));
+        assert_eq!(kitchen.recent_frames(3).map(|f| f.timestamp).collect::<Vec<_>>(), vec![2850, 2900, 2950]);
+        assert!(kitchen.detections.motion_detected);
+        assert!(!bedroom.detections.motion_detected);
+        assert!(kitchen.detections.motion_value > bedroom.detections.motion_value);
+
+        sources.set_receiver("bedroom", ReceiverState::Connected);
+        assert_eq!(sources.get("bedroom").unwrap().receiver, ReceiverState::Connected);
+        assert_eq!(sources.get("kitchen").unwrap().receiver, ReceiverState::Idle);
+
+        sources.clear_frames();
+        assert!(!sources.get("kitchen").unwrap().detections.motion_detected);
+    }
+}
@@ -0,0 +1,157 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 spectrum.rs - Spectrum Averaging
+// ═══════════════════════════════════════════════════════════════════════════════
+// Keeps the per-subcarrier mean magnitude of the last N frames.
+// Features:
+// - Running sums per subcarrier, updated as frames enter/leave the window
+// - Window sizes cycled from the UI (off / 8 / 16 / 32)
+// - Accumulator resets when the subcarrier count changes
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::collections::VecDeque;
+
+use crate::state::CsiFrame;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Averaging window sizes offered in the UI / أحجام نافذة المتوسط المتاحة في الواجهة
+pub const SPECTRUM_AVERAGE_STEPS: [usize; 3] = [8, 16, 32];
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Spectrum Averager / حاسب متوسط الطيف
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Running per-subcarrier mean over the last N frames
+/// متوسط متحرك لكل ناقل فرعي عبر آخر N إطار
+#[derive(Debug, Clone)]
+pub struct SpectrumAverager {
+    /// Number of frames averaged / عدد الإطارات في المتوسط
+    window: usize,
+
+    /// Magnitudes inside the window, oldest first / السعات داخل النافذة، الأقدم أولاً
+    frames: VecDeque<Vec<f64>>,
+
+    /// Running sum per subcarrier / المجموع المتحرك لكل ناقل فرعي
+    sums: Vec<f64>,
+}
+
+impl SpectrumAverager {
+    /// Create an averager over `window` frames
+    /// إنشاء حاسب متوسط على `window` إطار
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            frames: VecDeque::new(),
+            sums: Vec::new(),
+        }
+    }
+
+    /// Add a frame's magnitudes to the window
+    /// إضافة سعات إطار إلى النافذة
+    pub fn push(&mut self, mags: &[f64]) {
+        // Subcarrier count changed: start over
+        // تغير عدد الناقلات الفرعية: البدء من جديد
+        if mags.len() != self.sums.len() {
+            self.frames.clear();
+            self.sums = vec![0.0; mags.len()];
+        }
+
+        for (sum, &m) in self.sums.iter_mut().zip(mags) {
+            *sum += m;
+        }
+        self.frames.push_back(mags.to_vec());
+
+        if self.frames.len() > self.window {
+            if let Some(old) = self.frames.pop_front() {
+                for (sum, m) in self.sums.iter_mut().zip(old) {
+                    *sum -= m;
+                }
+            }
+        }
+    }
+
+    /// Mean magnitude per subcarrier / متوسط السعة لكل ناقل فرعي
+    pub fn mean(&self) -> Vec<f64> {
+        let n = self.frames.len().max(1) as f64;
+        self.sums.iter().map(|s| s / n).collect()
+    }
+
+    /// Frames currently in the window / عدد الإطارات الحالية في النافذة
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Configured window size / حجم النافذة المضبوط
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Change the window and refill it from recent frames
+    /// تغيير النافذة وإعادة ملئها من الإطارات الأخيرة
+    pub fn set_window(&mut self, window: usize, recent: &[CsiFrame]) {
+        self.window = window.max(1);
+        self.reset();
+        let start = recent.len().saturating_sub(self.window);
+        for frame in &recent[start..] {
+            self.push(&frame.mags);
+        }
+    }
+
+    /// Forget all frames / نسيان جميع الإطارات
+    pub fn reset(&mut self) {
+        self.frames.clear();
+        self.sums.clear();
+    }
+}
+
+impl Default for SpectrumAverager {
+    fn default() -> Self {
+        Self::new(SPECTRUM_AVERAGE_STEPS[0])
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_mean_matches_window() {
+        let mut avg = SpectrumAverager::new(4);
+        for i in 0..10 {
+            avg.push(&[i as f64, 2.0 * i as f64]);
+        }
+        // Last 4 frames: 6, 7, 8, 9
+        assert_eq!(avg.frame_count(), 4);
+        let mean = avg.mean();
+        assert!((mean[0] - 7.5).abs() < 1e-9);
+        assert!((mean[1] - 15.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_subcarrier_change_resets() {
+        let mut avg = SpectrumAverager::new(8);
+        avg.push(&[10.0, 10.0]);
+        avg.push(&[20.0, 20.0]);
+        avg.push(&[5.0, 5.0, 5.0]);
+        assert_eq!(avg.frame_count(), 1);
+        assert_eq!(avg.mean(), vec![5.0, 5.0, 5.0]);
+    }
+
+    #[test]
+    fn test_set_window_refills_from_recent() {
+        let frames: Vec<CsiFrame> = (0..20)
+            .map(|i| CsiFrame::new(i, vec![i as f64], vec![], Default::default(), None))
+            .collect();
+        let mut avg = SpectrumAverager::new(4);
+        avg.set_window(16, &frames);
+        assert_eq!(avg.window(), 16);
+        assert_eq!(avg.frame_count(), 16);
+        assert!((avg.mean()[0] - 11.5).abs() < 1e-9);
+    }
+}
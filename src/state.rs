@@ -5,8 +5,98 @@
 // Uses Arc<Mutex> for thread-safe sharing between serial reader and TUI threads.
 // ═══════════════════════════════════════════════════════════════════════════════
 
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use crate::csv_logger::CsvLogger;
+use std::time::{Duration, Instant};
+use chrono::{Local, TimeZone};
+use crate::calibration::Calibration;
+use crate::csv_writer::CsvWriter;
+use crate::detection_logger::DetectionLogger;
+use crate::detectors::{
+    median_magnitude, Aggregation, DetectorReadiness, DetectorWindows, DisplayScale, DisplayValues,
+    PrimaryDetector, MAD_TO_SIGMA,
+};
+use crate::dynamic::DynamicFilter;
+use crate::esp_clock::EspClock;
+use crate::event_log::EventLog;
+use crate::features::FeatureLogger;
+use crate::frame_buffer::FrameBuffer;
+use crate::interval_stats::{analyze_interval, IntervalStats};
+use crate::frame_rate::{FrameRateMeter, SmoothedRate};
+use crate::jitter::{JitterSummary, JitterTracker};
+use crate::latency::LatencyMonitor;
+use crate::mac_stats::MacTable;
+use crate::parser::{phases_of, ParseStats, DEFAULT_MIN_SUBCARRIERS};
+use crate::quality::{assess, Quality, QualityFloors};
+use crate::report::{LineRing, ReportOptions, MESSAGE_HISTORY_CAPACITY, RAW_LINE_CAPACITY};
+use crate::session_stats::SessionStats;
+use crate::sources::SourceSet;
+use crate::spectrum::{SpectrumAverager, SPECTRUM_AVERAGE_STEPS};
+use crate::subcarriers::{stream_layout, strip_null_subcarriers};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Constants / الثوابت
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Largest clock step applied to playback in one tick (ms)
+/// أكبر خطوة ساعة تطبق على التشغيل في نبضة واحدة
+pub const MAX_PLAYBACK_STEP_MS: f64 = 500.0;
+
+/// Slowest and fastest playback speed multipliers / أبطأ وأسرع مضاعف لسرعة التشغيل
+pub const MIN_PLAYBACK_SPEED: f64 = 0.25;
+pub const MAX_PLAYBACK_SPEED: f64 = 8.0;
+
+/// Default number of samples in the live CSI chart / العدد الافتراضي للعينات في الرسم المباشر
+pub const DEFAULT_CHART_SAMPLES: usize = 100;
+
+/// Smallest live chart window / أصغر نافذة للرسم المباشر
+pub const MIN_CHART_SAMPLES: usize = 10;
+
+/// Default live frame window (seconds) / نافذة الإطارات المباشرة الافتراضية (ثواني)
+pub const DEFAULT_WINDOW_SECS: u64 = 60;
+
+/// Live frame windows stepped through from the UI (seconds) / نوافذ الإطارات المتاحة من الواجهة
+pub const WINDOW_SECS_STEPS: [u64; 6] = [15, 30, 60, 120, 300, 600];
+
+/// Default warmup after serial start before detections are trusted (seconds)
+/// فترة الإحماء الافتراضية بعد بدء الاتصال قبل الوثوق بالكشف (ثواني)
+pub const DEFAULT_WARMUP_SECS: f64 = 3.0;
+
+/// How long a subcarrier count change stays on the receiver panel (ms)
+/// مدة بقاء تحذير تغير عدد الناقلات على لوحة المستقبل
+pub const SC_CHANGE_WARNING_MS: i64 = 5_000;
+
+/// Default quiet period for detection events after a playback seek
+/// فترة الصمت الافتراضية لأحداث الكشف بعد الانتقال في التشغيل
+pub const DEFAULT_SEEK_SETTLE: Duration = Duration::from_millis(1500);
+
+/// Default consecutive empty serial reads before warning of a silent link (0 = off)
+/// عدد القراءات الفارغة المتتالية الافتراضي قبل التحذير من رابط صامت (0 = معطل)
+pub const DEFAULT_SILENCE_TIMEOUTS: u32 = 30;
+
+/// Largest magnitude difference still treated as a repeated frame
+/// أكبر فرق في السعة يعتبر إطاراً مكرراً
+pub const DUPLICATE_EPSILON: f64 = 1e-6;
+
+/// Frames kept visible during playback / الإطارات المعروضة أثناء التشغيل
+pub const PLAYBACK_WINDOW_FRAMES: usize = 100;
+
+/// Default UI refresh rate (fps) / معدل تحديث الواجهة الافتراضي
+pub const DEFAULT_REFRESH_FPS: u32 = 20;
+
+/// UI refresh rates cycled from the UI (fps) / معدلات تحديث الواجهة المتاحة
+pub const REFRESH_RATE_STEPS: [u32; 5] = [2, 5, 10, 20, 30];
+
+/// Default serial baud rate / معدل البود التسلسلي الافتراضي
+pub const DEFAULT_BAUD_RATE: u32 = 115_200;
+
+/// Serial baud rates cycled from the UI / معدلات البود المتاحة من الواجهة
+pub const BAUD_RATE_STEPS: [u32; 5] = [9_600, 115_200, 460_800, 921_600, 1_500_000];
+
+/// Moving-average windows for the magnitude chart (1 = off) / نوافذ المتوسط المتحرك لرسم السعة
+pub const SMOOTHING_STEPS: [usize; 6] = [1, 3, 5, 9, 15, 25];
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 CSI Format Enum / نوع صيغة بيانات CSI
@@ -14,19 +104,79 @@ use crate::csv_logger::CsvLogger;
 
 /// Represents the format of CSI data received from ESP32
 /// يمثل صيغة بيانات CSI المستلمة من ESP32
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum CsiFormat {
     /// Real and Imaginary pairs (r, i) / أزواج حقيقية وتخيلية
     RealImag,
     /// Amplitude only values / قيم السعة فقط
     AmplitudeOnly,
     /// Unknown format / صيغة غير معروفة
+    #[default]
     Unknown,
 }
 
-impl Default for CsiFormat {
-    fn default() -> Self {
-        CsiFormat::Unknown
+impl CsiFormat {
+    /// Stable name for exports / اسم ثابت للتصدير
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CsiFormat::RealImag => "RealImag",
+            CsiFormat::AmplitudeOnly => "AmplitudeOnly",
+            CsiFormat::Unknown => "Unknown",
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Radio Metadata / بيانات الراديو الوصفية
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Radio fields reported alongside the CSI array (ESP `wifi_pkt_rx_ctrl_t`)
+/// حقول الراديو المرسلة مع مصفوفة CSI
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsiMeta {
+    /// Primary channel / القناة الأساسية
+    pub channel: Option<u8>,
+
+    /// Secondary channel: 0 none, 1 above, 2 below / القناة الثانوية: 0 لا يوجد، 1 فوق، 2 تحت
+    pub secondary_channel: Option<u8>,
+
+    /// Signal mode: 0 non-HT (11b/g), 1 HT (11n), 3 VHT (11ac) / نمط الإشارة
+    pub sig_mode: Option<u8>,
+
+    /// Channel bandwidth: 0 20MHz, 1 40MHz / عرض القناة
+    pub bandwidth: Option<u8>,
+}
+
+impl CsiMeta {
+    /// No field was reported (CSV playback, binary packets) / لم يُرسل أي حقل
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Wi-Fi mode from the reported signal mode and bandwidth
+    /// نمط الواي فاي من نمط الإشارة وعرض القناة المرسلين
+    pub fn wifi_standard(&self) -> Option<String> {
+        let mode = match self.sig_mode? {
+            0 => return Some("802.11b/g (20MHz)".to_string()),
+            1 => "Wi-Fi 4",
+            3 => "Wi-Fi 5",
+            _ => return None,
+        };
+        Some(match self.bandwidth {
+            Some(0) => format!("{} (20MHz)", mode),
+            Some(1) => format!("{} (40MHz)", mode),
+            _ => mode.to_string(),
+        })
+    }
+
+    /// Channel with the secondary side ("ch 6+", "ch 11-") / القناة مع الجهة الثانوية
+    pub fn channel_label(&self) -> Option<String> {
+        let side = match self.secondary_channel {
+            Some(1) => "+",
+            Some(2) => "-",
+            _ => "",
+        };
+        self.channel.map(|channel| format!("ch {}{}", channel, side))
     }
 }
 
@@ -48,19 +198,130 @@ pub struct CsiFrame {
     /// Raw (real, imag) pairs from CSI data / الأزواج الخام (حقيقي، تخيلي)
     pub pairs: Vec<(i32, i32)>,
     
+    /// Phase per subcarrier in radians, empty for amplitude-only data
+    /// الطور لكل ناقل فرعي بالراديان، فارغ لبيانات السعة فقط
+    /// phase = atan2(imag, real) for RealImag format
+    pub phases: Vec<f64>,
+    
     /// The detected format of this frame / صيغة هذا الإطار المكتشفة
     #[allow(dead_code)]
     pub format: CsiFormat,
+    
+    /// Received signal strength in dBm, if reported / قوة الإشارة المستقبلة إن وجدت
+    pub rssi: Option<i32>,
+    
+    /// Transmitter MAC (`AA:BB:CC:DD:EE:FF`), if well-formed / عنوان MAC للمرسل إن كان سليماً
+    pub mac: Option<String>,
+
+    /// Radio metadata from the block header, empty when not reported
+    /// بيانات الراديو من ترويسة الكتلة، فارغة إن لم تُرسل
+    pub meta: CsiMeta,
+
+    /// Subcarrier range of each concatenated stream; empty for a single stream
+    /// نطاق الناقلات لكل تدفق متسلسل؛ فارغ لتدفق واحد
+    pub streams: Vec<Range<usize>>,
+
+    /// Named source that received the frame; None for the primary reader
+    /// المصدر المسمى الذي استقبل الإطار؛ None للقارئ الأساسي
+    pub source: Option<String>,
+
+    /// Outlier-clamped magnitudes for the detectors and the chart average;
+    /// empty until the frame is cleaned / السعات بعد قص القيم الشاذة للكواشف
+    pub cleaned: Vec<f64>,
+
+    /// Threshold `cleaned` was computed with (0 = not cleaned)
+    /// العتبة التي حُسبت بها `cleaned` (0 = غير منظف)
+    pub cleaned_sigma: f64,
 }
 
 impl CsiFrame {
-    /// Create a new CSI frame / إنشاء إطار CSI جديد
-    pub fn new(timestamp: i64, mags: Vec<f64>, pairs: Vec<(i32, i32)>, format: CsiFormat) -> Self {
+    /// Create a new CSI frame; phases are derived from RealImag pairs
+    /// إنشاء إطار CSI جديد؛ يُشتق الطور من أزواج حقيقي/تخيلي
+    pub fn new(
+        timestamp: i64,
+        mags: Vec<f64>,
+        pairs: Vec<(i32, i32)>,
+        format: CsiFormat,
+        rssi: Option<i32>,
+    ) -> Self {
+        let phases = match format {
+            CsiFormat::RealImag => phases_of(&pairs),
+            _ => Vec::new(),
+        };
+        Self::with_phases(timestamp, mags, pairs, phases, format, rssi)
+    }
+
+    /// Create a CSI frame with phases the parser already computed
+    /// إنشاء إطار CSI بأطوار حسبها المحلل مسبقاً
+    pub fn with_phases(
+        timestamp: i64,
+        mags: Vec<f64>,
+        pairs: Vec<(i32, i32)>,
+        phases: Vec<f64>,
+        format: CsiFormat,
+        rssi: Option<i32>,
+    ) -> Self {
         Self {
             timestamp,
             mags,
             pairs,
+            phases,
             format,
+            rssi,
+            mac: None,
+            meta: CsiMeta::default(),
+            streams: Vec::new(),
+            source: None,
+            cleaned: Vec::new(),
+            cleaned_sigma: 0.0,
+        }
+    }
+
+    /// Attach the transmitter MAC / إرفاق عنوان MAC للمرسل
+    pub fn with_mac(mut self, mac: Option<String>) -> Self {
+        self.mac = mac;
+        self
+    }
+
+    /// Attach the radio metadata / إرفاق بيانات الراديو الوصفية
+    pub fn with_meta(mut self, meta: CsiMeta) -> Self {
+        self.meta = meta;
+        self
+    }
+
+    /// Tag the frame with its named source / وسم الإطار بمصدره المسمى
+    pub fn with_source(mut self, source: Option<String>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Number of streams (at least one) / عدد التدفقات (واحد على الأقل)
+    pub fn stream_count(&self) -> usize {
+        self.streams.len().max(1)
+    }
+
+    /// Subcarriers of stream `idx`; the whole frame when it has no such stream
+    /// ناقلات التدفق `idx`؛ الإطار كاملاً إن لم يوجد هذا التدفق
+    pub fn stream_range(&self, idx: usize) -> Range<usize> {
+        self.streams.get(idx).cloned().unwrap_or(0..self.mags.len())
+    }
+
+    /// Copy of this frame holding only stream `idx` / نسخة من الإطار تحوي التدفق `idx` فقط
+    pub fn stream_frame(&self, idx: usize) -> CsiFrame {
+        let range = self.stream_range(idx);
+        CsiFrame {
+            timestamp: self.timestamp,
+            mags: self.mags.get(range.clone()).unwrap_or_default().to_vec(),
+            pairs: self.pairs.get(range.clone()).unwrap_or_default().to_vec(),
+            phases: self.phases.get(range).unwrap_or_default().to_vec(),
+            format: self.format,
+            rssi: self.rssi,
+            mac: self.mac.clone(),
+            meta: self.meta.clone(),
+            streams: Vec::new(),
+            source: self.source.clone(),
+            cleaned: Vec::new(),
+            cleaned_sigma: 0.0,
         }
     }
 
@@ -68,6 +329,49 @@ impl CsiFrame {
     pub fn subcarrier_count(&self) -> usize {
         self.mags.len()
     }
+
+    /// Phase of subcarrier `idx` in radians (None for AmplitudeOnly or out of range)
+    /// طور الناقل الفرعي `idx` بالراديان
+    pub fn phase_at(&self, idx: usize) -> Option<f64> {
+        self.phases.get(idx).copied()
+    }
+
+    /// Magnitudes clamped to `threshold_sigma` standard deviations around the
+    /// frame's median; `mags` itself is left as received
+    /// السعات مقصوصة إلى `threshold_sigma` انحرافاً معيارياً حول وسيط الإطار
+    ///
+    /// The deviation is estimated from the median absolute deviation, so one
+    /// huge value cannot widen its own limit. Values are clamped rather than
+    /// dropped to keep subcarrier positions. A frame without spread, or a
+    /// threshold of 0, is returned unchanged.
+    pub fn clean_mags(&self, threshold_sigma: f64) -> Vec<f64> {
+        let median = median_magnitude(&self.mags);
+        let deviations: Vec<f64> = self.mags.iter().map(|m| (m - median).abs()).collect();
+        let limit = threshold_sigma * MAD_TO_SIGMA * median_magnitude(&deviations);
+        if limit <= 0.0 || !limit.is_finite() {
+            return self.mags.clone();
+        }
+        self.mags.iter().map(|&m| m.clamp(median - limit, median + limit)).collect()
+    }
+
+    /// Store `clean_mags(threshold_sigma)` next to `mags` (a threshold of 0 clears it)
+    /// تخزين `clean_mags(threshold_sigma)` بجانب `mags` (العتبة 0 تمسحها)
+    pub fn with_clean_mags(mut self, threshold_sigma: f64) -> Self {
+        self.cleaned_sigma = threshold_sigma.max(0.0);
+        self.cleaned = if self.cleaned_sigma > 0.0 { self.clean_mags(self.cleaned_sigma) } else { Vec::new() };
+        self
+    }
+
+    /// Whether `cleaned` matches `threshold_sigma` / هل `cleaned` محسوبة بهذه العتبة
+    pub fn is_cleaned_at(&self, threshold_sigma: f64) -> bool {
+        self.cleaned_sigma == threshold_sigma.max(0.0)
+    }
+
+    /// Magnitudes the analysis reads: the cleaned copy, or `mags` when not cleaned
+    /// السعات التي يقرؤها التحليل: النسخة المنظفة أو `mags` إن لم تنظف
+    pub fn analysis_mags(&self) -> &[f64] {
+        if self.cleaned.is_empty() { &self.mags } else { &self.cleaned }
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -95,6 +399,195 @@ pub struct DetectionResults {
     
     /// Door change value (0-100) / قيمة تغير الباب
     pub door_value: f64,
+    
+    /// Breathing rate in breaths per minute (0 = unknown) / معدل التنفس بالأنفاس في الدقيقة
+    pub breathing_bpm: f64,
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Baseline / خط الأساس
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Snapshot of the environment taken at calibration time
+/// لقطة للبيئة مأخوذة وقت المعايرة
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    /// When the baseline was captured (ms) / وقت التقاط خط الأساس
+    pub timestamp: i64,
+    
+    /// Mean magnitude per subcarrier / متوسط السعة لكل ناقل فرعي
+    pub mags: Vec<f64>,
+    
+    /// Detector values at capture time / قيم الكاشفات وقت الالتقاط
+    pub detections: DetectionResults,
+}
+
+impl Baseline {
+    /// Build a baseline from recent frames and detector values
+    /// بناء خط الأساس من الإطارات الأخيرة وقيم الكاشفات
+    pub fn capture(timestamp: i64, frames: &[CsiFrame], detections: &DetectionResults) -> Self {
+        let sc = frames.iter().map(|f| f.mags.len()).max().unwrap_or(0);
+        let mut sums = vec![0.0; sc];
+        let mut counts = vec![0usize; sc];
+        
+        for frame in frames {
+            for (i, &m) in frame.mags.iter().enumerate() {
+                sums[i] += m;
+                counts[i] += 1;
+            }
+        }
+        
+        let mags = sums
+            .iter()
+            .zip(&counts)
+            .map(|(&sum, &n)| if n > 0 { sum / n as f64 } else { 0.0 })
+            .collect();
+        
+        Self { timestamp, mags, detections: detections.clone() }
+    }
+
+    /// Change of each detector value since baseline (motion, presence, door)
+    /// تغير قيمة كل كاشف منذ خط الأساس (الحركة، الوجود، الباب)
+    pub fn deltas(&self, current: &DetectionResults) -> (f64, f64, f64) {
+        (
+            current.motion_value - self.detections.motion_value,
+            current.presence_value - self.detections.presence_value,
+            current.door_value - self.detections.door_value,
+        )
+    }
+
+    /// Mean absolute change per subcarrier between a frame and the baseline
+    /// متوسط التغير المطلق لكل ناقل فرعي بين إطار وخط الأساس
+    pub fn signal_delta(&self, mags: &[f64]) -> f64 {
+        let n = mags.len().min(self.mags.len());
+        if n == 0 {
+            return 0.0;
+        }
+        mags.iter().zip(&self.mags).map(|(a, b)| (a - b).abs()).sum::<f64>() / n as f64
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Chart Mode / وضع الرسم البياني
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// What the CSI chart shows / ما يعرضه رسم CSI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartMode {
+    /// Average magnitude per frame / متوسط السعة لكل إطار
+    #[default]
+    Magnitude,
+    /// Average magnitude minus its rolling median / متوسط السعة ناقص الوسيط المتحرك
+    Dynamic,
+    /// Magnitude per subcarrier / السعة لكل ناقل فرعي
+    Spectrum,
+    /// Magnitude spread per subcarrier over the window / تشتت السعة لكل ناقل فرعي عبر النافذة
+    Stability,
+}
+
+/// Clock the live frame window ends at / الساعة التي تنتهي عندها نافذة الإطارات
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowClock {
+    /// Now: live readers stamp frames with host time / الآن: القراء المباشرون يختمون الإطارات بوقت المضيف
+    #[default]
+    Wall,
+    /// The newest frame: a followed file may come from a machine with a skewed clock
+    /// أحدث إطار: الملف المتابع قد يأتي من جهاز بساعة منحرفة
+    NewestFrame,
+}
+
+/// Chart data captured when the view was frozen / بيانات الرسوم الملتقطة عند تجميد العرض
+#[derive(Debug, Clone)]
+pub struct ViewSnapshot {
+    /// Frames at freeze time / الإطارات وقت التجميد
+    pub frames: Vec<CsiFrame>,
+
+    /// Dynamic component at freeze time / المكون الديناميكي وقت التجميد
+    pub dynamic: DynamicFilter,
+
+    /// Averaged spectrum at freeze time / الطيف المتوسط وقت التجميد
+    pub spectrum: SpectrumAverager,
+
+    /// Detector chart histories (motion, presence, door) / تواريخ رسم الكاشفات
+    pub histories: [Vec<f64>; 3],
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Receiver State / حالة المستقبل
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Serial receiver connection state / حالة اتصال المستقبل التسلسلي
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReceiverState {
+    /// No reader running / لا يوجد قارئ يعمل
+    #[default]
+    Idle,
+    /// Opening the port; X cancels / فتح المنفذ؛ X يلغي
+    Connecting,
+    /// Port open and reading / المنفذ مفتوح والقراءة جارية
+    Connected,
+    /// Port lost, trying to reopen it / فُقد المنفذ، محاولة إعادة فتحه
+    Reconnecting,
+    /// Open or reconnect failed; details in the status message / فشل الفتح؛ التفاصيل في رسالة الحالة
+    Error,
+}
+
+impl ReceiverState {
+    /// Short label for the Receiver box / تسمية قصيرة لمربع المستقبل
+    pub fn label(self) -> &'static str {
+        match self {
+            ReceiverState::Idle => "STOPPED",
+            ReceiverState::Connecting => "CONNECTING",
+            ReceiverState::Connected => "ACTIVE",
+            ReceiverState::Reconnecting => "RECONNECTING",
+            ReceiverState::Error => "ERROR",
+        }
+    }
+
+    /// Is the port being opened (first time or again)? / هل يجري فتح المنفذ؟
+    pub fn is_connecting(self) -> bool {
+        matches!(self, ReceiverState::Connecting | ReceiverState::Reconnecting)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Port Picker / اختيار المنفذ
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One serial port offered by the picker / منفذ تسلسلي معروض في نافذة الاختيار
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortChoice {
+    /// Port name to open (e.g. "/dev/ttyUSB0") / اسم المنفذ المطلوب فتحه
+    pub name: String,
+
+    /// USB product / manufacturer, or the port type / وصف المنفذ
+    pub description: String,
+}
+
+/// Ports listed in the picker popup and the highlighted row
+/// المنافذ المعروضة في نافذة الاختيار والصف المحدد
+#[derive(Debug, Clone, Default)]
+pub struct PortPicker {
+    pub ports: Vec<PortChoice>,
+    pub selected: usize,
+}
+
+impl PortPicker {
+    /// Picker over `ports` with the first row highlighted / نافذة اختيار مع تحديد الصف الأول
+    pub fn new(ports: Vec<PortChoice>) -> Self {
+        Self { ports, selected: 0 }
+    }
+
+    /// Move the highlight by `delta` rows / تحريك التحديد بمقدار `delta` صفوف
+    pub fn move_selection(&mut self, delta: isize) {
+        let last = self.ports.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Highlighted port; None when no port is available / المنفذ المحدد؛ None عند عدم وجود منافذ
+    pub fn selected(&self) -> Option<&PortChoice> {
+        self.ports.get(self.selected)
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -104,39 +597,306 @@ pub struct DetectionResults {
 /// Main application state shared between threads
 /// حالة التطبيق الرئيسية المشتركة بين الخيوط
 pub struct AppState {
-    /// Is the serial receiver currently active? / هل المستقبل التسلسلي نشط حالياً؟
-    pub receiver_active: bool,
+    /// Serial receiver connection state / حالة اتصال المستقبل التسلسلي
+    pub receiver: ReceiverState,
     
     /// All CSI frames in memory (last 60 seconds) / جميع إطارات CSI في الذاكرة (آخر 60 ثانية)
-    pub frames: Vec<CsiFrame>,
+    pub frames: FrameBuffer,
+    
+    /// Incremented on every new display frame / يزداد مع كل إطار عرض جديد
+    pub frame_seq: u64,
     
     /// Maximum number of subcarriers ever seen / أقصى عدد ناقلات فرعية تم رؤيته
     pub max_sc: usize,
     
+    /// Time of the last subcarrier count change (ms) / وقت آخر تغير في عدد الناقلات
+    pub last_sc_change: Option<i64>,
+    
+    /// Subcarrier counts before and after that change / عدد الناقلات قبل التغير وبعده
+    pub sc_change: (usize, usize),
+
+    /// Radio metadata of the latest frame that reported any / بيانات الراديو لآخر إطار أرسلها
+    pub radio_meta: CsiMeta,
+    
     /// CSV logger instance (optional) / مثيل مسجل CSV (اختياري)
-    pub csv_logger: Option<CsvLogger>,
+    pub csv_logger: Option<CsvWriter>,
+    
+    /// Open a CSV recording when a live session starts (W toggles, off by default)
+    /// فتح تسجيل CSV عند بدء جلسة مباشرة (W يبدل، معطل افتراضياً)
+    pub record_csv: bool,
+    
+    /// Recovered log to append to on the next capture / سجل مسترد للإضافة إليه في الالتقاط التالي
+    pub resume_log: Option<PathBuf>,
+    
+    /// Detection history logger (optional) / مسجل تاريخ الكشف (اختياري)
+    pub detection_logger: Option<DetectionLogger>,
+    
+    /// Per-frame ML feature export (optional) / تصدير خصائص كل إطار (اختياري)
+    pub feature_logger: Option<FeatureLogger>,
+    
+    /// Seconds of live frames kept in memory / ثواني الإطارات المباشرة المحفوظة في الذاكرة
+    pub window_secs: u64,
+
+    /// Where the live window ends (Wall unless a file is followed)
+    /// نهاية نافذة البث (Wall ما لم يُتابع ملف)
+    pub window_clock: WindowClock,
     
     /// Current detection results / نتائج الكشف الحالية
     pub detections: DetectionResults,
     
+    /// Scaling shared by the detectors panel and chart / التحجيم المشترك بين لوحة الكاشفات ورسمها
+    pub display_scale: DisplayScale,
+    
+    /// Current detection values after scaling / قيم الكشف الحالية بعد التحجيم
+    pub display: DisplayValues,
+    
+    /// Detector window sizes / أحجام نوافذ الكاشفات
+    pub detector_windows: DetectorWindows,
+    
+    /// Which detectors had enough history on the last run / الكاشفات التي لديها تاريخ كافٍ
+    pub readiness: DetectorReadiness,
+    
+    /// Warmup duration after serial start (seconds) / مدة الإحماء بعد بدء الاتصال (ثواني)
+    pub warmup_secs: f64,
+    
+    /// When the current warmup started (ms) / وقت بدء الإحماء الحالي (ميلي ثانية)
+    pub warmup_started_ms: Option<i64>,
+    
+    /// Quiet period for detection events after a seek / فترة صمت أحداث الكشف بعد الانتقال
+    pub seek_settle: Duration,
+    
+    /// When playback last seeked / آخر وقت انتقال في التشغيل
+    pub last_seek_instant: Option<Instant>,
+    
+    /// How frame magnitudes are summarized (chart + detectors) / طريقة تلخيص السعات
+    pub aggregation: Aggregation,
+    
+    /// Detector whose analysis range the stats panel shows / الكاشف المعروض نطاقه في الإحصائيات
+    pub primary_detector: PrimaryDetector,
+    
     /// Status message to display / رسالة الحالة للعرض
     pub status_message: String,
     
+    /// Recent status messages, timestamped, for report bundles / رسائل الحالة الحديثة لحزم التقارير
+    pub message_history: LineRing,
+    
+    /// Status message last copied into the history / آخر رسالة حالة نُسخت إلى السجل
+    noted_status: String,
+    
+    /// Recent raw serial lines for report bundles / أسطر التسلسل الخام الحديثة لحزم التقارير
+    pub raw_lines: LineRing,
+    
+    /// What report bundles include / ما تتضمنه حزم التقارير
+    pub report_options: ReportOptions,
+    
     /// Serial port name / اسم المنفذ التسلسلي
     pub port_name: String,
     
+    /// Baud rate the next serial start opens with / معدل البود لبدء الاتصال التالي
+    pub baud_rate: u32,
+    
+    /// `port_name` was chosen in the picker; the next start skips auto-detection
+    /// تم اختيار المنفذ من النافذة؛ البدء التالي يتجاوز الاكتشاف التلقائي
+    pub port_chosen: bool,
+    
     /// Should the application quit? / هل يجب إنهاء التطبيق؟
     pub should_quit: bool,
     
-    /// History of motion values for chart / تاريخ قيم الحركة للرسم البياني
+    /// Write integrity sidecars for new CSV logs / كتابة ملفات السلامة الجانبية لسجلات CSV الجديدة
+    pub integrity_logging: bool,
+    
+    /// Rotate CSV logs into a new part at this size (None = never)
+    /// تدوير سجلات CSV إلى جزء جديد عند هذا الحجم (None = أبداً)
+    pub log_rotate_bytes: Option<u64>,
+    
+    /// Log serial blocks the parser rejects / تسجيل الكتل التي يرفضها المحلل
+    pub parse_failure_logging: bool,
+    
+    /// Keep reopening a lost serial port / متابعة إعادة فتح المنفذ المفقود
+    pub auto_reconnect: bool,
+    
+    /// Empty serial reads in a row before a "no bytes" warning (0 = off)
+    /// القراءات الفارغة المتتالية قبل تحذير "لا بايتات" (0 = معطل)
+    pub silence_timeouts: u32,
+    
+    /// Serial blocks with fewer subcarriers are dropped / الكتل ذات الناقلات الأقل تُهمل
+    pub min_subcarriers: usize,
+    
+    /// Per-subcarrier corrections for live frames (`--calibration`) / تصحيحات المعايرة للإطارات الحية
+    pub calibration: Option<Calibration>,
+    
+    /// Serial blocks dropped for having too few subcarriers / الكتل المهملة لقلة الناقلات
+    pub tiny_frames_rejected: u64,
+    
+    /// Serial blocks dropped because their array length jumped (log text inside)
+    /// الكتل المهملة لقفزة طول مصفوفتها (نص سجل بداخلها)
+    pub contaminated_frames: u64,
+    
+    /// Serial blocks seen, parsed and rejected / كتل التسلسل المرئية والمحللة والمرفوضة
+    pub parse_stats: ParseStats,
+    
+    /// Serial port streams binary packets instead of text / المنفذ يبث حزماً ثنائية بدلاً من النص
+    pub binary_serial: bool,
+    
+    /// UDP address S listens on for nexmon CSI instead of serial / عنوان UDP لاستقبال nexmon بدلاً من التسلسل
+    pub udp_source: Option<String>,
+    
+    /// Extra named receivers (one per room) / مستقبلات مسماة إضافية (واحد لكل غرفة)
+    pub sources: SourceSet,
+    
+    /// Binary packets skipped as corrupt / الحزم الثنائية المتخطاة لتلفها
+    pub corrupt_packets: u64,
+    
+    /// Format the serial parser must use; None detects it / الصيغة المفروضة على المحلل؛ لا شيء للكشف
+    pub forced_format: Option<CsiFormat>,
+    
+    /// Equal streams per frame (one per antenna); 0 uses the metadata / عدد التدفقات المتساوية لكل إطار
+    pub stream_split: usize,
+    
+    /// Stream plotted and analyzed; None uses the whole frame / التدفق المرسوم والمحلل؛ لا شيء للإطار كاملاً
+    pub selected_stream: Option<usize>,
+    
+    /// Skip serial frames identical to the previous one / تخطي الإطارات المطابقة للإطار السابق
+    pub drop_duplicate_frames: bool,
+    
+    /// Serial frames skipped as repeats / الإطارات المتخطاة لتكرارها
+    pub duplicates_dropped: u64,
+    
+    /// Frames dropped because the UI's frame queue was full / الإطارات المحذوفة لامتلاء طابور الواجهة
+    pub queue_dropped: u64,
+    
+    /// Sender and magnitudes of the previous serial frame / مرسل وسعات الإطار التسلسلي السابق
+    last_received: Option<(Option<String>, Vec<f64>)>,
+    
+    /// Remove guard/DC subcarriers before analysis (logs stay raw) / حذف ناقلات الحارس و DC قبل التحليل
+    pub strip_null_subcarriers: bool,
+    
+    /// Null subcarriers removed from the newest frame / الناقلات الفارغة المحذوفة من أحدث إطار
+    pub null_subcarriers: usize,
+    
+    /// Input quality below which detections are uncertain / جودة الإدخال التي تصبح الكشوفات دونها غير مؤكدة
+    pub quality_floors: QualityFloors,
+    
+    /// History of scaled motion values for chart / تاريخ قيم الحركة المحجمة للرسم البياني
     pub motion_history: Vec<f64>,
     
-    /// History of presence values for chart / تاريخ قيم الوجود للرسم البياني
+    /// History of scaled presence values for chart / تاريخ قيم الوجود المحجمة للرسم البياني
     pub presence_history: Vec<f64>,
     
-    /// History of door values for chart / تاريخ قيم الباب للرسم البياني
+    /// History of scaled door values for chart / تاريخ قيم الباب المحجمة للرسم البياني
     pub door_history: Vec<f64>,
     
+    /// Log of detector transitions / سجل تغيرات الكاشفات
+    pub event_log: EventLog,
+    
+    /// Counters for the current measurement period / عدادات فترة القياس الحالية
+    pub session: SessionStats,
+    
+    /// Samples shown in the live CSI chart / عدد العينات في رسم CSI المباشر
+    pub live_chart_samples: usize,
+    
+    /// Moving-average points on the magnitude chart, 1 = off / نقاط المتوسط المتحرك لرسم السعة
+    pub smoothing_window: usize,
+    
+    /// CSI chart mode / وضع رسم CSI
+    pub chart_mode: ChartMode,
+    
+    /// Subcarrier plotted in the magnitude chart; None plots the aggregate / الناقل المرسوم؛ لا شيء للتجميع
+    pub selected_subcarrier: Option<usize>,
+    
+    /// Y bounds of the CSI chart at the last render / حدود المحور الصادي عند آخر رسم
+    pub chart_auto_bounds: (f64, f64),
+    
+    /// Frozen Y bounds; auto-scaling resumes when None / حدود مجمدة؛ التحجيم التلقائي يعود عند None
+    pub locked_bounds: Option<(f64, f64)>,
+    
+    /// Charts show `view_snapshot` while receiving continues / الرسوم تعرض اللقطة بينما يستمر الاستقبال
+    pub view_frozen: bool,
+    
+    /// Chart data captured by the last freeze / بيانات الرسوم من آخر تجميد
+    pub view_snapshot: Option<ViewSnapshot>,
+    
+    /// Rolling-median filter for the dynamic component / مرشح الوسيط للمكون الديناميكي
+    pub dynamic: DynamicFilter,
+    
+    /// Per-subcarrier running mean for the spectrum view / المتوسط المتحرك لعرض الطيف
+    pub spectrum: SpectrumAverager,
+    
+    /// Show the averaged spectrum instead of the last frame / عرض الطيف المتوسط بدل آخر إطار
+    pub spectrum_averaging: bool,
+
+    /// Draw the last frame faintly under the averaged spectrum / رسم آخر إطار باهتاً تحت الطيف المتوسط
+    pub spectrum_overlay: bool,
+    
+    /// Animate the empty chart while waiting for data / تحريك الرسم الفارغ أثناء انتظار البيانات
+    pub idle_animation: bool,
+    
+    /// Summarize status in a short bar above full-width charts / تلخيص الحالة في شريط قصير فوق رسوم بعرض كامل
+    pub compact_status: bool,
+    
+    /// Idle animation frame counter / عداد إطارات حركة الانتظار
+    pub idle_tick: u64,
+    
+    /// Maximum UI redraws per second / أقصى عدد لإعادة رسم الواجهة في الثانية
+    pub ui_refresh_fps: u32,
+    
+    /// Redraws achieved over the last second / عدد مرات الرسم الفعلية في آخر ثانية
+    pub ui_achieved_fps: f64,
+    
+    /// Calibration baseline (if captured) / خط أساس المعايرة (إن تم التقاطه)
+    pub baseline: Option<Baseline>,
+    
+    /// Show detector deltas against the baseline / عرض فروقات الكاشفات مقارنة بخط الأساس
+    pub show_baseline_delta: bool,
+    
+    /// Recapture the baseline this often, for unattended runs / إعادة التقاط خط الأساس بهذا التكرار
+    pub recalibrate_every_secs: Option<u64>,
+    
+    /// Live inter-frame timing jitter / تذبذب التوقيت بين الإطارات المباشرة
+    pub jitter: JitterTracker,
+    
+    /// Frames pushed per second / الإطارات المضافة في الثانية
+    pub frame_rate: FrameRateMeter,
+
+    /// Frame rate below which the panel warns (0 = off) / معدل الإطارات الذي يُنبَّه دونه (0 = معطل)
+    pub fps_floor: f64,
+    
+    /// Smoothed rate from frame timestamps, with session min/max / المعدل المنعم مع الحدين
+    pub smoothed_rate: SmoothedRate,
+    
+    /// Anchors ESP timer values to host time / ربط قيم مؤقت ESP بوقت المضيف
+    pub esp_clock: EspClock,
+    
+    /// Jitter of the whole loaded file (playback) / تذبذب الملف المحمل بالكامل
+    pub file_jitter: Option<JitterSummary>,
+    
+    /// Per-stage processing time against the frame interval / زمن كل مرحلة مقابل الفاصل بين الإطارات
+    pub latency: LatencyMonitor,
+    
+    /// Transmitters seen in the last minute / المرسلون الذين ظهروا في آخر دقيقة
+    pub mac_stats: MacTable,
+    
+    /// Only accept frames from this MAC / قبول الإطارات من هذا العنوان فقط
+    pub mac_filter: Option<String>,
+    
+    /// Is the MAC table popup open? / هل نافذة جدول MAC مفتوحة؟
+    pub show_mac_table: bool,
+    
+    /// Selected row in the MAC table / الصف المحدد في جدول MAC
+    pub mac_table_selected: usize,
+    
+    /// Raw data inspector popup open / نافذة فاحص البيانات الخام مفتوحة
+    pub show_raw_inspector: bool,
+    
+    /// Inspector shows raw values in hex / الفاحص يعرض القيم الخام بالست عشري
+    pub raw_hex: bool,
+    
+    /// Serial port picker popup, when open / نافذة اختيار المنفذ التسلسلي عند فتحها
+    pub port_picker: Option<PortPicker>,
+    
+    /// "Quit while recording?" prompt open / سؤال تأكيد الخروج أثناء التسجيل مفتوح
+    pub confirm_quit: bool,
+    
     // ═══════════════════════════════════════════════════════════════════════
     // 🎬 Playback Mode Fields / حقول وضع التشغيل
     // ═══════════════════════════════════════════════════════════════════════
@@ -155,6 +915,22 @@ pub struct AppState {
     
     /// Total duration of loaded data in seconds / المدة الإجمالية للبيانات المحملة بالثواني
     pub playback_duration_secs: f64,
+    
+    /// Playback clock in ms since the first loaded frame / ساعة التشغيل بالميلي ثانية منذ أول إطار
+    pub playback_cursor_ms: f64,
+    
+    /// Playback speed multiplier; 1.0 is real time / مضاعف سرعة التشغيل؛ 1.0 هو الوقت الحقيقي
+    pub playback_speed: f64,
+    
+    /// Path of the last loaded CSV file (for quick reload) / مسار آخر ملف CSV محمل (لإعادة التحميل السريع)
+    pub last_csv_path: Option<PathBuf>,
+    
+    /// A / B interval markers (frame indices) / علامات الفترة A و B (فهارس الإطارات)
+    pub marker_a: Option<usize>,
+    pub marker_b: Option<usize>,
+    
+    /// Statistics popup for the A-B interval / نافذة إحصائيات الفترة A-B
+    pub interval_stats: Option<IntervalStats>,
 }
 
 impl AppState {
@@ -162,50 +938,226 @@ impl AppState {
     /// إنشاء حالة تطبيق جديدة بقيم افتراضية
     pub fn new() -> Self {
         Self {
-            receiver_active: false,
-            frames: Vec::new(),
+            receiver: ReceiverState::Idle,
+            frames: FrameBuffer::default(),
+            frame_seq: 0,
             max_sc: 0,
+            last_sc_change: None,
+            sc_change: (0, 0),
+            radio_meta: CsiMeta::default(),
             csv_logger: None,
+            record_csv: false,
+            resume_log: None,
+            detection_logger: None,
+            feature_logger: None,
+            window_secs: DEFAULT_WINDOW_SECS,
+            window_clock: WindowClock::Wall,
             detections: DetectionResults::default(),
+            display_scale: DisplayScale::default(),
+            display: DisplayValues::default(),
+            detector_windows: DetectorWindows::default(),
+            readiness: DetectorReadiness::default(),
+            warmup_secs: DEFAULT_WARMUP_SECS,
+            warmup_started_ms: None,
+            seek_settle: DEFAULT_SEEK_SETTLE,
+            last_seek_instant: None,
+            aggregation: Aggregation::default(),
+            primary_detector: PrimaryDetector::default(),
             status_message: "Press S to start serial, L to load CSV".to_string(),
+            message_history: LineRing::new(MESSAGE_HISTORY_CAPACITY),
+            noted_status: String::new(),
+            raw_lines: LineRing::new(RAW_LINE_CAPACITY),
+            report_options: ReportOptions::default(),
             port_name: "COM3".to_string(),
+            baud_rate: DEFAULT_BAUD_RATE,
+            port_chosen: false,
             should_quit: false,
+            integrity_logging: false,
+            log_rotate_bytes: None,
+            parse_failure_logging: false,
+            auto_reconnect: true,
+            silence_timeouts: DEFAULT_SILENCE_TIMEOUTS,
+            min_subcarriers: DEFAULT_MIN_SUBCARRIERS,
+            calibration: None,
+            tiny_frames_rejected: 0,
+            contaminated_frames: 0,
+            parse_stats: ParseStats::default(),
+            binary_serial: false,
+            udp_source: None,
+            sources: SourceSet::default(),
+            forced_format: None,
+            stream_split: 0,
+            selected_stream: None,
+            drop_duplicate_frames: false,
+            duplicates_dropped: 0,
+            queue_dropped: 0,
+            last_received: None,
+            corrupt_packets: 0,
+            strip_null_subcarriers: true,
+            null_subcarriers: 0,
+            quality_floors: QualityFloors::default(),
             motion_history: Vec::new(),
             presence_history: Vec::new(),
             door_history: Vec::new(),
+            event_log: EventLog::default(),
+            session: SessionStats::new(Instant::now()),
+            live_chart_samples: DEFAULT_CHART_SAMPLES,
+            smoothing_window: 1,
+            selected_subcarrier: None,
+            chart_mode: ChartMode::default(),
+            chart_auto_bounds: (0.0, 0.0),
+            locked_bounds: None,
+            view_frozen: false,
+            view_snapshot: None,
+            dynamic: DynamicFilter::default(),
+            spectrum: SpectrumAverager::default(),
+            spectrum_averaging: false,
+            spectrum_overlay: false,
+            idle_animation: false,
+            compact_status: false,
+            idle_tick: 0,
+            ui_refresh_fps: DEFAULT_REFRESH_FPS,
+            ui_achieved_fps: 0.0,
+            baseline: None,
+            show_baseline_delta: false,
+            recalibrate_every_secs: None,
+            jitter: JitterTracker::default(),
+            frame_rate: FrameRateMeter::default(),
+            fps_floor: 0.0,
+            smoothed_rate: SmoothedRate::default(),
+            esp_clock: EspClock::default(),
+            file_jitter: None,
+            latency: LatencyMonitor::default(),
+            mac_stats: MacTable::default(),
+            mac_filter: None,
+            show_mac_table: false,
+            mac_table_selected: 0,
+            show_raw_inspector: false,
+            raw_hex: false,
+            port_picker: None,
+            confirm_quit: false,
             // Playback fields
             loaded_frames: Vec::new(),
             playback_mode: false,
             playback_playing: false,
             playback_position: 0,
             playback_duration_secs: 0.0,
+            playback_cursor_ms: 0.0,
+            playback_speed: 1.0,
+            last_csv_path: None,
+            marker_a: None,
+            marker_b: None,
+            interval_stats: None,
         }
     }
 
     /// Add a new CSI frame and maintain 60-second window
     /// إضافة إطار CSI جديد والحفاظ على نافذة 60 ثانية
+    ///
+    /// Frames tagged with a named source go to that source only.
     pub fn push_frame(&mut self, frame: CsiFrame) {
+        if frame.source.is_some() {
+            self.route_source_frame(frame);
+            return;
+        }
+        let frame = self.clean_frame(frame);
+
+        // A bandwidth switch mid-stream / تبديل عرض النطاق أثناء البث
+        let count = frame.subcarrier_count();
+        if let Some(previous) = self.frames.last().map(CsiFrame::subcarrier_count).filter(|&n| n != count) {
+            self.record_sc_change(previous, count, frame.timestamp);
+        }
+
         // Update max subcarrier count / تحديث أقصى عدد للناقلات الفرعية
         if frame.subcarrier_count() > self.max_sc {
             self.max_sc = frame.subcarrier_count();
         }
+        if !frame.meta.is_empty() {
+            self.radio_meta = frame.meta.clone();
+        }
 
         // Add the frame / إضافة الإطار
+        self.jitter.push(frame.timestamp);
+        self.frame_rate.record(Instant::now(), frame.subcarrier_count());
+        self.smoothed_rate.push(frame.timestamp);
+        self.dynamic.push(frame.timestamp, self.aggregation.apply(frame.analysis_mags()));
+        self.spectrum.push(&frame.mags);
         self.frames.push(frame);
+        self.frame_seq += 1;
+        self.session.frames += 1;
 
         // Remove frames older than 60 seconds / حذف الإطارات الأقدم من 60 ثانية
         self.cleanup_old_frames();
     }
 
-    /// Update detection history for charts
-    /// تحديث تاريخ الكشف للرسوم البيانية
+    /// Add the frames released by one playback tick (keeps last PLAYBACK_WINDOW_FRAMES)
+    /// إضافة إطارات نبضة تشغيل واحدة (يحتفظ بآخر PLAYBACK_WINDOW_FRAMES إطار)
+    ///
+    /// Trimming happens once per batch, so fast playback stays O(batch + window).
+    pub fn push_playback_batch(&mut self, batch: Vec<CsiFrame>) {
+        let (tagged, batch): (Vec<CsiFrame>, Vec<CsiFrame>) = batch.into_iter().partition(|f| f.source.is_some());
+        for frame in tagged {
+            self.route_source_frame(frame);
+        }
+        let batch: Vec<CsiFrame> = batch.into_iter().map(|f| self.clean_frame(f)).collect();
+        let now = Instant::now();
+        for frame in &batch {
+            self.frame_rate.record(now, frame.subcarrier_count());
+            if frame.subcarrier_count() > self.max_sc {
+                self.max_sc = frame.subcarrier_count();
+            }
+            self.dynamic.push(frame.timestamp, self.aggregation.apply(frame.analysis_mags()));
+            self.spectrum.push(&frame.mags);
+        }
+        self.frame_seq += batch.len() as u64;
+
+        // Only the tail of an oversized batch can stay visible
+        // يبقى ذيل الدفعة الكبيرة فقط مرئياً
+        let skip = batch.len().saturating_sub(PLAYBACK_WINDOW_FRAMES);
+        self.frames.extend(batch.into_iter().skip(skip));
+
+        let excess = self.frames.len().saturating_sub(PLAYBACK_WINDOW_FRAMES);
+        self.frames.drop_oldest(excess);
+    }
+
+    /// Hand a tagged frame to its source, adding sources a recording names
+    /// تسليم الإطار الموسوم لمصدره مع إضافة المصادر التي يذكرها التسجيل
+    fn route_source_frame(&mut self, frame: CsiFrame) {
+        if let Some(name) = frame.source.as_deref() {
+            self.sources.add(name, "log");
+        }
+        self.sources.route(frame.with_clean_mags(self.detector_windows.outlier_sigma));
+    }
+
+    /// Strip guard/DC subcarriers if enabled, remembering how many went, mark
+    /// the frame's streams and clamp its outliers once for the analysis
+    /// حذف ناقلات الحارس و DC إن كان مفعلاً مع تذكر عددها، وتحديد تدفقات الإطار
+    /// وقص قيمه الشاذة مرة واحدة للتحليل
+    fn clean_frame(&mut self, frame: CsiFrame) -> CsiFrame {
+        let mut frame = if self.strip_null_subcarriers {
+            let (frame, removed) = strip_null_subcarriers(frame);
+            self.null_subcarriers = removed;
+            frame
+        } else {
+            frame
+        };
+        frame.streams = stream_layout(frame.subcarrier_count(), &frame.meta, self.stream_split);
+        frame.with_clean_mags(self.detector_windows.outlier_sigma)
+    }
+
+    /// Scale the current detections and add them to the chart history
+    /// تحجيم نتائج الكشف الحالية وإضافتها لتاريخ الرسم البياني
     pub fn update_detection_history(&mut self) {
         const MAX_HISTORY: usize = 100;
         
+        // Scaled once; the panel and the chart both read these
+        // تحجم مرة واحدة؛ اللوحة والرسم يقرآن نفس القيم
+        self.display = self.display_scale.apply(&self.detections);
+        
         // Add current values to history / إضافة القيم الحالية للتاريخ
-        self.motion_history.push(self.detections.motion_value);
-        self.presence_history.push(self.detections.presence_value);
-        self.door_history.push(self.detections.door_value);
+        self.motion_history.push(self.display.motion);
+        self.presence_history.push(self.display.presence);
+        self.door_history.push(self.display.door);
         
         // Keep only last MAX_HISTORY values / الاحتفاظ بآخر MAX_HISTORY قيمة فقط
         if self.motion_history.len() > MAX_HISTORY {
@@ -219,16 +1171,187 @@ impl AppState {
         }
     }
 
-    /// Remove frames older than 60 seconds
-    /// حذف الإطارات الأقدم من 60 ثانية
-    fn cleanup_old_frames(&mut self) {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = now - 60_000; // 60 seconds in milliseconds
-        
-        self.frames.retain(|f| f.timestamp > cutoff);
+    /// Capture the current frames and detector values as the baseline
+    /// التقاط الإطارات وقيم الكاشفات الحالية كخط أساس
+    pub fn capture_baseline(&mut self) -> bool {
+        self.recalibrate(chrono::Utc::now().timestamp_millis())
     }
 
-    /// Get the last N frames for display
+    /// Replace the baseline with the current frames and log it
+    /// استبدال خط الأساس بالإطارات الحالية وتسجيل ذلك
+    pub fn recalibrate(&mut self, now: i64) -> bool {
+        if self.frames.is_empty() {
+            return false;
+        }
+        
+        let replaced = self.baseline.is_some();
+        self.baseline = Some(Baseline::capture(now, &self.frames, &self.detections));
+        self.event_log.push(now, format!(
+            "📐 Baseline {} from {} frames",
+            if replaced { "recalibrated" } else { "captured" },
+            self.frames.len()
+        ));
+        true
+    }
+
+    /// Is a scheduled recalibration due?
+    /// هل حان موعد إعادة المعايرة المجدولة؟
+    ///
+    /// Without a baseline the first one is taken once warmup has ended.
+    pub fn recalibration_due(&self, now: i64) -> bool {
+        let Some(every_secs) = self.recalibrate_every_secs else {
+            return false;
+        };
+        if self.frames.is_empty() {
+            return false;
+        }
+        match &self.baseline {
+            Some(baseline) => now - baseline.timestamp >= every_secs as i64 * 1000,
+            None => self.warmup_started_ms.is_some() && self.warmup_remaining_secs(now).is_none(),
+        }
+    }
+
+    /// Start the warmup period (serial just connected)
+    /// بدء فترة الإحماء (تم الاتصال للتو)
+    pub fn start_warmup(&mut self, now: i64) {
+        self.warmup_started_ms = Some(now);
+    }
+
+    /// Note a subcarrier count change and clear the detector history
+    /// تسجيل تغير عدد الناقلات ومسح تاريخ الكاشفات
+    ///
+    /// The charted history and the median / spectrum windows restart so the
+    /// jump does not show up as a spike; frames are kept.
+    fn record_sc_change(&mut self, from: usize, to: usize, timestamp: i64) {
+        self.last_sc_change = Some(timestamp);
+        self.sc_change = (from, to);
+        self.status_message = format!("⚠️ Subcarrier count changed {} → {}, detector history cleared", from, to);
+
+        self.motion_history.clear();
+        self.presence_history.clear();
+        self.door_history.clear();
+        self.dynamic.reset();
+        self.spectrum.reset();
+    }
+
+    /// Warning for a subcarrier count change in the last SC_CHANGE_WARNING_MS
+    /// تحذير لتغير عدد الناقلات خلال آخر SC_CHANGE_WARNING_MS
+    pub fn sc_change_warning(&self, now: i64) -> Option<String> {
+        let changed = self.last_sc_change?;
+        let (from, to) = self.sc_change;
+        (now - changed < SC_CHANGE_WARNING_MS).then(|| format!("⚠️ Subcarriers changed {} → {}", from, to))
+    }
+
+    /// Write the current detections to the detection log, if enabled
+    /// كتابة نتائج الكشف الحالية إلى سجل الكشف إن كان مفعلاً
+    ///
+    /// Skipped while detections are settling, like the transition log.
+    pub fn log_detection_sample(&mut self, now: i64) {
+        if self.detections_settling(now) {
+            return;
+        }
+        let Some(logger) = self.detection_logger.as_mut() else { return };
+        if let Err(e) = logger.log_sample(now, &self.detections) {
+            self.detection_logger = None;
+            self.status_message = e.status_message();
+        }
+    }
+
+    /// Seconds of warmup left, or None once detections are trusted
+    /// الثواني المتبقية من الإحماء، أو None عند الوثوق بالكشف
+    pub fn warmup_remaining_secs(&self, now: i64) -> Option<f64> {
+        let started = self.warmup_started_ms?;
+        let remaining = self.warmup_secs - (now - started) as f64 / 1000.0;
+        (remaining > 0.0).then_some(remaining)
+    }
+
+    /// Is playback still settling after a seek?
+    /// هل ما زال التشغيل يستقر بعد الانتقال؟
+    pub fn seek_settling(&self, now: Instant) -> bool {
+        self.last_seek_instant.is_some_and(|seeked| now.duration_since(seeked) < self.seek_settle)
+    }
+
+    /// Are detections still untrusted (warming up, or settling after a seek)?
+    /// هل الكشف غير موثوق بعد (إحماء أو استقرار بعد الانتقال)؟
+    pub fn detections_settling(&self, now: i64) -> bool {
+        self.warmup_remaining_secs(now).is_some() || self.seek_settling(Instant::now())
+    }
+
+    /// Log detector state changes compared to the previous results
+    /// تسجيل تغيرات حالة الكاشفات مقارنة بالنتائج السابقة
+    ///
+    /// Nothing is logged while warming up or while playback settles after a seek.
+    pub fn log_detection_changes(&mut self, previous: &DetectionResults, now: i64) {
+        let current = self.detections.clone();
+        self.event_log.tick(now);
+        
+        if self.detections_settling(now) {
+            return;
+        }
+        self.session.record_detections(previous, &current);
+        
+        let changes = [
+            (previous.motion_detected, current.motion_detected, "🔴 Motion detected", "🟢 Motion stopped"),
+            (previous.human_present, current.human_present, "🔴 Human present", "🟢 Human left"),
+            (previous.door_open, current.door_open, "🔴 Door opened", "🟢 Door closed"),
+        ];
+        
+        for (before, after, on_msg, off_msg) in changes {
+            if before != after {
+                self.event_log.push(now, if after { on_msg } else { off_msg });
+            }
+        }
+    }
+
+    /// Remove frames older than 60 seconds
+    /// حذف الإطارات الأقدم من 60 ثانية
+    ///
+    /// The window ends now, or at the newest frame while a file is followed
+    /// (see `WindowClock`). Frames are in arrival order, so eviction stops at
+    /// the first one inside the window.
+    fn cleanup_old_frames(&mut self) {
+        let newest = match self.frames.last() {
+            Some(frame) => frame.timestamp,
+            None => return,
+        };
+        let end = match self.window_clock {
+            WindowClock::Wall => chrono::Utc::now().timestamp_millis(),
+            WindowClock::NewestFrame => newest,
+        };
+        let cutoff = end - self.window_ms();
+        
+        self.frames.drop_until(cutoff);
+    }
+
+    /// Live frame window in milliseconds / نافذة الإطارات المباشرة بالميلي ثانية
+    pub fn window_ms(&self) -> i64 {
+        self.window_secs as i64 * 1000
+    }
+
+    /// Set the live frame window; a shorter window prunes at once
+    /// تعيين نافذة الإطارات المباشرة؛ النافذة الأقصر تحذف فوراً
+    pub fn set_window_secs(&mut self, secs: u64) {
+        self.window_secs = secs.max(1);
+        // Playback keeps its own fixed frame window / التشغيل له نافذة إطارات ثابتة
+        if !self.playback_mode {
+            self.cleanup_old_frames();
+        }
+    }
+
+    /// Step the live frame window through WINDOW_SECS_STEPS
+    /// تغيير نافذة الإطارات المباشرة عبر WINDOW_SECS_STEPS
+    pub fn step_window(&mut self, grow: bool) {
+        let next = if grow {
+            WINDOW_SECS_STEPS.iter().copied().find(|&s| s > self.window_secs)
+        } else {
+            WINDOW_SECS_STEPS.iter().rev().copied().find(|&s| s < self.window_secs)
+        };
+        if let Some(secs) = next {
+            self.set_window_secs(secs);
+        }
+    }
+
+    /// Get the last N frames for display
     /// الحصول على آخر N إطار للعرض
     pub fn get_last_frames(&self, count: usize) -> &[CsiFrame] {
         let len = self.frames.len();
@@ -239,6 +1362,344 @@ impl AppState {
         }
     }
 
+    /// Grow or shrink the live chart window (×2 / ÷2)
+    /// تكبير أو تصغير نافذة الرسم المباشر
+    ///
+    /// Clamped to the retention buffer, but never below the default window.
+    pub fn zoom_live_chart(&mut self, grow: bool) {
+        let requested = if grow {
+            self.live_chart_samples.saturating_mul(2)
+        } else {
+            self.live_chart_samples / 2
+        };
+        let max = self.frames.len().max(DEFAULT_CHART_SAMPLES);
+        self.live_chart_samples = requested.clamp(MIN_CHART_SAMPLES, max);
+    }
+
+    /// Step the chart smoothing window through SMOOTHING_STEPS
+    /// تغيير نافذة تنعيم الرسم عبر SMOOTHING_STEPS
+    pub fn step_smoothing(&mut self, up: bool) {
+        let current = SMOOTHING_STEPS.iter().position(|&n| n >= self.smoothing_window).unwrap_or(0);
+        let next = if up {
+            (current + 1).min(SMOOTHING_STEPS.len() - 1)
+        } else {
+            current.saturating_sub(1)
+        };
+        self.smoothing_window = SMOOTHING_STEPS[next];
+    }
+
+    /// Cycle the CSI chart: magnitude → dynamic component → spectrum → stability
+    /// تدوير رسم CSI: السعة ← المكون الديناميكي ← الطيف ← الاستقرار
+    pub fn toggle_chart_mode(&mut self) {
+        self.chart_mode = match self.chart_mode {
+            ChartMode::Magnitude => ChartMode::Dynamic,
+            ChartMode::Dynamic => ChartMode::Spectrum,
+            ChartMode::Spectrum => ChartMode::Stability,
+            ChartMode::Stability => ChartMode::Magnitude,
+        };
+        // Bounds of one chart mean nothing in another / حدود وضع لا تعني شيئاً في آخر
+        self.locked_bounds = None;
+    }
+
+    /// Freeze the CSI chart at its current bounds, or resume auto-scaling
+    /// تجميد رسم CSI عند حدوده الحالية أو استئناف التحجيم التلقائي
+    pub fn toggle_bounds_lock(&mut self) -> bool {
+        self.locked_bounds = match self.locked_bounds {
+            Some(_) => None,
+            None => Some(self.chart_auto_bounds),
+        };
+        self.locked_bounds.is_some()
+    }
+
+    /// Freeze the charts on a snapshot of the current data, or go back to live
+    /// تجميد الرسوم على لقطة من البيانات الحالية أو العودة للبث المباشر
+    ///
+    /// Only rendering is affected: frames, detectors and logging keep running.
+    pub fn toggle_view_frozen(&mut self) -> bool {
+        self.view_frozen = !self.view_frozen;
+        self.view_snapshot = self.view_frozen.then(|| ViewSnapshot {
+            frames: self.frames.to_vec(),
+            dynamic: self.dynamic.clone(),
+            spectrum: self.spectrum.clone(),
+            histories: [
+                self.motion_history.clone(),
+                self.presence_history.clone(),
+                self.door_history.clone(),
+            ],
+        });
+        self.view_frozen
+    }
+
+    /// Last `count` frames the charts draw (the snapshot while frozen)
+    /// آخر `count` إطار ترسمه الرسوم (اللقطة أثناء التجميد)
+    pub fn chart_frames(&self, count: usize) -> &[CsiFrame] {
+        match self.view_snapshot.as_ref().filter(|_| self.view_frozen) {
+            Some(snapshot) => &snapshot.frames[snapshot.frames.len().saturating_sub(count)..],
+            None => self.get_last_frames(count),
+        }
+    }
+
+    /// Dynamic component the charts draw / المكون الديناميكي الذي ترسمه الرسوم
+    pub fn chart_dynamic(&self) -> &DynamicFilter {
+        match self.view_snapshot.as_ref().filter(|_| self.view_frozen) {
+            Some(snapshot) => &snapshot.dynamic,
+            None => &self.dynamic,
+        }
+    }
+
+    /// Averaged spectrum the charts draw / الطيف المتوسط الذي ترسمه الرسوم
+    pub fn chart_spectrum(&self) -> &SpectrumAverager {
+        match self.view_snapshot.as_ref().filter(|_| self.view_frozen) {
+            Some(snapshot) => &snapshot.spectrum,
+            None => &self.spectrum,
+        }
+    }
+
+    /// Motion, presence and door histories the detectors chart draws
+    /// تواريخ الحركة والوجود والباب التي يرسمها رسم الكاشفات
+    pub fn chart_histories(&self) -> [&[f64]; 3] {
+        match self.view_snapshot.as_ref().filter(|_| self.view_frozen) {
+            Some(snapshot) => [&snapshot.histories[0], &snapshot.histories[1], &snapshot.histories[2]],
+            None => [&self.motion_history, &self.presence_history, &self.door_history],
+        }
+    }
+
+    /// Cycle the magnitude aggregation mode (mean → median → trimmed mean)
+    /// تدوير وضع تجميع السعات
+    ///
+    /// The dynamic filter restarts so it doesn't mix two modes.
+    pub fn cycle_aggregation(&mut self) {
+        self.aggregation = self.aggregation.next();
+        self.dynamic.reset();
+    }
+
+    /// Cycle the forced CSI format: Auto → Real/Imag → Amplitude → Auto
+    /// تدوير الصيغة المفروضة: تلقائي ← حقيقي/تخيلي ← سعة ← تلقائي
+    pub fn cycle_forced_format(&mut self) {
+        self.forced_format = match self.forced_format {
+            None => Some(CsiFormat::RealImag),
+            Some(CsiFormat::RealImag) => Some(CsiFormat::AmplitudeOnly),
+            Some(_) => None,
+        };
+    }
+
+    /// Cycle the plotted stream: all → 0 → 1 → … → all
+    /// تدوير التدفق المرسوم: الكل ← 0 ← 1 ← … ← الكل
+    pub fn cycle_stream(&mut self) {
+        let count = self.frames.last().map_or(1, |f| f.stream_count());
+        self.selected_stream = match self.selected_stream {
+            None if count > 1 => Some(0),
+            Some(i) if i + 1 < count => Some(i + 1),
+            _ => None,
+        };
+    }
+
+    /// Label of the plotted stream / تسمية التدفق المرسوم
+    pub fn stream_label(&self) -> String {
+        let count = self.frames.last().map_or(1, |f| f.stream_count());
+        match self.selected_stream {
+            Some(i) => format!("Stream {}/{}", i + 1, count),
+            None if count > 1 => format!("All {} streams", count),
+            None => "Single stream".to_string(),
+        }
+    }
+
+    /// Subcarriers of `frame` in the selected stream / ناقلات الإطار في التدفق المختار
+    pub fn selected_range(&self, frame: &CsiFrame) -> Range<usize> {
+        match self.selected_stream {
+            Some(i) => frame.stream_range(i),
+            None => 0..frame.subcarrier_count(),
+        }
+    }
+
+    /// Frames the detectors see: the selected stream only, if one is chosen
+    /// الإطارات التي تراها الكاشفات: التدفق المختار فقط إن وُجد
+    pub fn detection_frames(&self) -> Cow<'_, [CsiFrame]> {
+        match self.selected_stream {
+            Some(i) => Cow::Owned(
+                self.frames
+                    .iter()
+                    .map(|f| f.stream_frame(i).with_clean_mags(self.detector_windows.outlier_sigma))
+                    .collect(),
+            ),
+            None => Cow::Borrowed(&self.frames),
+        }
+    }
+
+    /// Label of the parser format mode / تسمية وضع صيغة المحلل
+    pub fn format_mode_label(&self) -> &'static str {
+        match self.forced_format {
+            None => "Auto",
+            Some(CsiFormat::RealImag) => "Real/Imag (forced)",
+            Some(_) => "Amplitude (forced)",
+        }
+    }
+
+    /// Cycle spectrum averaging: off → 8 → 16 → 32 → off
+    /// تدوير متوسط الطيف: إيقاف ← 8 ← 16 ← 32 ← إيقاف
+    pub fn cycle_spectrum_average(&mut self) {
+        let current = self.spectrum.window();
+        let next = if !self.spectrum_averaging {
+            Some(SPECTRUM_AVERAGE_STEPS[0])
+        } else {
+            SPECTRUM_AVERAGE_STEPS.iter().copied().find(|&n| n > current)
+        };
+        
+        match next {
+            Some(n) => {
+                self.spectrum.set_window(n, &self.frames);
+                self.spectrum_averaging = true;
+            }
+            None => self.spectrum_averaging = false,
+        }
+    }
+
+    /// Record a frame's transmitter and check it against the MAC filter
+    /// تسجيل مرسل الإطار والتحقق منه مقابل مرشح MAC
+    ///
+    /// Frames without a MAC are always accepted.
+    pub fn accept_mac(&mut self, mac: Option<&str>, rssi: Option<i32>, now: i64) -> bool {
+        let Some(mac) = mac else { return true };
+        self.mac_stats.record(mac, rssi, now);
+        self.mac_stats.prune(now);
+        self.mac_filter.as_deref().is_none_or(|filter| filter == mac)
+    }
+
+    /// Close the CSV recording (flushed on drop); its path if one was open
+    /// إغلاق تسجيل CSV (يُفرغ عند الإسقاط)؛ مساره إن كان مفتوحاً
+    pub fn stop_recording(&mut self) -> Option<PathBuf> {
+        self.csv_logger.take().map(|logger| logger.file_path())
+    }
+
+    /// Start a new measurement period; frames and detections are kept
+    /// بدء فترة قياس جديدة مع الإبقاء على الإطارات والكشوفات
+    pub fn reset_session_stats(&mut self) {
+        self.session = SessionStats::new(Instant::now());
+        self.queue_dropped = 0;
+    }
+
+    /// Zero the serial block counters / تصفير عدادات كتل التسلسل
+    pub fn reset_parse_stats(&mut self) {
+        self.parse_stats = ParseStats::default();
+        self.tiny_frames_rejected = 0;
+        self.contaminated_frames = 0;
+    }
+
+    /// Check a received frame against the previous one when de-duplication is on
+    /// مقارنة الإطار المستلم بالسابق عند تفعيل إزالة التكرار
+    ///
+    /// True (and counted) when the sender matches and every magnitude is
+    /// within DUPLICATE_EPSILON; the frame should then be skipped.
+    pub fn is_duplicate_frame(&mut self, mac: Option<&str>, mags: &[f64]) -> bool {
+        if !self.drop_duplicate_frames {
+            return false;
+        }
+        let repeat = self.last_received.as_ref().is_some_and(|(last_mac, last_mags)| {
+            last_mac.as_deref() == mac
+                && last_mags.len() == mags.len()
+                && last_mags.iter().zip(mags).all(|(a, b)| (a - b).abs() <= DUPLICATE_EPSILON)
+        });
+        if repeat {
+            self.duplicates_dropped += 1;
+        } else {
+            self.last_received = Some((mac.map(str::to_string), mags.to_vec()));
+        }
+        repeat
+    }
+
+    /// Move the MAC table selection by `delta` rows
+    /// تحريك التحديد في جدول MAC
+    pub fn move_mac_selection(&mut self, delta: isize) {
+        let last = self.mac_stats.len().saturating_sub(1);
+        self.mac_table_selected = self.mac_table_selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Lock onto the selected MAC, or clear the filter if it is already the target
+    /// تثبيت المرشح على العنوان المحدد، أو إلغاؤه إذا كان هو الهدف
+    ///
+    /// Frames from the previous target are dropped so detectors start clean.
+    pub fn select_mac_filter(&mut self) {
+        let Some(mac) = self
+            .mac_stats
+            .sorted()
+            .get(self.mac_table_selected)
+            .map(|(mac, _)| mac.to_string())
+        else {
+            return;
+        };
+
+        self.mac_filter = if self.mac_filter.as_deref() == Some(mac.as_str()) {
+            None
+        } else {
+            Some(mac)
+        };
+        self.clear_frames();
+    }
+
+    /// Close the port picker, using its highlighted port for the next start
+    /// إغلاق نافذة المنفذ واستخدام المنفذ المحدد في البدء التالي
+    pub fn choose_port(&mut self) -> Option<String> {
+        let name = self.port_picker.take()?.selected()?.name.clone();
+        self.port_name = name.clone();
+        self.port_chosen = true;
+        Some(name)
+    }
+
+    /// Cycle the UI refresh rate through REFRESH_RATE_STEPS
+    /// تدوير معدل تحديث الواجهة
+    pub fn cycle_refresh_rate(&mut self) {
+        self.ui_refresh_fps = REFRESH_RATE_STEPS
+            .iter()
+            .copied()
+            .find(|&fps| fps > self.ui_refresh_fps)
+            .unwrap_or(REFRESH_RATE_STEPS[0]);
+    }
+
+    /// Cycle the serial baud rate through BAUD_RATE_STEPS
+    /// تدوير معدل البود التسلسلي
+    pub fn cycle_baud_rate(&mut self) {
+        self.baud_rate = BAUD_RATE_STEPS
+            .iter()
+            .copied()
+            .find(|&baud| baud > self.baud_rate)
+            .unwrap_or(BAUD_RATE_STEPS[0]);
+    }
+
+    /// Is the serial receiver connected and reading? / هل المستقبل متصل ويقرأ؟
+    pub fn receiver_active(&self) -> bool {
+        self.receiver == ReceiverState::Connected
+    }
+
+    /// Quality of the recent input against the configured floors
+    /// جودة الإدخال الحديث مقارنة بالحدود المضبوطة
+    pub fn detection_quality(&self) -> Quality {
+        assess(&self.frames, &self.quality_floors)
+    }
+
+    /// Copy a changed status message into the history
+    /// نسخ رسالة الحالة المتغيرة إلى السجل
+    ///
+    /// Progress updates that differ only in their numbers ("📥 Receiving
+    /// CSI: 64 subcarriers, 12 frames") replace the previous entry.
+    pub fn note_status_message(&mut self, now_ms: i64) {
+        if self.status_message == self.noted_status {
+            return;
+        }
+        let stem = |m: &str| m.split(|c: char| c.is_ascii_digit()).next().unwrap_or("").to_string();
+        let time = Local
+            .timestamp_millis_opt(now_ms)
+            .single()
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        let entry = format!("{} {}", time, self.status_message);
+        if !self.message_history.is_empty() && stem(&self.status_message) == stem(&self.noted_status) {
+            self.message_history.replace_last(entry);
+        } else {
+            self.message_history.push(entry);
+        }
+        self.noted_status = self.status_message.clone();
+    }
+
     /// Get total frame count
     /// الحصول على العدد الإجمالي للإطارات
     pub fn frame_count(&self) -> usize {
@@ -249,10 +1710,18 @@ impl AppState {
     /// مسح جميع الإطارات
     pub fn clear_frames(&mut self) {
         self.frames.clear();
+        self.sources.clear_frames();
         self.max_sc = 0;
+        self.radio_meta = CsiMeta::default();
+        self.null_subcarriers = 0;
         self.motion_history.clear();
         self.presence_history.clear();
         self.door_history.clear();
+        self.dynamic.reset();
+        self.spectrum.reset();
+        self.jitter.reset();
+        self.frame_rate.reset();
+        self.smoothed_rate.reset();
     }
 
     // ═══════════════════════════════════════════════════════════════════════
@@ -269,6 +1738,10 @@ impl AppState {
         self.playback_mode = true;
         self.playback_playing = true;
         self.playback_position = 0;
+        self.playback_cursor_ms = 0.0;
+        self.marker_a = None;
+        self.marker_b = None;
+        self.interval_stats = None;
         
         // Calculate duration from timestamps
         // حساب المدة من الطوابع الزمنية
@@ -281,6 +1754,8 @@ impl AppState {
         self.motion_history.clear();
         self.presence_history.clear();
         self.door_history.clear();
+        self.dynamic.reset();
+        self.spectrum.reset();
     }
 
     /// Toggle playback play/pause
@@ -297,6 +1772,7 @@ impl AppState {
         self.playback_mode = false;
         self.playback_playing = false;
         self.playback_position = 0;
+        self.playback_cursor_ms = 0.0;
     }
 
     /// Seek to a specific second in playback
@@ -315,6 +1791,7 @@ impl AppState {
             .iter()
             .position(|f| f.timestamp >= target_ts)
             .unwrap_or(0);
+        self.playback_cursor_ms = (self.loaded_frames[self.playback_position].timestamp - first_ts) as f64;
         
         // Reset display frames from this position
         // إعادة تعيين إطارات العرض من هذا الموقع
@@ -322,6 +1799,9 @@ impl AppState {
         self.motion_history.clear();
         self.presence_history.clear();
         self.door_history.clear();
+        self.dynamic.reset();
+        self.spectrum.reset();
+        self.last_seek_instant = Some(Instant::now());
     }
 
     /// Seek forward/backward by seconds
@@ -345,28 +1825,164 @@ impl AppState {
         (current_ts - first_ts) as f64 / 1000.0
     }
 
-    /// Advance playback by one frame
-    /// تقديم التشغيل بإطار واحد
-    pub fn advance_playback(&mut self) -> Option<CsiFrame> {
+    /// Advance the playback clock and return all frames that became due
+    /// تقديم ساعة التشغيل وإرجاع جميع الإطارات المستحقة
+    ///
+    /// Frames are released by their recorded timestamps, so playback speed
+    /// does not depend on how often the main loop ticks. The clock runs at
+    /// `playback_speed`; above 1x at least one frame is released per tick so
+    /// sparse recordings don't stall.
+    pub fn advance_playback(&mut self, elapsed_ms: f64) -> Vec<CsiFrame> {
+        let mut due = Vec::new();
+        
         if !self.playback_mode || !self.playback_playing {
-            return None;
+            return due;
         }
         
         if self.playback_position >= self.loaded_frames.len() {
             // Reached end, loop back or stop
             // وصلنا للنهاية، إعادة من البداية أو إيقاف
             self.playback_position = 0;
+            self.playback_cursor_ms = 0.0;
             self.frames.clear();
             self.motion_history.clear();
             self.presence_history.clear();
             self.door_history.clear();
-            return None;
+            return due;
+        }
+        
+        // Long stalls (file dialog, resize) should not skip ahead
+        // التوقفات الطويلة يجب ألا تسبب قفزة للأمام
+        self.playback_cursor_ms += elapsed_ms.clamp(0.0, MAX_PLAYBACK_STEP_MS) * self.playback_speed;
+        
+        let first_ts = self.loaded_frames[0].timestamp;
+        while let Some(frame) = self.loaded_frames.get(self.playback_position) {
+            if (frame.timestamp - first_ts) as f64 > self.playback_cursor_ms {
+                break;
+            }
+            due.push(frame.clone());
+            self.playback_position += 1;
         }
         
-        let frame = self.loaded_frames[self.playback_position].clone();
-        self.playback_position += 1;
+        // Sparse data at high speed: jump when the next frame is more than a full
+        // step away, otherwise the cursor's scaled time releases it
+        // بيانات متفرقة بسرعة عالية: القفز فقط إذا كان الإطار التالي أبعد من خطوة كاملة
+        if due.is_empty() && self.playback_speed > 1.0 && elapsed_ms > 0.0 {
+            let next = self.loaded_frames.get(self.playback_position);
+            let gap_ms = next.map_or(0.0, |f| (f.timestamp - first_ts) as f64 - self.playback_cursor_ms);
+            if let Some(frame) = next.filter(|_| gap_ms > MAX_PLAYBACK_STEP_MS * self.playback_speed) {
+                self.playback_cursor_ms = (frame.timestamp - first_ts) as f64;
+                due.push(frame.clone());
+                self.playback_position += 1;
+            }
+        }
         
-        Some(frame)
+        due
+    }
+
+    /// Step the charted subcarrier: aggregate → 0 → 1 → … → aggregate (or backwards)
+    /// تحريك الناقل المرسوم: التجميع ← 0 ← 1 ← … ← التجميع (أو عكسياً)
+    pub fn step_selected_subcarrier(&mut self, forward: bool) {
+        let count = self.frames.last().map_or(0, |f| f.subcarrier_count());
+        // Clamp a selection left over from wider frames / حصر اختيار متبقٍ من إطارات أعرض
+        let current = self.selected_subcarrier.map(|k| k.min(count.saturating_sub(1)));
+        self.selected_subcarrier = match (current, forward) {
+            _ if count == 0 => None,
+            (None, true) => Some(0),
+            (None, false) => Some(count - 1),
+            (Some(k), true) => (k + 1 < count).then_some(k + 1),
+            (Some(k), false) => k.checked_sub(1),
+        };
+    }
+
+    /// Standard deviation of each subcarrier's magnitude over the frame window
+    /// الانحراف المعياري لسعة كل ناقل فرعي عبر نافذة الإطارات
+    ///
+    /// Frames of differing lengths only count toward the indices they have.
+    pub fn subcarrier_stability(&self) -> Vec<f64> {
+        let width = self.frames.iter().map(|f| f.mags.len()).max().unwrap_or(0);
+        let mut count = vec![0usize; width];
+        let mut sum = vec![0.0; width];
+        let mut sum_sq = vec![0.0; width];
+        for frame in self.frames.iter() {
+            for (k, &mag) in frame.mags.iter().enumerate() {
+                count[k] += 1;
+                sum[k] += mag;
+                sum_sq[k] += mag * mag;
+            }
+        }
+        (0..width)
+            .map(|k| {
+                let n = count[k] as f64;
+                let mean = sum[k] / n;
+                (sum_sq[k] / n - mean * mean).max(0.0).sqrt()
+            })
+            .collect()
+    }
+
+    /// Magnitude-chart value of a frame: the selected subcarrier, or the
+    /// aggregate when none is selected or the frame is too short
+    /// قيمة الإطار في رسم السعة: الناقل المختار، أو التجميع إن لم يُختر أو كان الإطار قصيراً
+    pub fn chart_value(&self, frame: &CsiFrame) -> f64 {
+        match self.selected_subcarrier.and_then(|k| frame.mags.get(k)) {
+            Some(&mag) => mag,
+            None => self.aggregation.apply(frame.analysis_mags()),
+        }
+    }
+
+    /// What the magnitude chart plots ("SC #k" or "Average") / ما يرسمه رسم السعة
+    pub fn subcarrier_label(&self) -> String {
+        match self.selected_subcarrier {
+            Some(k) => format!("SC #{}", k),
+            None => "Average".to_string(),
+        }
+    }
+
+    /// Halve (or double) the playback speed within MIN/MAX_PLAYBACK_SPEED
+    /// تنصيف (أو مضاعفة) سرعة التشغيل ضمن الحدود
+    pub fn step_playback_speed(&mut self, faster: bool) {
+        let speed = if faster { self.playback_speed * 2.0 } else { self.playback_speed / 2.0 };
+        self.playback_speed = speed.clamp(MIN_PLAYBACK_SPEED, MAX_PLAYBACK_SPEED);
+    }
+
+    /// Put the A (or B) marker on the current playback frame; returns its second
+    /// وضع العلامة A (أو B) على إطار التشغيل الحالي؛ تُرجع الثانية
+    pub fn set_marker(&mut self, is_a: bool) -> Option<f64> {
+        if !self.playback_mode || self.loaded_frames.is_empty() {
+            return None;
+        }
+        // Last frame shown, or the seek target before anything was shown
+        // آخر إطار معروض، أو هدف الانتقال قبل عرض أي شيء
+        let index = if self.frames.is_empty() {
+            self.playback_position
+        } else {
+            self.playback_position.saturating_sub(1)
+        }
+        .min(self.loaded_frames.len() - 1);
+
+        if is_a {
+            self.marker_a = Some(index);
+        } else {
+            self.marker_b = Some(index);
+        }
+        let first_ts = self.loaded_frames[0].timestamp;
+        Some((self.loaded_frames[index].timestamp - first_ts) as f64 / 1000.0)
+    }
+
+    /// Compute statistics between the markers (in either order)
+    /// حساب الإحصائيات بين العلامتين (بأي ترتيب)
+    pub fn compute_interval_stats(&mut self) -> bool {
+        let (Some(a), Some(b)) = (self.marker_a, self.marker_b) else {
+            return false;
+        };
+        self.interval_stats = analyze_interval(
+            &self.loaded_frames,
+            a.min(b)..=a.max(b),
+            self.aggregation,
+            &self.detector_windows,
+            PLAYBACK_WINDOW_FRAMES,
+        );
+        self.interval_stats.is_some()
     }
 
     /// Get playback progress as percentage (0.0 - 1.0)
@@ -398,3 +2014,690 @@ pub type SharedState = Arc<Mutex<AppState>>;
 pub fn create_shared_state() -> SharedState {
     Arc::new(Mutex::new(AppState::new()))
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_frame(timestamp: i64) -> CsiFrame {
+        CsiFrame::new(timestamp, vec![10.0], vec![(10, 0)], CsiFormat::AmplitudeOnly, None)
+    }
+
+    /// State whose live window ends at the newest frame, for made-up timestamps
+    /// حالة تنتهي نافذتها عند أحدث إطار، للطوابع الزمنية المصطنعة
+    fn synthetic_clock_state() -> AppState {
+        let mut state = AppState::new();
+        state.window_clock = WindowClock::NewestFrame;
+        state
+    }
+
+    /// Loaded frames every 100ms for one second
+    fn playback_state() -> AppState {
+        let mut state = AppState::new();
+        state.loaded_frames = (0..=10).map(|i| create_test_frame(1_000 + i * 100)).collect();
+        state.start_playback();
+        state
+    }
+
+    #[test]
+    fn test_playback_pacing_independent_of_tick() {
+        // Same wall time with fast and slow ticks releases the same frames
+        // نفس الوقت الفعلي مع نبضات سريعة وبطيئة يطلق نفس الإطارات
+        let mut fast = playback_state();
+        let fast_count: usize = (0..25).map(|_| fast.advance_playback(20.0).len()).sum();
+
+        let mut slow = playback_state();
+        let slow_count: usize = (0..2).map(|_| slow.advance_playback(250.0).len()).sum();
+
+        assert_eq!(fast_count, 6); // frames at 0..=500ms
+        assert_eq!(fast_count, slow_count);
+        assert_eq!(fast.playback_position, slow.playback_position);
+    }
+
+    #[test]
+    fn test_playback_speed() {
+        let mut state = AppState::new();
+        // 10 frames, 100 ms apart / عشرة إطارات بفاصل 100 مللي ثانية
+        state.loaded_frames = (0..10).map(|i| create_test_frame(i * 100)).collect();
+        state.playback_mode = true;
+        state.playback_playing = true;
+
+        // 2x: 200 ms of recording per 100 ms tick / ضعف السرعة
+        state.step_playback_speed(true);
+        assert_eq!(state.playback_speed, 2.0);
+        assert_eq!(state.advance_playback(100.0).len(), 3); // 0, 100, 200
+        assert_eq!(state.advance_playback(100.0).len(), 2); // 300, 400
+
+        // 0.5x: a 100 ms tick covers only 50 ms / نصف السرعة
+        state.step_playback_speed(false);
+        state.step_playback_speed(false);
+        assert_eq!(state.playback_speed, 0.5);
+        assert_eq!(state.advance_playback(100.0).len(), 0);
+        assert_eq!(state.advance_playback(100.0).len(), 1);
+
+        // Clamped to 0.25x-8x / محدودة بين 0.25 و 8
+        for _ in 0..10 {
+            state.step_playback_speed(false);
+        }
+        assert_eq!(state.playback_speed, MIN_PLAYBACK_SPEED);
+        for _ in 0..10 {
+            state.step_playback_speed(true);
+        }
+        assert_eq!(state.playback_speed, MAX_PLAYBACK_SPEED);
+    }
+
+    #[test]
+    fn test_fast_playback_follows_speed_on_short_ticks() {
+        let mut state = AppState::new();
+        // 10 fps recording at 2x, driven by 20 ms UI ticks / تسجيل 10 إطارات/ث بضعف السرعة
+        state.loaded_frames = (0..100).map(|i| create_test_frame(i * 100)).collect();
+        state.playback_mode = true;
+        state.playback_playing = true;
+        state.playback_speed = 2.0;
+
+        // One second of wall time: ~one frame per 50 ms, not one per tick
+        // ثانية واحدة: إطار كل 50 مللي ثانية تقريباً وليس إطاراً لكل نبضة
+        let released: usize = (0..50).map(|_| state.advance_playback(20.0).len()).sum();
+        assert!((20..=21).contains(&released), "released {} frames", released);
+        assert!((state.playback_cursor_ms - 2_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sparse_playback_advances_each_tick() {
+        let mut state = AppState::new();
+        // One frame per minute / إطار واحد كل دقيقة
+        state.loaded_frames = (0..4).map(|i| create_test_frame(i * 60_000)).collect();
+        state.playback_mode = true;
+        state.playback_playing = true;
+        state.playback_speed = 4.0;
+
+        for expected in 1..=4 {
+            assert_eq!(state.advance_playback(50.0).len(), 1);
+            assert_eq!(state.playback_position, expected);
+        }
+        // The clock followed the jumps / الساعة تبعت القفزات
+        assert_eq!(state.playback_cursor_ms, 180_000.0);
+    }
+
+    #[test]
+    fn test_playback_paused_and_clamped() {
+        let mut state = playback_state();
+        state.playback_playing = false;
+        assert!(state.advance_playback(1_000.0).is_empty());
+
+        // A long stall only advances by MAX_PLAYBACK_STEP_MS
+        // توقف طويل يتقدم فقط بمقدار MAX_PLAYBACK_STEP_MS
+        state.playback_playing = true;
+        assert_eq!(state.advance_playback(10_000.0).len(), 6);
+    }
+
+    #[test]
+    fn test_live_chart_zoom_clamping() {
+        let mut state = AppState::new();
+
+        // Small buffer: can't grow past the default window
+        state.zoom_live_chart(true);
+        assert_eq!(state.live_chart_samples, DEFAULT_CHART_SAMPLES);
+
+        for _ in 0..10 {
+            state.zoom_live_chart(false);
+        }
+        assert_eq!(state.live_chart_samples, MIN_CHART_SAMPLES);
+
+        // Larger buffer: grows up to the buffer length
+        state.frames = (0..300).map(create_test_frame).collect();
+        for _ in 0..10 {
+            state.zoom_live_chart(true);
+        }
+        assert_eq!(state.live_chart_samples, 300);
+        assert_eq!(state.get_last_frames(state.live_chart_samples).len(), 300);
+    }
+
+    #[test]
+    fn test_subcarrier_stability() {
+        let mut state = synthetic_clock_state();
+        assert!(state.subcarrier_stability().is_empty());
+
+        // Subcarrier 0 steady, 1 swings by ±2, 2 only in the longer frames
+        // الناقل 0 ثابت، والناقل 1 يتأرجح، والناقل 2 في الإطارات الأطول فقط
+        state.push_frame(CsiFrame::new(0, vec![5.0, 8.0, 1.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.push_frame(CsiFrame::new(100, vec![5.0, 12.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.push_frame(CsiFrame::new(200, vec![5.0, 8.0, 3.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.push_frame(CsiFrame::new(300, vec![5.0, 12.0], vec![], CsiFormat::AmplitudeOnly, None));
+
+        assert_eq!(state.subcarrier_stability(), vec![0.0, 2.0, 1.0]);
+    }
+
+    #[test]
+    fn test_clean_mags_clamps_outliers() {
+        // One corrupted pair among ordinary subcarriers / زوج تالف بين ناقلات عادية
+        let mut mags: Vec<f64> = (0..32).map(|i| 20.0 + (i % 4) as f64).collect();
+        mags[10] = 90_000.0;
+        let frame = CsiFrame::new(0, mags.clone(), vec![], CsiFormat::AmplitudeOnly, None);
+
+        let cleaned = frame.clean_mags(4.0);
+        assert_eq!(cleaned.len(), 32);
+        assert!(cleaned[10] < 35.0, "outlier kept at {}", cleaned[10]);
+        assert_eq!(cleaned[..10], mags[..10]);
+        assert_eq!(cleaned[11..], mags[11..]);
+        assert_eq!(frame.mags, mags);
+
+        // Off, or a flat frame: unchanged / معطل أو إطار مسطح: دون تغيير
+        assert_eq!(frame.clean_mags(0.0), mags);
+        let flat = CsiFrame::new(0, vec![20.0, 20.0, 20.0, 500.0], vec![], CsiFormat::AmplitudeOnly, None);
+        assert_eq!(flat.clean_mags(4.0), flat.mags);
+    }
+
+    #[test]
+    fn test_frames_cleaned_once_on_push() {
+        let mut mags: Vec<f64> = (0..32).map(|i| 20.0 + (i % 4) as f64).collect();
+        mags[10] = 90_000.0;
+        let mut state = synthetic_clock_state();
+        state.push_frame(CsiFrame::new(0, mags.clone(), vec![], CsiFormat::AmplitudeOnly, None));
+
+        // Raw kept for logging, the clamped copy feeds the detectors and chart
+        // الخام يبقى للتسجيل والنسخة المقصوصة تغذي الكواشف والرسم
+        let frame = &state.frames[0];
+        assert_eq!(frame.mags, mags);
+        assert!(frame.is_cleaned_at(state.detector_windows.outlier_sigma));
+        assert!(frame.analysis_mags()[10] < 35.0);
+        assert!(state.chart_value(frame) < 35.0);
+        assert!(state.dynamic.median() < 35.0);
+        assert!(matches!(state.detection_frames(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_session_reset_keeps_frames() {
+        let mut state = synthetic_clock_state();
+        for i in 0..5 {
+            state.push_frame(create_test_frame(i * 100));
+        }
+        state.detections = DetectionResults { motion_detected: true, motion_value: 30.0, ..DetectionResults::default() };
+        state.log_detection_changes(&DetectionResults::default(), 0);
+        assert_eq!((state.session.frames, state.session.motion_events), (5, 1));
+
+        state.reset_session_stats();
+        assert_eq!(state.session.frames, 0);
+        assert_eq!(state.session.total_events(), 0);
+        assert_eq!(state.session.peak_motion, 0.0);
+        assert_eq!(state.frames.len(), 5);
+        assert!(state.detections.motion_detected);
+    }
+
+    #[test]
+    fn test_subcarrier_count_change() {
+        let mut state = synthetic_clock_state();
+        let frame = |t: i64, n: usize| CsiFrame::new(t, vec![10.0; n], vec![], CsiFormat::AmplitudeOnly, None);
+        for i in 0..5 {
+            state.push_frame(frame(i * 100, 64));
+        }
+        state.motion_history = vec![1.0, 2.0];
+        assert_eq!(state.last_sc_change, None);
+        assert_eq!(state.sc_change_warning(400), None);
+
+        // 64 → 128: warned, history restarts / تحذير وإعادة بدء التاريخ
+        state.push_frame(frame(500, 128));
+        assert_eq!(state.last_sc_change, Some(500));
+        assert!(state.status_message.contains("64 → 128"));
+        assert_eq!(state.frames.len(), 6);
+        assert!(state.motion_history.is_empty());
+
+        state.push_frame(frame(600, 128));
+        assert_eq!(state.last_sc_change, Some(500));
+        assert_eq!(state.sc_change_warning(600).as_deref(), Some("⚠️ Subcarriers changed 64 → 128"));
+        assert_eq!(state.sc_change_warning(500 + SC_CHANGE_WARNING_MS), None);
+    }
+
+    #[test]
+    fn test_cycle_baud_rate() {
+        let mut state = AppState::new();
+        let mut seen = vec![state.baud_rate];
+        for _ in 0..5 {
+            state.cycle_baud_rate();
+            seen.push(state.baud_rate);
+        }
+        assert_eq!(seen, vec![115_200, 460_800, 921_600, 1_500_000, 9_600, 115_200]);
+
+        // A rate outside the list moves on to the next listed one / معدل خارج القائمة ينتقل للتالي
+        state.baud_rate = 250_000;
+        state.cycle_baud_rate();
+        assert_eq!(state.baud_rate, 460_800);
+    }
+
+    #[test]
+    fn test_step_smoothing() {
+        let mut state = AppState::new();
+        state.step_smoothing(false);
+        assert_eq!(state.smoothing_window, 1);
+        state.step_smoothing(true);
+        state.step_smoothing(true);
+        assert_eq!(state.smoothing_window, 5);
+        for _ in 0..10 {
+            state.step_smoothing(true);
+        }
+        assert_eq!(state.smoothing_window, 25);
+    }
+
+    #[test]
+    fn test_port_picker_choice() {
+        let mut state = AppState::new();
+
+        // No ports: nothing to choose, auto-detection stays / لا منافذ: لا اختيار
+        state.port_picker = Some(PortPicker::new(Vec::new()));
+        state.port_picker.as_mut().unwrap().move_selection(1);
+        assert_eq!(state.choose_port(), None);
+        assert!(!state.port_chosen);
+        assert!(state.port_picker.is_none());
+
+        let port = |name: &str| PortChoice { name: name.to_string(), description: String::new() };
+        let mut picker = PortPicker::new(vec![port("/dev/ttyUSB0"), port("/dev/ttyACM0")]);
+        picker.move_selection(5);
+        picker.move_selection(-1);
+        picker.move_selection(1);
+        state.port_picker = Some(picker);
+        assert_eq!(state.choose_port().as_deref(), Some("/dev/ttyACM0"));
+        assert_eq!(state.port_name, "/dev/ttyACM0");
+        assert!(state.port_chosen);
+    }
+
+    #[test]
+    fn test_frozen_view_keeps_snapshot() {
+        let mut state = synthetic_clock_state();
+        for i in 0..5 {
+            state.push_frame(create_test_frame(i * 100));
+        }
+        state.motion_history = vec![1.0, 2.0];
+        assert!(state.toggle_view_frozen());
+
+        // Frames keep arriving, the charts don't move / الإطارات تستمر والرسوم ثابتة
+        for i in 5..9 {
+            state.push_frame(create_test_frame(i * 100));
+        }
+        state.motion_history.push(3.0);
+        assert_eq!(state.frames.len(), 9);
+        assert_eq!(state.chart_frames(100).len(), 5);
+        assert_eq!(state.chart_frames(2)[1].timestamp, 400);
+        assert_eq!(state.chart_histories()[0], &[1.0, 2.0]);
+
+        // Unfreeze: straight back to live data / فك التجميد: عودة مباشرة للبيانات الحية
+        assert!(!state.toggle_view_frozen());
+        assert_eq!(state.chart_frames(100).len(), 9);
+        assert_eq!(state.chart_histories()[0], &[1.0, 2.0, 3.0]);
+        assert!(state.view_snapshot.is_none());
+    }
+
+    #[test]
+    fn test_interval_markers() {
+        let mut state = AppState::new();
+        state.loaded_frames = (0..50)
+            .map(|i| CsiFrame::new(1_000 + i * 100, vec![10.0; 8], vec![], CsiFormat::AmplitudeOnly, None))
+            .collect();
+        assert_eq!(state.set_marker(true), None);
+
+        state.start_playback();
+        state.seek_to_second(4.0);
+        assert_eq!(state.set_marker(true), Some(4.0));
+        state.seek_to_second(1.0);
+        let due = state.advance_playback(200.0);
+        state.push_playback_batch(due);
+        assert_eq!(state.set_marker(false), Some(1.2));
+
+        // Markers work in either order / العلامات تعمل بأي ترتيب
+        assert!(state.compute_interval_stats());
+        let stats = state.interval_stats.as_ref().unwrap();
+        assert_eq!(stats.frame_count, 29);
+        assert_eq!(stats.start_secs, 1.2);
+
+        // Reloading clears the markers / إعادة التحميل تمسح العلامات
+        state.start_playback();
+        assert!(!state.compute_interval_stats());
+    }
+
+    #[test]
+    fn test_playback_batch_trims_in_bulk() {
+        let mut state = AppState::new();
+        state.push_playback_batch((0..30).map(create_test_frame).collect());
+        assert_eq!(state.frame_count(), 30);
+
+        // One large batch: window capped, newest frames kept, every frame counted
+        // دفعة كبيرة: النافذة محدودة، وتبقى أحدث الإطارات، ويُحسب كل إطار
+        state.push_playback_batch((30..100_030).map(create_test_frame).collect());
+        assert_eq!(state.frame_count(), PLAYBACK_WINDOW_FRAMES);
+        assert_eq!(state.frames[0].timestamp, 100_030 - PLAYBACK_WINDOW_FRAMES as i64);
+        assert_eq!(state.frames.last().unwrap().timestamp, 100_029);
+        assert_eq!(state.frame_seq, 100_030);
+        assert_eq!(state.spectrum.frame_count(), state.spectrum.window());
+
+        // Small batch on a full window shifts it / دفعة صغيرة على نافذة ممتلئة تزيحها
+        state.push_playback_batch((100_030..100_035).map(create_test_frame).collect());
+        assert_eq!(state.frame_count(), PLAYBACK_WINDOW_FRAMES);
+        assert_eq!(state.frames[0].timestamp, 100_035 - PLAYBACK_WINDOW_FRAMES as i64);
+    }
+
+    #[test]
+    fn test_null_subcarriers_stripped_on_push() {
+        // 64 SC with guard/DC zeros / 64 ناقلاً مع أصفار الحارس و DC
+        let nulls = crate::subcarriers::null_subcarriers(64);
+        let mags: Vec<f64> = (0..64).map(|i| if nulls.contains(&i) { 0.0 } else { 12.0 }).collect();
+        let frame = CsiFrame::new(0, mags, vec![], CsiFormat::AmplitudeOnly, None);
+
+        let mut state = synthetic_clock_state();
+        state.push_frame(frame.clone());
+        assert_eq!((state.max_sc, state.null_subcarriers), (56, 8));
+        assert!(state.frames[0].mags.iter().all(|&m| m == 12.0));
+
+        // Opt out keeps the raw vector / إلغاء الحذف يبقي المتجه الخام
+        state.clear_frames();
+        state.strip_null_subcarriers = false;
+        state.push_playback_batch(vec![frame]);
+        assert_eq!((state.max_sc, state.null_subcarriers), (64, 0));
+    }
+
+    #[test]
+    fn test_live_window_follows_newest_frame() {
+        // Old timestamps (e.g. a followed file) are kept relative to the newest row
+        // الطوابع القديمة (مثل ملف متابع) تُحفظ نسبةً لأحدث صف
+        let mut state = synthetic_clock_state();
+        for i in 0..8 {
+            state.push_frame(create_test_frame(i * 10_000));
+        }
+        assert_eq!(state.frame_count(), 6);
+        assert_eq!(state.frames[0].timestamp, 20_000);
+
+        // Live data ends the window now: a stale frame goes on the next push
+        // البيانات المباشرة تنهي النافذة الآن: الإطار القديم يُحذف عند الدفع التالي
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut live = AppState::new();
+        live.push_frame(create_test_frame(now - live.window_ms() - 5_000));
+        live.push_frame(create_test_frame(now - 5_000));
+        assert_eq!(live.frame_count(), 1);
+        assert_eq!(live.frames[0].timestamp, now - 5_000);
+    }
+
+    #[test]
+    fn test_window_resize_prunes_immediately() {
+        let mut state = synthetic_clock_state();
+        for i in 0..=120 {
+            state.push_frame(create_test_frame(i * 1_000));
+        }
+        assert_eq!(state.frame_count(), 60);
+
+        // Shrinking drops frames now, not on the next push / التصغير يحذف فوراً
+        state.step_window(false);
+        assert_eq!(state.window_secs, 30);
+        assert_eq!(state.frame_count(), 30);
+        assert_eq!(state.frames[0].timestamp, 91_000);
+
+        // Growing keeps more history from here on / التكبير يحفظ تاريخاً أطول
+        state.set_window_secs(300);
+        for i in 121..=400 {
+            state.push_frame(create_test_frame(i * 1_000));
+        }
+        assert_eq!(state.frame_count(), 300);
+
+        // Steps stop at the ends / الخطوات تتوقف عند الأطراف
+        state.set_window_secs(600);
+        state.step_window(true);
+        assert_eq!(state.window_secs, 600);
+    }
+
+    #[test]
+    fn test_spectrum_average_cycle() {
+        let mut state = AppState::new();
+        for i in 0..40 {
+            state.push_frame(create_test_frame(chrono::Utc::now().timestamp_millis() + i));
+        }
+        
+        let mut windows = Vec::new();
+        for _ in 0..4 {
+            state.cycle_spectrum_average();
+            windows.push(state.spectrum_averaging.then(|| state.spectrum.frame_count()));
+        }
+        assert_eq!(windows, vec![Some(8), Some(16), Some(32), None]);
+    }
+
+    #[test]
+    fn test_forced_format_cycle() {
+        let mut state = AppState::new();
+        let mut modes = vec![state.format_mode_label()];
+        for _ in 0..3 {
+            state.cycle_forced_format();
+            modes.push(state.format_mode_label());
+        }
+        assert_eq!(modes, vec!["Auto", "Real/Imag (forced)", "Amplitude (forced)", "Auto"]);
+        assert_eq!(state.forced_format, None);
+    }
+
+    #[test]
+    fn test_status_message_history() {
+        let mut state = AppState::new();
+        for message in ["✅ Connected to COM3", "📥 Receiving CSI: 64 subcarriers, 1 frames", "📥 Receiving CSI: 64 subcarriers, 2 frames"] {
+            state.status_message = message.to_string();
+            state.note_status_message(0);
+            state.note_status_message(0);
+        }
+        let history: Vec<&String> = state.message_history.iter().collect();
+        assert_eq!(history.len(), 2);
+        assert!(history[0].ends_with("✅ Connected to COM3"));
+        assert!(history[1].ends_with("2 frames"));
+    }
+
+    #[test]
+    fn test_warmup_suppresses_events() {
+        let mut state = AppState::new();
+        state.warmup_secs = 2.0;
+        state.start_warmup(10_000);
+        
+        let quiet = DetectionResults::default();
+        state.detections = DetectionResults { motion_detected: true, ..Default::default() };
+        
+        // Computed but not reported during warmup / محسوب لكن غير مُبلغ أثناء الإحماء
+        state.log_detection_changes(&quiet, 11_000);
+        assert!(state.warmup_remaining_secs(11_000).is_some());
+        assert!(state.event_log.entries().is_empty());
+        
+        // Reported once warmup has elapsed / يُبلغ بعد انتهاء الإحماء
+        state.log_detection_changes(&quiet, 12_500);
+        assert_eq!(state.warmup_remaining_secs(12_500), None);
+        assert_eq!(state.event_log.entries().len(), 1);
+        assert_eq!(state.event_log.entries()[0].message, "🔴 Motion detected");
+    }
+
+    #[test]
+    fn test_warmup_skips_detection_log() {
+        let path = std::path::PathBuf::from("test_state_detection_warmup.csv");
+        let mut state = AppState::new();
+        state.detection_logger = Some(DetectionLogger::new(path.clone()).unwrap());
+        state.warmup_secs = 2.0;
+        state.start_warmup(10_000);
+
+        // Only the sample after warmup reaches the file / العينة بعد الإحماء فقط تصل للملف
+        state.log_detection_sample(11_000);
+        state.log_detection_sample(12_500);
+        state.detection_logger.as_mut().unwrap().flush().unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.lines().nth(1).unwrap().starts_with("12500,"));
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_seek_suppresses_events() {
+        let mut state = AppState::new();
+        state.loaded_frames = (0..10)
+            .map(|i| CsiFrame::new(i * 1000, vec![1.0, 2.0], vec![], CsiFormat::AmplitudeOnly, None))
+            .collect();
+        state.seek_to_second(4.0);
+        
+        let quiet = DetectionResults::default();
+        state.detections = DetectionResults { motion_detected: true, ..Default::default() };
+        
+        // Right after the seek: not reported / مباشرة بعد الانتقال: غير مُبلغ
+        state.log_detection_changes(&quiet, 0);
+        assert!(state.seek_settling(Instant::now()));
+        assert!(state.event_log.entries().is_empty());
+        
+        // Settled once the interval has passed / يستقر بعد مرور الفترة
+        let seeked = state.last_seek_instant.unwrap();
+        assert!(!state.seek_settling(seeked + state.seek_settle));
+        state.seek_settle = Duration::ZERO;
+        state.log_detection_changes(&quiet, 0);
+        assert_eq!(state.event_log.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_baseline_deltas() {
+        let frames = vec![
+            CsiFrame::new(0, vec![10.0, 20.0], vec![], CsiFormat::AmplitudeOnly, None),
+            CsiFrame::new(1, vec![30.0, 40.0, 6.0], vec![], CsiFormat::AmplitudeOnly, None),
+        ];
+        let at_capture = DetectionResults {
+            motion_value: 50.0,
+            presence_value: 20.0,
+            door_value: 5.0,
+            ..Default::default()
+        };
+        let baseline = Baseline::capture(0, &frames, &at_capture);
+        assert_eq!(baseline.mags, vec![20.0, 30.0, 6.0]);
+
+        let now = DetectionResults {
+            motion_value: 62.3,
+            presence_value: 15.0,
+            door_value: 5.0,
+            ..Default::default()
+        };
+        let (motion, presence, door) = baseline.deltas(&now);
+        assert!((motion - 12.3).abs() < 1e-9);
+        assert!((presence + 5.0).abs() < 1e-9);
+        assert_eq!(door, 0.0);
+
+        assert!((baseline.signal_delta(&[22.0, 26.0]) - 3.0).abs() < 1e-9);
+        assert_eq!(baseline.signal_delta(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_recalibrate_replaces_baseline() {
+        let mut state = synthetic_clock_state();
+        state.warmup_secs = 0.0;
+        state.recalibrate_every_secs = Some(60);
+        assert!(!state.recalibrate(0));
+        assert!(!state.recalibration_due(0));
+
+        state.push_frame(CsiFrame::new(0, vec![10.0, 20.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.start_warmup(0);
+        assert!(state.recalibration_due(0));
+        assert!(state.recalibrate(0));
+        assert!(!state.recalibration_due(59_999));
+        assert!(state.recalibration_due(60_000));
+
+        // Environment shifted: the new baseline follows the current frames
+        // تغيرت البيئة: خط الأساس الجديد يتبع الإطارات الحالية
+        state.clear_frames();
+        state.push_frame(CsiFrame::new(60_000, vec![30.0, 40.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.push_frame(CsiFrame::new(60_050, vec![50.0, 60.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.detections.presence_value = 12.0;
+        assert!(state.recalibrate(60_100));
+
+        let baseline = state.baseline.as_ref().unwrap();
+        assert_eq!(baseline.timestamp, 60_100);
+        assert_eq!(baseline.mags, vec![40.0, 50.0]);
+        assert_eq!(baseline.detections.presence_value, 12.0);
+        let messages: Vec<&str> = state.event_log.entries().iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, vec!["📐 Baseline captured from 1 frames", "📐 Baseline recalibrated from 2 frames"]);
+    }
+
+    #[test]
+    fn test_seek_moves_playback_clock() {
+        let mut state = playback_state();
+        state.seek_to_second(0.5);
+        assert_eq!(state.playback_cursor_ms, 500.0);
+        assert_eq!(state.advance_playback(100.0).len(), 2);
+    }
+
+    #[test]
+    fn test_mac_filter_selection() {
+        let mut state = AppState::new();
+        assert!(state.accept_mac(Some("AA:AA:AA:AA:AA:01"), Some(-40), 0));
+        assert!(state.accept_mac(Some("AA:AA:AA:AA:AA:01"), Some(-41), 10));
+        assert!(state.accept_mac(Some("AA:AA:AA:AA:AA:02"), Some(-70), 20));
+
+        // Busiest transmitter is listed first / المرسل الأكثر نشاطاً يظهر أولاً
+        state.move_mac_selection(5);
+        assert_eq!(state.mac_table_selected, 1);
+        state.move_mac_selection(-5);
+        state.select_mac_filter();
+        assert_eq!(state.mac_filter.as_deref(), Some("AA:AA:AA:AA:AA:01"));
+
+        assert!(state.accept_mac(Some("AA:AA:AA:AA:AA:01"), None, 30));
+        assert!(!state.accept_mac(Some("AA:AA:AA:AA:AA:02"), None, 40));
+        assert!(state.accept_mac(None, None, 50));
+
+        // Enter on the current target clears the filter / Enter على الهدف الحالي يلغي المرشح
+        state.select_mac_filter();
+        assert_eq!(state.mac_filter, None);
+    }
+
+    #[test]
+    fn test_selected_subcarrier_cycle_and_fallback() {
+        let mut state = synthetic_clock_state();
+        state.step_selected_subcarrier(true);
+        assert_eq!(state.selected_subcarrier, None);
+
+        state.push_frame(CsiFrame::new(0, vec![1.0, 2.0, 9.0], vec![], CsiFormat::AmplitudeOnly, None));
+        state.step_selected_subcarrier(true);
+        assert_eq!((state.selected_subcarrier, state.subcarrier_label().as_str()), (Some(0), "SC #0"));
+        state.step_selected_subcarrier(false);
+        assert_eq!(state.selected_subcarrier, None);
+        state.step_selected_subcarrier(false);
+        assert_eq!(state.selected_subcarrier, Some(2));
+        assert_eq!(state.chart_value(state.frames.last().unwrap()), 9.0);
+        state.step_selected_subcarrier(true);
+        assert_eq!(state.subcarrier_label(), "Average");
+
+        // A shorter frame falls back to the aggregate / الإطار الأقصر يعود للتجميع
+        state.selected_subcarrier = Some(2);
+        let short = CsiFrame::new(1, vec![4.0, 6.0], vec![], CsiFormat::AmplitudeOnly, None);
+        assert_eq!(state.chart_value(&short), 5.0);
+
+        // A selection beyond the frame is clamped before stepping / الاختيار خارج الإطار يُحصر قبل التحريك
+        state.selected_subcarrier = Some(40);
+        state.step_selected_subcarrier(false);
+        assert_eq!(state.selected_subcarrier, Some(1));
+    }
+
+    #[test]
+    fn test_detectors_run_on_selected_stream() {
+        use crate::detectors::quick_detect;
+
+        // Two antennas: the first steady, the second swinging / هوائيان: الأول ثابت والثاني متقلب
+        let mut state = synthetic_clock_state();
+        state.stream_split = 2;
+        for i in 0..20 {
+            let swing = if i % 2 == 0 { 10.0 } else { 60.0 };
+            let mags: Vec<f64> = (0..16).map(|sc| if sc < 8 { 20.0 } else { swing }).collect();
+            state.push_frame(CsiFrame::new(i * 50, mags, vec![], CsiFormat::AmplitudeOnly, None));
+        }
+        assert_eq!(state.frames.last().unwrap().streams, vec![0..8, 8..16]);
+        assert_eq!(state.stream_label(), "All 2 streams");
+
+        state.cycle_stream();
+        assert_eq!(state.selected_stream, Some(0));
+        assert_eq!(state.detection_frames()[0].subcarrier_count(), 8);
+        assert_eq!(quick_detect(&state.detection_frames()).motion_value, 0.0);
+
+        state.cycle_stream();
+        assert_eq!(state.stream_label(), "Stream 2/2");
+        assert_eq!(state.selected_range(state.frames.last().unwrap()), 8..16);
+        assert!(quick_detect(&state.detection_frames()).motion_detected);
+
+        // Back to the whole frame / العودة للإطار كاملاً
+        state.cycle_stream();
+        assert_eq!(state.selected_stream, None);
+        assert_eq!(state.detection_frames()[0].subcarrier_count(), 16);
+    }
+}
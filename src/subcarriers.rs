@@ -0,0 +1,175 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 subcarriers.rs - Subcarrier Layouts
+// ═══════════════════════════════════════════════════════════════════════════════
+// Knows which subcarriers of a bandwidth never carry signal.
+// Features:
+// - Guard and DC null positions for the 64/128/256 subcarrier layouts
+// - Strips them only when they are actually zero in the frame
+// - Keeps per-index consistency: a frame is stripped as a whole or not at all
+// - Splits concatenated streams (LLTF + HT-LTF, or one per antenna)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::ops::{Range, RangeInclusive};
+
+use crate::state::{CsiFrame, CsiMeta};
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Layouts / التخطيطات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Null positions (lower guard, DC, upper guard) per subcarrier count,
+/// indexed from the lowest frequency with DC in the middle
+/// مواضع الناقلات الفارغة (حارس سفلي، DC، حارس علوي) لكل عدد ناقلات
+///
+/// ```text
+/// 64  (HT20):  used -28..-1, 1..28   → nulls 0-3, 32, 61-63
+/// 128 (HT40):  used -58..-2, 2..58   → nulls 0-5, 63-65, 123-127
+/// 256 (VHT80): used -122..-2, 2..122 → nulls 0-5, 127-129, 251-255
+/// ```
+const LAYOUTS: [(usize, [RangeInclusive<usize>; 3]); 3] = [
+    (64, [0..=3, 32..=32, 61..=63]),
+    (128, [0..=5, 63..=65, 123..=127]),
+    (256, [0..=5, 127..=129, 251..=255]),
+];
+
+/// Guard and DC indices for a layout, empty for unknown counts
+/// فهارس الحارس و DC لتخطيط ما، فارغة للأعداد غير المعروفة
+pub fn null_subcarriers(total_sc: usize) -> Vec<usize> {
+    LAYOUTS
+        .iter()
+        .find(|(count, _)| *count == total_sc)
+        .map(|(_, ranges)| ranges.iter().cloned().flatten().collect())
+        .unwrap_or_default()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Stripping / الحذف
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Remove the layout's null subcarriers when all of them are zero
+/// حذف الناقلات الفارغة للتخطيط عندما تكون كلها صفراً
+///
+/// Returns the frame and how many subcarriers were removed. A frame whose
+/// null positions carry values (unusual layout or reordered output) is left
+/// untouched so indices stay comparable between frames.
+pub fn strip_null_subcarriers(frame: CsiFrame) -> (CsiFrame, usize) {
+    let nulls = null_subcarriers(frame.subcarrier_count());
+    if nulls.is_empty() || !nulls.iter().all(|&i| frame.mags[i] == 0.0) {
+        return (frame, 0);
+    }
+
+    let cleaned = CsiFrame {
+        mags: without(frame.mags, &nulls),
+        pairs: without(frame.pairs, &nulls),
+        phases: without(frame.phases, &nulls),
+        ..frame
+    };
+    (cleaned, nulls.len())
+}
+
+/// Drop the (sorted) `nulls` indices from a per-subcarrier vector
+/// حذف الفهارس `nulls` (المرتبة) من متجه لكل ناقل فرعي
+fn without<T>(values: Vec<T>, nulls: &[usize]) -> Vec<T> {
+    values
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| nulls.binary_search(i).is_err())
+        .map(|(_, v)| v)
+        .collect()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Streams / التدفقات
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Subcarrier range of each stream in a frame; empty means one stream
+/// نطاق الناقلات لكل تدفق في الإطار؛ الفارغ يعني تدفقاً واحداً
+///
+/// A `split` above 1 cuts the frame into that many equal streams (one per
+/// antenna). Otherwise HT frames are split by their known layout:
+///
+/// ```text
+/// 128 (HT20): LLTF 0..64 + HT-LTF 64..128
+/// 192 (HT40): LLTF 0..64 + HT-LTF 64..192
+/// ```
+pub fn stream_layout(total_sc: usize, meta: &CsiMeta, split: usize) -> Vec<Range<usize>> {
+    if split > 1 {
+        if total_sc == 0 || !total_sc.is_multiple_of(split) {
+            return Vec::new();
+        }
+        let width = total_sc / split;
+        return (0..split).map(|i| i * width..(i + 1) * width).collect();
+    }
+    match (meta.sig_mode, total_sc) {
+        (Some(1), 128) => vec![0..64, 64..128],
+        (Some(1), 192) => vec![0..64, 64..192],
+        _ => Vec::new(),
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detectors::average_magnitude;
+    use crate::state::CsiFormat;
+
+    /// Synthetic HT20 frame: 10+10j on used subcarriers, zero on the nulls
+    fn ht20_frame() -> CsiFrame {
+        let nulls = null_subcarriers(64);
+        let pairs: Vec<(i32, i32)> = (0..64).map(|i| if nulls.contains(&i) { (0, 0) } else { (10, 10) }).collect();
+        let mags = pairs.iter().map(|&(r, i)| ((r * r + i * i) as f64).sqrt()).collect();
+        CsiFrame::new(0, mags, pairs, CsiFormat::RealImag, Some(-50))
+    }
+
+    #[test]
+    fn test_strip_standard_nulls() {
+        let raw = ht20_frame();
+        assert!(average_magnitude(&raw.mags) < 14.0);
+
+        let (clean, removed) = strip_null_subcarriers(raw.clone());
+        assert_eq!(removed, 8);
+        assert_eq!(clean.subcarrier_count(), 56);
+        assert_eq!(clean.pairs.len(), 56);
+        assert_eq!(clean.phases.len(), 56);
+        assert!(clean.mags.iter().all(|&m| m > 0.0));
+        assert!((average_magnitude(&clean.mags) - 200f64.sqrt()).abs() < 1e-9);
+
+        // The raw frame is untouched for logging / الإطار الخام لم يتغير للتسجيل
+        assert_eq!(raw.subcarrier_count(), 64);
+        assert_eq!(clean.rssi, Some(-50));
+    }
+
+    #[test]
+    fn test_non_null_layouts_are_kept() {
+        // Signal on a guard position: not this layout / إشارة في موضع حارس
+        let mut frame = ht20_frame();
+        frame.mags[0] = 3.0;
+        let (kept, removed) = strip_null_subcarriers(frame);
+        assert_eq!((kept.subcarrier_count(), removed), (64, 0));
+
+        // Unknown subcarrier counts are never stripped / الأعداد غير المعروفة لا تُحذف
+        let odd = CsiFrame::new(0, vec![0.0; 52], vec![], CsiFormat::AmplitudeOnly, None);
+        assert_eq!(strip_null_subcarriers(odd).1, 0);
+        assert_eq!(null_subcarriers(128).len(), 14);
+        assert_eq!(null_subcarriers(256).len(), 14);
+    }
+
+    #[test]
+    fn test_stream_layout() {
+        let ht = CsiMeta { sig_mode: Some(1), ..Default::default() };
+        assert_eq!(stream_layout(128, &ht, 0), vec![0..64, 64..128]);
+        assert_eq!(stream_layout(192, &ht, 0), vec![0..64, 64..192]);
+
+        // No metadata or an unknown count: one stream / بلا بيانات أو عدد غير معروف: تدفق واحد
+        assert!(stream_layout(128, &CsiMeta::default(), 0).is_empty());
+        assert!(stream_layout(64, &ht, 0).is_empty());
+
+        // A configured split wins; uneven splits are ignored / التقسيم المحدد يغلب؛ غير المتساوي يُتجاهل
+        assert_eq!(stream_layout(192, &ht, 3), vec![0..64, 64..128, 128..192]);
+        assert!(stream_layout(100, &CsiMeta::default(), 3).is_empty());
+    }
+}
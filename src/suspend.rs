@@ -0,0 +1,145 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 suspend.rs - Terminal Suspend / Resume
+// ═══════════════════════════════════════════════════════════════════════════════
+// Keeps the shell usable across Ctrl+Z, `fg` and `bg`.
+// Features:
+// - Enter / leave raw mode and the alternate screen in one place
+// - Restore the terminal before the process stops (Ctrl+Z or SIGTSTP)
+// - Re-enter the TUI on SIGCONT once back in the foreground
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::io::{self, Stdout};
+
+use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+
+use csi_tui::error::{CsiError, Result};
+
+/// Terminal type used by the viewer / نوع الطرفية المستخدم في العارض
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Terminal Modes / أوضاع الطرفية
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Enter raw mode and the alternate screen, then force a full redraw
+/// الدخول في الوضع الخام والشاشة البديلة ثم فرض إعادة رسم كاملة
+pub fn enter_tui(terminal: &mut Tui, focus_events: bool) -> Result<()> {
+    enable_raw_mode().map_err(CsiError::Terminal)?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture).map_err(CsiError::Terminal)?;
+    if focus_events {
+        execute!(terminal.backend_mut(), EnableFocusChange).map_err(CsiError::Terminal)?;
+    }
+    terminal.clear().map_err(CsiError::Terminal)
+}
+
+/// Give the terminal back to the shell
+/// إعادة الطرفية إلى الصدفة
+pub fn leave_tui(terminal: &mut Tui, focus_events: bool) -> Result<()> {
+    if focus_events {
+        execute!(terminal.backend_mut(), DisableFocusChange).map_err(CsiError::Terminal)?;
+    }
+    disable_raw_mode().map_err(CsiError::Terminal)?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture).map_err(CsiError::Terminal)?;
+    terminal.show_cursor().map_err(CsiError::Terminal)
+}
+
+/// Create the viewer terminal on stdout / إنشاء طرفية العارض
+pub fn new_terminal() -> Result<Tui> {
+    Terminal::new(CrosstermBackend::new(io::stdout())).map_err(CsiError::Terminal)
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Job Control Signals / إشارات التحكم بالمهام
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// SIGTSTP / SIGCONT flags while the viewer runs
+/// علامات SIGTSTP و SIGCONT أثناء تشغيل العارض
+///
+/// In raw mode Ctrl+Z arrives as a key, so SIGTSTP only comes from `kill`.
+#[cfg(unix)]
+pub struct JobControl {
+    stop: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    cont: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    ids: Vec<signal_hook::SigId>,
+}
+
+#[cfg(unix)]
+impl JobControl {
+    /// Catch SIGTSTP and SIGCONT / التقاط SIGTSTP و SIGCONT
+    pub fn register() -> Result<Self> {
+        use signal_hook::consts::{SIGCONT, SIGTSTP};
+        use std::sync::{atomic::AtomicBool, Arc};
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let cont = Arc::new(AtomicBool::new(false));
+        let ids = vec![
+            signal_hook::flag::register(SIGTSTP, Arc::clone(&stop)).map_err(CsiError::Terminal)?,
+            signal_hook::flag::register(SIGCONT, Arc::clone(&cont)).map_err(CsiError::Terminal)?,
+        ];
+        Ok(Self { stop, cont, ids })
+    }
+
+    /// Was a stop requested from outside? / هل طُلب الإيقاف من الخارج؟
+    pub fn take_stop(&self) -> bool {
+        self.stop.swap(false, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Did the process just continue? / هل استؤنفت العملية للتو؟
+    pub fn take_continue(&self) -> bool {
+        self.cont.swap(false, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Stop the process like the default SIGTSTP; returns after `fg` / `bg`
+    /// إيقاف العملية كالسلوك الافتراضي؛ تعود بعد `fg` أو `bg`
+    pub fn stop_process(&self) {
+        let _ = signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP);
+    }
+}
+
+#[cfg(unix)]
+impl Drop for JobControl {
+    fn drop(&mut self) {
+        for id in self.ids.drain(..) {
+            signal_hook::low_level::unregister(id);
+        }
+    }
+}
+
+/// Is this process the terminal's foreground job?
+/// هل هذه العملية هي المهمة الأمامية للطرفية؟
+#[cfg(unix)]
+pub fn is_foreground() -> bool {
+    // SAFETY: plain syscalls without pointers / استدعاءات نظام بدون مؤشرات
+    unsafe { libc::tcgetpgrp(libc::STDIN_FILENO) == libc::getpgrp() }
+}
+
+/// No job control outside Unix / لا يوجد تحكم بالمهام خارج يونكس
+#[cfg(not(unix))]
+pub struct JobControl;
+
+#[cfg(not(unix))]
+impl JobControl {
+    pub fn register() -> Result<Self> {
+        Ok(Self)
+    }
+
+    pub fn take_stop(&self) -> bool {
+        false
+    }
+
+    pub fn take_continue(&self) -> bool {
+        false
+    }
+
+    pub fn stop_process(&self) {}
+}
+
+#[cfg(not(unix))]
+pub fn is_foreground() -> bool {
+    true
+}
@@ -1,7 +1,9 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 // 📦 ui/charts.rs - Chart Components
 // ═══════════════════════════════════════════════════════════════════════════════
-// Contains: CSI magnitude chart, Detectors chart (Motion, Presence, Door)
+// Contains: CSI magnitude / spectrum chart (with detector range bands and one
+//           line per named source),
+//           Subcarrier stability bars, Detectors chart (Motion, Presence, Door)
 // ═══════════════════════════════════════════════════════════════════════════════
 
 use ratatui::{
@@ -13,41 +15,61 @@ use ratatui::{
     Frame,
 };
 
-use crate::state::AppState;
+use csi_tui::detectors::PrimaryDetector;
+use csi_tui::state::{AppState, ChartMode, CsiFrame};
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Constants / الثوابت
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Number of samples to display in the chart / عدد العينات للعرض في الرسم البياني
-const CHART_SAMPLES: usize = 100;
+/// Line colors for named sources; the primary reader stays cyan
+/// ألوان خطوط المصادر المسماة؛ القارئ الأساسي يبقى سماوياً
+const SOURCE_COLORS: [Color; 4] = [Color::Yellow, Color::Magenta, Color::LightGreen, Color::LightRed];
 
 /// Y-axis range for the chart / نطاق المحور الصادي للرسم البياني
 const Y_AXIS_MIN: f64 = 0.0;
 const Y_AXIS_MAX: f64 = 100.0;
 
+/// Smallest half-range of the dynamic chart / أصغر نصف نطاق لرسم المكون الديناميكي
+const MIN_DYNAMIC_RANGE: f64 = 1.0;
+
+/// Samples the idle sweep line moves per render / خطوة خط المسح لكل رسم
+const IDLE_SWEEP_STEP: u64 = 2;
+
+/// Deviation, relative to the median subcarrier, above which a bar is noisy
+/// الانحراف نسبة للناقل الوسيط الذي يصبح فوقه العمود مشوشاً
+const NOISY_STABILITY_RATIO: f64 = 2.0;
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Chart Panel / لوحة الرسم البياني
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Render the right chart panel
 /// رسم لوحة الرسم البياني اليمنى
-pub fn render_chart_panel(frame: &mut Frame, area: Rect, state: &AppState) {
-    // Split into two charts: CSI magnitude and Detectors
-    // تقسيم إلى رسمين: سعة CSI والكاشفات
+pub fn render_chart_panel(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    // Split into two charts and the event log
+    // تقسيم إلى رسمين وسجل الأحداث
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(50), // CSI Chart
-            Constraint::Percentage(50), // Detectors Chart
+            Constraint::Percentage(40), // CSI Chart
+            Constraint::Percentage(40), // Detectors Chart
+            Constraint::Percentage(20), // Event Log
         ])
         .split(area);
 
-    // Render CSI magnitude chart / رسم رسم بياني سعة CSI
-    render_csi_chart(frame, chunks[0], state);
+    // Render CSI magnitude or spectrum chart / رسم رسم بياني سعة أو طيف CSI
+    match state.chart_mode {
+        ChartMode::Spectrum => render_spectrum_chart(frame, chunks[0], state),
+        ChartMode::Stability => render_stability_chart(frame, chunks[0], state),
+        _ => render_csi_chart(frame, chunks[0], state),
+    }
     
     // Render detectors chart / رسم رسم بياني الكاشفات
     render_detectors_chart(frame, chunks[1], state);
+    
+    // Render event log / رسم سجل الأحداث
+    super::events::render(frame, chunks[2], state);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -56,26 +78,79 @@ pub fn render_chart_panel(frame: &mut Frame, area: Rect, state: &AppState) {
 
 /// Render the CSI magnitude chart
 /// رسم رسم بياني سعة CSI
-fn render_csi_chart(frame: &mut Frame, area: Rect, state: &AppState) {
+///
+/// Remembers the auto-scaled bounds so they can be locked (Y).
+fn render_csi_chart(frame: &mut Frame, area: Rect, state: &mut AppState) {
     // Prepare data for the chart / تحضير البيانات للرسم البياني
-    let frames = state.get_last_frames(CHART_SAMPLES);
+    let samples = state.live_chart_samples;
+    let frames = state.chart_frames(samples);
     
     // Create data points for the chart
     // إنشاء نقاط البيانات للرسم البياني
-    let data_points: Vec<(f64, f64)> = frames
-        .iter()
-        .enumerate()
-        .map(|(i, frame)| {
-            let avg_mag = if frame.mags.is_empty() {
-                0.0
-            } else {
-                frame.mags.iter().sum::<f64>() / frame.mags.len() as f64
+    let (data_points, auto_bounds, title): (Vec<(f64, f64)>, [f64; 2], String) = match state.chart_mode {
+        ChartMode::Magnitude | ChartMode::Spectrum | ChartMode::Stability => {
+            let points = magnitude_points(state, frames.iter());
+            let mut title = match state.selected_subcarrier {
+                Some(_) => format!("📈 {} (Last {} Samples)", state.subcarrier_label(), samples),
+                None => format!("📈 CSI Magnitude (Last {} Samples)", samples),
             };
-            (i as f64, avg_mag.min(Y_AXIS_MAX))
-        })
-        .collect();
+            if state.smoothing_window > 1 {
+                title.push_str(&format!(" smoothed ({})", state.smoothing_window));
+            }
+            (points, [Y_AXIS_MIN, Y_AXIS_MAX], title)
+        }
+        ChartMode::Dynamic => {
+            // Deviation from the rolling median, centered at zero
+            // الانحراف عن الوسيط المتحرك، متمركز حول الصفر
+            let points: Vec<(f64, f64)> = state
+                .chart_dynamic()
+                .last_residuals(samples)
+                .enumerate()
+                .map(|(i, r)| (i as f64, r))
+                .collect();
+            let bounds = symmetric_bounds(points.iter().map(|&(_, r)| r));
+            (points, bounds, format!("📈 CSI Dynamic Component (Last {} Samples)", samples))
+        }
+    };
+
+    // Named sources alongside the live magnitude (not in a frozen snapshot)
+    // المصادر المسماة بجانب السعة المباشرة (ليس في اللقطة المجمدة)
+    let source_points: Vec<(String, Vec<(f64, f64)>)> =
+        if state.chart_mode == ChartMode::Magnitude && !state.view_frozen {
+            state
+                .sources
+                .iter()
+                .map(|source| (source.name.clone(), magnitude_points(state, source.recent_frames(samples))))
+                .filter(|(_, points)| !points.is_empty())
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-    let datasets = if data_points.is_empty() {
+    // Locked bounds win over auto-scaling / الحدود المقفلة تتقدم على التحجيم التلقائي
+    state.chart_auto_bounds = (auto_bounds[0], auto_bounds[1]);
+    let (y_bounds, title) = match state.locked_bounds {
+        Some((min, max)) => ([min, max], format!("{} 🔒", title)),
+        None => (auto_bounds, title),
+    };
+
+    // Sweeping line while waiting, if enabled / خط مسح أثناء الانتظار إن كان مفعلاً
+    let no_data = data_points.is_empty() && source_points.is_empty();
+    let idle_points = if no_data && state.idle_animation {
+        idle_sweep_points(state.idle_tick, samples, y_bounds)
+    } else {
+        Vec::new()
+    };
+
+    let datasets = if !idle_points.is_empty() {
+        let dots = ".".repeat((state.idle_tick / 4 % 4) as usize);
+        vec![Dataset::default()
+            .name(format!("Waiting for data{}", dots))
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::DarkGray))
+            .data(&idle_points)]
+    } else if no_data {
         vec![Dataset::default()
             .name("No Data")
             .marker(symbols::Marker::Braille)
@@ -83,20 +158,194 @@ fn render_csi_chart(frame: &mut Frame, area: Rect, state: &AppState) {
             .style(Style::default().fg(Color::Gray))
             .data(&[])]
     } else {
-        vec![
+        let mut datasets = vec![
             Dataset::default()
                 .name("CSI Magnitude")
                 .marker(symbols::Marker::Braille)
                 .graph_type(GraphType::Line)
                 .style(Style::default().fg(Color::Cyan))
                 .data(&data_points),
-        ]
+        ];
+        for ((name, points), color) in source_points.iter().zip(SOURCE_COLORS.iter().cycle()) {
+            datasets.push(
+                Dataset::default()
+                    .name(name.as_str())
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(*color))
+                    .data(points),
+            );
+        }
+        datasets
     };
 
+    let x_bounds = x_axis_bounds(samples);
     let x_labels = vec![
         Span::raw("0"),
-        Span::raw(format!("{}", CHART_SAMPLES / 2)),
-        Span::raw(format!("{}", CHART_SAMPLES)),
+        Span::raw(format!("{}", samples / 2)),
+        Span::raw(format!("{}", samples)),
+    ];
+
+    let y_labels = vec![
+        Span::raw(format!("{:.0}", y_bounds[0])),
+        Span::raw(format!("{:.0}", (y_bounds[0] + y_bounds[1]) / 2.0)),
+        Span::raw(format!("{:.0}", y_bounds[1])),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green)),
+        )
+        .x_axis(
+            Axis::default()
+                .title("Sample")
+                .style(Style::default().fg(Color::Gray))
+                .bounds(x_bounds)
+                .labels(x_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Magnitude")
+                .style(Style::default().fg(Color::Gray))
+                .bounds(y_bounds)
+                .labels(y_labels),
+        );
+
+    frame.render_widget(chart, area);
+}
+
+/// Chart points for `frames`, smoothed for display only (stored frames are untouched)
+/// نقاط الرسم للإطارات، منعّمة للعرض فقط (الإطارات المخزنة لا تتغير)
+fn magnitude_points<'a>(state: &AppState, frames: impl Iterator<Item = &'a CsiFrame>) -> Vec<(f64, f64)> {
+    let values: Vec<f64> = frames.map(|frame| state.chart_value(frame)).collect();
+    moving_average(&values, state.smoothing_window)
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| (i as f64, value.min(Y_AXIS_MAX)))
+        .collect()
+}
+
+/// Trailing `window`-point moving average; the first points average what exists
+/// متوسط متحرك لآخر `window` نقاط؛ النقاط الأولى تستخدم المتاح
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| {
+            sum += value;
+            if i >= window {
+                sum -= values[i - window];
+            }
+            sum / (i + 1).min(window) as f64
+        })
+        .collect()
+}
+
+/// Vertical line for the idle placeholder, moving with each render
+/// خط عمودي لعنصر الانتظار يتحرك مع كل رسم
+fn idle_sweep_points(tick: u64, samples: usize, y_bounds: [f64; 2]) -> Vec<(f64, f64)> {
+    let x = (tick.wrapping_mul(IDLE_SWEEP_STEP) % samples.max(1) as u64) as f64;
+    vec![(x, y_bounds[0]), (x, y_bounds[1])]
+}
+
+/// X-axis bounds for a window of `samples` points
+/// حدود المحور السيني لنافذة من `samples` نقطة
+fn x_axis_bounds(samples: usize) -> [f64; 2] {
+    [0.0, samples.max(1) as f64]
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Spectrum Chart / رسم بياني الطيف
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render magnitude per subcarrier, optionally averaged over N frames
+/// رسم السعة لكل ناقل فرعي، مع متوسط اختياري على N إطار
+fn render_spectrum_chart(frame: &mut Frame, area: Rect, state: &AppState) {
+    let to_points = |mags: &[f64]| -> Vec<(f64, f64)> {
+        mags.iter()
+            .enumerate()
+            .map(|(i, &m)| (i as f64, m.min(Y_AXIS_MAX)))
+            .collect()
+    };
+    
+    // Only the selected stream, so concatenated streams don't form a sawtooth
+    // التدفق المختار فقط حتى لا تشكل التدفقات المتسلسلة شكل منشار
+    let last = state.chart_frames(1).last();
+    let range = last.map(|f| state.selected_range(f)).unwrap_or_default();
+    let last_points = last.map(|f| to_points(&f.mags[range.clone()])).unwrap_or_default();
+    let mean = state.chart_spectrum().mean();
+    let avg_points = to_points(mean.get(range.clone()).unwrap_or(&mean));
+    let sc_count = range.len();
+
+    // One thin band per detector along the bottom, one text row apart
+    // شريط رفيع لكل كاشف في الأسفل، بفارق صف نصي واحد
+    let row_height = (Y_AXIS_MAX - Y_AXIS_MIN) / area.height.saturating_sub(4).max(1) as f64;
+    let bands: Vec<(PrimaryDetector, Vec<(f64, f64)>)> = PrimaryDetector::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| {
+            let y = Y_AXIS_MIN + row_height * (i as f64 + 0.5);
+            (d, band_points(state.detector_windows.ratios.range(sc_count, d), y))
+        })
+        .collect();
+    let band_datasets = bands.iter().map(|(d, points)| {
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(detector_color(*d)))
+            .data(points)
+    });
+
+    // Averaged line, on top of a faint instantaneous frame when overlaid (~)
+    // خط المتوسط، فوق إطار لحظي باهت عند التراكب (~)
+    let (datasets, title) = if state.spectrum_averaging {
+        let overlay = state.spectrum_overlay.then(|| {
+            Dataset::default()
+                .name("Last Frame")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::DarkGray))
+                .data(&last_points)
+        });
+        (
+            overlay
+                .into_iter()
+                .chain([Dataset::default()
+                    .name(format!("Avg {}", state.chart_spectrum().frame_count()))
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(Color::Cyan))
+                    .data(&avg_points)])
+                .collect(),
+            format!("📊 CSI Spectrum (Avg of {} / {} Frames)",
+                state.chart_spectrum().frame_count(), state.chart_spectrum().window()),
+        )
+    } else {
+        (
+            vec![Dataset::default()
+                .name("Last Frame")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&last_points)],
+            "📊 CSI Spectrum (Last Frame)".to_string(),
+        )
+    };
+    let title = match state.selected_stream {
+        Some(_) => format!("{} · {}", title, state.stream_label()),
+        None => title,
+    };
+    let datasets: Vec<Dataset> = band_datasets.chain(datasets).collect();
+
+    let x_labels = vec![
+        Span::raw("0"),
+        Span::raw(format!("{}", sc_count / 2)),
+        Span::raw(format!("{}", sc_count)),
     ];
 
     let y_labels = vec![
@@ -108,15 +357,15 @@ fn render_csi_chart(frame: &mut Frame, area: Rect, state: &AppState) {
     let chart = Chart::new(datasets)
         .block(
             Block::default()
-                .title("📈 CSI Magnitude (Last 100 Samples)")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Green)),
         )
         .x_axis(
             Axis::default()
-                .title("Sample")
+                .title("Subcarrier")
                 .style(Style::default().fg(Color::Gray))
-                .bounds([0.0, CHART_SAMPLES as f64])
+                .bounds(x_axis_bounds(sc_count))
                 .labels(x_labels),
         )
         .y_axis(
@@ -130,32 +379,166 @@ fn render_csi_chart(frame: &mut Frame, area: Rect, state: &AppState) {
     frame.render_widget(chart, area);
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Stability Chart / رسم الاستقرار
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render one bar per subcarrier, colored by how much its magnitude varies
+/// رسم عمود لكل ناقل فرعي ملون حسب تغير سعته
+fn render_stability_chart(frame: &mut Frame, area: Rect, state: &AppState) {
+    let deviations = state.subcarrier_stability();
+    let [stable, moderate, noisy] = stability_bars(&deviations);
+    let y_max = deviations.iter().fold(MIN_DYNAMIC_RANGE, |acc, &d| acc.max(d)).ceil();
+
+    let datasets = [
+        (stable, "Stable", Color::Green),
+        (moderate, "Moderate", Color::Yellow),
+        (noisy, "Noisy", Color::Red),
+    ];
+    let datasets: Vec<Dataset> = datasets
+        .iter()
+        .map(|(points, name, color)| {
+            Dataset::default()
+                .name(*name)
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Bar)
+                .style(Style::default().fg(*color))
+                .data(points)
+        })
+        .collect();
+
+    let sc_count = deviations.len();
+    let x_labels = vec![
+        Span::raw("0"),
+        Span::raw(format!("{}", sc_count / 2)),
+        Span::raw(format!("{}", sc_count)),
+    ];
+    let y_labels = vec![
+        Span::raw("0"),
+        Span::raw(format!("{:.1}", y_max / 2.0)),
+        Span::raw(format!("{:.1}", y_max)),
+    ];
+
+    let chart = Chart::new(datasets)
+        .block(
+            Block::default()
+                .title(format!("📶 Subcarrier Stability (σ over {} Frames)", state.frames.len()))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green)),
+        )
+        .x_axis(
+            Axis::default()
+                .title("Subcarrier")
+                .style(Style::default().fg(Color::Gray))
+                .bounds(x_axis_bounds(sc_count))
+                .labels(x_labels),
+        )
+        .y_axis(
+            Axis::default()
+                .title("Std Dev")
+                .style(Style::default().fg(Color::Gray))
+                .bounds([0.0, y_max])
+                .labels(y_labels),
+        );
+
+    frame.render_widget(chart, area);
+}
+
+/// Bars split into stable / moderate / noisy against the median deviation
+/// الأعمدة مقسمة إلى مستقرة / متوسطة / مشوشة مقارنة بوسيط الانحراف
+///
+/// Stable is at most the median, noisy above `NOISY_STABILITY_RATIO` × median.
+fn stability_bars(deviations: &[f64]) -> [Vec<(f64, f64)>; 3] {
+    let mut sorted = deviations.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+
+    let mut bars: [Vec<(f64, f64)>; 3] = Default::default();
+    for (k, &deviation) in deviations.iter().enumerate() {
+        let class = if deviation <= median {
+            0
+        } else if deviation <= median * NOISY_STABILITY_RATIO {
+            1
+        } else {
+            2
+        };
+        bars[class].push((k as f64, deviation));
+    }
+    bars
+}
+
+/// Horizontal segment spanning an analysis range at height `y`; empty if the range is
+/// خط أفقي يغطي نطاق التحليل على الارتفاع `y`؛ فارغ إذا كان النطاق فارغاً
+fn band_points((start, end): (usize, usize), y: f64) -> Vec<(f64, f64)> {
+    if end > start {
+        vec![(start as f64, y), (end as f64, y)]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Theme color of a detector, shared by its history line and range band
+/// لون الكاشف في السمة، مشترك بين خط تاريخه وشريط نطاقه
+pub(super) fn detector_color(detector: PrimaryDetector) -> Color {
+    match detector {
+        PrimaryDetector::Motion => Color::Red,
+        PrimaryDetector::Presence => Color::Green,
+        PrimaryDetector::Door => Color::Blue,
+    }
+}
+
+/// Y-axis bounds symmetric around zero covering all values
+/// حدود محور صادي متماثلة حول الصفر تغطي جميع القيم
+fn symmetric_bounds(values: impl Iterator<Item = f64>) -> [f64; 2] {
+    let peak = values.fold(MIN_DYNAMIC_RANGE, |acc, v| acc.max(v.abs()));
+    [-peak, peak]
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Detectors Chart / رسم بياني الكاشفات
 // ═══════════════════════════════════════════════════════════════════════════════
 
+/// Y-axis upper bound covering the scaled detector history
+/// الحد الأعلى للمحور الصادي الذي يغطي تاريخ الكاشفات المحجم
+///
+/// Starts at the scale's floor and grows in steps of half that floor, so a
+/// spike stays on the chart instead of being clipped.
+fn detectors_y_max(state: &AppState) -> f64 {
+    let floor = state.display_scale.chart_floor();
+    let peak = state
+        .chart_histories()
+        .iter()
+        .flat_map(|history| history.iter())
+        .fold(0.0_f64, |acc, &v| acc.max(v));
+    if peak <= floor {
+        floor
+    } else {
+        let step = floor / 2.0;
+        (peak / step).ceil() * step
+    }
+}
+
 /// Render the detectors chart with 3 lines
 /// رسم رسم بياني الكاشفات مع 3 خطوط
 fn render_detectors_chart(frame: &mut Frame, area: Rect, state: &AppState) {
+    let [motion_history, presence_history, door_history] = state.chart_histories();
+
     // Prepare motion data / تحضير بيانات الحركة
-    let motion_data: Vec<(f64, f64)> = state
-        .motion_history
+    let motion_data: Vec<(f64, f64)> = motion_history
         .iter()
         .enumerate()
         .map(|(i, &v)| (i as f64, v))
         .collect();
 
     // Prepare presence data / تحضير بيانات الوجود
-    let presence_data: Vec<(f64, f64)> = state
-        .presence_history
+    let presence_data: Vec<(f64, f64)> = presence_history
         .iter()
         .enumerate()
         .map(|(i, &v)| (i as f64, v))
         .collect();
 
     // Prepare door data / تحضير بيانات الباب
-    let door_data: Vec<(f64, f64)> = state
-        .door_history
+    let door_data: Vec<(f64, f64)> = door_history
         .iter()
         .enumerate()
         .map(|(i, &v)| (i as f64, v))
@@ -168,19 +551,19 @@ fn render_detectors_chart(frame: &mut Frame, area: Rect, state: &AppState) {
             .name("🔴 Motion")
             .marker(symbols::Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Red))
+            .style(Style::default().fg(detector_color(PrimaryDetector::Motion)))
             .data(&motion_data),
         Dataset::default()
             .name("🟢 Presence")
             .marker(symbols::Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Green))
+            .style(Style::default().fg(detector_color(PrimaryDetector::Presence)))
             .data(&presence_data),
         Dataset::default()
             .name("🔵 Door")
             .marker(symbols::Marker::Braille)
             .graph_type(GraphType::Line)
-            .style(Style::default().fg(Color::Blue))
+            .style(Style::default().fg(detector_color(PrimaryDetector::Door)))
             .data(&door_data),
     ];
 
@@ -190,16 +573,20 @@ fn render_detectors_chart(frame: &mut Frame, area: Rect, state: &AppState) {
         Span::raw("100"),
     ];
 
+    let y_max = detectors_y_max(state);
     let y_labels = vec![
         Span::raw("0"),
-        Span::raw("250"),
-        Span::raw("500"),
+        Span::raw(format!("{:.0}", y_max / 2.0)),
+        Span::raw(format!("{:.0}", y_max)),
     ];
 
     let chart = Chart::new(datasets)
         .block(
             Block::default()
-                .title("🔍 Detectors (Motion | Presence | Door)")
+                .title(format!(
+                    "🔍 Detectors (Motion | Presence | Door) [{}]",
+                    state.display_scale.label()
+                ))
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(Color::Yellow)),
         )
@@ -214,9 +601,101 @@ fn render_detectors_chart(frame: &mut Frame, area: Rect, state: &AppState) {
             Axis::default()
                 .title("Intensity")
                 .style(Style::default().fg(Color::Gray))
-                .bounds([0.0, 500.0])  // زيادة من 100 إلى 500
+                .bounds([0.0, y_max])
                 .labels(y_labels),
         );
 
     frame.render_widget(chart, area);
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_bands_follow_ratios() {
+        let mut state = AppState::new();
+        let ratios = &mut state.detector_windows.ratios;
+        assert_eq!(band_points(ratios.range(64, PrimaryDetector::Door), 2.0), vec![(24.0, 2.0), (40.0, 2.0)]);
+
+        // Widening at runtime moves the band / التوسيع أثناء التشغيل يحرك الشريط
+        ratios.step(PrimaryDetector::Door, true);
+        assert_eq!(band_points(ratios.range(64, PrimaryDetector::Door), 2.0), vec![(22.0, 2.0), (41.0, 2.0)]);
+
+        // No frames → no band / بدون إطارات ← بدون شريط
+        assert!(band_points(ratios.range(0, PrimaryDetector::Motion), 2.0).is_empty());
+    }
+
+    #[test]
+    fn test_spectrum_overlay_toggle() {
+        use csi_tui::state::{ChartMode, CsiFormat, CsiFrame, WindowClock};
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let mut state = AppState::new();
+        state.window_clock = WindowClock::NewestFrame;
+        state.chart_mode = ChartMode::Spectrum;
+        state.cycle_spectrum_average();
+        for i in 0..8 {
+            state.push_frame(CsiFrame::new(i * 100, vec![20.0 + i as f64; 32], vec![], CsiFormat::AmplitudeOnly, None));
+        }
+
+        let screen = |state: &AppState| {
+            let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+            terminal.draw(|frame| render_spectrum_chart(frame, frame.area(), state)).unwrap();
+            terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+
+        // Off by default: the averaged line only / معطل افتراضياً: خط المتوسط فقط
+        let plain = screen(&state);
+        assert!(plain.contains("Avg 8"));
+        assert!(!plain.contains("Last Frame"));
+
+        state.spectrum_overlay = true;
+        let overlaid = screen(&state);
+        assert!(overlaid.contains("Avg 8"));
+        assert!(overlaid.contains("Last Frame"));
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let values = [2.0, 4.0, 6.0, 8.0, 10.0];
+        assert_eq!(moving_average(&values, 1), values.to_vec());
+        assert_eq!(moving_average(&values, 3), vec![2.0, 3.0, 4.0, 6.0, 8.0]);
+        assert_eq!(moving_average(&values, 0), values.to_vec());
+        assert!(moving_average(&[], 5).is_empty());
+    }
+
+    #[test]
+    fn test_x_axis_bounds() {
+        assert_eq!(x_axis_bounds(100), [0.0, 100.0]);
+        assert_eq!(x_axis_bounds(400), [0.0, 400.0]);
+        assert_eq!(x_axis_bounds(0), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_detectors_y_max() {
+        let mut state = AppState::new();
+        assert_eq!(detectors_y_max(&state), 200.0);
+        state.door_history = vec![50.0, 230.0];
+        assert_eq!(detectors_y_max(&state), 300.0);
+    }
+
+    #[test]
+    fn test_stability_bars() {
+        let [stable, moderate, noisy] = stability_bars(&[1.0, 1.0, 1.5, 1.0, 4.0]);
+        assert_eq!(stable, vec![(0.0, 1.0), (1.0, 1.0), (3.0, 1.0)]);
+        assert_eq!(moderate, vec![(2.0, 1.5)]);
+        assert_eq!(noisy, vec![(4.0, 4.0)]);
+        assert!(stability_bars(&[]).iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn test_symmetric_bounds() {
+        assert_eq!(symmetric_bounds([2.0, -7.5, 3.0].into_iter()), [-7.5, 7.5]);
+        assert_eq!(symmetric_bounds(std::iter::empty()), [-MIN_DYNAMIC_RANGE, MIN_DYNAMIC_RANGE]);
+    }
+}
@@ -0,0 +1,44 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/confirm_quit.rs - Quit Confirmation Popup
+// ═══════════════════════════════════════════════════════════════════════════════
+// Asked before quitting while a CSV recording is open; only Y quits
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use csi_tui::state::AppState;
+use super::helpers::centered_rect;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Quit Confirmation Popup / نافذة تأكيد الخروج
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render the "quit while recording?" popup / رسم نافذة تأكيد الخروج أثناء التسجيل
+pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
+    let popup = centered_rect(50, 20, area);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut text = vec![Line::from(Span::styled(
+        "Recording in progress — quit anyway? y/N",
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ))];
+    if let Some(logger) = &state.csv_logger {
+        text.push(Line::from(Span::styled(format!("{}", logger.file_path().display()), dim)));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled("Y quit and close the file  any other key stays", dim)));
+
+    let block = Block::default()
+        .title("⚠️ Quit")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text).block(block), popup);
+}
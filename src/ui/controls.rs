@@ -12,7 +12,7 @@ use ratatui::{
     Frame,
 };
 
-use crate::state::AppState;
+use csi_tui::state::AppState;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Controls Help Box / مربع مساعدة التحكم
@@ -56,6 +56,126 @@ fn render_normal_controls() -> Vec<Line<'static>> {
             Span::styled("L", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
             Span::raw(" Load CSV"),
         ]),
+        Line::from(vec![
+            Span::styled("F5", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Reload Last CSV"),
+        ]),
+        Line::from(vec![
+            Span::styled("F6", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Auto-Reconnect On/Off"),
+        ]),
+        Line::from(vec![
+            Span::styled("F7", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Pick Serial Port"),
+        ]),
+        Line::from(vec![
+            Span::styled("F8", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Cycle Baud Rate"),
+        ]),
+        Line::from(vec![
+            Span::styled("W", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Record CSV On/Off"),
+        ]),
+        Line::from(vec![
+            Span::styled("T", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Follow Growing CSV"),
+        ]),
+        Line::from(vec![
+            Span::styled("J", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Export JSON"),
+        ]),
+        Line::from(vec![
+            Span::styled("+/-", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Zoom Chart"),
+        ]),
+        Line::from(vec![
+            Span::styled(";/'", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Smooth Chart"),
+        ]),
+        Line::from(vec![
+            Span::styled("</>", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Frame Window"),
+        ]),
+        Line::from(vec![
+            Span::styled("V", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Chart View"),
+        ]),
+        Line::from(vec![
+            Span::styled("Y", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Lock Chart Scale"),
+        ]),
+        Line::from(vec![
+            Span::styled("h/H", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Chart Subcarrier"),
+        ]),
+        Line::from(vec![
+            Span::styled("A", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Spectrum Avg"),
+        ]),
+        Line::from(vec![
+            Span::styled("~", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Avg + Last Frame"),
+        ]),
+        Line::from(vec![
+            Span::styled("O", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Compact Status"),
+        ]),
+        Line::from(vec![
+            Span::styled("P", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Analysis Detector"),
+        ]),
+        Line::from(vec![
+            Span::styled("( )", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Narrow/Widen Range"),
+        ]),
+        Line::from(vec![
+            Span::styled("K", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" CSI Format"),
+        ]),
+        Line::from(vec![
+            Span::styled("N", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Plot Stream"),
+        ]),
+        Line::from(vec![
+            Span::styled("G", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Aggregation"),
+        ]),
+        Line::from(vec![
+            Span::styled("M", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" MAC Table / Filter"),
+        ]),
+        Line::from(vec![
+            Span::styled("F", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" UI Refresh Rate"),
+        ]),
+        Line::from(vec![
+            Span::styled("E", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Event Log Tail/Review"),
+        ]),
+        Line::from(vec![
+            Span::styled("Z", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Reset Block Counters"),
+        ]),
+        Line::from(vec![
+            Span::styled("U", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Report Bundle"),
+        ]),
+        Line::from(vec![
+            Span::styled("F2", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Raw Inspector"),
+        ]),
+        Line::from(vec![
+            Span::styled("F3", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Freeze View"),
+        ]),
+        Line::from(vec![
+            Span::styled("F4", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Reset Session Stats"),
+        ]),
+        Line::from(vec![
+            Span::styled("C/D", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
+            Span::raw(" Baseline / Δ"),
+        ]),
         Line::from(vec![
             Span::styled("Q", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
             Span::raw(" Quit"),
@@ -87,6 +207,22 @@ fn render_playback_controls() -> Vec<Line<'static>> {
             Span::styled("R", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
             Span::raw(" Restart"),
         ]),
+        Line::from(vec![
+            Span::styled("{/}", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Marker A/B"),
+        ]),
+        Line::from(vec![
+            Span::styled("[/]", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Speed ½x/2x"),
+        ]),
+        Line::from(vec![
+            Span::styled("I", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" A-B Stats"),
+        ]),
+        Line::from(vec![
+            Span::styled("F5", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" Reload"),
+        ]),
         Line::from(vec![
             Span::styled("B", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
             Span::raw(" Back to Live"),
@@ -0,0 +1,65 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/events.rs - Event Log Panel
+// ═══════════════════════════════════════════════════════════════════════════════
+// Displays the most recent detector transitions, following or scrolled back
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use chrono::{Local, TimeZone};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use csi_tui::state::AppState;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Event Log Box / مربع سجل الأحداث
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render the event log, newest entry at the bottom
+/// رسم سجل الأحداث، أحدث إدخال في الأسفل
+///
+/// Tail mode shows the newest entries; review mode keeps the scrolled view.
+pub fn render(frame: &mut Frame, area: Rect, state: &mut AppState) {
+    let visible = area.height.saturating_sub(2) as usize;
+    state.event_log.set_viewport(visible);
+    let skip = state.event_log.scroll_top();
+    let entries = state.event_log.entries();
+
+    let text: Vec<Line> = if entries.is_empty() {
+        vec![Line::from(Span::styled("No events yet", Style::default().fg(Color::DarkGray)))]
+    } else {
+        entries
+            .iter()
+            .skip(skip)
+            .take(visible)
+            .map(|entry| {
+                let time = Local
+                    .timestamp_millis_opt(entry.timestamp)
+                    .single()
+                    .map(|t| t.format("%H:%M:%S").to_string())
+                    .unwrap_or_default();
+                Line::from(vec![
+                    Span::styled(format!("[{}] ", time), Style::default().fg(Color::DarkGray)),
+                    Span::raw(entry.message.clone()),
+                ])
+            })
+            .collect()
+    };
+
+    let title = if state.event_log.auto_scroll() {
+        "📜 Events".to_string()
+    } else {
+        format!("📜 Events [review {}-{}/{}]", skip + 1, (skip + visible).min(entries.len()), entries.len())
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Gray));
+
+    let paragraph = Paragraph::new(text).block(block);
+    frame.render_widget(paragraph, area);
+}
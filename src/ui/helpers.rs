@@ -12,7 +12,6 @@ use ratatui::layout::{Constraint, Direction, Layout, Rect};
 
 /// Create a centered rect with given percentage of parent area
 /// إنشاء مستطيل في المنتصف بنسبة معينة من المنطقة الأصل
-#[allow(dead_code)]
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
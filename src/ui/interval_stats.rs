@@ -0,0 +1,45 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/interval_stats.rs - A-B Interval Statistics Popup
+// ═══════════════════════════════════════════════════════════════════════════════
+// Numbers for the stretch between the playback markers; W saves them to a file
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use csi_tui::interval_stats::IntervalStats;
+use super::helpers::centered_rect;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Interval Statistics Popup / نافذة إحصائيات الفترة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render the A-B statistics popup
+/// رسم نافذة إحصائيات الفترة A-B
+pub fn render(frame: &mut Frame, area: Rect, stats: &IntervalStats) {
+    let popup = centered_rect(50, 40, area);
+
+    let mut text: Vec<Line> = stats
+        .report()
+        .lines()
+        .map(|line| Line::from(format!("  {}", line)))
+        .collect();
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "W save to file  Esc close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let block = Block::default()
+        .title("📏 A-B Interval")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text).block(block), popup);
+}
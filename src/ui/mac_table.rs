@@ -0,0 +1,70 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/mac_table.rs - MAC Table Popup
+// ═══════════════════════════════════════════════════════════════════════════════
+// Lists transmitters seen in the last minute; Enter locks the filter onto one
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use csi_tui::state::AppState;
+use super::helpers::centered_rect;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 MAC Table Popup / نافذة جدول MAC
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render the per-MAC statistics popup
+/// رسم نافذة إحصائيات كل عنوان MAC
+pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
+    let popup = centered_rect(60, 50, area);
+    let rows = state.mac_stats.sorted();
+
+    let mut text = vec![Line::from(Span::styled(
+        format!("  {:<17}  {:>7}  {:>7}  {:>5}", "MAC", "Frames", "Rate/s", "RSSI"),
+        Style::default().fg(Color::DarkGray),
+    ))];
+
+    if rows.is_empty() {
+        text.push(Line::from(Span::styled(
+            "  No transmitters seen in the last minute",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    for (i, (mac, entry)) in rows.iter().enumerate() {
+        let marker = if state.mac_filter.as_deref() == Some(*mac) { "🎯" } else { "  " };
+        let rssi = entry.last_rssi.map(|r| r.to_string()).unwrap_or_else(|| "-".to_string());
+        let mut style = Style::default();
+        if i == state.mac_table_selected {
+            style = style.fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+        }
+        text.push(Line::from(Span::styled(
+            format!("{}{:<17}  {:>7}  {:>7.1}  {:>5}", marker, mac, entry.frame_count(), entry.rate(), rssi),
+            style,
+        )));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        "↑/↓ select  Enter lock / unlock filter  Esc close",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let title = match &state.mac_filter {
+        Some(mac) => format!("📶 Transmitters (filter: {})", mac),
+        None => "📶 Transmitters (no filter)".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text).block(block), popup);
+}
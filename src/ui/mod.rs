@@ -3,23 +3,38 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 // This module implements the TUI using Ratatui.
 // Features:
-// - Two-column layout (Status | Chart)
+// - Two-column layout (Status | Chart), or a compact summary bar above the charts
 // - Live magnitude graph
 // - Detection status display
 // - Keyboard controls display
+// - Per-MAC table popup
+// - A-B interval statistics popup
+// - Raw data inspector popup (decimal or hex)
+// - Serial port picker popup
+// - Quit confirmation while recording
+// - Color fallbacks for 8-color and monochrome terminals
 // ═══════════════════════════════════════════════════════════════════════════════
 
 mod charts;
+mod confirm_quit;
 mod controls;
+mod events;
 mod helpers;
+mod interval_stats;
+mod mac_table;
+mod port_picker;
+mod raw_inspector;
 mod status_panel;
+mod theme;
 
 use ratatui::{
     layout::{Constraint, Direction, Layout},
     Frame,
 };
 
-use crate::state::SharedState;
+use csi_tui::state::SharedState;
+
+pub use theme::ColorDepth;
 
 // Re-export helpers for external use (if needed)
 #[allow(unused_imports)]
@@ -31,25 +46,210 @@ pub use helpers::centered_rect;
 
 /// Render the entire UI
 /// رسم واجهة المستخدم بالكامل
-pub fn render(frame: &mut Frame, state: &SharedState) {
+pub fn render(frame: &mut Frame, state: &SharedState, depth: ColorDepth) {
     // Get state data / الحصول على بيانات الحالة
-    let state_guard = match state.lock() {
+    let mut state_guard = match state.lock() {
         Ok(guard) => guard,
         Err(_) => return,
     };
 
-    // Create main layout: two columns / إنشاء التخطيط الرئيسي: عمودين
-    let main_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(30), // Left panel - Status / اللوحة اليسرى - الحالة
-            Constraint::Percentage(70), // Right panel - Chart / اللوحة اليمنى - الرسم البياني
-        ])
-        .split(frame.area());
+    // Advance the idle placeholder animation / تقديم حركة عنصر الانتظار
+    if state_guard.idle_animation && state_guard.frames.is_empty() {
+        state_guard.idle_tick = state_guard.idle_tick.wrapping_add(1);
+    }
+
+    if state_guard.compact_status {
+        // Compact: summary bar on top, charts get the full width below
+        // مضغوط: شريط ملخص في الأعلى، والرسوم تأخذ العرض الكامل أسفله
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(status_panel::COMPACT_ROWS),
+                Constraint::Min(0),
+            ])
+            .split(frame.area());
+        status_panel::render_compact(frame, rows[0], &state_guard);
+        charts::render_chart_panel(frame, rows[1], &mut state_guard);
+    } else {
+        // Create main layout: two columns / إنشاء التخطيط الرئيسي: عمودين
+        let main_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(30), // Left panel - Status / اللوحة اليسرى - الحالة
+                Constraint::Percentage(70), // Right panel - Chart / اللوحة اليمنى - الرسم البياني
+            ])
+            .split(frame.area());
+
+        // Render left panel (Status) / رسم اللوحة اليسرى (الحالة)
+        status_panel::render(frame, main_chunks[0], &state_guard);
+
+        // Render right panel (Chart) / رسم اللوحة اليمنى (الرسم البياني)
+        charts::render_chart_panel(frame, main_chunks[1], &mut state_guard);
+    }
+
+    // Popups on top / النوافذ المنبثقة في الأعلى
+    if state_guard.show_mac_table {
+        mac_table::render(frame, frame.area(), &state_guard);
+    }
+    if let Some(stats) = &state_guard.interval_stats {
+        interval_stats::render(frame, frame.area(), stats);
+    }
+    if state_guard.show_raw_inspector {
+        raw_inspector::render(frame, frame.area(), &state_guard);
+    }
+    if let Some(picker) = &state_guard.port_picker {
+        port_picker::render(frame, frame.area(), picker, &state_guard.port_name);
+    }
+    if state_guard.confirm_quit {
+        confirm_quit::render(frame, frame.area(), &state_guard);
+    }
+
+    // Degrade colors last so every widget is covered / تخفيض الألوان أخيراً ليشمل كل العناصر
+    theme::apply(frame.buffer_mut(), depth);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csi_tui::state::create_shared_state;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_idle_animation_advances_per_render() {
+        let state = create_shared_state();
+        let mut terminal = Terminal::new(TestBackend::new(160, 60)).unwrap();
+
+        // Off by default: static placeholder / معطل افتراضياً: عنصر ثابت
+        terminal.draw(|frame| render(frame, &state, ColorDepth::Full)).unwrap();
+        assert_eq!(state.lock().unwrap().idle_tick, 0);
+
+        state.lock().unwrap().idle_animation = true;
+        for expected in 1..=3 {
+            terminal.draw(|frame| render(frame, &state, ColorDepth::Full)).unwrap();
+            assert_eq!(state.lock().unwrap().idle_tick, expected);
+        }
+    }
+
+    #[test]
+    fn test_compact_layout_uses_fewer_rows() {
+        let state = create_shared_state();
+        state.lock().unwrap().port_name = "/dev/ttyUSB0".to_string();
+
+        // Last row holding a status value / آخر صف يحمل قيمة حالة
+        let last_status_row = |compact: bool| {
+            state.lock().unwrap().compact_status = compact;
+            let mut terminal = Terminal::new(TestBackend::new(160, 60)).unwrap();
+            terminal.draw(|frame| render(frame, &state, ColorDepth::Full)).unwrap();
+            let buffer = terminal.backend().buffer().clone();
+            let rows: Vec<String> = (0..buffer.area.height)
+                .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+                .collect();
+            ["/dev/ttyUSB0", "Frames:", "Motion:", "Door:"]
+                .iter()
+                .map(|key| rows.iter().position(|r| r.contains(key)).unwrap_or_else(|| panic!("{} missing", key)))
+                .max()
+                .unwrap()
+        };
+
+        let full = last_status_row(false);
+        let compact = last_status_row(true);
+        assert!(compact < status_panel::COMPACT_ROWS as usize);
+        assert!(compact < full, "compact {} vs full {}", compact, full);
+    }
+
+    #[test]
+    fn test_degraded_theme_keeps_series_distinct() {
+        use ratatui::style::{Color, Modifier};
+
+        let state = create_shared_state();
+        {
+            let mut s = state.lock().unwrap();
+            s.motion_history = vec![100.0; 50];
+            s.presence_history = vec![250.0; 50];
+            s.door_history = vec![400.0; 50];
+        }
+
+        for depth in [ColorDepth::Ansi8, ColorDepth::Mono] {
+            let mut terminal = Terminal::new(TestBackend::new(160, 60)).unwrap();
+            terminal.draw(|frame| render(frame, &state, depth)).unwrap();
+            let cells = terminal.backend().buffer().content();
+
+            // No shades outside the basic 8 / لا ظلال خارج الألوان الثمانية
+            assert!(cells.iter().all(|c| !matches!(c.fg, Color::DarkGray | Color::Rgb(..) | Color::Indexed(_))));
+
+            // Braille cells of the three series look different
+            // خلايا Braille للسلاسل الثلاث تبدو مختلفة
+            let mut series: Vec<(Color, Modifier)> = cells
+                .iter()
+                .filter(|c| c.symbol().chars().all(|ch| ('\u{2801}'..='\u{28FF}').contains(&ch)))
+                .map(|c| (c.fg, c.modifier))
+                .collect();
+            series.sort_by_key(|(fg, m)| (format!("{:?}", fg), m.bits()));
+            series.dedup();
+            assert_eq!(series.len(), 3, "{:?}: {:?}", depth, series);
+            if depth == ColorDepth::Mono {
+                assert!(cells.iter().all(|c| c.fg == Color::Reset && c.bg == Color::Reset));
+            }
+        }
+    }
+
+    #[test]
+    fn test_panel_and_chart_share_scaled_values() {
+        let state = create_shared_state();
+        {
+            let mut s = state.lock().unwrap();
+            // Twice the motion threshold (42 × 5) / ضعف عتبة الحركة
+            s.detections.motion_value = 420.0;
+            s.update_detection_history();
+            assert_eq!(s.display.motion, 200.0);
+            assert_eq!(s.motion_history.last(), Some(&s.display.motion));
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(160, 60)).unwrap();
+        terminal.draw(|frame| render(frame, &state, ColorDepth::Full)).unwrap();
+        let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+
+        // Panel reads the scaled value, chart axis is on the same scale
+        // اللوحة تقرأ القيمة المحجمة، ومحور الرسم على نفس المقياس
+        assert!(screen.contains("(200.0)"));
+        assert!(!screen.contains("(420.0)"));
+        assert!(screen.contains("100 = threshold"));
+    }
+
+    #[test]
+    fn test_locked_bounds_survive_new_data() {
+        let state = create_shared_state();
+        let mut terminal = Terminal::new(TestBackend::new(160, 60)).unwrap();
+        {
+            let mut s = state.lock().unwrap();
+            s.chart_mode = csi_tui::state::ChartMode::Dynamic;
+            for i in 0..40 {
+                s.dynamic.push(i * 100, 50.0 + if i % 2 == 0 { 5.0 } else { -5.0 });
+            }
+        }
+        terminal.draw(|frame| render(frame, &state, ColorDepth::Full)).unwrap();
 
-    // Render left panel (Status) / رسم اللوحة اليسرى (الحالة)
-    status_panel::render(frame, main_chunks[0], &state_guard);
+        // Lock captures what was drawn / القفل يلتقط ما تم رسمه
+        let locked = {
+            let mut s = state.lock().unwrap();
+            assert!(s.toggle_bounds_lock());
+            assert_eq!(s.locked_bounds, Some(s.chart_auto_bounds));
+            s.chart_auto_bounds
+        };
 
-    // Render right panel (Chart) / رسم اللوحة اليمنى (الرسم البياني)
-    charts::render_chart_panel(frame, main_chunks[1], &state_guard);
+        // A large swing rescales the auto bounds but not the chart
+        // تأرجح كبير يغير الحدود التلقائية لكن ليس الرسم
+        state.lock().unwrap().dynamic.push(4_000, 500.0);
+        terminal.draw(|frame| render(frame, &state, ColorDepth::Full)).unwrap();
+        let s = state.lock().unwrap();
+        assert!(s.chart_auto_bounds.1 > locked.1);
+        assert_eq!(s.locked_bounds, Some(locked));
+        let screen: String = terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect();
+        assert!(screen.contains(&format!("{:.0}", locked.1)));
+        assert!(screen.contains("🔒"));
+    }
 }
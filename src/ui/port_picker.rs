@@ -0,0 +1,55 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/port_picker.rs - Serial Port Picker Popup
+// ═══════════════════════════════════════════════════════════════════════════════
+// Lists the serial ports with their USB descriptions; Enter picks one for S
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use csi_tui::state::PortPicker;
+use super::helpers::centered_rect;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Port Picker Popup / نافذة اختيار المنفذ
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render the serial port picker popup; `current` is marked
+/// رسم نافذة اختيار المنفذ التسلسلي مع تمييز المنفذ الحالي
+pub fn render(frame: &mut Frame, area: Rect, picker: &PortPicker, current: &str) {
+    let popup = centered_rect(60, 50, area);
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut text = Vec::new();
+    if picker.ports.is_empty() {
+        text.push(Line::from(Span::styled("  No serial ports found - plug in the ESP and reopen (F7)", dim)));
+    }
+
+    for (i, port) in picker.ports.iter().enumerate() {
+        let marker = if port.name == current { "🔌" } else { "  " };
+        let mut style = Style::default();
+        if i == picker.selected {
+            style = style.fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+        }
+        text.push(Line::from(Span::styled(
+            format!("{}{:<16}  {}", marker, port.name, port.description),
+            style,
+        )));
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled("↑/↓ select  Enter use for next connect (S)  Esc close", dim)));
+
+    let block = Block::default()
+        .title(format!("🔌 Serial Ports (current: {})", current))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text).block(block), popup);
+}
@@ -0,0 +1,147 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/raw_inspector.rs - Raw Data Inspector Popup
+// ═══════════════════════════════════════════════════════════════════════════════
+// Newest frame's raw pairs and serial block; X switches decimal / hex
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use csi_tui::state::AppState;
+use super::helpers::centered_rect;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Value Formatting / تنسيق القيم
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// One raw value in decimal or hex / قيمة خام واحدة بالعشري أو الست عشري
+///
+/// Hex uses the narrowest two's-complement width that holds the value, so
+/// byte-packed firmware values read as bytes (-5 → 0xFB).
+pub fn format_value(value: i32, hex: bool) -> String {
+    if !hex {
+        return value.to_string();
+    }
+    match value {
+        -128..=255 => format!("0x{:02X}", value as u8),
+        -32_768..=65_535 => format!("0x{:04X}", value as u16),
+        _ => format!("0x{:08X}", value as u32),
+    }
+}
+
+/// (real, imag) pairs joined on one line / الأزواج في سطر واحد
+pub fn format_pairs(pairs: &[(i32, i32)], hex: bool) -> String {
+    pairs
+        .iter()
+        .map(|&(re, im)| format!("({},{})", format_value(re, hex), format_value(im, hex)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Serial block with the integers of its CSI array rewritten
+/// الكتلة التسلسلية مع إعادة كتابة أعداد مصفوفة CSI
+///
+/// The header (MAC, RSSI, ...) is left as received.
+fn format_block(block: &str, hex: bool) -> String {
+    let Some(start) = block.find('[').filter(|_| hex) else {
+        return block.to_string();
+    };
+    let (header, array) = block.split_at(start);
+    let mut out = String::with_capacity(block.len() * 2);
+    out.push_str(header);
+    let mut number = String::new();
+    for c in array.chars().chain(std::iter::once(' ')) {
+        let starts_negative = c == '-' && number.is_empty();
+        if c.is_ascii_digit() || starts_negative {
+            number.push(c);
+            continue;
+        }
+        match number.parse::<i32>() {
+            Ok(value) => out.push_str(&format_value(value, true)),
+            Err(_) => out.push_str(&number),
+        }
+        number.clear();
+        out.push(c);
+    }
+    out.pop();
+    out
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Raw Inspector Popup / نافذة فاحص البيانات الخام
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Render the raw data inspector popup
+/// رسم نافذة فاحص البيانات الخام
+pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
+    let popup = centered_rect(70, 60, area);
+    let hex = state.raw_hex;
+    let dim = Style::default().fg(Color::DarkGray);
+
+    let mut text = Vec::new();
+    match state.frames.last() {
+        Some(last) => {
+            text.push(Line::from(Span::styled(
+                format!("  Newest frame: {:?}, {} subcarriers", last.format, last.subcarrier_count()),
+                dim,
+            )));
+            text.push(Line::from(format!("  {}", format_pairs(&last.pairs, hex))));
+        }
+        None => text.push(Line::from(Span::styled("  No frames yet", dim))),
+    }
+
+    text.push(Line::from(""));
+    match state.raw_lines.iter().last() {
+        Some(block) => {
+            text.push(Line::from(Span::styled("  Newest serial block:", dim)));
+            text.push(Line::from(format!("  {}", format_block(block, hex))));
+        }
+        None => text.push(Line::from(Span::styled("  No serial lines", dim))),
+    }
+
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled("X decimal / hex  Esc close", dim)));
+
+    let block = Block::default()
+        .title(if hex { "🔬 Raw Inspector (hex)" } else { "🔬 Raw Inspector (decimal)" })
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(text).block(block).wrap(Wrap { trim: false }), popup);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_and_decimal_pairs() {
+        let pairs = [(10, -5), (0, -128), (255, -1), (-129, 300), (70_000, -40_000)];
+
+        assert_eq!(
+            format_pairs(&pairs, false),
+            "(10,-5) (0,-128) (255,-1) (-129,300) (70000,-40000)"
+        );
+
+        // Negatives shown as two's complement of the narrowest width
+        // القيم السالبة بالمتمم الثنائي لأضيق عرض
+        assert_eq!(
+            format_pairs(&pairs, true),
+            "(0x0A,0xFB) (0x00,0x80) (0xFF,0xFF) (0xFF7F,0x012C) (0x00011170,0xFFFF63C0)"
+        );
+
+        assert_eq!(format_block("mac:24:0A rssi:-45 [1,-2,3]", true), "mac:24:0A rssi:-45 [0x01,0xFE,0x03]");
+        assert_eq!(format_block("mac:24:0A rssi:-45 [1,-2,3]", false), "mac:24:0A rssi:-45 [1,-2,3]");
+        assert_eq!(format_block("[- 4 -]", true), "[- 0x04 -]");
+    }
+}
@@ -1,7 +1,9 @@
 // ═══════════════════════════════════════════════════════════════════════════════
 // 📦 ui/status_panel.rs - Status Panel Components
 // ═══════════════════════════════════════════════════════════════════════════════
-// Contains: Receiver status, Statistics, Detectors status, Playback bar
+// Contains: Receiver status, Statistics, Detectors status (with one row per
+//           named source), Playback bar, and a compact three-line summary
+//           for short terminals
 // ═══════════════════════════════════════════════════════════════════════════════
 
 use ratatui::{
@@ -12,9 +14,24 @@ use ratatui::{
     Frame,
 };
 
-use crate::state::AppState;
+use std::time::Instant;
+
+use chrono::TimeZone;
+
+use csi_tui::detectors::{get_subcarrier_info, range_is_degenerate, PrimaryDetector};
+use csi_tui::latency::{ms, Stage};
+use csi_tui::sources::SourceState;
+use csi_tui::state::{AppState, ReceiverState};
+use super::charts::detector_color;
 use super::controls;
 
+/// Spinner frame duration (ms) / مدة إطار المؤشر الدوار
+const SPINNER_STEP_MS: i64 = 100;
+
+/// Panel height from which all three analysis ranges are listed
+/// ارتفاع اللوحة الذي تُعرض منه نطاقات التحليل الثلاثة
+const TALL_PANEL_ROWS: u16 = 40;
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Main Status Panel / لوحة الحالة الرئيسية
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -22,13 +39,16 @@ use super::controls;
 /// Render the left status panel
 /// رسم لوحة الحالة اليسرى
 pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
+    // Tall panels list every detector's range / اللوحات الطويلة تعرض نطاق كل كاشف
+    let all_ranges = area.height >= TALL_PANEL_ROWS;
+
     // Split into sections / التقسيم إلى أقسام
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(5),  // Receiver status / حالة المستقبل
-            Constraint::Length(7),  // Stats / الإحصائيات
-            Constraint::Length(9),  // Detectors / الكاشفات
+            Constraint::Length(if all_ranges { 19 } else { 17 }), // Stats, down to the Log line / الإحصائيات حتى سطر السجل
+            Constraint::Length(9 + state.sources.len() as u16), // Detectors + source rows / الكاشفات وصفوف المصادر
             Constraint::Length(5),  // Playback bar / شريط التشغيل
             Constraint::Min(8),     // Controls / التحكم
         ])
@@ -36,7 +56,7 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
 
     // Render each section / رسم كل قسم
     render_receiver_status(frame, chunks[0], state);
-    render_stats(frame, chunks[1], state);
+    render_stats(frame, chunks[1], state, all_ranges);
     render_detectors(frame, chunks[2], state);
     render_playback_bar(frame, chunks[3], state);
     controls::render(frame, chunks[4], state);
@@ -49,18 +69,21 @@ pub fn render(frame: &mut Frame, area: Rect, state: &AppState) {
 /// Render receiver status box
 /// رسم مربع حالة المستقبل
 fn render_receiver_status(frame: &mut Frame, area: Rect, state: &AppState) {
-    let (status_text, status_color) = if state.receiver_active {
-        ("● ACTIVE", Color::Green)
-    } else {
-        ("○ STOPPED", Color::Red)
-    };
+    let mut status = vec![Span::raw("Status: "), receiver_span(state)];
+    status.extend(frozen_span(state));
 
+    // A subcarrier count change outranks the UI rate / تغير عدد الناقلات يتقدم على معدل الواجهة
+    let alert = match state.sc_change_warning(chrono::Utc::now().timestamp_millis()) {
+        Some(warning) => Line::from(Span::styled(
+            warning,
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        None => budget_line(state),
+    };
     let text = vec![
-        Line::from(vec![
-            Span::raw("Status: "),
-            Span::styled(status_text, Style::default().fg(status_color).add_modifier(Modifier::BOLD)),
-        ]),
+        Line::from(status),
         Line::from(Span::raw(&state.status_message)),
+        alert,
     ];
 
     let block = Block::default()
@@ -72,24 +95,125 @@ fn render_receiver_status(frame: &mut Frame, area: Rect, state: &AppState) {
     frame.render_widget(paragraph, area);
 }
 
+/// Receiver state with its marker and color / حالة المستقبل مع رمزها ولونها
+fn receiver_span(state: &AppState) -> Span<'static> {
+    let (marker, status_color) = match state.receiver {
+        ReceiverState::Idle => ("○", Color::Red),
+        ReceiverState::Connecting | ReceiverState::Reconnecting => (spinner_frame(), Color::Yellow),
+        ReceiverState::Connected => ("●", Color::Green),
+        ReceiverState::Error => ("✖", Color::Red),
+    };
+    Span::styled(
+        format!("{} {}", marker, state.receiver.label()),
+        Style::default().fg(status_color).add_modifier(Modifier::BOLD),
+    )
+}
+
+/// "FROZEN" marker while the charts show a snapshot / علامة التجميد أثناء عرض اللقطة
+fn frozen_span(state: &AppState) -> Option<Span<'static>> {
+    state.view_frozen.then(|| {
+        Span::styled(" ❄️ FROZEN", Style::default().fg(Color::LightBlue).add_modifier(Modifier::BOLD))
+    })
+}
+
+/// Braille spinner frame for the current time / إطار المؤشر الدوار للوقت الحالي
+fn spinner_frame() -> &'static str {
+    const FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+    let tick = chrono::Utc::now().timestamp_millis() / SPINNER_STEP_MS;
+    FRAMES[tick.rem_euclid(FRAMES.len() as i64) as usize]
+}
+
+/// UI rate, or a warning while processing cannot keep up with the frames
+/// معدل الواجهة، أو تحذير عندما لا تواكب المعالجة الإطارات
+fn budget_line(state: &AppState) -> Line<'static> {
+    match state.latency.budget_ms() {
+        Some(budget) if state.latency.over_budget(Instant::now()) => Line::from(Span::styled(
+            format!("⚠️ Falling behind: {:.1} > {:.1} ms/frame", ms(state.latency.total()), budget),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )),
+        _ => Line::from(Span::styled(
+            format!("UI: {:.0}/{} fps", state.ui_achieved_fps, state.ui_refresh_fps),
+            Style::default().fg(Color::DarkGray),
+        )),
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Statistics / الإحصائيات
 // ═══════════════════════════════════════════════════════════════════════════════
 
 /// Render statistics box
 /// رسم مربع الإحصائيات
-fn render_stats(frame: &mut Frame, area: Rect, state: &AppState) {
-    // Get Wi-Fi standard based on subcarrier count
-    let wifi_info = crate::detectors::get_subcarrier_info(state.max_sc);
+fn render_stats(frame: &mut Frame, area: Rect, state: &AppState, all_ranges: bool) {
+    // Primary detector only, or all three with the primary marked
+    // الكاشف الأساسي فقط، أو الثلاثة مع تمييز الأساسي
+    let ratios = &state.detector_windows.ratios;
+    let range_lines: Vec<Line> = if all_ranges {
+        PrimaryDetector::ALL
+            .iter()
+            .map(|&d| {
+                let marker = if d == state.primary_detector { "▶" } else { " " };
+                analysis_line(&format!("{}{}", marker, d.label()), state.max_sc, ratios.range(state.max_sc, d), detector_color(d))
+            })
+            .collect()
+    } else {
+        let primary = state.primary_detector;
+        vec![analysis_line(primary.label(), state.max_sc, ratios.range(state.max_sc, primary), Color::Green)]
+    };
 
-    let text = vec![
+    let mut text = vec![
         Line::from(vec![
             Span::raw("Frames: "),
             Span::styled(
                 format!("{}", state.frame_count()),
                 Style::default().fg(Color::Yellow),
             ),
+            Span::raw(" in "),
+            Span::styled(format!("{}s", state.window_secs), Style::default().fg(Color::Cyan)),
+            if state.tiny_frames_rejected > 0 {
+                Span::styled(
+                    format!(" ({} tiny dropped)", state.tiny_frames_rejected),
+                    Style::default().fg(Color::Red),
+                )
+            } else {
+                Span::raw("")
+            },
+            if state.contaminated_frames > 0 {
+                Span::styled(
+                    format!(" ({} contaminated)", state.contaminated_frames),
+                    Style::default().fg(Color::Red),
+                )
+            } else {
+                Span::raw("")
+            },
+            if state.corrupt_packets > 0 {
+                Span::styled(
+                    format!(" ({} corrupt)", state.corrupt_packets),
+                    Style::default().fg(Color::Red),
+                )
+            } else {
+                Span::raw("")
+            },
+            if state.duplicates_dropped > 0 {
+                Span::styled(
+                    format!(" ({} dup)", state.duplicates_dropped),
+                    Style::default().fg(Color::DarkGray),
+                )
+            } else {
+                Span::raw("")
+            },
+            if state.queue_dropped > 0 {
+                Span::styled(
+                    format!(" ({} queue drop)", state.queue_dropped),
+                    Style::default().fg(Color::Red),
+                )
+            } else {
+                Span::raw("")
+            },
         ]),
+        rate_line(state, Instant::now()),
+        smoothed_rate_line(state),
+        session_line(state, Instant::now()),
         Line::from(vec![
             Span::raw("SC: "),
             Span::styled(
@@ -98,23 +222,61 @@ fn render_stats(frame: &mut Frame, area: Rect, state: &AppState) {
             ),
             Span::raw(" "),
             Span::styled(
-                wifi_info.wifi_standard,
+                wifi_label(state),
                 Style::default().fg(Color::Cyan),
             ),
+            if state.null_subcarriers > 0 {
+                Span::styled(
+                    format!(" (-{} null)", state.null_subcarriers),
+                    Style::default().fg(Color::DarkGray),
+                )
+            } else {
+                Span::raw("")
+            },
+        ]),
+    ];
+    text.extend(range_lines);
+    text.extend([
+        blocks_line(state),
+        Line::from(vec![
+            Span::raw("Format: "),
+            Span::styled(
+                state.format_mode_label(),
+                Style::default().fg(if state.forced_format.is_some() { Color::Yellow } else { Color::Cyan }),
+            ),
         ]),
         Line::from(vec![
-            Span::raw("Analysis: "),
+            Span::raw("Aggregate: "),
+            Span::styled(state.aggregation.label(), Style::default().fg(Color::Yellow)),
+            Span::raw(" Chart: "),
             Span::styled(
-                format!("[{}-{}]", wifi_info.analysis_range.0, wifi_info.analysis_range.1),
-                Style::default().fg(Color::Green),
+                state.subcarrier_label(),
+                Style::default().fg(if state.selected_subcarrier.is_some() { Color::Magenta } else { Color::DarkGray }),
             ),
-            Span::raw(format!(" ({})", wifi_info.analysis_range.1 - wifi_info.analysis_range.0)),
+        ]),
+        jitter_line(state),
+        latency_line(state),
+        Line::from(vec![
+            Span::raw("RSSI: "),
+            match state.frames.last().and_then(|f| f.rssi) {
+                Some(rssi) => Span::styled(format!("{} dBm", rssi), Style::default().fg(Color::Green)),
+                None => Span::styled("--", Style::default().fg(Color::DarkGray)),
+            },
+        ]),
+        Line::from(vec![
+            Span::raw("MAC: "),
+            match state.frames.last().and_then(|f| f.mac.as_deref()) {
+                Some(mac) => Span::styled(mac.to_string(), Style::default().fg(Color::Magenta)),
+                None => Span::styled("--", Style::default().fg(Color::DarkGray)),
+            },
         ]),
         Line::from(vec![
             Span::raw("Port: "),
             Span::styled(&state.port_name, Style::default().fg(Color::Cyan)),
+            Span::styled(format!(" @ {}", state.baud_rate), Style::default().fg(Color::DarkGray)),
         ]),
-    ];
+        log_line(state),
+    ]);
 
     let block = Block::default()
         .title("📊 Statistics")
@@ -125,51 +287,315 @@ fn render_stats(frame: &mut Frame, area: Rect, state: &AppState) {
     frame.render_widget(paragraph, area);
 }
 
+/// Frames per second now and on average, with the newest frame's subcarriers
+/// الإطارات في الثانية الآن وفي المتوسط مع ناقلات أحدث إطار
+fn rate_line(state: &AppState, now: Instant) -> Line<'static> {
+    let meter = &state.frame_rate;
+    let rate = meter.rate(now);
+    Line::from(vec![
+        Span::raw("Rate: "),
+        Span::styled(
+            format!("{:.1} fps", rate),
+            Style::default().fg(rate_color(rate, state.fps_floor, meter.has_frames())),
+        ),
+        Span::styled(format!(" (avg {:.1})", meter.average(now)), Style::default().fg(Color::DarkGray)),
+        Span::raw(format!(" last {} SC", meter.last_subcarriers())),
+        Span::styled(format!(" {}", byte_rate_label(meter.byte_rate(now))), Style::default().fg(Color::Cyan)),
+    ])
+}
+
+/// Rate color: yellow below the floor, red below half of it (stalls)
+/// لون المعدل: أصفر دون الحد الأدنى وأحمر دون نصفه (توقف)
+///
+/// Without a floor only a stopped stream is red; before any frame it is grey.
+fn rate_color(rate: f64, floor: f64, has_frames: bool) -> Color {
+    if !has_frames {
+        Color::DarkGray
+    } else if floor <= 0.0 {
+        if rate > 0.0 { Color::Green } else { Color::Red }
+    } else if rate >= floor {
+        Color::Green
+    } else if rate >= floor / 2.0 {
+        Color::Yellow
+    } else {
+        Color::Red
+    }
+}
+
+/// Link throughput, e.g. "12.3 kB/s" / إنتاجية الرابط
+fn byte_rate_label(bytes_per_sec: f64) -> String {
+    format!("{}/s", byte_label(bytes_per_sec))
+}
+
+/// Byte count in B, kB or MB / عدد البايتات بوحدة B أو kB أو MB
+fn byte_label(bytes: f64) -> String {
+    if bytes >= 1_000_000.0 {
+        format!("{:.1} MB", bytes / 1_000_000.0)
+    } else if bytes >= 1_000.0 {
+        format!("{:.1} kB", bytes / 1_000.0)
+    } else {
+        format!("{:.0} B", bytes)
+    }
+}
+
+/// CSV recording file and size, or "not recording" / ملف تسجيل CSV وحجمه
+fn log_line(state: &AppState) -> Line<'static> {
+    let Some(logger) = &state.csv_logger else {
+        let hint = if state.record_csv { "starts with the session (W)" } else { "not recording (W)" };
+        return Line::from(vec![Span::raw("Log: "), Span::styled(hint, Style::default().fg(Color::DarkGray))]);
+    };
+    let name = logger.file_path().file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    Line::from(vec![
+        Span::raw("Log: "),
+        Span::styled("REC ● ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        Span::styled(name, Style::default().fg(Color::Cyan)),
+        Span::styled(
+            format!(" ({})", byte_label(logger.bytes_written() as f64)),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+/// Smoothed rate from frame timestamps, with its session min/max
+/// المعدل المنعم من الطوابع الزمنية مع أدنى وأعلى قيمة في الجلسة
+fn smoothed_rate_line(state: &AppState) -> Line<'static> {
+    let rate = &state.smoothed_rate;
+    match (rate.fps(), rate.range()) {
+        (Some(fps), Some((min, max))) => Line::from(vec![
+            Span::raw("Smooth: "),
+            Span::styled(format!("{:.1} fps", fps), Style::default().fg(Color::Cyan)),
+            Span::styled(format!(" (min {:.1} max {:.1})", min, max), Style::default().fg(Color::DarkGray)),
+        ]),
+        _ => Line::from(vec![Span::raw("Smooth: "), Span::styled("--", Style::default().fg(Color::DarkGray))]),
+    }
+}
+
+/// Serial blocks parsed and rejected, with the newest rejection reason
+/// كتل التسلسل المحللة والمرفوضة مع سبب أحدث رفض
+fn blocks_line(state: &AppState) -> Line<'static> {
+    let stats = state.parse_stats;
+    let mut spans = vec![
+        Span::raw("Blocks: "),
+        Span::styled(format!("{}/{} ok", stats.parsed, stats.seen), Style::default().fg(Color::Yellow)),
+    ];
+    if stats.rejected > 0 {
+        spans.push(Span::styled(format!(" {} bad", stats.rejected), Style::default().fg(Color::Red)));
+    }
+    if let Some(failure) = stats.last_failure {
+        spans.push(Span::styled(format!(" ({})", failure), Style::default().fg(Color::DarkGray)));
+    }
+    Line::from(spans)
+}
+
+/// Subcarrier range a detector analyses; "--" before any frame
+/// نطاق الناقلات الذي يحلله الكاشف؛ "--" قبل أي إطار
+fn analysis_line(label: &str, total_sc: usize, (start, end): (usize, usize), color: Color) -> Line<'static> {
+    let range = if total_sc == 0 {
+        Span::styled("--", Style::default().fg(Color::DarkGray))
+    } else if range_is_degenerate(total_sc, (start, end)) {
+        Span::styled("range too small — check data", Style::default().fg(Color::Yellow))
+    } else {
+        Span::styled(format!("[{}-{}] ({})", start, end, end - start), Style::default().fg(color))
+    };
+    Line::from(vec![Span::raw(format!("Analysis ({}): ", label)), range])
+}
+
+/// Wi-Fi mode and channel as reported, else guessed from the subcarrier count
+/// نمط الواي فاي والقناة كما أُرسلا، وإلا تخمين من عدد الناقلات
+fn wifi_label(state: &AppState) -> String {
+    let meta = &state.radio_meta;
+    let standard = meta
+        .wifi_standard()
+        .unwrap_or_else(|| get_subcarrier_info(state.max_sc, state.primary_detector).wifi_standard);
+    match meta.channel_label() {
+        Some(channel) => format!("{} {}", standard, channel),
+        None => standard,
+    }
+}
+
+/// Inter-frame jitter: whole file in playback, last minute when live
+/// تذبذب الفواصل: الملف كاملاً في التشغيل، وآخر دقيقة في البث المباشر
+fn jitter_line(state: &AppState) -> Line<'static> {
+    let summary = if state.playback_mode { state.file_jitter } else { state.jitter.summary() };
+    match summary {
+        Some(j) => Line::from(vec![
+            Span::raw("Jitter: "),
+            Span::styled(
+                format!("σ {:.0} p95 {:.0} max {:.0} ms", j.std_dev_ms, j.p95_ms, j.max_gap_ms),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]),
+        None => Line::from(vec![
+            Span::raw("Jitter: "),
+            Span::styled("-", Style::default().fg(Color::DarkGray)),
+        ]),
+    }
+}
+
+/// Rolling one-second max per pipeline stage (ms)
+/// القيمة القصوى خلال الثانية الأخيرة لكل مرحلة من المسار
+fn latency_line(state: &AppState) -> Line<'static> {
+    let stages = Stage::ALL
+        .iter()
+        .map(|&stage| format!("{} {:.1}", stage.label(), ms(state.latency.max(stage))))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Line::from(vec![
+        Span::raw("Latency: "),
+        Span::styled(format!("{} ms", stages), Style::default().fg(Color::Yellow)),
+    ])
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Detectors Status / حالة الكاشفات
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Render detectors status box
-/// رسم مربع حالة الكاشفات
-fn render_detectors(frame: &mut Frame, area: Rect, state: &AppState) {
-    let motion_status = if state.detections.motion_detected {
-        ("🔴 DETECTED", Color::Red)
+/// Verdict text and color for motion, human presence and door
+/// نص الحكم ولونه للحركة والوجود البشري والباب
+fn detector_statuses(state: &AppState) -> [(String, Color); 3] {
+    // Detections are not trusted until warmup has elapsed
+    // لا يوثق بالكشف حتى تنتهي فترة الإحماء
+    let warmup = state.warmup_remaining_secs(chrono::Utc::now().timestamp_millis());
+    let warming = || ("⏳ Warming up".to_string(), Color::DarkGray);
+    
+    // Detectors without enough frame history show what they still need
+    // الكاشفات بدون تاريخ كافٍ تعرض ما تحتاجه
+    let (motion_min, presence_min, door_min) = state.detector_windows.min_frames();
+    let frame_count = state.frame_count();
+    let collecting = |min: usize| (format!("⏳ {}/{} frames", frame_count.min(min), min), Color::DarkGray);
+    let ready = state.readiness;
+    
+    // Marginal input quality mutes the verdicts and adds "(?)"
+    // جودة الإدخال الهامشية تخفت الأحكام وتضيف "(?)"
+    let quality = state.detection_quality();
+    let judged = |(text, color): (String, Color)| {
+        if quality.uncertain {
+            (format!("{} (?)", text), Color::DarkGray)
+        } else {
+            (text, color)
+        }
+    };
+    
+    let motion_status = if warmup.is_some() {
+        warming()
+    } else if !ready.motion {
+        collecting(motion_min)
+    } else if state.detections.motion_detected {
+        judged(("🔴 DETECTED".to_string(), Color::Red))
     } else {
-        ("🟢 None", Color::Green)
+        judged(("🟢 None".to_string(), Color::Green))
     };
 
-    let human_status = if state.detections.human_present {
-        ("🔴 PRESENT", Color::Red)
+    let human_status = if warmup.is_some() {
+        warming()
+    } else if !ready.presence {
+        collecting(presence_min)
+    } else if state.detections.human_present {
+        judged(("🔴 PRESENT".to_string(), Color::Red))
     } else {
-        ("🟢 Not Present", Color::Green)
+        judged(("🟢 Not Present".to_string(), Color::Green))
     };
 
-    let door_status = if state.detections.door_open {
-        ("🔴 OPEN", Color::Red)
+    let door_status = if warmup.is_some() {
+        warming()
+    } else if !ready.door {
+        collecting(door_min)
+    } else if state.detections.door_open {
+        judged(("🔴 OPEN".to_string(), Color::Red))
     } else {
-        ("🟢 Closed", Color::Green)
+        judged(("🟢 Closed".to_string(), Color::Green))
     };
 
-    let text = vec![
+    [motion_status, human_status, door_status]
+}
+
+/// Render detectors status box
+/// رسم مربع حالة الكاشفات
+fn render_detectors(frame: &mut Frame, area: Rect, state: &AppState) {
+    let warmup = state.warmup_remaining_secs(chrono::Utc::now().timestamp_millis());
+    let quality = state.detection_quality();
+    let [motion_status, human_status, door_status] = detector_statuses(state);
+
+    // Deltas against the calibration baseline (optional)
+    // الفروقات مقارنة بخط أساس المعايرة (اختياري)
+    let deltas = match (&state.baseline, state.show_baseline_delta) {
+        (Some(baseline), true) => {
+            let base = state.display_scale.apply(&baseline.detections);
+            Some((
+                state.display.motion - base.motion,
+                state.display.presence - base.presence,
+                state.display.door - base.door,
+            ))
+        }
+        _ => None,
+    };
+    let delta_span = |delta: Option<f64>| match delta {
+        Some(d) => Span::styled(format!(" {:+.1}", d), Style::default().fg(Color::Cyan)),
+        None => Span::raw(""),
+    };
+
+    let trend_span = |history: &[f64]| Span::styled(
+        format!(" {}", trend_arrow(history)),
+        Style::default().fg(Color::Cyan),
+    );
+
+    let mut text = vec![
         Line::from(vec![
             Span::raw("Motion: "),
             Span::styled(motion_status.0, Style::default().fg(motion_status.1)),
-            Span::styled(format!(" ({:.1})", state.detections.motion_value), Style::default().fg(Color::Yellow)),
+            Span::styled(format!(" ({:.1})", state.display.motion), Style::default().fg(Color::Yellow)),
+            trend_span(&state.motion_history),
+            delta_span(deltas.map(|d| d.0)),
         ]),
         Line::from(vec![
             Span::raw("Human: "),
             Span::styled(human_status.0, Style::default().fg(human_status.1)),
-            Span::styled(format!(" ({:.1})", state.detections.presence_value), Style::default().fg(Color::Yellow)),
+            Span::styled(format!(" ({:.1})", state.display.presence), Style::default().fg(Color::Yellow)),
+            trend_span(&state.presence_history),
+            delta_span(deltas.map(|d| d.1)),
         ]),
         Line::from(vec![
             Span::raw("Door: "),
             Span::styled(door_status.0, Style::default().fg(door_status.1)),
-            Span::styled(format!(" ({:.1})", state.detections.door_value), Style::default().fg(Color::Yellow)),
+            Span::styled(format!(" ({:.1})", state.display.door), Style::default().fg(Color::Yellow)),
+            trend_span(&state.door_history),
+            delta_span(deltas.map(|d| d.2)),
+        ]),
+        Line::from(vec![
+            Span::raw("Breathing: "),
+            if warmup.is_none() && state.detections.breathing_bpm > 0.0 {
+                Span::styled(
+                    format!("{:.1} bpm", state.detections.breathing_bpm),
+                    Style::default().fg(Color::Green),
+                )
+            } else {
+                Span::styled("--", Style::default().fg(Color::DarkGray))
+            },
         ]),
     ];
 
+    text.extend(state.sources.iter().map(source_row));
+
+    if let (Some(baseline), Some(_)) = (&state.baseline, deltas) {
+        let captured = chrono::Local
+            .timestamp_millis_opt(baseline.timestamp)
+            .single()
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_default();
+        let signal = state.frames.last().map(|f| baseline.signal_delta(&f.mags)).unwrap_or(0.0);
+        text.push(Line::from(Span::styled(
+            format!("Δ vs baseline @ {} (signal {:.1})", captured, signal),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let title = match (warmup, quality.reason(&state.quality_floors)) {
+        (Some(secs), _) => format!("🔍 Detectors (warming up {:.1}s)", secs),
+        (None, Some(reason)) => format!("🔍 Detectors (? {})", reason),
+        (None, None) => "🔍 Detectors".to_string(),
+    };
     let block = Block::default()
-        .title("🔍 Detectors")
+        .title(title)
         .borders(Borders::ALL)
         .border_style(Style::default().fg(Color::Yellow));
 
@@ -177,6 +603,70 @@ fn render_detectors(frame: &mut Frame, area: Rect, state: &AppState) {
     frame.render_widget(paragraph, area);
 }
 
+/// Compact detection row for one named source / صف كشف مختصر لمصدر مسمى
+///
+/// `🏠 kitchen ● Motion ● Human ● Door`, each dot red while detected.
+fn source_row(source: &SourceState) -> Line<'static> {
+    let connected = source.receiver == ReceiverState::Connected;
+    let mut spans = vec![Span::styled(
+        format!("🏠 {}", source.name),
+        Style::default().fg(if connected { Color::Cyan } else { Color::DarkGray }),
+    )];
+    let detections = &source.detections;
+    for (label, active) in [
+        ("Motion", detections.motion_detected),
+        ("Human", detections.human_present),
+        ("Door", detections.door_open),
+    ] {
+        let color = match (connected, active) {
+            (false, _) => Color::DarkGray,
+            (true, true) => Color::Red,
+            (true, false) => Color::Green,
+        };
+        spans.push(Span::styled(" ●", Style::default().fg(color)));
+        spans.push(Span::raw(format!(" {}", label)));
+    }
+    if !connected {
+        spans.push(Span::styled(
+            format!(" ({})", source.receiver.label()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Session uptime, events and peak motion / مدة الجلسة والأحداث وقمة الحركة
+fn session_line(state: &AppState, now: Instant) -> Line<'static> {
+    let session = &state.session;
+    let secs = session.uptime(now).as_secs();
+    Line::from(vec![
+        Span::raw("Session: "),
+        Span::styled(
+            format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::raw(format!(" {} events", session.total_events())),
+        Span::styled(
+            format!(" peak {:.1}", session.peak_motion),
+            Style::default().fg(Color::DarkGray),
+        ),
+    ])
+}
+
+/// Changes smaller than this are shown as held (half the displayed precision)
+/// التغيرات الأصغر من هذا تعرض كثابتة (نصف دقة العرض)
+const TREND_EPSILON: f64 = 0.05;
+
+/// Trend arrow from the last two history samples (→ if fewer than two)
+/// سهم الاتجاه من آخر عينتين في التاريخ
+fn trend_arrow(history: &[f64]) -> &'static str {
+    match history {
+        [.., prev, last] if last - prev > TREND_EPSILON => "↑",
+        [.., prev, last] if prev - last > TREND_EPSILON => "↓",
+        _ => "→",
+    }
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // 🔹 Playback Bar / شريط التشغيل
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -191,12 +681,18 @@ fn render_playback_bar(frame: &mut Frame, area: Rect, state: &AppState) {
         
         let play_status = if state.playback_playing { "▶️" } else { "⏸️" };
         
-        let label = format!("{} {:.1}s / {:.1}s", play_status, current_sec, total_sec);
+        let label = format!("{} {:.1}s / {:.1}s  {}x", play_status, current_sec, total_sec, state.playback_speed);
+        
+        // Show loaded file name in title / عرض اسم الملف المحمل في العنوان
+        let title = match state.last_csv_path.as_ref().and_then(|p| p.file_name()) {
+            Some(name) => format!("🎬 Playback - {}", name.to_string_lossy()),
+            None => "🎬 Playback".to_string(),
+        };
         
         let gauge = Gauge::default()
             .block(
                 Block::default()
-                    .title("🎬 Playback")
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_style(Style::default().fg(Color::Cyan)),
             )
@@ -221,3 +717,186 @@ fn render_playback_bar(frame: &mut Frame, area: Rect, state: &AppState) {
         frame.render_widget(paragraph, area);
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Compact Summary / الملخص المضغوط
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Rows taken by the compact summary (three lines plus borders)
+/// الصفوف التي يشغلها الملخص المضغوط (ثلاثة أسطر مع الحدود)
+pub const COMPACT_ROWS: u16 = 5;
+
+/// Render receiver, statistics and detectors as one dense box
+/// رسم المستقبل والإحصائيات والكاشفات في مربع واحد كثيف
+pub fn render_compact(frame: &mut Frame, area: Rect, state: &AppState) {
+    let separator = || Span::styled(" │ ", Style::default().fg(Color::DarkGray));
+    let [motion, human, door] = detector_statuses(state);
+    let verdict = |label: &'static str, (text, color): (String, Color)| {
+        vec![Span::raw(label), Span::styled(text, Style::default().fg(color))]
+    };
+
+    let mut detectors = verdict("Motion: ", motion);
+    detectors.push(separator());
+    detectors.extend(verdict("Human: ", human));
+    detectors.push(separator());
+    detectors.extend(verdict("Door: ", door));
+    if state.detections.breathing_bpm > 0.0 {
+        detectors.push(separator());
+        detectors.push(Span::styled(
+            format!("{:.1} bpm", state.detections.breathing_bpm),
+            Style::default().fg(Color::Green),
+        ));
+    }
+
+    let text = vec![
+        Line::from(vec![
+            receiver_span(state),
+            frozen_span(state).unwrap_or_default(),
+            Span::raw(" "),
+            Span::styled(state.port_name.clone(), Style::default().fg(Color::Cyan)),
+            separator(),
+            Span::raw("Frames: "),
+            Span::styled(format!("{}", state.frame_count()), Style::default().fg(Color::Yellow)),
+            separator(),
+            Span::raw("SC: "),
+            Span::styled(format!("{}", state.max_sc), Style::default().fg(Color::Magenta)),
+            separator(),
+            Span::raw("RSSI: "),
+            match state.frames.last().and_then(|f| f.rssi) {
+                Some(rssi) => Span::styled(format!("{} dBm", rssi), Style::default().fg(Color::Green)),
+                None => Span::styled("--", Style::default().fg(Color::DarkGray)),
+            },
+        ]),
+        Line::from(detectors),
+        Line::from(Span::raw(state.status_message.clone())),
+    ];
+
+    let block = Block::default()
+        .title("📡 Status (O: full layout)")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    let paragraph = Paragraph::new(text).block(block);
+    frame.render_widget(paragraph, area);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trend_arrow() {
+        assert_eq!(trend_arrow(&[1.0, 2.0]), "↑");
+        assert_eq!(trend_arrow(&[5.0, 9.0, 3.0]), "↓");
+        assert_eq!(trend_arrow(&[4.0, 4.0]), "→");
+        // Below display precision counts as held / أقل من دقة العرض يعتبر ثابتاً
+        assert_eq!(trend_arrow(&[4.0, 4.04]), "→");
+
+        // Fewer than two samples / أقل من عينتين
+        assert_eq!(trend_arrow(&[7.0]), "→");
+        assert_eq!(trend_arrow(&[]), "→");
+    }
+
+    #[test]
+    fn test_rate_color_and_throughput() {
+        assert_eq!(rate_color(0.0, 100.0, false), Color::DarkGray);
+        assert_eq!(rate_color(98.0, 0.0, true), Color::Green);
+        assert_eq!(rate_color(0.0, 0.0, true), Color::Red);
+
+        // Floor of 100 fps / حد أدنى 100 إطار في الثانية
+        assert_eq!(rate_color(100.0, 100.0, true), Color::Green);
+        assert_eq!(rate_color(70.0, 100.0, true), Color::Yellow);
+        assert_eq!(rate_color(20.0, 100.0, true), Color::Red);
+
+        assert_eq!(byte_rate_label(0.0), "0 B/s");
+        assert_eq!(byte_rate_label(512.0), "512 B/s");
+        assert_eq!(byte_rate_label(25_600.0), "25.6 kB/s");
+        assert_eq!(byte_label(1_234_567.0), "1.2 MB");
+    }
+
+    #[test]
+    fn test_wifi_label_prefers_reported_mode() {
+        use csi_tui::state::{CsiFormat, CsiFrame, CsiMeta};
+
+        // CSV playback: guessed from 64 subcarriers / تشغيل CSV: تخمين من 64 ناقلاً
+        let mut state = AppState::new();
+        state.push_frame(CsiFrame::new(0, vec![1.0; 64], vec![], CsiFormat::AmplitudeOnly, None));
+        assert_eq!(wifi_label(&state), "Wi-Fi 4/5 (20MHz)");
+
+        // Reported HT40 on channel 6 above / HT40 مرسل على القناة 6 فوق
+        let meta = CsiMeta { channel: Some(6), secondary_channel: Some(1), sig_mode: Some(1), bandwidth: Some(1) };
+        state.push_frame(CsiFrame::new(1, vec![1.0; 64], vec![], CsiFormat::AmplitudeOnly, None).with_meta(meta));
+        assert_eq!(wifi_label(&state), "Wi-Fi 4 (40MHz) ch 6+");
+
+        // A frame without metadata keeps the last report / إطار بلا بيانات يبقي آخر تقرير
+        state.push_frame(CsiFrame::new(2, vec![1.0; 64], vec![], CsiFormat::AmplitudeOnly, None));
+        assert_eq!(wifi_label(&state), "Wi-Fi 4 (40MHz) ch 6+");
+    }
+
+    #[test]
+    fn test_degenerate_analysis_line() {
+        let text = |line: Line| line.spans.iter().map(|s| s.content.to_string()).collect::<String>();
+
+        // No frames yet / لا إطارات بعد
+        assert_eq!(text(analysis_line("Motion", 0, (0, 0), Color::Green)), "Analysis (Motion): --");
+        for total_sc in [1, 2] {
+            let line = text(analysis_line("Motion", total_sc, (0, 1), Color::Green));
+            assert_eq!(line, "Analysis (Motion): range too small — check data");
+        }
+        assert_eq!(text(analysis_line("Motion", 3, (1, 2), Color::Green)), "Analysis (Motion): [1-2] (1)");
+    }
+
+    #[test]
+    fn test_recording_marker_shown_once() {
+        use csi_tui::csv_logger::{open_marker_for, CsvLogger};
+        use csi_tui::csv_writer::CsvWriter;
+        use ratatui::{backend::TestBackend, Terminal};
+
+        let path = std::env::temp_dir().join(format!("csi_status_rec_{}.csv", std::process::id()));
+        let mut state = AppState::new();
+        let screen = |state: &AppState, height: u16| {
+            let mut terminal = Terminal::new(TestBackend::new(60, height)).unwrap();
+            terminal.draw(|frame| render(frame, frame.area(), state)).unwrap();
+            terminal.backend().buffer().content().iter().map(|c| c.symbol()).collect::<String>()
+        };
+        assert_eq!(screen(&state, 50).matches("REC ●").count(), 0);
+
+        // On the Log line only / على سطر السجل فقط
+        state.csv_logger = Some(CsvWriter::spawn(CsvLogger::new(path.clone()).unwrap()));
+        assert_eq!(screen(&state, 50).matches("REC ●").count(), 1);
+
+        state.csv_logger = None;
+        let _ = std::fs::remove_file(open_marker_for(&path));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_source_rows() {
+        use csi_tui::state::{CsiFormat, CsiFrame};
+
+        let mut state = AppState::new();
+        state.sources.add("kitchen", "/dev/ttyUSB1");
+        state.sources.add("bedroom", "/dev/ttyUSB2");
+        state.sources.set_receiver("kitchen", ReceiverState::Connected);
+
+        // Kitchen frames swing, the primary view gets none / إطارات المطبخ تتأرجح والعرض الأساسي لا يستلم شيئاً
+        for i in 0..60 {
+            let level = if i % 2 == 0 { 35.0 } else { 5.0 };
+            let frame = CsiFrame::new(i * 50, vec![level; 32], vec![], CsiFormat::AmplitudeOnly, None);
+            state.push_frame(frame.with_source(Some("kitchen".to_string())));
+        }
+        assert!(state.frames.is_empty());
+        state.sources.run_detectors(state.aggregation, &state.detector_windows);
+
+        let rows: Vec<Line> = state.sources.iter().map(source_row).collect();
+        let text = |line: &Line| line.spans.iter().map(|s| s.content.to_string()).collect::<String>();
+        assert_eq!(text(&rows[0]), "🏠 kitchen ● Motion ● Human ● Door");
+        assert_eq!(rows[0].spans[1].style.fg, Some(Color::Red));
+        assert_eq!(text(&rows[1]), "🏠 bedroom ● Motion ● Human ● Door (STOPPED)");
+        assert_eq!(rows[1].spans[1].style.fg, Some(Color::DarkGray));
+    }
+}
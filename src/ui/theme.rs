@@ -0,0 +1,195 @@
+// ═══════════════════════════════════════════════════════════════════════════════
+// 📦 ui/theme.rs - Color Capability Fallbacks
+// ═══════════════════════════════════════════════════════════════════════════════
+// Keeps the UI legible on 8-color and monochrome terminals.
+// Features:
+// - Color depth detection from NO_COLOR / COLORTERM / TERM
+// - Palette roles mapped down to the 8 basic ANSI colors
+// - Monochrome: colors replaced by distinct bold / underline / italic / dim styles
+// ═══════════════════════════════════════════════════════════════════════════════
+
+use std::str::FromStr;
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+use csi_tui::error::CsiError;
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Color Depth / عمق الألوان
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// How many colors the terminal can show
+/// عدد الألوان التي تستطيع الطرفية عرضها
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorDepth {
+    /// 16+ colors, palette used as-is / 16 لوناً أو أكثر، اللوحة كما هي
+    #[default]
+    Full,
+
+    /// 8 basic ANSI colors only / ألوان ANSI الثمانية الأساسية فقط
+    Ansi8,
+
+    /// No colors / بدون ألوان
+    Mono,
+}
+
+impl ColorDepth {
+    /// Detect from the environment / الكشف من متغيرات البيئة
+    pub fn detect() -> Self {
+        let colorterm = std::env::var("COLORTERM").ok();
+        let term = std::env::var("TERM").ok();
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self::from_env(colorterm.as_deref(), term.as_deref(), no_color)
+    }
+
+    /// Heuristics behind `detect` / الاستدلالات خلف `detect`
+    ///
+    /// No TERM at all is a Windows console, which has full color. Plain
+    /// `xterm` and `*-16color` terminals have the 16-color palette, so they
+    /// get Full too; only 8-color terminals (linux, screen, vt100, ...) get Ansi8.
+    fn from_env(colorterm: Option<&str>, term: Option<&str>, no_color: bool) -> Self {
+        if no_color {
+            return ColorDepth::Mono;
+        }
+        if matches!(colorterm, Some("truecolor") | Some("24bit")) {
+            return ColorDepth::Full;
+        }
+        let Some(term) = term else {
+            return ColorDepth::Full;
+        };
+        if term.is_empty() || term == "dumb" {
+            ColorDepth::Mono
+        } else if term == "xterm"
+            || ["16color", "256color", "direct", "truecolor", "kitty", "alacritty", "wezterm"]
+                .iter()
+                .any(|hint| term.contains(hint))
+        {
+            ColorDepth::Full
+        } else {
+            // linux, vt100, screen, xterm-color ... / طرفيات الألوان الثمانية
+            ColorDepth::Ansi8
+        }
+    }
+}
+
+impl FromStr for ColorDepth {
+    type Err = CsiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "full" | "16" | "256" | "truecolor" => Ok(ColorDepth::Full),
+            "8" | "ansi8" => Ok(ColorDepth::Ansi8),
+            "mono" | "none" | "0" => Ok(ColorDepth::Mono),
+            other => Err(CsiError::InvalidInput(format!(
+                "unknown color depth '{}' (expected auto, full, 8 or mono)", other
+            ))),
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Palette Fallbacks / بدائل لوحة الألوان
+// ═══════════════════════════════════════════════════════════════════════════════
+//
+// Roles in the palette / أدوار الألوان في اللوحة:
+//   Cyan     primary text, borders, CSI series     → plain in mono
+//   Green    OK / presence series                  → bold
+//   Red      errors, alerts / motion series        → bold + underline
+//   Blue     door series                           → underline
+//   Yellow   warnings, detector borders            → italic
+//   Magenta  controls, baseline                    → bold + italic
+//   DarkGray hints, placeholders, gauge track      → dim (default color on 8 colors)
+
+/// Foreground on a terminal with the 8 basic colors
+/// لون المقدمة على طرفية بثمانية ألوان أساسية
+fn fg_ansi8(color: Color) -> (Color, Modifier) {
+    match color {
+        Color::DarkGray => (Color::Reset, Modifier::DIM),
+        Color::LightRed => (Color::Red, Modifier::empty()),
+        Color::LightGreen => (Color::Green, Modifier::empty()),
+        Color::LightYellow => (Color::Yellow, Modifier::empty()),
+        Color::LightBlue => (Color::Blue, Modifier::empty()),
+        Color::LightMagenta => (Color::Magenta, Modifier::empty()),
+        Color::LightCyan => (Color::Cyan, Modifier::empty()),
+        Color::White => (Color::Gray, Modifier::empty()),
+        Color::Rgb(..) | Color::Indexed(_) => (Color::Reset, Modifier::empty()),
+        basic => (basic, Modifier::empty()),
+    }
+}
+
+/// Modifier standing in for a color on a monochrome terminal
+/// المعدِّل البديل للون على طرفية أحادية اللون
+fn mono_modifier(color: Color) -> Modifier {
+    match fg_ansi8(color).0 {
+        Color::Green => Modifier::BOLD,
+        Color::Red => Modifier::BOLD | Modifier::UNDERLINED,
+        Color::Blue => Modifier::UNDERLINED,
+        Color::Yellow => Modifier::ITALIC,
+        Color::Magenta => Modifier::BOLD | Modifier::ITALIC,
+        _ if color == Color::DarkGray => Modifier::DIM,
+        _ => Modifier::empty(),
+    }
+}
+
+/// Rewrite a rendered frame for the terminal's color depth
+/// إعادة كتابة الإطار المرسوم حسب عمق ألوان الطرفية
+///
+/// Runs after all widgets drew, so every widget gets the same fallbacks.
+pub fn apply(buffer: &mut Buffer, depth: ColorDepth) {
+    if depth == ColorDepth::Full {
+        return;
+    }
+
+    for cell in buffer.content.iter_mut() {
+        let (fg, bg) = (cell.fg, cell.bg);
+        match depth {
+            ColorDepth::Ansi8 => {
+                let (new_fg, extra) = fg_ansi8(fg);
+                cell.fg = new_fg;
+                cell.modifier |= extra;
+                // Gray backgrounds (gauge track) fall back to the default
+                // الخلفيات الرمادية (مسار المقياس) تعود للافتراضي
+                cell.bg = fg_ansi8(bg).0;
+            }
+            ColorDepth::Mono => {
+                cell.modifier |= mono_modifier(fg);
+                // Any real background becomes reverse video (selection, gauge label)
+                // أي خلفية حقيقية تصبح عرضاً معكوساً
+                if !matches!(bg, Color::Reset | Color::DarkGray) {
+                    cell.modifier |= Modifier::REVERSED;
+                }
+                cell.fg = Color::Reset;
+                cell.bg = Color::Reset;
+            }
+            ColorDepth::Full => {}
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// 🔹 Unit Tests / اختبارات الوحدة
+// ═══════════════════════════════════════════════════════════════════════════════
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_env() {
+        assert_eq!(ColorDepth::from_env(Some("truecolor"), Some("xterm"), false), ColorDepth::Full);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-256color"), false), ColorDepth::Full);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-16color"), false), ColorDepth::Full);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm"), false), ColorDepth::Full);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-color"), false), ColorDepth::Ansi8);
+        assert_eq!(ColorDepth::from_env(None, Some("screen"), false), ColorDepth::Ansi8);
+        assert_eq!(ColorDepth::from_env(None, Some("linux"), false), ColorDepth::Ansi8);
+        assert_eq!(ColorDepth::from_env(None, Some("dumb"), false), ColorDepth::Mono);
+        assert_eq!(ColorDepth::from_env(Some("truecolor"), None, true), ColorDepth::Mono);
+        assert_eq!(ColorDepth::from_env(None, None, false), ColorDepth::Full);
+        assert_eq!("8".parse::<ColorDepth>().unwrap(), ColorDepth::Ansi8);
+        assert!("sepia".parse::<ColorDepth>().is_err());
+    }
+}